@@ -1,4 +1,4 @@
-# Benchmarks for port scanner
+//! Benchmarks for port scanner
 
 use criterion::{black_box, criterion_group, criterion_main, Criterion, BenchmarkId};
 use port_scanner::prelude::*;
@@ -9,6 +9,7 @@ fn bench_scan_single_port(c: &mut Criterion) {
     let mut group = c.benchmark_group("single_port_scan");
     
     let ip: IpAddr = "127.0.0.1".parse().unwrap();
+    let rt = tokio::runtime::Runtime::new().unwrap();
     
     for port in [80, 443, 8080].iter() {
         group.bench_with_input(BenchmarkId::from_parameter(port), port, |b, &port| {
@@ -22,7 +23,7 @@ fn bench_scan_single_port(c: &mut Criterion) {
                     .unwrap();
                 
                 let scanner = PortScanner::new(config).unwrap();
-                let _ = scanner.scan_all(|_| {});
+                rt.block_on(scanner.scan_all(|_| {}));
             });
         });
     }
@@ -34,6 +35,7 @@ fn bench_scan_port_range(c: &mut Criterion) {
     let mut group = c.benchmark_group("port_range_scan");
     
     let ip: IpAddr = "127.0.0.1".parse().unwrap();
+    let rt = tokio::runtime::Runtime::new().unwrap();
     
     for size in [10, 50, 100].iter() {
         group.bench_with_input(BenchmarkId::from_parameter(size), size, |b, &size| {
@@ -48,7 +50,7 @@ fn bench_scan_port_range(c: &mut Criterion) {
                     .unwrap();
                 
                 let scanner = PortScanner::new(config).unwrap();
-                let _ = scanner.scan_all(|_| {});
+                rt.block_on(scanner.scan_all(|_| {}));
             });
         });
     }
@@ -61,6 +63,7 @@ fn bench_parallel_vs_sequential(c: &mut Criterion) {
     
     let ip: IpAddr = "127.0.0.1".parse().unwrap();
     let port_count = 50;
+    let rt = tokio::runtime::Runtime::new().unwrap();
     
     group.bench_function("sequential", |b| {
         b.iter(|| {
@@ -73,7 +76,7 @@ fn bench_parallel_vs_sequential(c: &mut Criterion) {
                 .unwrap();
             
             let scanner = PortScanner::new(config).unwrap();
-            let _ = scanner.scan_all(|_| {});
+            rt.block_on(scanner.scan_all(|_| {}));
         });
     });
     
@@ -89,7 +92,7 @@ fn bench_parallel_vs_sequential(c: &mut Criterion) {
                 .unwrap();
             
             let scanner = PortScanner::new(config).unwrap();
-            let _ = scanner.scan_all(|_| {});
+            rt.block_on(scanner.scan_all(|_| {}));
         });
     });
     
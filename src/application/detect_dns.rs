@@ -0,0 +1,321 @@
+//! DNS service detection use case
+
+use std::net::SocketAddr;
+use std::time::Duration;
+use tokio::net::{TcpStream as AsyncTcpStream, UdpSocket};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::time::timeout as async_timeout;
+use tracing::{debug, trace, warn};
+
+use crate::domain::{Port, ServiceVersion};
+use crate::constants::*;
+use crate::infrastructure::record_attempt;
+use crate::scanning::Detector;
+
+const DNS_TYPE_TXT: u16 = 16;
+const DNS_TYPE_AXFR: u16 = 252;
+const DNS_CLASS_IN: u16 = 1;
+const DNS_CLASS_CHAOS: u16 = 3;
+const DNS_RCODE_NOERROR: u8 = 0;
+
+/// Result of a parsed DNS response
+#[derive(Debug, Clone, PartialEq)]
+pub struct DnsResponse {
+    pub rcode: u8,
+    pub answer_count: u16,
+    pub txt: Option<String>,
+}
+
+/// DNS detector: `version.bind` CHAOS TXT query and optional AXFR check
+pub struct DnsDetector {
+    axfr_domain: Option<String>,
+}
+
+impl DnsDetector {
+    pub fn new() -> Self {
+        Self { axfr_domain: None }
+    }
+
+    /// Also attempt a zone transfer of `domain` over TCP
+    pub fn with_axfr_domain(mut self, domain: impl Into<String>) -> Self {
+        self.axfr_domain = Some(domain.into());
+        self
+    }
+
+    /// Async DNS detection; the version's protocol is the transport that
+    /// answered.
+    ///
+    /// ```
+    /// use port_scanner::application::DnsDetector;
+    /// use std::time::Duration;
+    /// use tokio::net::UdpSocket;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// // Answers version.bind with a canned TXT record
+    /// let server = UdpSocket::bind("127.0.0.1:0").await?;
+    /// let addr = server.local_addr()?;
+    /// tokio::spawn(async move {
+    ///     let mut buf = [0u8; 512];
+    ///     let (n, peer) = server.recv_from(&mut buf).await.unwrap();
+    ///     let mut reply = buf[..n].to_vec();
+    ///     reply[2..4].copy_from_slice(&[0x84, 0x00]); // response, authoritative
+    ///     reply[6..8].copy_from_slice(&[0x00, 0x01]); // one answer
+    ///     let txt = b"9.18.24-1-Debian";
+    ///     reply.extend_from_slice(&[0xc0, 0x0c, 0x00, 0x10, 0x00, 0x03, 0, 0, 0, 0]);
+    ///     reply.extend_from_slice(&[0x00, txt.len() as u8 + 1, txt.len() as u8]);
+    ///     reply.extend_from_slice(txt);
+    ///     server.send_to(&reply, peer).await.unwrap();
+    /// });
+    ///
+    /// let version = DnsDetector::new().detect_async(&addr, Duration::from_secs(1)).await.unwrap();
+    /// assert_eq!(version.protocol, "udp");
+    /// assert_eq!(version.product.as_deref(), Some("BIND"));
+    /// assert_eq!(version.version.as_deref(), Some("9.18.24-1-Debian"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn detect_async(&self, socket: &SocketAddr, timeout: Duration) -> Option<ServiceVersion> {
+        debug!("Attempting DNS detection on {}", socket);
+
+        let query = Self::build_query(0x5053, "version.bind", DNS_TYPE_TXT, DNS_CLASS_CHAOS);
+
+        // Resolvers usually answer CHAOS queries over UDP; fall back to TCP
+        let (response, transport) = match Self::query_udp(socket, &query, timeout).await {
+            Some(response) => (response, "udp"),
+            None => (Self::query_tcp(socket, &query, timeout).await?, "tcp"),
+        };
+
+        let mut version = ServiceVersion::new("DNS", transport);
+        let mut extra = Vec::new();
+
+        match response.txt {
+            Some(txt) => {
+                debug!("version.bind answered on {}: {}", socket, txt);
+                let server = Self::identify_server(&txt);
+                extra.push(format!("server={}", server));
+                if server != "unknown" {
+                    version = version.with_product(server);
+                }
+                version = version
+                    .with_version(txt.clone())
+                    .with_banner(txt)
+                    .with_confidence(CONFIDENCE_HIGH);
+            }
+            None => {
+                // A well-formed reply still proves a DNS server is listening
+                trace!("version.bind not disclosed on {} (rcode {})", socket, response.rcode);
+                version = version.with_confidence(CONFIDENCE_MEDIUM);
+            }
+        }
+
+        if let Some(domain) = &self.axfr_domain {
+            let allowed = self.check_axfr(socket, domain, timeout).await;
+            extra.push(format!(
+                "axfr({})={}",
+                domain,
+                match allowed {
+                    Some(true) => "allowed",
+                    Some(false) => "refused",
+                    None => "no-response",
+                }
+            ));
+        }
+
+        if !extra.is_empty() {
+            version = version.with_extra_info(extra.join("; "));
+        }
+
+        Some(version)
+    }
+
+    /// Attempt an AXFR of `domain`; `Some(true)` means the transfer was allowed
+    async fn check_axfr(&self, socket: &SocketAddr, domain: &str, timeout: Duration) -> Option<bool> {
+        let query = Self::build_query(0x5054, domain, DNS_TYPE_AXFR, DNS_CLASS_IN);
+        let response = Self::query_tcp(socket, &query, timeout).await?;
+        let allowed = response.rcode == DNS_RCODE_NOERROR && response.answer_count > 0;
+        if allowed {
+            warn!("Zone transfer of {} allowed by {}", domain, socket);
+        }
+        Some(allowed)
+    }
+
+    async fn query_udp(socket: &SocketAddr, query: &[u8], timeout: Duration) -> Option<DnsResponse> {
+        let bind_addr: SocketAddr = if socket.is_ipv4() {
+            "0.0.0.0:0".parse().ok()?
+        } else {
+            "[::]:0".parse().ok()?
+        };
+        record_attempt();
+        let udp = UdpSocket::bind(bind_addr).await.ok()?;
+        udp.connect(socket).await.ok()?;
+        udp.send(query).await.ok()?;
+
+        let mut buffer = vec![0u8; DNS_BUFFER_SIZE];
+        match async_timeout(timeout, udp.recv(&mut buffer)).await {
+            Ok(Ok(n)) => {
+                trace!("Received UDP DNS response ({} bytes)", n);
+                Self::parse_response(&buffer[..n])
+            }
+            Ok(Err(e)) => {
+                trace!("UDP DNS query to {} failed: {}", socket, e);
+                None
+            }
+            Err(_) => {
+                trace!("UDP DNS query to {} timed out", socket);
+                None
+            }
+        }
+    }
+
+    async fn query_tcp(socket: &SocketAddr, query: &[u8], timeout: Duration) -> Option<DnsResponse> {
+        record_attempt();
+        let mut stream = match async_timeout(timeout, AsyncTcpStream::connect(socket)).await {
+            Ok(Ok(stream)) => stream,
+            _ => {
+                trace!("TCP DNS connect to {} failed", socket);
+                return None;
+            }
+        };
+
+        // DNS over TCP prefixes each message with its length
+        let mut framed = (query.len() as u16).to_be_bytes().to_vec();
+        framed.extend_from_slice(query);
+        stream.write_all(&framed).await.ok()?;
+
+        let read = async {
+            let mut len = [0u8; 2];
+            stream.read_exact(&mut len).await?;
+            let len = (u16::from_be_bytes(len) as usize).min(DNS_BUFFER_SIZE);
+            let mut buffer = vec![0u8; len];
+            stream.read_exact(&mut buffer).await?;
+            Ok::<_, std::io::Error>(buffer)
+        };
+
+        match async_timeout(timeout, read).await {
+            Ok(Ok(buffer)) => {
+                trace!("Received TCP DNS response ({} bytes)", buffer.len());
+                Self::parse_response(&buffer)
+            }
+            _ => {
+                trace!("TCP DNS query to {} failed or timed out", socket);
+                None
+            }
+        }
+    }
+
+    /// Build a single-question DNS query
+    pub fn build_query(id: u16, name: &str, qtype: u16, qclass: u16) -> Vec<u8> {
+        let mut packet = Vec::with_capacity(32 + name.len());
+        packet.extend_from_slice(&id.to_be_bytes());
+        packet.extend_from_slice(&[0x00, 0x00]); // Flags: standard query
+        packet.extend_from_slice(&[0x00, 0x01]); // QDCOUNT
+        packet.extend_from_slice(&[0x00, 0x00]); // ANCOUNT
+        packet.extend_from_slice(&[0x00, 0x00]); // NSCOUNT
+        packet.extend_from_slice(&[0x00, 0x00]); // ARCOUNT
+        for label in name.trim_end_matches('.').split('.').filter(|l| !l.is_empty()) {
+            let label = &label.as_bytes()[..label.len().min(63)];
+            packet.push(label.len() as u8);
+            packet.extend_from_slice(label);
+        }
+        packet.push(0x00);
+        packet.extend_from_slice(&qtype.to_be_bytes());
+        packet.extend_from_slice(&qclass.to_be_bytes());
+        packet
+    }
+
+    /// Parse a DNS response, extracting the rcode and the first TXT answer
+    pub fn parse_response(data: &[u8]) -> Option<DnsResponse> {
+        if data.len() < 12 || data[2] & 0x80 == 0 {
+            debug!("Not a DNS response ({} bytes)", data.len());
+            return None;
+        }
+
+        let rcode = data[3] & 0x0f;
+        let question_count = u16::from_be_bytes([data[4], data[5]]);
+        let answer_count = u16::from_be_bytes([data[6], data[7]]);
+        // Answers may be truncated (large AXFR replies); the header is enough then
+        let txt = Self::first_txt(data, question_count, answer_count);
+
+        Some(DnsResponse { rcode, answer_count, txt })
+    }
+
+    fn first_txt(data: &[u8], question_count: u16, answer_count: u16) -> Option<String> {
+        let mut offset = 12;
+        for _ in 0..question_count {
+            offset = Self::skip_name(data, offset)? + 4;
+        }
+
+        for _ in 0..answer_count {
+            offset = Self::skip_name(data, offset)?;
+            let header = data.get(offset..offset + 10)?;
+            let rtype = u16::from_be_bytes([header[0], header[1]]);
+            let rdlength = u16::from_be_bytes([header[8], header[9]]) as usize;
+            offset += 10;
+            let rdata = data.get(offset..offset + rdlength)?;
+            offset += rdlength;
+
+            if rtype == DNS_TYPE_TXT {
+                let (&len, rest) = rdata.split_first()?;
+                let text = &rest[..(len as usize).min(rest.len())];
+                return Some(String::from_utf8_lossy(text).trim().to_string());
+            }
+        }
+
+        None
+    }
+
+    /// Skip over a (possibly compressed) domain name, returning the next offset
+    fn skip_name(data: &[u8], mut offset: usize) -> Option<usize> {
+        loop {
+            let len = *data.get(offset)?;
+            match len {
+                0 => return Some(offset + 1),
+                l if l & 0xc0 == 0xc0 => return Some(offset + 2),
+                l => offset += 1 + l as usize,
+            }
+        }
+    }
+
+    /// Map a `version.bind` string to the server software
+    fn identify_server(txt: &str) -> &'static str {
+        let lower = txt.to_lowercase();
+        if lower.contains("unbound") {
+            "Unbound"
+        } else if lower.contains("dnsmasq") {
+            "dnsmasq"
+        } else if lower.contains("powerdns") {
+            "PowerDNS"
+        } else if lower.contains("bind") || lower.starts_with(|c: char| c.is_ascii_digit()) {
+            // BIND reports a bare version number by default
+            "BIND"
+        } else {
+            "unknown"
+        }
+    }
+}
+
+impl Default for DnsDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait::async_trait]
+impl Detector for DnsDetector {
+    fn name(&self) -> &str {
+        "DnsDetector"
+    }
+
+    fn can_detect(&self, port: Port) -> bool {
+        port == 53
+    }
+
+    fn detect_service(&self, _socket: &SocketAddr, _timeout: Duration) -> Option<ServiceVersion> {
+        None // Async-only detector, see detect_service_async
+    }
+
+    async fn detect_service_async(&self, socket: &SocketAddr, timeout: Duration) -> Option<ServiceVersion> {
+        self.detect_async(socket, timeout).await
+    }
+}
@@ -0,0 +1,371 @@
+//! IMAP and POP3 service detection use case: capability enumeration
+
+use std::net::SocketAddr;
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncWrite, AsyncWriteExt};
+use tokio::net::TcpStream as AsyncTcpStream;
+use tokio::time::timeout as async_timeout;
+use tracing::{debug, trace};
+
+use crate::application::TlsDetector;
+use crate::domain::{Port, ServiceVersion};
+use crate::constants::*;
+use crate::infrastructure::{read_capped, record_attempt};
+use crate::scanning::Detector;
+
+const IMAP_CAPABILITY_TAG: &str = "a001";
+const IMAP_CAPABILITY_COMMAND: &[u8] = b"a001 CAPABILITY\r\n";
+const IMAP_LOGOUT_COMMAND: &[u8] = b"a002 LOGOUT\r\n";
+const POP3_CAPA_COMMAND: &[u8] = b"CAPA\r\n";
+const POP3_QUIT_COMMAND: &[u8] = b"QUIT\r\n";
+
+/// Server software recognized in greetings and capability lists
+const MAIL_SERVER_SOFTWARE: [&str; 7] = ["Dovecot", "Courier", "Cyrus", "Zimbra", "Exchange", "UW IMAP", "Qpopper"];
+
+/// Features advertised in an IMAP `CAPABILITY` or POP3 `CAPA` response
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MailCapabilities {
+    /// Capabilities as advertised, e.g. `IDLE` or `SASL PLAIN LOGIN`
+    pub capabilities: Vec<String>,
+    /// `STARTTLS` (IMAP) or `STLS` (POP3) is offered
+    pub starttls: bool,
+    pub auth_mechanisms: Vec<String>,
+}
+
+impl MailCapabilities {
+    fn add_auth_mechanism(&mut self, mechanism: &str) {
+        let mechanism = mechanism.to_uppercase();
+        if !mechanism.is_empty() && !self.auth_mechanisms.contains(&mechanism) {
+            self.auth_mechanisms.push(mechanism);
+        }
+    }
+}
+
+/// IMAP detector: greeting plus `CAPABILITY`, over TLS on port 993
+pub struct ImapDetector;
+
+impl ImapDetector {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Async IMAP detection
+    pub async fn detect_async(socket: &SocketAddr, timeout: Duration) -> Option<ServiceVersion> {
+        debug!("Attempting IMAP detection on {}", socket);
+        if implicit_tls(socket.port()) {
+            let mut stream = TlsDetector::connect(socket, timeout, &[]).await?;
+            Self::detect_on_stream(&mut stream, timeout, true).await
+        } else {
+            let mut stream = connect(socket, timeout).await?;
+            Self::detect_on_stream(&mut stream, timeout, false).await
+        }
+    }
+
+    /// IMAP detection over a connected stream; `tls` names the service
+    /// `IMAPS` and leaves out the STARTTLS verdict.
+    ///
+    /// ```
+    /// use port_scanner::application::ImapDetector;
+    /// use std::time::Duration;
+    /// use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    /// use tokio::net::{TcpListener, TcpStream};
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// let listener = TcpListener::bind("127.0.0.1:0").await?;
+    /// let addr = listener.local_addr()?;
+    /// tokio::spawn(async move {
+    ///     let (mut socket, _) = listener.accept().await.unwrap();
+    ///     socket.write_all(b"* OK [CAPABILITY IMAP4rev1] Dovecot (Ubuntu) ready.\r\n").await.unwrap();
+    ///     let mut buf = [0u8; 64];
+    ///     let _ = socket.read(&mut buf).await;
+    ///     socket.write_all(b"* CAPABILITY IMAP4rev1 IDLE STARTTLS AUTH=PLAIN AUTH=LOGIN\r\n\
+    ///                        a001 OK Pre-login capabilities listed, post-login capabilities have more.\r\n").await.unwrap();
+    /// });
+    ///
+    /// let mut stream = TcpStream::connect(addr).await?;
+    /// let version = ImapDetector::detect_on_stream(&mut stream, Duration::from_secs(2), false).await.unwrap();
+    /// assert_eq!(version.service_name, "IMAP");
+    /// assert_eq!(version.extra_info.as_deref(),
+    ///     Some("software=Dovecot; capabilities=IMAP4rev1/IDLE/STARTTLS/AUTH=PLAIN/AUTH=LOGIN; starttls=yes; auth=PLAIN/LOGIN"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn detect_on_stream<S>(stream: &mut S, timeout: Duration, tls: bool) -> Option<ServiceVersion>
+    where
+        S: AsyncRead + AsyncWrite + Unpin,
+    {
+        let greeting = read_line(stream, timeout).await?;
+        let upper = greeting.to_uppercase();
+        if !["* OK", "* PREAUTH", "* BYE"].iter().any(|prefix| upper.starts_with(prefix)) {
+            trace!("Not an IMAP greeting: {:?}", greeting);
+            return None;
+        }
+
+        let name = if tls { "IMAPS" } else { "IMAP" };
+        let version = ServiceVersion::new(name, "tcp")
+            .with_banner(greeting.as_str())
+            .with_confidence(CONFIDENCE_HIGH);
+
+        if stream.write_all(IMAP_CAPABILITY_COMMAND).await.is_err() {
+            return Some(version);
+        }
+        let read = read_capped(stream, timeout, |data| Self::parse_capability(data).is_some()).await;
+        let Some(capabilities) = Self::parse_capability(&read.data) else {
+            trace!("No tagged CAPABILITY response");
+            return Some(version);
+        };
+        let _ = stream.write_all(IMAP_LOGOUT_COMMAND).await;
+
+        Some(version.with_extra_info(extra_info(&greeting, &capabilities, tls)))
+    }
+
+    /// Parse a `CAPABILITY` response; `None` until the tagged status line
+    /// has been received.
+    ///
+    /// Capabilities come from untagged `* CAPABILITY` lines; a server that
+    /// rejects the command yields an empty list.
+    pub fn parse_capability(data: &[u8]) -> Option<MailCapabilities> {
+        let text = String::from_utf8_lossy(data);
+        let mut capabilities = MailCapabilities::default();
+        for line in complete_lines(&text) {
+            let mut words = line.split_whitespace();
+            match (words.next(), words.next()) {
+                (Some("*"), Some(keyword)) if keyword.eq_ignore_ascii_case("CAPABILITY") => {
+                    for capability in words {
+                        let upper = capability.to_uppercase();
+                        if upper == "STARTTLS" {
+                            capabilities.starttls = true;
+                        } else if let Some(mechanism) = upper.strip_prefix("AUTH=") {
+                            capabilities.add_auth_mechanism(mechanism);
+                        }
+                        if !capabilities.capabilities.iter().any(|c| c.eq_ignore_ascii_case(capability)) {
+                            capabilities.capabilities.push(capability.to_string());
+                        }
+                    }
+                }
+                (Some(tag), status) if tag.eq_ignore_ascii_case(IMAP_CAPABILITY_TAG) => {
+                    if !status.is_some_and(|s| s.eq_ignore_ascii_case("OK")) {
+                        return Some(MailCapabilities::default());
+                    }
+                    return Some(capabilities);
+                }
+                _ => {}
+            }
+        }
+        None
+    }
+}
+
+impl Default for ImapDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait::async_trait]
+impl Detector for ImapDetector {
+    fn name(&self) -> &str {
+        "ImapDetector"
+    }
+
+    fn can_detect(&self, port: Port) -> bool {
+        matches!(port, 143 | 993)
+    }
+
+    fn detect_service(&self, _socket: &SocketAddr, _timeout: Duration) -> Option<ServiceVersion> {
+        None // Async-only detector, see detect_service_async
+    }
+
+    async fn detect_service_async(&self, socket: &SocketAddr, timeout: Duration) -> Option<ServiceVersion> {
+        Self::detect_async(socket, timeout).await
+    }
+}
+
+/// POP3 detector: greeting plus `CAPA`, over TLS on port 995
+pub struct Pop3Detector;
+
+impl Pop3Detector {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Async POP3 detection
+    pub async fn detect_async(socket: &SocketAddr, timeout: Duration) -> Option<ServiceVersion> {
+        debug!("Attempting POP3 detection on {}", socket);
+        if implicit_tls(socket.port()) {
+            let mut stream = TlsDetector::connect(socket, timeout, &[]).await?;
+            Self::detect_on_stream(&mut stream, timeout, true).await
+        } else {
+            let mut stream = connect(socket, timeout).await?;
+            Self::detect_on_stream(&mut stream, timeout, false).await
+        }
+    }
+
+    /// POP3 detection over a connected stream; `tls` names the service
+    /// `POP3S` and leaves out the STARTTLS verdict.
+    ///
+    /// ```
+    /// use port_scanner::application::Pop3Detector;
+    /// use std::time::Duration;
+    /// use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    /// use tokio::net::{TcpListener, TcpStream};
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// let listener = TcpListener::bind("127.0.0.1:0").await?;
+    /// let addr = listener.local_addr()?;
+    /// tokio::spawn(async move {
+    ///     let (mut socket, _) = listener.accept().await.unwrap();
+    ///     socket.write_all(b"+OK Hello there.\r\n").await.unwrap();
+    ///     let mut buf = [0u8; 64];
+    ///     let _ = socket.read(&mut buf).await;
+    ///     socket.write_all(b"+OK Here's what I can do:\r\nSTLS\r\nTOP\r\nUSER\r\n\
+    ///                        SASL LOGIN CRAM-MD5\r\nIMPLEMENTATION Courier Mail Server\r\n.\r\n").await.unwrap();
+    /// });
+    ///
+    /// let mut stream = TcpStream::connect(addr).await?;
+    /// let version = Pop3Detector::detect_on_stream(&mut stream, Duration::from_secs(2), false).await.unwrap();
+    /// assert_eq!(version.service_name, "POP3");
+    /// assert_eq!(version.extra_info.as_deref(),
+    ///     Some("software=Courier; capabilities=STLS/TOP/USER/SASL LOGIN CRAM-MD5/IMPLEMENTATION Courier Mail Server; \
+    ///           starttls=yes; auth=LOGIN/CRAM-MD5"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn detect_on_stream<S>(stream: &mut S, timeout: Duration, tls: bool) -> Option<ServiceVersion>
+    where
+        S: AsyncRead + AsyncWrite + Unpin,
+    {
+        let greeting = read_line(stream, timeout).await?;
+        if !greeting.starts_with("+OK") {
+            trace!("Not a POP3 greeting: {:?}", greeting);
+            return None;
+        }
+
+        let name = if tls { "POP3S" } else { "POP3" };
+        let version = ServiceVersion::new(name, "tcp")
+            .with_banner(greeting.as_str())
+            .with_confidence(CONFIDENCE_HIGH);
+
+        if stream.write_all(POP3_CAPA_COMMAND).await.is_err() {
+            return Some(version);
+        }
+        let read = read_capped(stream, timeout, |data| Self::parse_capa(data).is_some()).await;
+        let Some(capabilities) = Self::parse_capa(&read.data) else {
+            trace!("Incomplete CAPA response");
+            return Some(version);
+        };
+        let _ = stream.write_all(POP3_QUIT_COMMAND).await;
+
+        Some(version.with_extra_info(extra_info(&greeting, &capabilities, tls)))
+    }
+
+    /// Parse a `CAPA` response; `None` until the terminating `.` line (or an
+    /// `-ERR` status) has been received
+    pub fn parse_capa(data: &[u8]) -> Option<MailCapabilities> {
+        let text = String::from_utf8_lossy(data);
+        let mut lines = complete_lines(&text);
+        let status = lines.next()?;
+        if status.starts_with("-ERR") {
+            return Some(MailCapabilities::default());
+        }
+
+        let mut capabilities = MailCapabilities::default();
+        for line in lines {
+            if line == "." {
+                return Some(capabilities);
+            }
+            let mut words = line.split_whitespace();
+            match words.next().map(str::to_uppercase).as_deref() {
+                Some("STLS") => capabilities.starttls = true,
+                Some("SASL") => words.for_each(|mechanism| capabilities.add_auth_mechanism(mechanism)),
+                _ => {}
+            }
+            capabilities.capabilities.push(line.to_string());
+        }
+        None
+    }
+}
+
+impl Default for Pop3Detector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait::async_trait]
+impl Detector for Pop3Detector {
+    fn name(&self) -> &str {
+        "Pop3Detector"
+    }
+
+    fn can_detect(&self, port: Port) -> bool {
+        matches!(port, 110 | 995)
+    }
+
+    fn detect_service(&self, _socket: &SocketAddr, _timeout: Duration) -> Option<ServiceVersion> {
+        None // Async-only detector, see detect_service_async
+    }
+
+    async fn detect_service_async(&self, socket: &SocketAddr, timeout: Duration) -> Option<ServiceVersion> {
+        Self::detect_async(socket, timeout).await
+    }
+}
+
+/// IMAPS and POP3S start with a TLS handshake
+fn implicit_tls(port: Port) -> bool {
+    matches!(port, 993 | 995)
+}
+
+async fn connect(socket: &SocketAddr, timeout: Duration) -> Option<AsyncTcpStream> {
+    record_attempt();
+    match async_timeout(timeout, AsyncTcpStream::connect(socket)).await {
+        Ok(Ok(stream)) => Some(stream),
+        _ => {
+            trace!("Mail connect to {} failed", socket);
+            None
+        }
+    }
+}
+
+/// Read the first line, without its line ending
+async fn read_line<S: AsyncRead + Unpin>(stream: &mut S, timeout: Duration) -> Option<String> {
+    let read = read_capped(stream, timeout, |data| data.contains(&b'\n')).await;
+    let text = String::from_utf8_lossy(&read.data);
+    let line = text.lines().next()?.trim();
+    (!line.is_empty()).then(|| line.to_string())
+}
+
+/// Lines terminated by a newline, without their line endings; a trailing
+/// partial line is left out
+fn complete_lines(text: &str) -> impl Iterator<Item = &str> {
+    text.split_inclusive('\n')
+        .filter(|line| line.ends_with('\n'))
+        .map(|line| line.trim_end_matches(['\r', '\n']))
+}
+
+/// `software=Dovecot; capabilities=IMAP4rev1/IDLE; starttls=yes; auth=PLAIN`
+fn extra_info(greeting: &str, capabilities: &MailCapabilities, tls: bool) -> String {
+    let mut extra = Vec::new();
+    let texts = std::iter::once(greeting).chain(capabilities.capabilities.iter().map(String::as_str));
+    if let Some(software) = mail_server_software(texts) {
+        extra.push(format!("software={}", software));
+    }
+    extra.push(format!("capabilities={}", capabilities.capabilities.join("/")));
+    if !tls {
+        extra.push(format!("starttls={}", if capabilities.starttls { "yes" } else { "no" }));
+    }
+    if !capabilities.auth_mechanisms.is_empty() {
+        extra.push(format!("auth={}", capabilities.auth_mechanisms.join("/")));
+    }
+    extra.join("; ")
+}
+
+/// First known server product named in any of `texts`
+fn mail_server_software<'a>(mut texts: impl Iterator<Item = &'a str>) -> Option<&'static str> {
+    texts.find_map(|text| {
+        let text = text.to_lowercase();
+        MAIL_SERVER_SOFTWARE.iter().copied().find(|software| text.contains(&software.to_lowercase()))
+    })
+}
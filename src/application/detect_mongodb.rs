@@ -0,0 +1,397 @@
+//! MongoDB service detection use case
+
+use std::net::SocketAddr;
+use std::time::Duration;
+use tokio::net::TcpStream as AsyncTcpStream;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::time::timeout as async_timeout;
+use tracing::{debug, trace, warn};
+
+use crate::domain::{Port, ServiceVersion};
+use crate::constants::*;
+use crate::infrastructure::{read_limits, record_attempt};
+use crate::scanning::Detector;
+
+const OP_REPLY: i32 = 1;
+const OP_QUERY: i32 = 2004;
+const OP_MSG: i32 = 2013;
+const HEADER_LEN: usize = 16;
+/// OP_MSG is available from wire version 6 (MongoDB 3.6)
+const OP_MSG_MIN_WIRE_VERSION: i64 = 6;
+/// Error code returned when a command needs authentication
+const UNAUTHORIZED: i64 = 13;
+
+/// A decoded BSON value; only the types the detector inspects are kept
+#[derive(Debug, Clone, PartialEq)]
+pub enum BsonValue {
+    Double(f64),
+    String(String),
+    Document(Vec<(String, BsonValue)>),
+    Array(Vec<BsonValue>),
+    Bool(bool),
+    Int32(i32),
+    Int64(i64),
+    Null,
+    /// Any other type, skipped over
+    Other,
+}
+
+impl BsonValue {
+    fn as_i64(&self) -> Option<i64> {
+        match self {
+            BsonValue::Double(d) => Some(*d as i64),
+            BsonValue::Int32(i) => Some(*i as i64),
+            BsonValue::Int64(i) => Some(*i),
+            BsonValue::Bool(b) => Some(*b as i64),
+            _ => None,
+        }
+    }
+
+    fn as_str(&self) -> Option<&str> {
+        match self {
+            BsonValue::String(s) => Some(s),
+            _ => None,
+        }
+    }
+}
+
+/// Top-level fields of a command reply
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CommandReply(pub Vec<(String, BsonValue)>);
+
+impl CommandReply {
+    pub fn get(&self, key: &str) -> Option<&BsonValue> {
+        self.0.iter().find(|(k, _)| k == key).map(|(_, v)| v)
+    }
+
+    /// `ok: 1` (sent as a double, int or bool depending on the server)
+    pub fn is_ok(&self) -> bool {
+        self.get("ok").and_then(BsonValue::as_i64) == Some(1)
+    }
+
+    pub fn error_code(&self) -> Option<i64> {
+        self.get("code").and_then(BsonValue::as_i64)
+    }
+}
+
+/// MongoDB detector: handshake, server version and whether auth is enforced
+pub struct MongoDbDetector;
+
+impl MongoDbDetector {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Async MongoDB detection
+    ///
+    /// ```
+    /// use port_scanner::application::detect_mongodb::MongoDbDetector;
+    /// use std::net::SocketAddr;
+    /// use std::time::Duration;
+    /// use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    /// use tokio::net::TcpListener;
+    ///
+    /// fn key(element_type: u8, key: &str) -> Vec<u8> {
+    ///     [&[element_type], key.as_bytes(), &[0]].concat()
+    /// }
+    /// fn int(name: &str, value: i32) -> Vec<u8> {
+    ///     [key(0x10, name), value.to_le_bytes().to_vec()].concat()
+    /// }
+    /// fn string(name: &str, value: &str) -> Vec<u8> {
+    ///     [key(0x02, name), ((value.len() + 1) as i32).to_le_bytes().to_vec(), value.as_bytes().to_vec(), vec![0]].concat()
+    /// }
+    /// fn document(elements: &[Vec<u8>]) -> Vec<u8> {
+    ///     let body = elements.concat();
+    ///     [((body.len() + 5) as i32).to_le_bytes().to_vec(), body, vec![0]].concat()
+    /// }
+    /// /// Wire message: OP_REPLY (1) or OP_MSG (2013) carrying `document`
+    /// fn message(op_code: i32, document: Vec<u8>) -> Vec<u8> {
+    ///     let prefix = if op_code == 1 { vec![0; 20] } else { vec![0; 5] };
+    ///     let length = (16 + prefix.len() + document.len()) as i32;
+    ///     [length.to_le_bytes(), 0i32.to_le_bytes(), 0i32.to_le_bytes(), op_code.to_le_bytes()].concat()
+    ///         .into_iter().chain(prefix).chain(document).collect()
+    /// }
+    ///
+    /// /// Answer isMaster, buildInfo and then listDatabases with `list_databases`
+    /// async fn serve(list_databases: Vec<u8>) -> std::io::Result<SocketAddr> {
+    ///     let listener = TcpListener::bind("127.0.0.1:0").await?;
+    ///     let addr = listener.local_addr()?;
+    ///     let replies = [
+    ///         message(1, document(&[int("ismaster", 1), string("setName", "rs0"), int("maxWireVersion", 17), int("ok", 1)])),
+    ///         message(2013, document(&[string("version", "7.0.5"), int("ok", 1)])),
+    ///         message(2013, list_databases),
+    ///     ];
+    ///     tokio::spawn(async move {
+    ///         let (mut stream, _) = listener.accept().await?;
+    ///         for reply in replies {
+    ///             let mut length = [0u8; 4];
+    ///             stream.read_exact(&mut length).await?;
+    ///             stream.read_exact(&mut vec![0; i32::from_le_bytes(length) as usize - 4]).await?;
+    ///             stream.write_all(&reply).await?;
+    ///         }
+    ///         std::io::Result::Ok(())
+    ///     });
+    ///     Ok(addr)
+    /// }
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// let timeout = Duration::from_secs(1);
+    /// let databases = [key(0x04, "databases"), document(&[
+    ///     [key(0x03, "0"), document(&[string("name", "admin")])].concat(),
+    ///     [key(0x03, "1"), document(&[string("name", "shop")])].concat(),
+    /// ])].concat();
+    /// let open = serve(document(&[databases, int("ok", 1)])).await?;
+    /// let version = MongoDbDetector::detect_async(&open, timeout).await.unwrap();
+    /// assert_eq!(version.version.as_deref(), Some("7.0.5"));
+    /// assert!(version.unauthenticated);
+    /// assert_eq!(version.extra_info.as_deref(), Some("auth=none; databases=2; replset=rs0; wire=17"));
+    ///
+    /// let unauthorized = document(&[int("ok", 0), string("errmsg", "requires authentication"), int("code", 13)]);
+    /// let secured = serve(unauthorized).await?;
+    /// let version = MongoDbDetector::detect_async(&secured, timeout).await.unwrap();
+    /// assert_eq!(version.version.as_deref(), Some("7.0.5"));
+    /// assert!(!version.unauthenticated);
+    /// assert_eq!(version.extra_info.as_deref(), Some("auth=required; replset=rs0; wire=17"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn detect_async(socket: &SocketAddr, timeout: Duration) -> Option<ServiceVersion> {
+        debug!("Attempting MongoDB detection on {}", socket);
+
+        record_attempt();
+        let mut stream = match async_timeout(timeout, AsyncTcpStream::connect(socket)).await {
+            Ok(Ok(stream)) => stream,
+            _ => {
+                trace!("MongoDB connect to {} failed", socket);
+                return None;
+            }
+        };
+
+        // Legacy isMaster over OP_QUERY is answered by every server version
+        let hello = Self::command(&mut stream, timeout, 1, false, "isMaster").await?;
+        if !hello.is_ok() {
+            debug!("Not a MongoDB handshake reply on {}", socket);
+            return None;
+        }
+        let wire_version = hello.get("maxWireVersion").and_then(BsonValue::as_i64).unwrap_or(0);
+        let op_msg = wire_version >= OP_MSG_MIN_WIRE_VERSION;
+
+        let mut version = ServiceVersion::new("MongoDB", "tcp").with_confidence(CONFIDENCE_HIGH);
+        let mut extra = Vec::new();
+
+        // buildInfo is allowed before authentication
+        if let Some(build) = Self::command(&mut stream, timeout, 2, op_msg, "buildInfo").await {
+            if let Some(v) = build.get("version").and_then(BsonValue::as_str) {
+                version = version.with_version(v);
+            }
+        }
+
+        match Self::command(&mut stream, timeout, 3, op_msg, "listDatabases").await {
+            Some(reply) if reply.is_ok() => {
+                warn!("MongoDB on {} allows unauthenticated access", socket);
+                extra.push("auth=none".to_string());
+                if let Some(BsonValue::Array(databases)) = reply.get("databases") {
+                    extra.push(format!("databases={}", databases.len()));
+                }
+                version = version.with_unauthenticated(true);
+            }
+            Some(reply) if reply.error_code() == Some(UNAUTHORIZED) => extra.push("auth=required".to_string()),
+            other => trace!("listDatabases on {} inconclusive: {:?}", socket, other.and_then(|r| r.error_code())),
+        }
+
+        if let Some(set) = hello.get("setName").and_then(BsonValue::as_str) {
+            extra.push(format!("replset={}", set));
+        }
+        extra.push(format!("wire={}", wire_version));
+        Some(version.with_extra_info(extra.join("; ")))
+    }
+
+    /// Run `{ <name>: 1 }` against the admin database
+    async fn command(stream: &mut AsyncTcpStream, timeout: Duration, request_id: i32, op_msg: bool, name: &str) -> Option<CommandReply> {
+        let message = if op_msg {
+            Self::build_op_msg(request_id, name)
+        } else {
+            Self::build_op_query(request_id, name)
+        };
+        stream.write_all(&message).await.ok()?;
+
+        let mut length = [0u8; 4];
+        async_timeout(timeout, stream.read_exact(&mut length)).await.ok()?.ok()?;
+        let length = i32::from_le_bytes(length);
+        let max_length = MONGODB_MAX_MESSAGE_SIZE.min(read_limits().max_bytes);
+        if length < HEADER_LEN as i32 || length as usize > max_length {
+            debug!("MongoDB reply length {} outside 16..={}", length, max_length);
+            return None;
+        }
+        let mut message = vec![0u8; length as usize];
+        message[..4].copy_from_slice(&length.to_le_bytes());
+        async_timeout(timeout, stream.read_exact(&mut message[4..])).await.ok()?.ok()?;
+        Self::parse_reply(&message)
+    }
+
+    /// Legacy OP_QUERY on `admin.$cmd`
+    pub fn build_op_query(request_id: i32, command: &str) -> Vec<u8> {
+        let mut body = Vec::new();
+        body.extend_from_slice(&0i32.to_le_bytes()); // flags
+        body.extend_from_slice(b"admin.$cmd\0");
+        body.extend_from_slice(&0i32.to_le_bytes()); // numberToSkip
+        body.extend_from_slice(&(-1i32).to_le_bytes()); // numberToReturn
+        body.extend_from_slice(&encode_document(&[(command, BsonValue::Int32(1))]));
+        frame(request_id, OP_QUERY, body)
+    }
+
+    /// OP_MSG with a single body section
+    pub fn build_op_msg(request_id: i32, command: &str) -> Vec<u8> {
+        let mut body = Vec::new();
+        body.extend_from_slice(&0u32.to_le_bytes()); // flagBits
+        body.push(0); // section kind: body
+        body.extend_from_slice(&encode_document(&[
+            (command, BsonValue::Int32(1)),
+            ("$db", BsonValue::String("admin".to_string())),
+        ]));
+        frame(request_id, OP_MSG, body)
+    }
+
+    /// Extract the reply document from an OP_REPLY or OP_MSG message
+    pub fn parse_reply(message: &[u8]) -> Option<CommandReply> {
+        let op_code = i32::from_le_bytes(message.get(12..16)?.try_into().ok()?);
+        let document = match op_code {
+            // flags, cursorID, startingFrom, numberReturned
+            OP_REPLY => message.get(HEADER_LEN + 20..)?,
+            // flagBits, then a kind-0 section
+            OP_MSG if message.get(HEADER_LEN + 4) == Some(&0) => message.get(HEADER_LEN + 5..)?,
+            _ => return None,
+        };
+        let (fields, _) = decode_document(document)?;
+        Some(CommandReply(fields))
+    }
+}
+
+impl Default for MongoDbDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait::async_trait]
+impl Detector for MongoDbDetector {
+    fn name(&self) -> &str {
+        "MongoDbDetector"
+    }
+
+    fn can_detect(&self, port: Port) -> bool {
+        // mongod, shard and config server defaults
+        matches!(port, 27017..=27019)
+    }
+
+    fn detect_service(&self, _socket: &SocketAddr, _timeout: Duration) -> Option<ServiceVersion> {
+        None // Async-only detector, see detect_service_async
+    }
+
+    async fn detect_service_async(&self, socket: &SocketAddr, timeout: Duration) -> Option<ServiceVersion> {
+        Self::detect_async(socket, timeout).await
+    }
+}
+
+/// Prepend a wire protocol header
+fn frame(request_id: i32, op_code: i32, body: Vec<u8>) -> Vec<u8> {
+    let mut message = Vec::with_capacity(HEADER_LEN + body.len());
+    message.extend_from_slice(&((HEADER_LEN + body.len()) as i32).to_le_bytes());
+    message.extend_from_slice(&request_id.to_le_bytes());
+    message.extend_from_slice(&0i32.to_le_bytes()); // responseTo
+    message.extend_from_slice(&op_code.to_le_bytes());
+    message.extend_from_slice(&body);
+    message
+}
+
+/// Encode a flat document of int32 and string fields
+fn encode_document(fields: &[(&str, BsonValue)]) -> Vec<u8> {
+    let mut elements = Vec::new();
+    for (key, value) in fields {
+        match value {
+            BsonValue::Int32(i) => {
+                elements.push(0x10);
+                elements.extend_from_slice(key.as_bytes());
+                elements.push(0);
+                elements.extend_from_slice(&i.to_le_bytes());
+            }
+            BsonValue::String(s) => {
+                elements.push(0x02);
+                elements.extend_from_slice(key.as_bytes());
+                elements.push(0);
+                elements.extend_from_slice(&((s.len() + 1) as i32).to_le_bytes());
+                elements.extend_from_slice(s.as_bytes());
+                elements.push(0);
+            }
+            _ => {}
+        }
+    }
+    let mut document = Vec::with_capacity(elements.len() + 5);
+    document.extend_from_slice(&((elements.len() + 5) as i32).to_le_bytes());
+    document.extend_from_slice(&elements);
+    document.push(0);
+    document
+}
+
+/// Decode a BSON document, returning its fields and encoded length
+pub fn decode_document(data: &[u8]) -> Option<(Vec<(String, BsonValue)>, usize)> {
+    let length = i32::from_le_bytes(data.get(..4)?.try_into().ok()?);
+    let length = usize::try_from(length).ok().filter(|&l| l >= 5)?;
+    let document = data.get(..length)?;
+
+    let mut fields = Vec::new();
+    let mut pos = 4;
+    while document[pos] != 0 {
+        let element_type = document[pos];
+        let key_end = pos + 1 + document.get(pos + 1..)?.iter().position(|&b| b == 0)?;
+        let key = String::from_utf8_lossy(&document[pos + 1..key_end]).into_owned();
+        let (value, size) = decode_value(element_type, document.get(key_end + 1..)?)?;
+        fields.push((key, value));
+        pos = key_end + 1 + size;
+        if pos >= length {
+            return None;
+        }
+    }
+    Some((fields, length))
+}
+
+/// Decode one element value, returning it and the bytes consumed
+fn decode_value(element_type: u8, data: &[u8]) -> Option<(BsonValue, usize)> {
+    let fixed = |n: usize| data.get(..n);
+    Some(match element_type {
+        0x01 => (BsonValue::Double(f64::from_le_bytes(fixed(8)?.try_into().ok()?)), 8),
+        0x02 => {
+            let len = usize::try_from(i32::from_le_bytes(fixed(4)?.try_into().ok()?)).ok().filter(|&l| l >= 1)?;
+            let bytes = data.get(4..4 + len - 1)?;
+            (BsonValue::String(String::from_utf8_lossy(bytes).into_owned()), 4 + len)
+        }
+        0x03 => {
+            let (fields, len) = decode_document(data)?;
+            (BsonValue::Document(fields), len)
+        }
+        0x04 => {
+            let (fields, len) = decode_document(data)?;
+            (BsonValue::Array(fields.into_iter().map(|(_, v)| v).collect()), len)
+        }
+        0x05 => {
+            let len = usize::try_from(i32::from_le_bytes(fixed(4)?.try_into().ok()?)).ok()?;
+            (BsonValue::Other, 5 + len)
+        }
+        0x07 => (BsonValue::Other, 12),
+        0x08 => (BsonValue::Bool(*data.first()? != 0), 1),
+        0x09 | 0x11 => (BsonValue::Other, 8),
+        0x0A => (BsonValue::Null, 0),
+        0x06 | 0x7F | 0xFF => (BsonValue::Other, 0),
+        0x0B => {
+            // Regex: pattern and options cstrings
+            let first = data.iter().position(|&b| b == 0)?;
+            let second = data.get(first + 1..)?.iter().position(|&b| b == 0)?;
+            (BsonValue::Other, first + second + 2)
+        }
+        0x10 => (BsonValue::Int32(i32::from_le_bytes(fixed(4)?.try_into().ok()?)), 4),
+        0x12 => (BsonValue::Int64(i64::from_le_bytes(fixed(8)?.try_into().ok()?)), 8),
+        0x13 => (BsonValue::Other, 16),
+        _ => return None,
+    })
+}
@@ -1,146 +1,301 @@
-/// OS detection use case
+//! OS detection use case
 
 use std::net::{SocketAddr, TcpStream};
 use std::io::{Read, Write};
+use std::sync::Arc;
 use std::time::Duration;
+use rand::Rng;
 use tokio::net::TcpStream as AsyncTcpStream;
-use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::io::AsyncWriteExt;
 use tokio::time::timeout as async_timeout;
 use tracing::{debug, trace, warn};
 
-use crate::domain::{Port, OSInfo};
+use crate::domain::{Port, OSInfo, ServiceVersion};
 use crate::constants::*;
-use crate::scanning::Detector;
+use crate::infrastructure::{read_capped, record_attempt};
+use crate::scanning::{Detector, DetectionCache};
+
+const SMB2_MAGIC: &[u8] = b"\xfeSMB";
+const SMB2_HEADER_LEN: usize = 64;
+/// Every SMB2/3 dialect, so the server answers with the highest it supports
+pub const SMB2_DIALECTS: [u16; 5] = [0x0202, 0x0210, 0x0300, 0x0302, 0x0311];
+const SMB2_DIALECT_311: u16 = 0x0311;
+const SMB2_PREAUTH_INTEGRITY_CAPABILITIES: u16 = 0x0001;
+const SMB2_HASH_SHA512: u16 = 0x0001;
+const SMB2_PREAUTH_SALT_LEN: usize = 32;
+const SMB1_DIALECTS: [&str; 3] = ["NT LM 0.12", "SMB 2.002", "SMB 2.???"];
 
 /// SMB-based OS fingerprinter
-pub struct SMBFingerprinter;
+pub struct SMBFingerprinter {
+    cache: Option<Arc<DetectionCache>>,
+    dialects: Vec<u16>,
+}
 
 impl SMBFingerprinter {
     pub fn new() -> Self {
-        Self
+        Self { cache: None, dialects: SMB2_DIALECTS.to_vec() }
+    }
+
+    /// Offer only these SMB2 dialect codes (e.g. `[0x0202]` to check whether
+    /// a server still accepts 2.0.2). By default all of `SMB2_DIALECTS` are
+    /// offered and the negotiated dialect is the server's highest.
+    pub fn with_dialects(mut self, dialects: &[u16]) -> Self {
+        self.dialects = dialects.to_vec();
+        self
+    }
+
+    /// Share fingerprints through `cache` so service and OS detection
+    /// on the same port use a single SMB negotiation
+    pub fn with_cache(mut self, cache: Arc<DetectionCache>) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Fingerprint once per host:port when a cache is attached
+    async fn cached_fingerprint(&self, socket: &SocketAddr, timeout: Duration) -> Option<OSInfo> {
+        if let Some(os_info) = self.cache.as_ref().and_then(|c| c.os_info(socket)) {
+            trace!("Reusing cached SMB fingerprint for {}", socket);
+            return Some(os_info);
+        }
+        let os_info = Self::negotiate_async(socket, timeout, &self.dialects).await;
+        if !os_info.is_detected() {
+            return None;
+        }
+        if let Some(cache) = &self.cache {
+            cache.store_os_info(*socket, os_info.clone());
+        }
+        Some(os_info)
     }
 
     /// Async SMB OS fingerprinting (NEW - for async scanning)
+    ///
+    /// Tries an SMB2-only NEGOTIATE first and falls back to the SMBv1
+    /// multi-protocol NEGOTIATE on a fresh connection, so hosts with SMBv1
+    /// disabled as well as SMBv1-only hosts are fingerprinted.
+    ///
+    /// ```
+    /// use port_scanner::SMBFingerprinter;
+    /// use std::time::Duration;
+    /// use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    /// use tokio::net::TcpListener;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// // An SMBv1-only host: drops the SMB2 negotiate, answers the SMBv1 one
+    /// let listener = TcpListener::bind("127.0.0.1:0").await?;
+    /// let addr = listener.local_addr()?;
+    /// let server = tokio::spawn(async move {
+    ///     let mut signatures = Vec::new();
+    ///     for _ in 0..2 {
+    ///         let (mut stream, _) = listener.accept().await?;
+    ///         let mut header = [0u8; 4];
+    ///         stream.read_exact(&mut header).await?;
+    ///         let mut request = vec![0u8; u32::from_be_bytes([0, header[1], header[2], header[3]]) as usize];
+    ///         stream.read_exact(&mut request).await?;
+    ///         signatures.push(request[..4].to_vec());
+    ///         if request.starts_with(b"\xffSMB") {
+    ///             let mut reply = b"\xffSMB\x72".to_vec();
+    ///             reply.resize(37, 0);
+    ///             stream.write_all(&[0, 0, 0, reply.len() as u8]).await?;
+    ///             stream.write_all(&reply).await?;
+    ///         }
+    ///     }
+    ///     std::io::Result::Ok(signatures)
+    /// });
+    ///
+    /// let os_info = SMBFingerprinter::fingerprint_async(&addr, Duration::from_secs(1)).await;
+    /// assert_eq!(os_info.smb_version.as_deref(), Some("SMB 1.0"));
+    /// assert_eq!(server.await??, [b"\xfeSMB".to_vec(), b"\xffSMB".to_vec()]);
+    /// # Ok(())
+    /// # }
+    /// ```
     pub async fn fingerprint_async(socket: &SocketAddr, timeout: Duration) -> OSInfo {
+        Self::negotiate_async(socket, timeout, &SMB2_DIALECTS).await
+    }
+
+    async fn negotiate_async(socket: &SocketAddr, timeout: Duration, dialects: &[u16]) -> OSInfo {
         debug!("=== Starting Async SMB OS Fingerprinting ===");
         debug!("Target: {}", socket);
         debug!("Timeout: {:?}", timeout);
 
-        match async_timeout(timeout, AsyncTcpStream::connect(socket)).await {
-            Ok(Ok(mut stream)) => {
-                debug!("Successfully connected to SMB port (async)");
-
-                // Send SMB negotiate packet
-                let negotiate_packet = Self::build_smb_negotiate_packet();
-
-                debug!("Sending SMB negotiate packet ({} bytes)", negotiate_packet.len());
-                trace!("Packet data: {:02x?}", &negotiate_packet[..std::cmp::min(32, negotiate_packet.len())]);
-
-                if stream.write_all(&negotiate_packet).await.is_err() {
-                    warn!("Failed to send async SMB negotiate packet to {}", socket);
-                    return OSInfo::new();
-                }
-
-                // Read response with timeout
-                let mut buffer = vec![0u8; SMB_BUFFER_SIZE];
-                match async_timeout(
-                    Duration::from_millis(SMB_TIMEOUT_MS),
-                    stream.read(&mut buffer)
-                ).await {
-                    Ok(Ok(n)) if n > 0 => {
-                        debug!("Received async SMB response ({} bytes)", n);
-                        trace!("Response data: {:02x?}", &buffer[..std::cmp::min(64, n)]);
-                        let os_info = Self::parse_smb_response(&buffer[..n]);
-                        if os_info.is_detected() {
-                            debug!("Successfully detected OS: {}", os_info.summary());
-                        } else {
-                            debug!("Could not determine OS from async SMB response");
-                        }
-                        os_info
-                    }
-                    Ok(Ok(_)) => {
-                        warn!("Received empty async SMB response from {}", socket);
-                        OSInfo::new()
-                    }
-                    Ok(Err(e)) => {
-                        warn!("Failed to read async SMB response from {}: {}", socket, e);
-                        OSInfo::new()
-                    }
-                    Err(_) => {
-                        warn!("Timeout reading async SMB response from {}", socket);
-                        OSInfo::new()
-                    }
-                }
-            }
-            Ok(Err(e)) => {
-                warn!("Failed to connect for async SMB fingerprinting: {}", e);
-                OSInfo::new()
-            }
-            Err(_) => {
-                warn!("Timeout connecting for async SMB fingerprinting");
-                OSInfo::new()
+        for (label, packet) in Self::negotiate_ladder(dialects) {
+            debug!("Sending {} negotiate packet ({} bytes)", label, packet.len());
+            let Some(response) = Self::exchange_async(socket, &packet, timeout).await else {
+                continue;
+            };
+            let os_info = Self::parse_smb_response(&response);
+            if os_info.is_detected() {
+                debug!("Successfully detected OS: {}", os_info.summary());
+                return os_info;
             }
+            debug!("Could not determine OS from {} negotiate response", label);
         }
+        OSInfo::new()
     }
 
     /// Sync SMB OS fingerprinting (kept for compatibility)
     pub fn fingerprint(socket: &SocketAddr, timeout: Duration) -> OSInfo {
+        Self::negotiate(socket, timeout, &SMB2_DIALECTS)
+    }
+
+    fn negotiate(socket: &SocketAddr, timeout: Duration, dialects: &[u16]) -> OSInfo {
         debug!("=== Starting SMB OS Fingerprinting ===");
         debug!("Target: {}", socket);
         debug!("Timeout: {:?}", timeout);
-        
-        match TcpStream::connect_timeout(socket, timeout) {
-            Ok(mut stream) => {
-                debug!("Successfully connected to SMB port");
-                let _ = stream.set_read_timeout(Some(Duration::from_millis(SMB_TIMEOUT_MS)));
-                let _ = stream.set_write_timeout(Some(timeout));
-                
-                // Send SMB negotiate packet
-                let negotiate_packet = Self::build_smb_negotiate_packet();
-                
-                debug!("Sending SMB negotiate packet ({} bytes)", negotiate_packet.len());
-                trace!("Packet data: {:02x?}", &negotiate_packet[..std::cmp::min(32, negotiate_packet.len())]);
-                
-                if stream.write_all(&negotiate_packet).is_err() {
-                    warn!("Failed to send SMB negotiate packet to {}", socket);
-                    return OSInfo::new();
-                }
-                
-                // Read response
-                let mut buffer = vec![0u8; SMB_BUFFER_SIZE];
-                match stream.read(&mut buffer) {
-                    Ok(n) if n > 0 => {
-                        debug!("Received SMB response ({} bytes)", n);
-                        trace!("Response data: {:02x?}", &buffer[..std::cmp::min(64, n)]);
-                        let os_info = Self::parse_smb_response(&buffer[..n]);
-                        if os_info.is_detected() {
-                            debug!("Successfully detected OS: {}", os_info.summary());
-                        } else {
-                            debug!("Could not determine OS from SMB response");
-                        }
-                        os_info
-                    }
-                    Ok(_) => {
-                        warn!("Received empty SMB response from {}", socket);
-                        OSInfo::new()
-                    }
-                    Err(e) => {
-                        warn!("Failed to read SMB response from {}: {}", socket, e);
-                        OSInfo::new()
-                    }
-                }
+
+        for (label, packet) in Self::negotiate_ladder(dialects) {
+            debug!("Sending {} negotiate packet ({} bytes)", label, packet.len());
+            let Some(response) = Self::exchange(socket, &packet, timeout) else {
+                continue;
+            };
+            let os_info = Self::parse_smb_response(&response);
+            if os_info.is_detected() {
+                debug!("Successfully detected OS: {}", os_info.summary());
+                return os_info;
+            }
+            debug!("Could not determine OS from {} negotiate response", label);
+        }
+        OSInfo::new()
+    }
+
+    /// Negotiate packets in the order they are tried
+    fn negotiate_ladder(dialects: &[u16]) -> [(&'static str, Vec<u8>); 2] {
+        [
+            ("SMB2", Self::build_smb2_negotiate_packet(dialects)),
+            ("SMBv1", Self::build_smb_negotiate_packet()),
+        ]
+    }
+
+    /// Send one negotiate packet on a new connection and read the reply
+    async fn exchange_async(socket: &SocketAddr, packet: &[u8], timeout: Duration) -> Option<Vec<u8>> {
+        record_attempt();
+        let mut stream = match async_timeout(timeout, AsyncTcpStream::connect(socket)).await {
+            Ok(Ok(stream)) => stream,
+            Ok(Err(e)) => {
+                warn!("Failed to connect for async SMB fingerprinting: {}", e);
+                return None;
+            }
+            Err(_) => {
+                warn!("Timeout connecting for async SMB fingerprinting");
+                return None;
             }
+        };
+        trace!("Packet data: {:02x?}", &packet[..std::cmp::min(32, packet.len())]);
+        if stream.write_all(packet).await.is_err() {
+            warn!("Failed to send async SMB negotiate packet to {}", socket);
+            return None;
+        }
+
+        // The 4-byte NetBIOS session header carries the frame length
+        let read = read_capped(&mut stream, Duration::from_millis(SMB_TIMEOUT_MS), |data| {
+            data.len() >= 4 && data.len() >= 4 + u32::from_be_bytes([0, data[1], data[2], data[3]]) as usize
+        }).await;
+        if read.truncated {
+            warn!("SMB response from {} exceeded the read limit", socket);
+            return None;
+        }
+        if read.data.is_empty() {
+            // Hosts that refuse a dialect usually just close the connection
+            debug!("SMB negotiate got no response from {}", socket);
+            return None;
+        }
+        debug!("Received async SMB response ({} bytes)", read.data.len());
+        trace!("Response data: {:02x?}", &read.data[..std::cmp::min(64, read.data.len())]);
+        Some(read.data)
+    }
+
+    /// Blocking counterpart of `exchange_async`
+    fn exchange(socket: &SocketAddr, packet: &[u8], timeout: Duration) -> Option<Vec<u8>> {
+        record_attempt();
+        let mut stream = match TcpStream::connect_timeout(socket, timeout) {
+            Ok(stream) => stream,
             Err(e) => {
                 warn!("Failed to connect for SMB fingerprinting: {}", e);
-                OSInfo::new()
+                return None;
+            }
+        };
+        let _ = stream.set_read_timeout(Some(Duration::from_millis(SMB_TIMEOUT_MS)));
+        let _ = stream.set_write_timeout(Some(timeout));
+        trace!("Packet data: {:02x?}", &packet[..std::cmp::min(32, packet.len())]);
+        if stream.write_all(packet).is_err() {
+            warn!("Failed to send SMB negotiate packet to {}", socket);
+            return None;
+        }
+
+        let mut buffer = vec![0u8; SMB_BUFFER_SIZE];
+        match stream.read(&mut buffer) {
+            Ok(n) if n > 0 => {
+                debug!("Received SMB response ({} bytes)", n);
+                trace!("Response data: {:02x?}", &buffer[..std::cmp::min(64, n)]);
+                buffer.truncate(n);
+                Some(buffer)
             }
+            Ok(_) => {
+                debug!("SMB negotiate closed without a response by {}", socket);
+                None
+            }
+            Err(e) => {
+                debug!("Failed to read SMB response from {}: {}", socket, e);
+                None
+            }
+        }
+    }
+
+    /// SMB2 NEGOTIATE offering `dialects`; offering 3.1.1 adds the
+    /// mandatory pre-authentication integrity negotiate context
+    pub fn build_smb2_negotiate_packet(dialects: &[u16]) -> Vec<u8> {
+        let with_contexts = dialects.contains(&SMB2_DIALECT_311);
+        let mut smb = Vec::with_capacity(SMB2_HEADER_LEN + 36 + dialects.len() * 2);
+        smb.extend_from_slice(SMB2_MAGIC);
+        smb.extend_from_slice(&(SMB2_HEADER_LEN as u16).to_le_bytes()); // StructureSize
+        smb.extend_from_slice(&[0x00, 0x00]); // CreditCharge
+        smb.extend_from_slice(&[0x00; 4]); // Status
+        smb.extend_from_slice(&[0x00, 0x00]); // Command: NEGOTIATE
+        smb.extend_from_slice(&[0x01, 0x00]); // CreditRequest
+        smb.extend_from_slice(&[0x00; 4]); // Flags
+        smb.extend_from_slice(&[0x00; 4]); // NextCommand
+        smb.extend_from_slice(&[0x00; 8]); // MessageId
+        smb.extend_from_slice(&[0x00; 4]); // Reserved (ProcessId)
+        smb.extend_from_slice(&[0x00; 4]); // TreeId
+        smb.extend_from_slice(&[0x00; 8]); // SessionId
+        smb.extend_from_slice(&[0x00; 16]); // Signature
+
+        smb.extend_from_slice(&36u16.to_le_bytes()); // StructureSize
+        smb.extend_from_slice(&(dialects.len() as u16).to_le_bytes());
+        smb.extend_from_slice(&[0x01, 0x00]); // SecurityMode: signing enabled
+        smb.extend_from_slice(&[0x00, 0x00]); // Reserved
+        smb.extend_from_slice(&[0x00; 4]); // Capabilities
+        smb.extend_from_slice(&[0x00; 16]); // ClientGuid
+        // NegotiateContextOffset/Count/Reserved2, or ClientStartTime before 3.1.1
+        let context_offset_at = smb.len();
+        smb.extend_from_slice(&[0x00; 8]);
+        for dialect in dialects {
+            smb.extend_from_slice(&dialect.to_le_bytes());
+        }
+
+        if with_contexts {
+            // Negotiate contexts start 8-byte aligned
+            smb.resize(smb.len().next_multiple_of(8), 0x00);
+            let offset = smb.len() as u32;
+            smb[context_offset_at..context_offset_at + 4].copy_from_slice(&offset.to_le_bytes());
+            smb[context_offset_at + 4..context_offset_at + 6].copy_from_slice(&1u16.to_le_bytes());
+
+            let data_len = 6 + SMB2_PREAUTH_SALT_LEN;
+            smb.extend_from_slice(&SMB2_PREAUTH_INTEGRITY_CAPABILITIES.to_le_bytes()); // ContextType
+            smb.extend_from_slice(&(data_len as u16).to_le_bytes()); // DataLength
+            smb.extend_from_slice(&[0x00; 4]); // Reserved
+            smb.extend_from_slice(&1u16.to_le_bytes()); // HashAlgorithmCount
+            smb.extend_from_slice(&(SMB2_PREAUTH_SALT_LEN as u16).to_le_bytes()); // SaltLength
+            smb.extend_from_slice(&SMB2_HASH_SHA512.to_le_bytes());
+            let salt: [u8; SMB2_PREAUTH_SALT_LEN] = crate::infrastructure::with_rng(|rng| rng.gen());
+            smb.extend_from_slice(&salt);
         }
+        Self::netbios_frame(smb)
     }
 
+    /// SMBv1 NEGOTIATE listing NT LM 0.12 plus the SMB2 upgrade dialects
     fn build_smb_negotiate_packet() -> Vec<u8> {
-        // Simplified SMB negotiate packet (SMB1)
-        vec![
-            0x00, 0x00, 0x00, 0x85, // NetBIOS header
+        let mut smb = vec![
             0xff, 0x53, 0x4d, 0x42, // SMB header "\xffSMB"
             0x72, // Negotiate Protocol
             0x00, 0x00, 0x00, 0x00, // Status
@@ -154,12 +309,71 @@ impl SMBFingerprinter {
             0x00, 0x00, // UID
             0x00, 0x00, // MID
             0x00, // Word count
-            0x62, 0x00, // Byte count
-            0x02, // Dialects buffer
-        ]
+        ];
+        let mut dialects = Vec::new();
+        for name in SMB1_DIALECTS {
+            dialects.push(0x02); // Dialect buffer format
+            dialects.extend_from_slice(name.as_bytes());
+            dialects.push(0x00);
+        }
+        smb.extend_from_slice(&(dialects.len() as u16).to_le_bytes());
+        smb.extend_from_slice(&dialects);
+        Self::netbios_frame(smb)
+    }
+
+    fn netbios_frame(message: Vec<u8>) -> Vec<u8> {
+        let len = message.len() as u32;
+        let mut packet = vec![0x00, (len >> 16) as u8, (len >> 8) as u8, len as u8];
+        packet.extend_from_slice(&message);
+        packet
+    }
+
+    /// Version string and matching Windows release for an SMB2 dialect code
+    pub fn smb2_dialect_info(dialect: u16) -> Option<(&'static str, &'static str)> {
+        match dialect {
+            0x0202 => Some(("SMB 2.0.2", "Vista/Server 2008")),
+            0x0210 => Some(("SMB 2.1", "7/Server 2008 R2")),
+            0x0300 => Some(("SMB 3.0", "8/Server 2012")),
+            0x0302 => Some(("SMB 3.0.2", "8.1/Server 2012 R2")),
+            0x0311 => Some(("SMB 3.1.1", "10/11/Server 2016 or later")),
+            _ => None,
+        }
     }
 
-    fn parse_smb_response(data: &[u8]) -> OSInfo {
+    /// Parse an SMB2 or SMBv1 negotiate response (NetBIOS framed)
+    ///
+    /// ```
+    /// use port_scanner::SMBFingerprinter;
+    ///
+    /// let frame = |message: Vec<u8>| [vec![0, 0, 0, message.len() as u8], message].concat();
+    ///
+    /// // SMB2 header, then the NEGOTIATE response: StructureSize, SecurityMode, DialectRevision
+    /// let mut smb2 = b"\xfeSMB".to_vec();
+    /// smb2.resize(64, 0);
+    /// smb2.extend_from_slice(&[65, 0, 1, 0, 0x02, 0x03]);
+    /// let os_info = SMBFingerprinter::parse_smb_response(&frame(smb2));
+    /// assert_eq!(os_info.smb_version.as_deref(), Some("SMB 3.0.2"));
+    /// assert_eq!(os_info.os_version.as_deref(), Some("8.1/Server 2012 R2"));
+    ///
+    /// // The exact dialect the server picked is reported
+    /// for (dialect, version) in [(0x0210u16, "SMB 2.1"), (0x0311, "SMB 3.1.1")] {
+    ///     let mut smb2 = b"\xfeSMB".to_vec();
+    ///     smb2.resize(64, 0);
+    ///     smb2.extend_from_slice(&[65, 0, 1, 0]);
+    ///     smb2.extend_from_slice(&dialect.to_le_bytes());
+    ///     let os_info = SMBFingerprinter::parse_smb_response(&frame(smb2));
+    ///     assert_eq!(os_info.smb_version.as_deref(), Some(version));
+    /// }
+    ///
+    /// let mut smb1 = b"\xffSMB\x72".to_vec();
+    /// smb1.resize(37, 0);
+    /// let os_info = SMBFingerprinter::parse_smb_response(&frame(smb1));
+    /// assert_eq!(os_info.smb_version.as_deref(), Some("SMB 1.0"));
+    /// assert_eq!(os_info.os_name.as_deref(), Some("Windows/Samba"));
+    ///
+    /// assert!(!SMBFingerprinter::parse_smb_response(b"\0\0\0\x1cHTTP/1.1 400 Bad Request\r\n\r\n").is_detected());
+    /// ```
+    pub fn parse_smb_response(data: &[u8]) -> OSInfo {
         debug!("Parsing SMB response ({} bytes)", data.len());
         
         if data.len() < 32 {
@@ -170,19 +384,27 @@ impl SMBFingerprinter {
         let mut os_info = OSInfo::new();
         
         // Check for SMB2/3
-        if data.len() > 4 && &data[4..8] == b"\xfeSMB" {
+        if &data[4..8] == SMB2_MAGIC {
             debug!("Detected SMB2/3 protocol signature");
             trace!("SMB header: {:02x?}", &data[4..8]);
-            os_info = os_info.with_smb_version("SMB 2.x/3.x");
-            
-            // Modern Windows (7+)
-            debug!("Identified as modern Windows (7 or later)");
+
+            // NEGOTIATE response body: StructureSize, SecurityMode, DialectRevision
+            let body = 4 + SMB2_HEADER_LEN;
+            let dialect = data.get(body + 4..body + 6).map(|d| u16::from_le_bytes([d[0], d[1]]));
+            let (smb_version, windows_version) = dialect
+                .and_then(Self::smb2_dialect_info)
+                .unwrap_or(("SMB 2.x/3.x", "7 or later"));
+            debug!("Negotiated dialect {:04x?} ({})", dialect, smb_version);
+            if dialect.is_some_and(|d| d < 0x0300) {
+                debug!("Server's highest SMB dialect is below 3.0 (no SMB encryption)");
+            }
             os_info = os_info
+                .with_smb_version(smb_version)
                 .with_os_name("Windows")
-                .with_os_version("7 or later");
+                .with_os_version(windows_version);
         }
         // Check for SMB1
-        else if data.len() > 4 && &data[4..8] == b"\xffSMB" {
+        else if &data[4..8] == b"\xffSMB" {
             debug!("Detected SMB1 protocol signature");
             trace!("SMB header: {:02x?}", &data[4..8]);
             os_info = os_info.with_smb_version("SMB 1.0");
@@ -191,7 +413,7 @@ impl SMBFingerprinter {
             debug!("Identified as Windows/Samba (SMB1)");
             os_info = os_info.with_os_name("Windows/Samba");
         } else {
-            debug!("Unknown SMB response signature: {:02x?}", &data[4..std::cmp::min(8, data.len())]);
+            debug!("Unknown SMB response signature: {:02x?}", &data[4..8]);
         }
         
         os_info
@@ -204,6 +426,7 @@ impl Default for SMBFingerprinter {
     }
 }
 
+#[async_trait::async_trait]
 impl Detector for SMBFingerprinter {
     fn name(&self) -> &str {
         "SMBFingerprinter"
@@ -213,10 +436,21 @@ impl Detector for SMBFingerprinter {
         port == 445 // SMB port
     }
 
-    fn detect_service(&self, _socket: &SocketAddr, _timeout: Duration) -> Option<crate::domain::ServiceVersion> {
+    fn detect_service(&self, _socket: &SocketAddr, _timeout: Duration) -> Option<ServiceVersion> {
         None // This detector only does OS detection
     }
 
+    async fn detect_service_async(&self, socket: &SocketAddr, timeout: Duration) -> Option<ServiceVersion> {
+        // Without a cache the OS pass would negotiate again; leave the port to other detectors
+        self.cache.as_ref()?;
+        let os_info = self.cached_fingerprint(socket, timeout).await?;
+        let mut version = ServiceVersion::new("SMB", "tcp").with_confidence(CONFIDENCE_HIGH);
+        if let Some(smb_version) = os_info.smb_version {
+            version = version.with_version(smb_version);
+        }
+        Some(version)
+    }
+
     fn detect_os(&self, socket: &SocketAddr, timeout: Duration) -> Option<OSInfo> {
         let os_info = Self::fingerprint(socket, timeout);
         if os_info.is_detected() {
@@ -225,4 +459,8 @@ impl Detector for SMBFingerprinter {
             None
         }
     }
+
+    async fn detect_os_async(&self, socket: &SocketAddr, timeout: Duration) -> Option<OSInfo> {
+        self.cached_fingerprint(socket, timeout).await
+    }
 }
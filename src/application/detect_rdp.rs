@@ -0,0 +1,241 @@
+//! RDP service detection use case
+
+use std::net::SocketAddr;
+use std::time::Duration;
+use tokio::net::TcpStream as AsyncTcpStream;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::time::timeout as async_timeout;
+use tracing::{debug, trace};
+
+use crate::domain::{Port, ServiceVersion};
+use crate::constants::*;
+use crate::infrastructure::record_attempt;
+use crate::scanning::Detector;
+
+// Security protocols (MS-RDPBCGR 2.2.1.1.1)
+pub const PROTOCOL_RDP: u32 = 0x0000_0000;
+pub const PROTOCOL_SSL: u32 = 0x0000_0001;
+pub const PROTOCOL_HYBRID: u32 = 0x0000_0002;
+pub const PROTOCOL_RDSTLS: u32 = 0x0000_0004;
+pub const PROTOCOL_HYBRID_EX: u32 = 0x0000_0008;
+
+// RDP_NEG_RSP flags
+const EXTENDED_CLIENT_DATA_SUPPORTED: u8 = 0x01;
+const DYNVC_GFX_PROTOCOL_SUPPORTED: u8 = 0x02;
+const RESTRICTED_ADMIN_MODE_SUPPORTED: u8 = 0x08;
+
+// RDP_NEG_FAILURE codes
+const HYBRID_REQUIRED_BY_SERVER: u32 = 0x0000_0005;
+
+const TYPE_RDP_NEG_RSP: u8 = 0x02;
+const TYPE_RDP_NEG_FAILURE: u8 = 0x03;
+const X224_CONNECTION_CONFIRM: u8 = 0xd0;
+
+/// Outcome of an X.224 connection negotiation
+#[derive(Debug, Clone, PartialEq)]
+pub enum RdpNegotiation {
+    /// Server selected a security protocol
+    Selected { protocol: u32, flags: u8 },
+    /// Server rejected the requested protocols
+    Failure(u32),
+    /// Connection Confirm without negotiation data (Standard RDP only)
+    Legacy,
+}
+
+/// RDP detector: X.224 Connection Request with RDP negotiation
+pub struct RdpDetector;
+
+impl RdpDetector {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Async RDP detection
+    pub async fn detect_async(socket: &SocketAddr, timeout: Duration) -> Option<ServiceVersion> {
+        debug!("Attempting RDP detection on {}", socket);
+
+        let offered = PROTOCOL_SSL | PROTOCOL_HYBRID | PROTOCOL_HYBRID_EX;
+        let negotiation = Self::negotiate(socket, offered, timeout).await?;
+
+        let mut extra = Vec::new();
+        match &negotiation {
+            RdpNegotiation::Selected { protocol, flags } => {
+                extra.push(format!("protocol={}", Self::protocol_name(*protocol)));
+                if *protocol & (PROTOCOL_HYBRID | PROTOCOL_HYBRID_EX) != 0 {
+                    // NLA is in use; it is only required if TLS-only clients are turned away
+                    let tls_only = Self::negotiate(socket, PROTOCOL_SSL, timeout).await;
+                    let required = tls_only == Some(RdpNegotiation::Failure(HYBRID_REQUIRED_BY_SERVER));
+                    extra.push(format!("nla={}", if required { "required" } else { "supported" }));
+                } else {
+                    extra.push("nla=not-required".to_string());
+                }
+                if flags & RESTRICTED_ADMIN_MODE_SUPPORTED != 0 {
+                    extra.push("restricted-admin=supported".to_string());
+                }
+                if let Some(hint) = Self::version_hint(*flags) {
+                    extra.push(format!("hint={}", hint));
+                }
+            }
+            RdpNegotiation::Failure(code) => {
+                extra.push(format!("negotiation-failure={}", Self::failure_name(*code)));
+                if *code == HYBRID_REQUIRED_BY_SERVER {
+                    extra.push("nla=required".to_string());
+                }
+            }
+            RdpNegotiation::Legacy => {
+                extra.push(format!("protocol={}", Self::protocol_name(PROTOCOL_RDP)));
+                extra.push("nla=not-supported".to_string());
+                extra.push("hint=pre-Vista server".to_string());
+            }
+        }
+
+        Some(
+            ServiceVersion::new("RDP", "tcp")
+                .with_confidence(CONFIDENCE_HIGH)
+                .with_extra_info(extra.join("; ")),
+        )
+    }
+
+    /// Send one Connection Request offering `protocols` and parse the reply
+    async fn negotiate(socket: &SocketAddr, protocols: u32, timeout: Duration) -> Option<RdpNegotiation> {
+        record_attempt();
+        let mut stream = match async_timeout(timeout, AsyncTcpStream::connect(socket)).await {
+            Ok(Ok(stream)) => stream,
+            _ => {
+                trace!("RDP connect to {} failed", socket);
+                return None;
+            }
+        };
+
+        stream.write_all(&Self::build_connection_request(protocols)).await.ok()?;
+
+        let mut buffer = vec![0u8; RDP_BUFFER_SIZE];
+        match async_timeout(timeout, stream.read(&mut buffer)).await {
+            Ok(Ok(n)) if n > 0 => {
+                trace!("Received RDP response ({} bytes)", n);
+                Self::parse_connection_confirm(&buffer[..n])
+            }
+            _ => {
+                trace!("No RDP response from {}", socket);
+                None
+            }
+        }
+    }
+
+    /// Build a TPKT-framed X.224 Connection Request with an RDP_NEG_REQ
+    pub fn build_connection_request(protocols: u32) -> Vec<u8> {
+        let mut packet = vec![
+            0x03, 0x00, 0x00, 0x13, // TPKT: version 3, length 19
+            0x0e, 0xe0, 0x00, 0x00, 0x00, 0x00, 0x00, // X.224 CR TPDU
+            0x01, 0x00, 0x08, 0x00, // RDP_NEG_REQ: type, flags, length
+        ];
+        packet.extend_from_slice(&protocols.to_le_bytes());
+        packet
+    }
+
+    /// Parse a TPKT-framed X.224 Connection Confirm
+    ///
+    /// ```
+    /// use port_scanner::application::detect_rdp::{RdpDetector, RdpNegotiation, PROTOCOL_HYBRID};
+    ///
+    /// // Windows Server 2019 selecting CredSSP (NLA)
+    /// let confirm = [
+    ///     0x03, 0x00, 0x00, 0x13, 0x0e, 0xd0, 0x00, 0x00, 0x12, 0x34, 0x00,
+    ///     0x02, 0x1f, 0x08, 0x00, 0x02, 0x00, 0x00, 0x00,
+    /// ];
+    /// assert_eq!(
+    ///     RdpDetector::parse_connection_confirm(&confirm),
+    ///     Some(RdpNegotiation::Selected { protocol: PROTOCOL_HYBRID, flags: 0x1f }),
+    /// );
+    ///
+    /// // TLS-only client turned away: HYBRID_REQUIRED_BY_SERVER
+    /// let failure = [
+    ///     0x03, 0x00, 0x00, 0x13, 0x0e, 0xd0, 0x00, 0x00, 0x12, 0x34, 0x00,
+    ///     0x03, 0x00, 0x08, 0x00, 0x05, 0x00, 0x00, 0x00,
+    /// ];
+    /// assert_eq!(RdpDetector::parse_connection_confirm(&failure), Some(RdpNegotiation::Failure(5)));
+    ///
+    /// // No negotiation data from pre-Vista servers; anything else is not RDP
+    /// assert_eq!(RdpDetector::parse_connection_confirm(&confirm[..11]), Some(RdpNegotiation::Legacy));
+    /// assert_eq!(RdpDetector::parse_connection_confirm(b"HTTP/1.1 400 Bad Request\r\n"), None);
+    /// ```
+    pub fn parse_connection_confirm(data: &[u8]) -> Option<RdpNegotiation> {
+        // TPKT header (4) + X.224 CC TPDU (7)
+        if data.len() < 11 || data[0] != 0x03 || data[5] & 0xf0 != X224_CONNECTION_CONFIRM {
+            debug!("Not an X.224 Connection Confirm ({} bytes)", data.len());
+            return None;
+        }
+
+        let Some(neg) = data.get(11..19) else {
+            return Some(RdpNegotiation::Legacy);
+        };
+        let value = u32::from_le_bytes([neg[4], neg[5], neg[6], neg[7]]);
+        match neg[0] {
+            TYPE_RDP_NEG_RSP => Some(RdpNegotiation::Selected { protocol: value, flags: neg[1] }),
+            TYPE_RDP_NEG_FAILURE => Some(RdpNegotiation::Failure(value)),
+            other => {
+                debug!("Unknown RDP negotiation type 0x{:02x}", other);
+                None
+            }
+        }
+    }
+
+    fn protocol_name(protocol: u32) -> &'static str {
+        match protocol {
+            PROTOCOL_RDP => "Standard RDP",
+            PROTOCOL_SSL => "TLS",
+            PROTOCOL_HYBRID => "CredSSP",
+            PROTOCOL_RDSTLS => "RDSTLS",
+            PROTOCOL_HYBRID_EX => "CredSSP (early user auth)",
+            _ => "unknown",
+        }
+    }
+
+    fn failure_name(code: u32) -> &'static str {
+        match code {
+            0x01 => "SSL_REQUIRED_BY_SERVER",
+            0x02 => "SSL_NOT_ALLOWED_BY_SERVER",
+            0x03 => "SSL_CERT_NOT_ON_SERVER",
+            0x04 => "INCONSISTENT_FLAGS",
+            HYBRID_REQUIRED_BY_SERVER => "HYBRID_REQUIRED_BY_SERVER",
+            0x06 => "SSL_WITH_USER_AUTH_REQUIRED_BY_SERVER",
+            _ => "unknown",
+        }
+    }
+
+    /// Rough server generation from negotiation flags
+    fn version_hint(flags: u8) -> Option<&'static str> {
+        if flags & DYNVC_GFX_PROTOCOL_SUPPORTED != 0 {
+            Some("Windows 8.1/Server 2012 R2 or later")
+        } else if flags & EXTENDED_CLIENT_DATA_SUPPORTED != 0 {
+            Some("Windows 7/Server 2008 R2 or later")
+        } else {
+            None
+        }
+    }
+}
+
+impl Default for RdpDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait::async_trait]
+impl Detector for RdpDetector {
+    fn name(&self) -> &str {
+        "RdpDetector"
+    }
+
+    fn can_detect(&self, port: Port) -> bool {
+        port == 3389
+    }
+
+    fn detect_service(&self, _socket: &SocketAddr, _timeout: Duration) -> Option<ServiceVersion> {
+        None // Async-only detector, see detect_service_async
+    }
+
+    async fn detect_service_async(&self, socket: &SocketAddr, timeout: Duration) -> Option<ServiceVersion> {
+        Self::detect_async(socket, timeout).await
+    }
+}
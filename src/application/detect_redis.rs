@@ -0,0 +1,193 @@
+//! Redis service detection use case
+
+use std::net::SocketAddr;
+use std::time::Duration;
+use tokio::net::TcpStream as AsyncTcpStream;
+use tokio::io::AsyncWriteExt;
+use tokio::time::timeout as async_timeout;
+use tracing::{debug, trace, warn};
+
+use crate::domain::{Port, ServiceVersion};
+use crate::constants::*;
+use crate::infrastructure::{read_capped, record_attempt};
+use crate::scanning::Detector;
+
+const INFO_COMMAND: &[u8] = b"INFO server\r\n";
+
+/// Outcome of an unauthenticated `INFO` request
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RedisInfo {
+    /// The server answered with its INFO section: no authentication required
+    Open {
+        version: Option<String>,
+        mode: Option<String>,
+        os: Option<String>,
+    },
+    /// `-NOAUTH`: a password is required
+    AuthRequired,
+    /// `-DENIED`: protected mode refuses remote clients
+    ProtectedMode,
+    /// A reply that does not come from Redis
+    Unrecognized,
+}
+
+/// Redis detector: checks whether `INFO` is answered without `AUTH`
+pub struct RedisDetector;
+
+impl RedisDetector {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Async Redis detection
+    ///
+    /// ```
+    /// use port_scanner::application::detect_redis::RedisDetector;
+    /// use std::net::SocketAddr;
+    /// use std::time::Duration;
+    /// use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    /// use tokio::net::TcpListener;
+    ///
+    /// /// Answer one `INFO` request with `reply`
+    /// async fn serve(reply: Vec<u8>) -> std::io::Result<SocketAddr> {
+    ///     let listener = TcpListener::bind("127.0.0.1:0").await?;
+    ///     let addr = listener.local_addr()?;
+    ///     tokio::spawn(async move {
+    ///         let (mut stream, _) = listener.accept().await?;
+    ///         let mut request = [0u8; 64];
+    ///         stream.read(&mut request).await?;
+    ///         stream.write_all(&reply).await
+    ///     });
+    ///     Ok(addr)
+    /// }
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// let timeout = Duration::from_secs(1);
+    /// let info = "# Server\r\nredis_version:7.2.4\r\nredis_mode:standalone\r\nos:Linux 6.5.0 x86_64\r\n";
+    /// let open = serve(format!("${}\r\n{}\r\n", info.len(), info).into_bytes()).await?;
+    /// let version = RedisDetector::detect_async(&open, timeout).await.unwrap();
+    /// assert_eq!(version.version.as_deref(), Some("7.2.4"));
+    /// assert!(version.unauthenticated);
+    /// assert_eq!(version.extra_info.as_deref(), Some("auth=none; mode=standalone; os=Linux 6.5.0 x86_64"));
+    ///
+    /// let protected = serve(b"-NOAUTH Authentication required.\r\n".to_vec()).await?;
+    /// let version = RedisDetector::detect_async(&protected, timeout).await.unwrap();
+    /// assert_eq!((version.version, version.unauthenticated), (None, false));
+    /// assert_eq!(version.extra_info.as_deref(), Some("auth=required"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn detect_async(socket: &SocketAddr, timeout: Duration) -> Option<ServiceVersion> {
+        debug!("Attempting Redis detection on {}", socket);
+
+        record_attempt();
+        let mut stream = match async_timeout(timeout, AsyncTcpStream::connect(socket)).await {
+            Ok(Ok(stream)) => stream,
+            _ => {
+                trace!("Redis connect to {} failed", socket);
+                return None;
+            }
+        };
+
+        stream.write_all(INFO_COMMAND).await.ok()?;
+        let info = Self::read_reply(&mut stream, timeout).await?;
+
+        let version = ServiceVersion::new("Redis", "tcp").with_confidence(CONFIDENCE_HIGH);
+        let version = match info {
+            RedisInfo::Open { version: redis_version, mode, os } => {
+                warn!("Redis on {} allows unauthenticated access", socket);
+                let mut extra = vec!["auth=none".to_string()];
+                if let Some(mode) = mode {
+                    extra.push(format!("mode={}", mode));
+                }
+                if let Some(os) = os {
+                    extra.push(format!("os={}", os));
+                }
+                let version = version
+                    .with_unauthenticated(true)
+                    .with_extra_info(extra.join("; "));
+                match redis_version {
+                    Some(v) => version.with_version(v),
+                    None => version,
+                }
+            }
+            RedisInfo::AuthRequired => version.with_extra_info("auth=required"),
+            RedisInfo::ProtectedMode => version.with_extra_info("auth=protected-mode"),
+            RedisInfo::Unrecognized => {
+                debug!("Not a Redis reply on {}", socket);
+                return None;
+            }
+        };
+        Some(version)
+    }
+
+    /// Read until a complete reply has arrived
+    async fn read_reply(stream: &mut AsyncTcpStream, timeout: Duration) -> Option<RedisInfo> {
+        let read = read_capped(stream, timeout, |data| Self::parse_info_reply(data).is_some()).await;
+        Self::parse_info_reply(&read.data)
+    }
+
+    /// Parse a reply to `INFO`; `None` while more data is needed
+    pub fn parse_info_reply(data: &[u8]) -> Option<RedisInfo> {
+        let line_end = data.windows(2).position(|w| w == b"\r\n")?;
+        let line = String::from_utf8_lossy(&data[..line_end]);
+
+        if let Some(error) = line.strip_prefix('-') {
+            let code = error.split_whitespace().next().unwrap_or("");
+            return Some(match code {
+                "NOAUTH" => RedisInfo::AuthRequired,
+                "DENIED" => RedisInfo::ProtectedMode,
+                _ => RedisInfo::Unrecognized,
+            });
+        }
+
+        // Bulk string: `$<len>\r\n<body>\r\n`
+        let Some(Ok(len)) = line.strip_prefix('$').map(str::parse::<usize>) else {
+            return Some(RedisInfo::Unrecognized);
+        };
+        let body = data.get(line_end + 2..line_end + 2 + len)?;
+        let body = String::from_utf8_lossy(body);
+
+        let field = |name: &str| {
+            body.lines()
+                .find_map(|l| l.strip_prefix(name)?.strip_prefix(':'))
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty())
+        };
+        let version = field("redis_version");
+        if version.is_none() && !body.contains("# Server") {
+            return Some(RedisInfo::Unrecognized);
+        }
+        Some(RedisInfo::Open {
+            version,
+            mode: field("redis_mode"),
+            os: field("os"),
+        })
+    }
+}
+
+impl Default for RedisDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait::async_trait]
+impl Detector for RedisDetector {
+    fn name(&self) -> &str {
+        "RedisDetector"
+    }
+
+    fn can_detect(&self, port: Port) -> bool {
+        port == 6379
+    }
+
+    fn detect_service(&self, _socket: &SocketAddr, _timeout: Duration) -> Option<ServiceVersion> {
+        None // Async-only detector, see detect_service_async
+    }
+
+    async fn detect_service_async(&self, socket: &SocketAddr, timeout: Duration) -> Option<ServiceVersion> {
+        Self::detect_async(socket, timeout).await
+    }
+}
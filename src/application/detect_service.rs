@@ -1,52 +1,245 @@
-/// Service detection use case
+//! Service detection use case
 
 use std::net::{SocketAddr, TcpStream};
 use std::io::{Read, Write};
 use std::time::Duration;
 use tokio::net::TcpStream as AsyncTcpStream;
-use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::io::AsyncWriteExt;
 use tokio::time::timeout as async_timeout;
 use tracing::{debug, trace, warn};
 
-use crate::domain::{Port, ServiceVersion};
+use crate::domain::{BannerRuleSet, Port, ServiceVersion, ServiceRepository, StaticServiceRepository};
 use crate::constants::*;
-use crate::scanning::Detector;
+use crate::infrastructure::{read_capped, record_attempt};
+use crate::scanning::{Detector, ScanConfig};
 
 /// Version detector implementation
-pub struct VersionDetector;
+pub struct VersionDetector {
+    /// Pause between connecting and the first banner read
+    initial_delay: Option<Duration>,
+    /// How long to wait for a banner, and for the answer to a probe
+    banner_timeout: Duration,
+    /// Keep the hex of the bytes received in `banner_hex`
+    raw_banner: bool,
+}
 
 impl VersionDetector {
     pub fn new() -> Self {
-        Self
+        Self {
+            initial_delay: None,
+            banner_timeout: Duration::from_millis(BANNER_READ_TIMEOUT_MS),
+            raw_banner: false,
+        }
+    }
+
+    /// Detector with the banner delay and timeout from scan settings
+    pub fn for_config(config: &ScanConfig) -> Self {
+        Self::new()
+            .with_initial_delay(config.banner_initial_delay)
+            .with_banner_timeout(config.banner_timeout)
+            .with_raw_banner(config.include_raw_banner)
+    }
+
+    /// Wait `delay` after connecting before reading the banner, so services
+    /// that greet late are not probed (or given up on) too early.
+    ///
+    /// ```
+    /// use port_scanner::prelude::*;
+    /// use std::io::Write;
+    /// use std::net::TcpListener;
+    /// use std::time::Duration;
+    ///
+    /// // Greets 200ms after accept, but hangs up on clients that spoke first
+    /// let listener = TcpListener::bind("127.0.0.1:0")?;
+    /// let port = listener.local_addr()?.port();
+    /// std::thread::spawn(move || {
+    ///     for mut stream in listener.incoming().flatten() {
+    ///         std::thread::spawn(move || {
+    ///             std::thread::sleep(Duration::from_millis(200));
+    ///             stream.set_nonblocking(true).unwrap();
+    ///             let mut buf = [0u8; 64];
+    ///             if matches!(stream.peek(&mut buf), Err(e) if e.kind() == std::io::ErrorKind::WouldBlock) {
+    ///                 stream.set_nonblocking(false).unwrap();
+    ///                 let _ = stream.write_all(b"220 slowftpd 1.0 ready\r\n");
+    ///                 std::thread::sleep(Duration::from_secs(1));
+    ///             }
+    ///         });
+    ///     }
+    /// });
+    ///
+    /// let banner = |delay: Option<Duration>| -> anyhow::Result<Option<String>> {
+    ///     // Give up on a silent peer after 100ms and send a probe instead
+    ///     let config = ScanConfigBuilder::new()
+    ///         .target("127.0.0.1".parse()?)
+    ///         .custom_ports(vec![port])
+    ///         .detect_versions(true)
+    ///         .max_read_duration(Duration::from_millis(100))
+    ///         .banner_initial_delay(delay)
+    ///         .build()?;
+    ///     let results = PortScanner::new(config)?.scan_all_blocking(|_| {})?;
+    ///     Ok(results.results[0].service_version.as_ref().and_then(|v| v.banner.clone()))
+    /// };
+    ///
+    /// assert_eq!(banner(None)?, None);
+    /// let found = banner(Some(Duration::from_millis(300)))?;
+    /// assert!(found.is_some_and(|banner| banner.contains("slowftpd")));
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn with_initial_delay(mut self, delay: Option<Duration>) -> Self {
+        self.initial_delay = delay;
+        self
+    }
+
+    /// Wait up to `timeout` for a banner (and for the answer to a probe)
+    /// instead of `BANNER_READ_TIMEOUT_MS`; `None` keeps the default. The
+    /// connect itself still uses the scan timeout.
+    ///
+    /// ```
+    /// use port_scanner::application::VersionDetector;
+    /// use port_scanner::scanning::Detector;
+    /// use std::io::Write;
+    /// use std::net::TcpListener;
+    /// use std::time::Duration;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// // Greets 300ms after accept, but hangs up on clients that spoke first
+    /// let listener = TcpListener::bind("127.0.0.1:0")?;
+    /// let socket = listener.local_addr()?;
+    /// std::thread::spawn(move || {
+    ///     for mut stream in listener.incoming().flatten() {
+    ///         std::thread::spawn(move || {
+    ///             std::thread::sleep(Duration::from_millis(300));
+    ///             stream.set_nonblocking(true).unwrap();
+    ///             let mut buf = [0u8; 64];
+    ///             if matches!(stream.peek(&mut buf), Err(e) if e.kind() == std::io::ErrorKind::WouldBlock) {
+    ///                 stream.set_nonblocking(false).unwrap();
+    ///                 let _ = stream.write_all(b"220 slowftpd 1.0 ready\r\n");
+    ///                 std::thread::sleep(Duration::from_secs(1));
+    ///             }
+    ///         });
+    ///     }
+    /// });
+    /// let connect_timeout = Duration::from_millis(500);
+    ///
+    /// // The default banner timeout outlasts the pause
+    /// let found = VersionDetector::new().detect_service_async(&socket, connect_timeout).await;
+    /// assert!(found.and_then(|v| v.banner).is_some_and(|banner| banner.contains("slowftpd")));
+    ///
+    /// // A 100ms banner timeout gives up and probes before the greeting
+    /// let impatient = VersionDetector::new().with_banner_timeout(Some(Duration::from_millis(100)));
+    /// let found = impatient.detect_service_async(&socket, connect_timeout).await;
+    /// assert!(found.and_then(|v| v.banner).is_none());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_banner_timeout(mut self, timeout: Option<Duration>) -> Self {
+        if let Some(timeout) = timeout {
+            self.banner_timeout = timeout;
+        }
+        self
+    }
+
+    /// Record the first `RAW_BANNER_HEX_BYTES` bytes received as hex in
+    /// `banner_hex`, so binary greetings survive the lossy text banner.
+    ///
+    /// ```
+    /// use port_scanner::application::VersionDetector;
+    /// use port_scanner::scanning::Detector;
+    /// use std::io::Write;
+    /// use std::net::TcpListener;
+    /// use std::time::Duration;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// // A binary greeting that is not valid UTF-8
+    /// let greeting: &[u8] = &[0x4a, 0x00, 0x00, 0x00, 0x0a, 0xff, 0xfe, b'8', b'.', b'0', b'\n'];
+    /// let listener = TcpListener::bind("127.0.0.1:0")?;
+    /// let socket = listener.local_addr()?;
+    /// std::thread::spawn(move || {
+    ///     for mut stream in listener.incoming().flatten() {
+    ///         let _ = stream.write_all(greeting);
+    ///     }
+    /// });
+    ///
+    /// let detector = VersionDetector::new().with_raw_banner(true);
+    /// let version = detector.detect_service_async(&socket, Duration::from_secs(1)).await.unwrap();
+    ///
+    /// let json = serde_json::to_value(&version)?;
+    /// let hex = json["banner_hex"].as_str().unwrap();
+    /// let bytes: Vec<u8> = (0..hex.len()).step_by(2)
+    ///     .map(|i| u8::from_str_radix(&hex[i..i + 2], 16))
+    ///     .collect::<Result<_, _>>()?;
+    /// assert_eq!(bytes, greeting);
+    ///
+    /// // Left out unless asked for
+    /// let version = VersionDetector::new().detect_service_async(&socket, Duration::from_secs(1)).await.unwrap();
+    /// assert!(serde_json::to_value(&version)?.get("banner_hex").is_none());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_raw_banner(mut self, raw: bool) -> Self {
+        self.raw_banner = raw;
+        self
+    }
+
+    /// Async version detection with the banner settings of `config`,
+    /// connecting within `config.timeout`
+    ///
+    /// ```
+    /// use port_scanner::application::VersionDetector;
+    /// use port_scanner::prelude::*;
+    /// use std::io::Write;
+    /// use std::net::TcpListener;
+    /// use std::time::Duration;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// // Greets 300ms after accept, but hangs up on clients that spoke first
+    /// let listener = TcpListener::bind("127.0.0.1:0")?;
+    /// let socket = listener.local_addr()?;
+    /// std::thread::spawn(move || {
+    ///     for mut stream in listener.incoming().flatten() {
+    ///         std::thread::spawn(move || {
+    ///             std::thread::sleep(Duration::from_millis(300));
+    ///             stream.set_nonblocking(true).unwrap();
+    ///             let mut buf = [0u8; 64];
+    ///             if matches!(stream.peek(&mut buf), Err(e) if e.kind() == std::io::ErrorKind::WouldBlock) {
+    ///                 stream.set_nonblocking(false).unwrap();
+    ///                 let _ = stream.write_all(b"220 slowftpd 1.0 ready\r\n");
+    ///                 std::thread::sleep(Duration::from_secs(1));
+    ///             }
+    ///         });
+    ///     }
+    /// });
+    /// let config = |banner_timeout| ScanConfigBuilder::new()
+    ///     .target(socket.ip())
+    ///     .custom_ports(vec![socket.port()])
+    ///     .banner_timeout(banner_timeout)
+    ///     .build();
+    ///
+    /// let found = VersionDetector::detect_version_async(&socket, &config(None)?).await;
+    /// assert!(found.banner.is_some_and(|banner| banner.contains("slowftpd")));
+    ///
+    /// let impatient = config(Some(Duration::from_millis(100)))?;
+    /// assert!(VersionDetector::detect_version_async(&socket, &impatient).await.banner.is_none());
+    /// assert!(VersionDetector::detect_version(&socket, &impatient).banner.is_none());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn detect_version_async(socket: &SocketAddr, config: &ScanConfig) -> ServiceVersion {
+        Self::for_config(config).grab_async(socket, config.timeout).await
     }
 
-    /// Async version detection (NEW - for async scanning)
-    pub async fn detect_version_async(socket: &SocketAddr, timeout: Duration) -> ServiceVersion {
+    async fn grab_async(&self, socket: &SocketAddr, timeout: Duration) -> ServiceVersion {
         let port = socket.port();
 
         debug!("Attempting async version detection on port {}", port);
 
         // Try to connect and grab banner with async
+        record_attempt();
         match async_timeout(timeout, AsyncTcpStream::connect(socket)).await {
-            Ok(Ok(mut stream)) => {
-                let mut buffer = vec![0u8; BANNER_BUFFER_SIZE];
-
-                // Try reading banner first
-                match async_timeout(
-                    Duration::from_millis(BANNER_READ_TIMEOUT_MS), 
-                    stream.read(&mut buffer)
-                ).await {
-                    Ok(Ok(n)) if n > 0 => {
-                        let banner = String::from_utf8_lossy(&buffer[..n]).to_string();
-                        trace!("Received banner from port {}: {}", port, banner);
-                        return Self::parse_banner(port, &banner);
-                    }
-                    _ => {
-                        // Try sending a probe
-                        return Self::send_probe_and_read_async(port, &mut stream, &mut buffer).await;
-                    }
-                }
-            }
+            Ok(Ok(mut stream)) => self.grab_on_stream(port, &mut stream).await,
             Ok(Err(e)) => {
                 warn!("Failed to connect for async version detection on port {}: {}", port, e);
                 ServiceVersion::unknown()
@@ -58,16 +251,49 @@ impl VersionDetector {
         }
     }
 
-    /// Sync version detection (kept for compatibility)
-    pub fn detect_version(socket: &SocketAddr, timeout: Duration) -> ServiceVersion {
+    /// Async version detection over an already-connected stream, with the
+    /// default banner timeout (see `grab_on_stream` for scan settings).
+    ///
+    /// Lets the scan connection double as the banner-grab connection.
+    pub async fn detect_version_on_stream(port: Port, stream: &mut AsyncTcpStream) -> ServiceVersion {
+        Self::new().grab_on_stream(port, stream).await
+    }
+
+    /// `detect_version_on_stream` with this detector's banner delay and timeout
+    pub async fn grab_on_stream(&self, port: Port, stream: &mut AsyncTcpStream) -> ServiceVersion {
+        if let Some(delay) = self.initial_delay {
+            tokio::time::sleep(delay).await;
+        }
+        // Try reading banner first; one line is enough to identify the service
+        let read = read_capped(stream, self.banner_timeout, |data| data.contains(&b'\n')).await;
+        if read.data.is_empty() {
+            // Try sending a probe
+            return self.send_probe_and_read_async(port, stream).await;
+        }
+
+        trace!("Received banner from port {}: {}", port, String::from_utf8_lossy(&read.data));
+        self.identify(port, &read.data).with_banner_truncated(read.truncated)
+    }
+
+    /// Sync version detection with the banner settings of `config` (kept
+    /// for compatibility)
+    pub fn detect_version(socket: &SocketAddr, config: &ScanConfig) -> ServiceVersion {
+        Self::for_config(config).grab(socket, config.timeout)
+    }
+
+    fn grab(&self, socket: &SocketAddr, timeout: Duration) -> ServiceVersion {
         let port = socket.port();
         
         debug!("Attempting version detection on port {}", port);
         
         // Try to connect and grab banner
+        record_attempt();
         match TcpStream::connect_timeout(socket, timeout) {
             Ok(mut stream) => {
-                let _ = stream.set_read_timeout(Some(Duration::from_millis(BANNER_READ_TIMEOUT_MS)));
+                if let Some(delay) = self.initial_delay {
+                    std::thread::sleep(delay);
+                }
+                let _ = stream.set_read_timeout(Some(self.banner_timeout));
                 let _ = stream.set_write_timeout(Some(timeout));
                 
                 let mut buffer = vec![0u8; BANNER_BUFFER_SIZE];
@@ -75,13 +301,12 @@ impl VersionDetector {
                 // Try reading banner
                 match stream.read(&mut buffer) {
                     Ok(n) if n > 0 => {
-                        let banner = String::from_utf8_lossy(&buffer[..n]).to_string();
-                        trace!("Received banner from port {}: {}", port, banner);
-                        Self::parse_banner(port, &banner)
+                        trace!("Received banner from port {}: {}", port, String::from_utf8_lossy(&buffer[..n]));
+                        self.identify(port, &buffer[..n])
                     }
                     _ => {
                         // Try sending a probe
-                        Self::send_probe_and_read(port, &mut stream, &mut buffer)
+                        self.send_probe_and_read(port, &mut stream, &mut buffer)
                     }
                 }
             }
@@ -92,13 +317,13 @@ impl VersionDetector {
         }
     }
 
-    async fn send_probe_and_read_async(port: Port, stream: &mut AsyncTcpStream, buffer: &mut [u8]) -> ServiceVersion {
+    async fn send_probe_and_read_async(&self, port: Port, stream: &mut AsyncTcpStream) -> ServiceVersion {
         let probe: &[u8] = match port {
             80 | 8080 | 8443 => b"GET / HTTP/1.0\r\n\r\n",
             21 => b"",  // FTP sends banner automatically
             22 => b"",  // SSH sends banner automatically
             25 => b"EHLO scanner\r\n",
-            _ => b"",
+            _ => return self.send_generic_probes_async(port, stream).await,
         };
 
         if !probe.is_empty() {
@@ -106,20 +331,67 @@ impl VersionDetector {
             let _ = stream.write_all(probe).await;
         }
 
-        match async_timeout(
-            Duration::from_millis(BANNER_READ_TIMEOUT_MS),
-            stream.read(buffer)
-        ).await {
-            Ok(Ok(n)) if n > 0 => {
-                let banner = String::from_utf8_lossy(&buffer[..n]).to_string();
-                trace!("Received async response from port {}: {}", port, banner);
-                Self::parse_banner(port, &banner)
+        let read = read_capped(stream, self.banner_timeout, |data| response_complete(probe, data)).await;
+        if read.data.is_empty() {
+            return Self::guess_from_port(port).unwrap_or_else(ServiceVersion::unknown);
+        }
+
+        trace!("Received async response from port {}: {}", port, String::from_utf8_lossy(&read.data));
+        self.identify(port, &read.data).with_banner_truncated(read.truncated)
+    }
+
+    /// Escalate through `GENERIC_PROBES` until one gets an answer, giving
+    /// up when the peer closes or `GENERIC_PROBE_BUDGET` runs out.
+    ///
+    /// ```
+    /// use port_scanner::application::VersionDetector;
+    /// use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    /// use tokio::net::{TcpListener, TcpStream};
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// let listener = TcpListener::bind("127.0.0.1:0").await?;
+    /// let addr = listener.local_addr()?;
+    /// // Silent until it sees an HTTP request (the third probe after the initial wait)
+    /// tokio::spawn(async move {
+    ///     let (mut socket, _) = listener.accept().await.unwrap();
+    ///     let mut seen = Vec::new();
+    ///     let mut buf = [0u8; 256];
+    ///     while !seen.windows(4).any(|w| w == b"GET ") {
+    ///         let n = socket.read(&mut buf).await.unwrap();
+    ///         seen.extend_from_slice(&buf[..n]);
+    ///     }
+    ///     socket.write_all(b"HTTP/1.0 200 OK\r\nServer: quietd/2.1\r\n\r\n").await.unwrap();
+    /// });
+    ///
+    /// let mut stream = TcpStream::connect(addr).await?;
+    /// let version = VersionDetector::detect_version_on_stream(addr.port(), &mut stream).await;
+    /// assert_eq!(version.service_name, "HTTP");
+    /// assert_eq!(version.banner.as_deref(), Some("quietd/2.1"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    async fn send_generic_probes_async(&self, port: Port, stream: &mut AsyncTcpStream) -> ServiceVersion {
+        let deadline = tokio::time::Instant::now() + GENERIC_PROBE_BUDGET;
+        for &probe in GENERIC_PROBES {
+            let remaining = deadline.saturating_duration_since(tokio::time::Instant::now());
+            if remaining.is_zero() {
+                break;
+            }
+            trace!("Sending generic probe {:?} to port {}", String::from_utf8_lossy(probe), port);
+            if stream.write_all(probe).await.is_err() {
+                break;
+            }
+            let read = read_capped(stream, remaining.min(GENERIC_PROBE_READ_TIMEOUT), |data| response_complete(probe, data)).await;
+            if !read.data.is_empty() {
+                trace!("Received response to generic probe from port {}: {}", port, String::from_utf8_lossy(&read.data));
+                return self.identify(port, &read.data).with_banner_truncated(read.truncated);
             }
-            _ => ServiceVersion::unknown(),
         }
+        Self::guess_from_port(port).unwrap_or_else(ServiceVersion::unknown)
     }
 
-    fn send_probe_and_read(port: Port, stream: &mut TcpStream, buffer: &mut [u8]) -> ServiceVersion {
+    fn send_probe_and_read(&self, port: Port, stream: &mut TcpStream, buffer: &mut [u8]) -> ServiceVersion {
         let probe: &[u8] = match port {
             80 | 8080 | 8443 => b"GET / HTTP/1.0\r\n\r\n",
             21 => b"",  // FTP sends banner automatically
@@ -135,70 +407,181 @@ impl VersionDetector {
 
         match stream.read(buffer) {
             Ok(n) if n > 0 => {
-                let banner = String::from_utf8_lossy(&buffer[..n]).to_string();
-                trace!("Received response from port {}: {}", port, banner);
-                Self::parse_banner(port, &banner)
+                trace!("Received response from port {}: {}", port, String::from_utf8_lossy(&buffer[..n]));
+                self.identify(port, &buffer[..n])
             }
-            _ => ServiceVersion::unknown(),
+            _ => Self::guess_from_port(port).unwrap_or_else(ServiceVersion::unknown),
         }
     }
 
-    fn parse_banner(port: Port, banner: &str) -> ServiceVersion {
-        let banner_lower = banner.to_lowercase();
-        
-        // SSH detection
-        if banner_lower.starts_with("ssh-") {
-            let parts: Vec<&str> = banner.split_whitespace().collect();
-            if parts.len() >= 2 {
-                return ServiceVersion::new("SSH", "tcp")
-                    .with_version(parts[0].trim_start_matches("SSH-"))
-                    .with_banner(parts[1]);
+    /// Service behind the bytes a port sent, with their hex kept when
+    /// raw banners are requested
+    fn identify(&self, port: Port, data: &[u8]) -> ServiceVersion {
+        let response = String::from_utf8_lossy(data);
+        let mut version = Self::parse_banner(port, &response);
+        if version.service_name.starts_with("HTTP") {
+            if let Some(headers) = security_headers(&response) {
+                version.extra_info = Some(match version.extra_info {
+                    Some(extra) => format!("{}; {}", extra, headers),
+                    None => headers,
+                });
             }
-            return ServiceVersion::new("SSH", "tcp").with_banner(banner);
         }
-        
-        // HTTP detection
-        if banner_lower.contains("http/") {
-            if let Some(server_line) = banner.lines().find(|l| l.to_lowercase().starts_with("server:")) {
-                let server = server_line.trim_start_matches("Server:").trim().to_string();
-                return ServiceVersion::new("HTTP", "tcp").with_banner(server);
-            }
-            return ServiceVersion::new("HTTP", "tcp").with_banner("HTTP");
-        }
-        
-        // FTP detection
-        if banner_lower.contains("ftp") || banner.starts_with("220") {
-            return ServiceVersion::new("FTP", "tcp").with_banner(banner);
+        if self.raw_banner {
+            version.with_banner_bytes(data)
+        } else {
+            version
         }
-        
-        // SMTP detection
-        if banner.starts_with("220 ") && (banner_lower.contains("smtp") || banner_lower.contains("mail")) {
-            return ServiceVersion::new("SMTP", "tcp").with_banner(banner);
+    }
+
+    /// Identify the service with the highest-confidence banner rule, or
+    /// fall back to the port's well-known service
+    fn parse_banner(port: Port, banner: &str) -> ServiceVersion {
+        match BannerRuleSet::builtin().best_match(banner) {
+            Some(found) => ServiceVersion::from(found),
+            None => Self::guess_from_port(port).unwrap_or_else(ServiceVersion::unknown).with_banner(banner),
         }
-        
-        // Default
-        ServiceVersion::new("unknown", "tcp").with_banner(banner)
+    }
+
+    /// Guess the service from the port number alone (low confidence).
+    ///
+    /// A banner naming the product scores far higher than the port number:
+    ///
+    /// ```
+    /// use port_scanner::application::VersionDetector;
+    /// use port_scanner::constants::{CONFIDENCE_HIGH, CONFIDENCE_LOW};
+    /// use tokio::io::AsyncWriteExt;
+    /// use tokio::net::{TcpListener, TcpStream};
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// let listener = TcpListener::bind("127.0.0.1:0").await?;
+    /// let addr = listener.local_addr()?;
+    /// tokio::spawn(async move {
+    ///     let (mut socket, _) = listener.accept().await.unwrap();
+    ///     socket.write_all(b"HTTP/1.1 200 OK\r\nServer: nginx/1.24.0\r\n\r\n").await.unwrap();
+    /// });
+    ///
+    /// let mut stream = TcpStream::connect(addr).await?;
+    /// let version = VersionDetector::detect_version_on_stream(addr.port(), &mut stream).await;
+    /// assert_eq!(version.product.as_deref(), Some("nginx"));
+    /// assert_eq!(version.confidence, CONFIDENCE_HIGH);
+    ///
+    /// // A bare open port only has its number to go on
+    /// let guess = VersionDetector::guess_from_port(22).unwrap();
+    /// assert_eq!(guess.service_name, "SSH");
+    /// assert_eq!(guess.confidence, CONFIDENCE_LOW);
+    /// assert!(guess.port_based);
+    /// assert!(VersionDetector::guess_from_port(47_113).is_none());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn guess_from_port(port: Port) -> Option<ServiceVersion> {
+        StaticServiceRepository::new()
+            .get_service_name(port)
+            .map(|name| ServiceVersion::port_guess(name, "tcp"))
     }
 }
 
+/// HTTP headers end with a blank line; other services answer with a line
+fn response_complete(probe: &[u8], data: &[u8]) -> bool {
+    if probe.starts_with(b"GET ") {
+        data.windows(4).any(|w| w == b"\r\n\r\n")
+    } else {
+        data.contains(&b'\n')
+    }
+}
+
+/// Which of `HTTP_SECURITY_HEADERS` an HTTP response sends, as
+/// `missing-headers=...; present-headers=...`; `None` if `response` is not
+/// an HTTP response.
+///
+/// ```
+/// use port_scanner::application::VersionDetector;
+/// use tokio::io::{AsyncReadExt, AsyncWriteExt};
+/// use tokio::net::{TcpListener, TcpStream};
+///
+/// # #[tokio::main]
+/// # async fn main() -> anyhow::Result<()> {
+/// let listener = TcpListener::bind("127.0.0.1:0").await?;
+/// let addr = listener.local_addr()?;
+/// // No HSTS and no CSP
+/// tokio::spawn(async move {
+///     let (mut socket, _) = listener.accept().await.unwrap();
+///     let mut seen = Vec::new();
+///     let mut buf = [0u8; 256];
+///     while !seen.windows(4).any(|w| w == b"GET ") {
+///         let n = socket.read(&mut buf).await.unwrap();
+///         seen.extend_from_slice(&buf[..n]);
+///     }
+///     socket.write_all(b"HTTP/1.1 200 OK\r\nServer: nginx/1.24.0\r\n\
+///         x-frame-options: DENY\r\nX-Content-Type-Options: nosniff\r\n\r\n<html>").await.unwrap();
+/// });
+///
+/// let mut stream = TcpStream::connect(addr).await?;
+/// let version = VersionDetector::detect_version_on_stream(addr.port(), &mut stream).await;
+/// assert_eq!(version.service_name, "HTTP");
+/// assert_eq!(version.extra_info.as_deref(), Some(
+///     "missing-headers=Strict-Transport-Security,Content-Security-Policy; \
+///      present-headers=X-Frame-Options,X-Content-Type-Options"
+/// ));
+/// # Ok(())
+/// # }
+/// ```
+fn security_headers(response: &str) -> Option<String> {
+    let mut lines = response.lines();
+    if !lines.next()?.starts_with("HTTP/") {
+        return None;
+    }
+    let sent: Vec<&str> = lines
+        .take_while(|line| !line.trim().is_empty())
+        .filter_map(|line| line.split_once(':').map(|(name, _)| name.trim()))
+        .collect();
+    let (present, missing): (Vec<&str>, Vec<&str>) = HTTP_SECURITY_HEADERS.iter()
+        .partition(|header| sent.iter().any(|name| name.eq_ignore_ascii_case(header)));
+
+    let mut parts = Vec::new();
+    if !missing.is_empty() {
+        parts.push(format!("missing-headers={}", missing.join(",")));
+    }
+    if !present.is_empty() {
+        parts.push(format!("present-headers={}", present.join(",")));
+    }
+    Some(parts.join("; "))
+}
+
 impl Default for VersionDetector {
     fn default() -> Self {
         Self::new()
     }
 }
 
+#[async_trait::async_trait]
 impl Detector for VersionDetector {
     fn name(&self) -> &str {
         "VersionDetector"
     }
 
-    fn can_detect(&self, port: Port) -> bool {
-        // Can attempt detection on most common ports
-        matches!(port, 21 | 22 | 23 | 25 | 80 | 110 | 143 | 443 | 8080 | 8443)
+    fn can_detect(&self, _port: Port) -> bool {
+        // Generic banner grabbing works on any TCP port
+        true
+    }
+
+    fn grabs_banner(&self) -> bool {
+        true
     }
 
     fn detect_service(&self, socket: &SocketAddr, timeout: Duration) -> Option<ServiceVersion> {
-        let version = Self::detect_version(socket, timeout);
+        let version = self.grab(socket, timeout);
+        if version.service_name != "unknown" || version.banner.is_some() {
+            Some(version)
+        } else {
+            None
+        }
+    }
+
+    async fn detect_service_async(&self, socket: &SocketAddr, timeout: Duration) -> Option<ServiceVersion> {
+        let version = self.grab_async(socket, timeout).await;
         if version.service_name != "unknown" || version.banner.is_some() {
             Some(version)
         } else {
@@ -0,0 +1,204 @@
+//! SMTP service detection use case
+
+use std::net::SocketAddr;
+use std::time::Duration;
+use tokio::net::TcpStream as AsyncTcpStream;
+use tokio::io::AsyncWriteExt;
+use tokio::time::timeout as async_timeout;
+use tracing::{debug, trace};
+
+use crate::domain::{Port, ServiceVersion};
+use crate::constants::*;
+use crate::infrastructure::{read_capped, record_attempt};
+use crate::scanning::Detector;
+
+const EHLO_COMMAND: &[u8] = b"EHLO scanner\r\n";
+const QUIT_COMMAND: &[u8] = b"QUIT\r\n";
+
+/// A complete (possibly multi-line) SMTP reply
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SmtpReply {
+    pub code: u16,
+    /// Text of each line with the code and separator removed
+    pub lines: Vec<String>,
+}
+
+/// Extensions advertised in an EHLO response
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SmtpCapabilities {
+    /// Keyword plus parameters, e.g. `SIZE 35882577`
+    pub extensions: Vec<String>,
+    pub starttls: bool,
+    pub auth_mechanisms: Vec<String>,
+}
+
+/// SMTP detector: greeting plus EHLO capability enumeration
+pub struct SmtpDetector;
+
+impl SmtpDetector {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Async SMTP detection
+    ///
+    /// ```
+    /// use port_scanner::application::SmtpDetector;
+    /// use std::time::Duration;
+    /// use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+    /// use tokio::net::TcpListener;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// let listener = TcpListener::bind("127.0.0.1:0").await?;
+    /// let addr = listener.local_addr()?;
+    /// tokio::spawn(async move {
+    ///     let (stream, _) = listener.accept().await.unwrap();
+    ///     let mut stream = BufReader::new(stream);
+    ///     stream.write_all(b"220 mail.example.com ESMTP Postfix (Ubuntu)\r\n").await.unwrap();
+    ///     let mut ehlo = String::new();
+    ///     stream.read_line(&mut ehlo).await.unwrap();
+    ///     assert!(ehlo.starts_with("EHLO "));
+    ///     stream.write_all(concat!(
+    ///         "250-mail.example.com\r\n",
+    ///         "250-PIPELINING\r\n",
+    ///         "250-SIZE 10240000\r\n",
+    ///         "250-STARTTLS\r\n",
+    ///         "250-AUTH PLAIN LOGIN\r\n",
+    ///         "250 8BITMIME\r\n",
+    ///     ).as_bytes()).await.unwrap();
+    /// });
+    ///
+    /// let smtp = SmtpDetector::detect_async(&addr, Duration::from_secs(2)).await.unwrap();
+    /// assert_eq!(smtp.service_name, "SMTP");
+    /// assert_eq!(smtp.banner.as_deref(), Some("mail.example.com ESMTP Postfix (Ubuntu)"));
+    /// assert_eq!(
+    ///     smtp.extra_info.as_deref(),
+    ///     Some("capabilities=PIPELINING/SIZE 10240000/STARTTLS/AUTH PLAIN LOGIN/8BITMIME; starttls=yes; auth=PLAIN/LOGIN"),
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn detect_async(socket: &SocketAddr, timeout: Duration) -> Option<ServiceVersion> {
+        debug!("Attempting SMTP detection on {}", socket);
+
+        record_attempt();
+        let mut stream = match async_timeout(timeout, AsyncTcpStream::connect(socket)).await {
+            Ok(Ok(stream)) => stream,
+            _ => {
+                trace!("SMTP connect to {} failed", socket);
+                return None;
+            }
+        };
+
+        let greeting = Self::read_reply(&mut stream, timeout).await?;
+        if greeting.code != 220 {
+            debug!("Not an SMTP greeting on {} (code {})", socket, greeting.code);
+            return None;
+        }
+
+        let mut version = ServiceVersion::new("SMTP", "tcp")
+            .with_banner(greeting.lines.join(" "))
+            .with_confidence(CONFIDENCE_HIGH);
+
+        if stream.write_all(EHLO_COMMAND).await.is_err() {
+            return Some(version);
+        }
+        let capabilities = match Self::read_reply(&mut stream, timeout).await {
+            Some(reply) if reply.code == 250 => Self::parse_ehlo(&reply),
+            other => {
+                trace!("EHLO rejected by {}: {:?}", socket, other.map(|r| r.code));
+                return Some(version);
+            }
+        };
+        let _ = stream.write_all(QUIT_COMMAND).await;
+
+        let mut extra = vec![format!("capabilities={}", capabilities.extensions.join("/"))];
+        extra.push(format!("starttls={}", if capabilities.starttls { "yes" } else { "no" }));
+        if !capabilities.auth_mechanisms.is_empty() {
+            extra.push(format!("auth={}", capabilities.auth_mechanisms.join("/")));
+        }
+        version = version.with_extra_info(extra.join("; "));
+        Some(version)
+    }
+
+    /// Read until a complete reply has arrived
+    async fn read_reply(stream: &mut AsyncTcpStream, timeout: Duration) -> Option<SmtpReply> {
+        let read = read_capped(stream, timeout, |data| Self::parse_reply(data).is_some()).await;
+        Self::parse_reply(&read.data)
+    }
+
+    /// Parse a reply; `None` until its final `NNN ` line has been received.
+    ///
+    /// Continuation lines use `NNN-text`, the last line `NNN text`.
+    pub fn parse_reply(data: &[u8]) -> Option<SmtpReply> {
+        let text = String::from_utf8_lossy(data);
+        let mut lines = Vec::new();
+        for line in text.split_inclusive('\n') {
+            if !line.ends_with('\n') {
+                return None; // Partial line, wait for more
+            }
+            let line = line.trim_end_matches(['\r', '\n']);
+            let code: u16 = line.get(..3)?.parse().ok()?;
+            let separator = line.as_bytes().get(3).copied();
+            lines.push(line.get(4..).unwrap_or("").trim().to_string());
+            if separator != Some(b'-') {
+                return Some(SmtpReply { code, lines });
+            }
+        }
+        None
+    }
+
+    /// Extract extensions, STARTTLS and AUTH mechanisms from an EHLO reply
+    pub fn parse_ehlo(reply: &SmtpReply) -> SmtpCapabilities {
+        let mut capabilities = SmtpCapabilities::default();
+        // The first line is the server's domain and greeting
+        for line in reply.lines.iter().skip(1).filter(|l| !l.is_empty()) {
+            // Older servers also advertise `AUTH=LOGIN PLAIN`
+            let normalized = line.replacen('=', " ", 1);
+            let mut words = normalized.split_whitespace();
+            let keyword = words.next().unwrap_or_default().to_uppercase();
+            match keyword.as_str() {
+                "STARTTLS" => capabilities.starttls = true,
+                "AUTH" => {
+                    for mechanism in words.map(str::to_uppercase) {
+                        if !capabilities.auth_mechanisms.contains(&mechanism) {
+                            capabilities.auth_mechanisms.push(mechanism);
+                        }
+                    }
+                }
+                _ => {}
+            }
+            if !capabilities.extensions.iter().any(|e| e.eq_ignore_ascii_case(line)) {
+                capabilities.extensions.push(line.clone());
+            }
+        }
+        capabilities
+    }
+}
+
+impl Default for SmtpDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait::async_trait]
+impl Detector for SmtpDetector {
+    fn name(&self) -> &str {
+        "SmtpDetector"
+    }
+
+    fn can_detect(&self, port: Port) -> bool {
+        // SMTP and submission; 465 starts with TLS and is left to the banner grabber
+        matches!(port, 25 | 587)
+    }
+
+    fn detect_service(&self, _socket: &SocketAddr, _timeout: Duration) -> Option<ServiceVersion> {
+        None // Async-only detector, see detect_service_async
+    }
+
+    async fn detect_service_async(&self, socket: &SocketAddr, timeout: Duration) -> Option<ServiceVersion> {
+        Self::detect_async(socket, timeout).await
+    }
+}
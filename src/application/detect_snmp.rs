@@ -0,0 +1,226 @@
+//! SNMP service detection use case
+
+use std::net::SocketAddr;
+use std::time::Duration;
+use tokio::net::UdpSocket;
+use tokio::time::timeout as async_timeout;
+use tracing::{debug, trace, warn};
+
+use crate::domain::{Port, ServiceVersion};
+use crate::constants::*;
+use crate::infrastructure::record_attempt;
+use crate::scanning::Detector;
+
+const BER_INTEGER: u8 = 0x02;
+const BER_OCTET_STRING: u8 = 0x04;
+const BER_NULL: u8 = 0x05;
+const BER_OID: u8 = 0x06;
+const BER_SEQUENCE: u8 = 0x30;
+const SNMP_GET_REQUEST: u8 = 0xa0;
+const SNMP_GET_RESPONSE: u8 = 0xa2;
+const SNMP_VERSION_1: u8 = 0x00;
+
+/// OID 1.3.6.1.2.1.1.1.0 (sysDescr.0), BER encoded
+const SYS_DESCR_OID: &[u8] = &[0x2b, 0x06, 0x01, 0x02, 0x01, 0x01, 0x01, 0x00];
+
+/// Result of a parsed SNMP GetResponse
+#[derive(Debug, Clone, PartialEq)]
+pub struct SnmpResponse {
+    pub community: String,
+    pub error_status: u8,
+    pub sys_descr: Option<String>,
+}
+
+/// SNMP detector: `sysDescr.0` GetRequest over UDP with a list of communities
+pub struct SnmpDetector {
+    communities: Vec<String>,
+}
+
+impl SnmpDetector {
+    pub fn new() -> Self {
+        Self {
+            communities: vec![DEFAULT_SNMP_COMMUNITY.to_string()],
+        }
+    }
+
+    /// Try these community strings in order instead of the default
+    pub fn with_communities(mut self, communities: Vec<String>) -> Self {
+        if !communities.is_empty() {
+            self.communities = communities;
+        }
+        self
+    }
+
+    /// Async SNMP detection; `None` means no community got an answer
+    pub async fn detect_async(&self, socket: &SocketAddr, timeout: Duration) -> Option<ServiceVersion> {
+        debug!("Attempting SNMP detection on {}", socket);
+
+        for (index, community) in self.communities.iter().enumerate() {
+            let request = Self::build_get_request(index as u8 + 1, community);
+            // Agents silently drop requests with a wrong community
+            let Some(response) = Self::query(socket, &request, timeout).await else {
+                trace!("No SNMP response from {} for community '{}'", socket, community);
+                continue;
+            };
+
+            let mut extra = vec![format!("community={}", response.community)];
+            if response.community == DEFAULT_SNMP_COMMUNITY {
+                warn!("SNMP on {} accepts the default '{}' community", socket, DEFAULT_SNMP_COMMUNITY);
+                extra.push("default-community=open".to_string());
+            }
+
+            let mut version = ServiceVersion::new("SNMP", "udp")
+                .with_version("v1")
+                .with_confidence(CONFIDENCE_HIGH);
+            if let Some(descr) = response.sys_descr {
+                version = version.with_banner(descr);
+            }
+            return Some(version.with_extra_info(extra.join("; ")));
+        }
+
+        None
+    }
+
+    async fn query(socket: &SocketAddr, request: &[u8], timeout: Duration) -> Option<SnmpResponse> {
+        let bind_addr: SocketAddr = if socket.is_ipv4() {
+            "0.0.0.0:0".parse().ok()?
+        } else {
+            "[::]:0".parse().ok()?
+        };
+        record_attempt();
+        let udp = UdpSocket::bind(bind_addr).await.ok()?;
+        udp.connect(socket).await.ok()?;
+        udp.send(request).await.ok()?;
+
+        let mut buffer = vec![0u8; SNMP_BUFFER_SIZE];
+        match async_timeout(timeout, udp.recv(&mut buffer)).await {
+            Ok(Ok(n)) => {
+                trace!("Received SNMP response ({} bytes)", n);
+                Self::parse_response(&buffer[..n])
+            }
+            Ok(Err(e)) => {
+                trace!("SNMP query to {} failed: {}", socket, e);
+                None
+            }
+            Err(_) => None,
+        }
+    }
+
+    /// Build an SNMPv1 GetRequest for `sysDescr.0`
+    pub fn build_get_request(request_id: u8, community: &str) -> Vec<u8> {
+        let varbind = Self::tlv(BER_SEQUENCE, &[
+            Self::tlv(BER_OID, SYS_DESCR_OID),
+            Self::tlv(BER_NULL, &[]),
+        ].concat());
+        let pdu = Self::tlv(SNMP_GET_REQUEST, &[
+            Self::tlv(BER_INTEGER, &[request_id]),
+            Self::tlv(BER_INTEGER, &[0x00]), // error-status
+            Self::tlv(BER_INTEGER, &[0x00]), // error-index
+            Self::tlv(BER_SEQUENCE, &varbind),
+        ].concat());
+        Self::tlv(BER_SEQUENCE, &[
+            Self::tlv(BER_INTEGER, &[SNMP_VERSION_1]),
+            Self::tlv(BER_OCTET_STRING, community.as_bytes()),
+            pdu,
+        ].concat())
+    }
+
+    /// Parse a GetResponse, extracting the community and `sysDescr` value
+    pub fn parse_response(data: &[u8]) -> Option<SnmpResponse> {
+        let (tag, message, _) = Self::read_tlv(data, 0)?;
+        if tag != BER_SEQUENCE {
+            debug!("Not an SNMP message ({} bytes)", data.len());
+            return None;
+        }
+
+        let (_, _version, offset) = Self::expect(message, 0, BER_INTEGER)?;
+        let (_, community, offset) = Self::expect(message, offset, BER_OCTET_STRING)?;
+        let (_, pdu, _) = Self::expect(message, offset, SNMP_GET_RESPONSE)?;
+
+        let (_, _request_id, offset) = Self::expect(pdu, 0, BER_INTEGER)?;
+        let (_, error_status, offset) = Self::expect(pdu, offset, BER_INTEGER)?;
+        let (_, _error_index, offset) = Self::expect(pdu, offset, BER_INTEGER)?;
+        let (_, varbinds, _) = Self::expect(pdu, offset, BER_SEQUENCE)?;
+
+        // First varbind: SEQUENCE { OID, value }
+        let sys_descr = Self::expect(varbinds, 0, BER_SEQUENCE)
+            .and_then(|(_, varbind, _)| {
+                let (_, _oid, offset) = Self::expect(varbind, 0, BER_OID)?;
+                let (_, value, _) = Self::expect(varbind, offset, BER_OCTET_STRING)?;
+                Some(String::from_utf8_lossy(value).trim().to_string())
+            });
+
+        Some(SnmpResponse {
+            community: String::from_utf8_lossy(community).to_string(),
+            error_status: error_status.last().copied().unwrap_or(0),
+            sys_descr,
+        })
+    }
+
+    fn tlv(tag: u8, content: &[u8]) -> Vec<u8> {
+        let mut out = vec![tag];
+        let len = content.len();
+        if len < 0x80 {
+            out.push(len as u8);
+        } else if len <= 0xff {
+            out.extend_from_slice(&[0x81, len as u8]);
+        } else {
+            out.push(0x82);
+            out.extend_from_slice(&(len as u16).to_be_bytes());
+        }
+        out.extend_from_slice(content);
+        out
+    }
+
+    fn expect(data: &[u8], offset: usize, tag: u8) -> Option<(u8, &[u8], usize)> {
+        Self::read_tlv(data, offset).filter(|(t, _, _)| *t == tag)
+    }
+
+    /// Read one BER TLV at `offset`, returning (tag, content, next offset)
+    fn read_tlv(data: &[u8], offset: usize) -> Option<(u8, &[u8], usize)> {
+        let tag = *data.get(offset)?;
+        let first = *data.get(offset + 1)? as usize;
+        let (len, header) = if first & 0x80 == 0 {
+            (first, 2)
+        } else {
+            let count = first & 0x7f;
+            if count == 0 || count > 2 {
+                return None;
+            }
+            let bytes = data.get(offset + 2..offset + 2 + count)?;
+            (bytes.iter().fold(0usize, |acc, &b| (acc << 8) | b as usize), 2 + count)
+        };
+        let start = offset + header;
+        let content = data.get(start..start + len)?;
+        Some((tag, content, start + len))
+    }
+}
+
+impl Default for SnmpDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait::async_trait]
+impl Detector for SnmpDetector {
+    fn name(&self) -> &str {
+        "SnmpDetector"
+    }
+
+    fn can_detect(&self, port: Port) -> bool {
+        port == 161
+    }
+
+    fn is_udp(&self) -> bool {
+        true
+    }
+
+    fn detect_service(&self, _socket: &SocketAddr, _timeout: Duration) -> Option<ServiceVersion> {
+        None // Async-only detector, see detect_service_async
+    }
+
+    async fn detect_service_async(&self, socket: &SocketAddr, timeout: Duration) -> Option<ServiceVersion> {
+        self.detect_async(socket, timeout).await
+    }
+}
@@ -0,0 +1,236 @@
+//! Telnet service detection use case
+
+use std::net::SocketAddr;
+use std::time::Duration;
+use tokio::net::TcpStream as AsyncTcpStream;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::time::{timeout as async_timeout, timeout_at, Instant};
+use tracing::{debug, trace};
+
+use crate::domain::{Port, ServiceVersion};
+use crate::constants::*;
+use crate::infrastructure::{read_limits, record_attempt};
+use crate::scanning::Detector;
+
+const IAC: u8 = 255;
+const DONT: u8 = 254;
+const DO: u8 = 253;
+const WONT: u8 = 252;
+const WILL: u8 = 251;
+const SB: u8 = 250;
+const SE: u8 = 240;
+
+/// Reads before giving up on a server that keeps negotiating
+const TELNET_MAX_READS: usize = 4;
+
+/// Readable text and negotiation replies extracted from a Telnet chunk
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TelnetChunk {
+    /// Data with all IAC sequences removed
+    pub text: Vec<u8>,
+    /// Replies refusing every DO/WILL the server sent
+    pub replies: Vec<u8>,
+    /// Options the server negotiated, in order of appearance
+    pub options: Vec<u8>,
+}
+
+/// Telnet detector: declines option negotiation and captures the login banner
+pub struct TelnetDetector;
+
+impl TelnetDetector {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Async Telnet detection
+    ///
+    /// ```
+    /// use port_scanner::application::TelnetDetector;
+    /// use std::time::Duration;
+    /// use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    /// use tokio::net::TcpListener;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// // Negotiates ECHO and SUPPRESS-GO-AHEAD, then greets once answered
+    /// let listener = TcpListener::bind("127.0.0.1:0").await?;
+    /// let addr = listener.local_addr()?;
+    /// tokio::spawn(async move {
+    ///     let (mut stream, _) = listener.accept().await.unwrap();
+    ///     stream.write_all(&[255, 253, 1, 255, 251, 3]).await.unwrap();
+    ///     let mut replies = [0; 6];
+    ///     stream.read_exact(&mut replies).await.unwrap();
+    ///     // IAC WONT ECHO, IAC DONT SUPPRESS-GO-AHEAD
+    ///     assert_eq!(replies, [255, 252, 1, 255, 254, 3]);
+    ///     stream.write_all(b"\r\nUbuntu 22.04.4 LTS\r\nrouter login: ").await.unwrap();
+    /// });
+    ///
+    /// let telnet = TelnetDetector::detect_async(&addr, Duration::from_secs(2)).await.unwrap();
+    /// assert_eq!(telnet.service_name, "Telnet");
+    /// assert_eq!(telnet.banner.as_deref(), Some("Ubuntu 22.04.4 LTS router login:"));
+    /// assert_eq!(telnet.extra_info.as_deref(), Some("options=ECHO/SUPPRESS-GO-AHEAD"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn detect_async(socket: &SocketAddr, timeout: Duration) -> Option<ServiceVersion> {
+        debug!("Attempting Telnet detection on {}", socket);
+
+        record_attempt();
+        let mut stream = match async_timeout(timeout, AsyncTcpStream::connect(socket)).await {
+            Ok(Ok(stream)) => stream,
+            _ => {
+                trace!("Telnet connect to {} failed", socket);
+                return None;
+            }
+        };
+
+        let limits = read_limits();
+        let deadline = Instant::now() + limits.max_duration;
+        let mut buffer = vec![0u8; TELNET_BUFFER_SIZE];
+        let mut received = 0;
+        let mut truncated = false;
+        let mut text = Vec::new();
+        let mut options = Vec::new();
+
+        // Servers usually hold the banner back until negotiation is answered
+        for _ in 0..TELNET_MAX_READS {
+            let remaining = limits.max_bytes.saturating_sub(received);
+            if remaining == 0 || Instant::now() >= deadline {
+                truncated = true;
+                break;
+            }
+            let chunk_size = buffer.len().min(remaining);
+            let n = match timeout_at(deadline.min(Instant::now() + timeout), stream.read(&mut buffer[..chunk_size])).await {
+                Ok(Ok(n)) if n > 0 => n,
+                Err(_) if Instant::now() >= deadline => {
+                    truncated = received > 0;
+                    break;
+                }
+                _ => break,
+            };
+            received += n;
+            let chunk = Self::parse_chunk(&buffer[..n]);
+            trace!("Telnet chunk from {}: {} text bytes, {} options", socket, chunk.text.len(), chunk.options.len());
+            text.extend_from_slice(&chunk.text);
+            for option in chunk.options {
+                if !options.contains(&option) {
+                    options.push(option);
+                }
+            }
+
+            if chunk.replies.is_empty() {
+                if !text.is_empty() {
+                    break;
+                }
+            } else if stream.write_all(&chunk.replies).await.is_err() {
+                break;
+            }
+        }
+
+        if text.is_empty() && options.is_empty() {
+            return None;
+        }
+
+        let banner = String::from_utf8_lossy(&text).trim().to_string();
+        // Negotiation is what distinguishes Telnet from a plain text service
+        let confidence = if options.is_empty() { CONFIDENCE_MEDIUM } else { CONFIDENCE_HIGH };
+        let mut version = ServiceVersion::new("Telnet", "tcp").with_confidence(confidence);
+        if !banner.is_empty() {
+            version = version.with_banner(banner).with_banner_truncated(truncated);
+        }
+        if !options.is_empty() {
+            let names: Vec<String> = options.iter().map(|&o| Self::option_name(o)).collect();
+            version = version.with_extra_info(format!("options={}", names.join("/")));
+        }
+        Some(version)
+    }
+
+    /// Split a chunk into readable text and refusals for the server's requests.
+    ///
+    /// `IAC IAC` is an escaped 0xFF data byte; subnegotiation blocks and
+    /// other commands are dropped. NUL padding is removed from the text.
+    pub fn parse_chunk(data: &[u8]) -> TelnetChunk {
+        let mut chunk = TelnetChunk::default();
+        let mut i = 0;
+        while i < data.len() {
+            let byte = data[i];
+            if byte != IAC {
+                if byte != 0 {
+                    chunk.text.push(byte);
+                }
+                i += 1;
+                continue;
+            }
+
+            match data.get(i + 1).copied() {
+                Some(IAC) => {
+                    chunk.text.push(IAC);
+                    i += 2;
+                }
+                Some(command @ (DO | DONT | WILL | WONT)) => {
+                    let Some(&option) = data.get(i + 2) else { break };
+                    match command {
+                        DO => chunk.replies.extend_from_slice(&[IAC, WONT, option]),
+                        WILL => chunk.replies.extend_from_slice(&[IAC, DONT, option]),
+                        _ => {}
+                    }
+                    chunk.options.push(option);
+                    i += 3;
+                }
+                Some(SB) => {
+                    // Skip to the closing IAC SE
+                    let end = data[i + 2..].windows(2).position(|w| w == [IAC, SE]);
+                    i = match end {
+                        Some(pos) => i + 2 + pos + 2,
+                        None => data.len(),
+                    };
+                }
+                Some(_) => i += 2,
+                None => break,
+            }
+        }
+        chunk
+    }
+
+    fn option_name(option: u8) -> String {
+        match option {
+            0 => "BINARY".to_string(),
+            1 => "ECHO".to_string(),
+            3 => "SUPPRESS-GO-AHEAD".to_string(),
+            5 => "STATUS".to_string(),
+            24 => "TERMINAL-TYPE".to_string(),
+            31 => "NAWS".to_string(),
+            32 => "TERMINAL-SPEED".to_string(),
+            33 => "LINEMODE".to_string(),
+            35 => "X-DISPLAY".to_string(),
+            36 => "ENVIRON".to_string(),
+            39 => "NEW-ENVIRON".to_string(),
+            other => other.to_string(),
+        }
+    }
+}
+
+impl Default for TelnetDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait::async_trait]
+impl Detector for TelnetDetector {
+    fn name(&self) -> &str {
+        "TelnetDetector"
+    }
+
+    fn can_detect(&self, port: Port) -> bool {
+        port == 23
+    }
+
+    fn detect_service(&self, _socket: &SocketAddr, _timeout: Duration) -> Option<ServiceVersion> {
+        None // Async-only detector, see detect_service_async
+    }
+
+    async fn detect_service_async(&self, socket: &SocketAddr, timeout: Duration) -> Option<ServiceVersion> {
+        Self::detect_async(socket, timeout).await
+    }
+}
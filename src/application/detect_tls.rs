@@ -0,0 +1,218 @@
+//! TLS service detection use case: protocol version and ALPN
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::TcpStream as AsyncTcpStream;
+use tokio::time::timeout as async_timeout;
+use tokio_rustls::rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use tokio_rustls::rustls::crypto::{self, CryptoProvider};
+use tokio_rustls::rustls::pki_types::{CertificateDer, ServerName, UnixTime};
+use tokio_rustls::rustls::{ClientConfig, DigitallySignedStruct, ProtocolVersion, SignatureScheme};
+use tokio_rustls::client::TlsStream;
+use tokio_rustls::TlsConnector;
+use tracing::{debug, trace};
+
+use crate::domain::{Port, ServiceVersion};
+use crate::constants::*;
+use crate::infrastructure::record_attempt;
+use crate::scanning::Detector;
+
+/// Offered in preference order; the server picks one or none
+const ALPN_PROTOCOLS: [&[u8]; 2] = [b"h2", b"http/1.1"];
+
+/// Parameters the server chose during the handshake
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TlsHandshake {
+    /// "1.2" or "1.3"
+    pub version: String,
+    /// Negotiated ALPN protocol, e.g. `h2`; `None` if the server ignored ALPN
+    pub alpn: Option<String>,
+}
+
+impl TlsHandshake {
+    /// `tls=1.3; alpn=h2`
+    pub fn extra_info(&self) -> String {
+        format!("tls={}; alpn={}", self.version, self.alpn.as_deref().unwrap_or("none"))
+    }
+}
+
+/// TLS detector for HTTPS ports.
+///
+/// Completes a handshake offering `h2` and `http/1.1` and records the TLS
+/// version and ALPN selection. Certificates are not validated: the scanner
+/// inspects the server, it does not trust it. Servers that only speak TLS 1.0
+/// or 1.1 fail the handshake and are left to the banner grabber.
+pub struct TlsDetector;
+
+impl TlsDetector {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Async TLS detection
+    pub async fn detect_async(socket: &SocketAddr, timeout: Duration) -> Option<ServiceVersion> {
+        debug!("Attempting TLS detection on {}", socket);
+        let handshake = Self::handshake(socket, timeout).await?;
+        debug!("TLS {} on {}, ALPN {:?}", handshake.version, socket, handshake.alpn);
+
+        Some(ServiceVersion::new("HTTPS", "tcp")
+            .with_confidence(CONFIDENCE_HIGH)
+            .with_extra_info(handshake.extra_info()))
+    }
+
+    /// Run a TLS handshake and report what the server negotiated
+    ///
+    /// ```
+    /// use port_scanner::application::TlsDetector;
+    /// use std::sync::Arc;
+    /// use std::time::Duration;
+    /// use tokio::net::TcpListener;
+    /// use tokio_rustls::rustls::crypto::ring;
+    /// use tokio_rustls::rustls::pki_types::PrivatePkcs8KeyDer;
+    /// use tokio_rustls::rustls::ServerConfig;
+    /// use tokio_rustls::TlsAcceptor;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// // Local TLS server with a self-signed certificate that selects h2
+    /// let certified = rcgen::generate_simple_self_signed(vec!["localhost".into()])?;
+    /// let key = PrivatePkcs8KeyDer::from(certified.key_pair.serialize_der());
+    /// let mut config = ServerConfig::builder_with_provider(Arc::new(ring::default_provider()))
+    ///     .with_safe_default_protocol_versions()?
+    ///     .with_no_client_auth()
+    ///     .with_single_cert(vec![certified.cert.der().clone()], key.into())?;
+    /// config.alpn_protocols = vec![b"h2".to_vec()];
+    /// let acceptor = TlsAcceptor::from(Arc::new(config));
+    /// let listener = TcpListener::bind("127.0.0.1:0").await?;
+    /// let addr = listener.local_addr()?;
+    /// tokio::spawn(async move {
+    ///     while let Ok((stream, _)) = listener.accept().await {
+    ///         let _ = acceptor.accept(stream).await;
+    ///     }
+    /// });
+    ///
+    /// let handshake = TlsDetector::handshake(&addr, Duration::from_secs(2)).await.expect("handshake");
+    /// assert_eq!(handshake.alpn.as_deref(), Some("h2"));
+    /// assert_eq!(handshake.version, "1.3");
+    ///
+    /// let service = TlsDetector::detect_async(&addr, Duration::from_secs(2)).await.expect("detected");
+    /// assert_eq!(service.extra_info.as_deref(), Some("tls=1.3; alpn=h2"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn handshake(socket: &SocketAddr, timeout: Duration) -> Option<TlsHandshake> {
+        let tls = Self::connect(socket, timeout, &ALPN_PROTOCOLS).await?;
+        let (_, connection) = tls.get_ref();
+        let version = match connection.protocol_version()? {
+            ProtocolVersion::TLSv1_3 => "1.3".to_string(),
+            ProtocolVersion::TLSv1_2 => "1.2".to_string(),
+            other => format!("{:?}", other),
+        };
+        let alpn = connection.alpn_protocol().map(|p| String::from_utf8_lossy(p).into_owned());
+        Some(TlsHandshake { version, alpn })
+    }
+
+    /// Connect and complete a handshake offering `alpn`, for detectors of
+    /// TLS-wrapped protocols too
+    pub(crate) async fn connect(socket: &SocketAddr, timeout: Duration, alpn: &[&[u8]]) -> Option<TlsStream<AsyncTcpStream>> {
+        record_attempt();
+        let stream = match async_timeout(timeout, AsyncTcpStream::connect(socket)).await {
+            Ok(Ok(stream)) => stream,
+            _ => {
+                trace!("TLS connect to {} failed", socket);
+                return None;
+            }
+        };
+
+        let connector = TlsConnector::from(Self::client_config(alpn)?);
+        let server_name = ServerName::IpAddress(socket.ip().into());
+        match async_timeout(timeout, connector.connect(server_name, stream)).await {
+            Ok(Ok(tls)) => Some(tls),
+            Ok(Err(e)) => {
+                debug!("TLS handshake with {} failed: {}", socket, e);
+                None
+            }
+            Err(_) => {
+                trace!("TLS handshake with {} timed out", socket);
+                None
+            }
+        }
+    }
+
+    fn client_config(alpn: &[&[u8]]) -> Option<Arc<ClientConfig>> {
+        let provider = Arc::new(crypto::ring::default_provider());
+        let mut config = ClientConfig::builder_with_provider(Arc::clone(&provider))
+            .with_safe_default_protocol_versions()
+            .ok()?
+            .dangerous()
+            .with_custom_certificate_verifier(Arc::new(AcceptAnyCertificate(provider)))
+            .with_no_client_auth();
+        config.alpn_protocols = alpn.iter().map(|p| p.to_vec()).collect();
+        Some(Arc::new(config))
+    }
+}
+
+impl Default for TlsDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait::async_trait]
+impl Detector for TlsDetector {
+    fn name(&self) -> &str {
+        "TlsDetector"
+    }
+
+    fn can_detect(&self, port: Port) -> bool {
+        matches!(port, 443 | 8443)
+    }
+
+    fn detect_service(&self, _socket: &SocketAddr, _timeout: Duration) -> Option<ServiceVersion> {
+        None // Async-only detector, see detect_service_async
+    }
+
+    async fn detect_service_async(&self, socket: &SocketAddr, timeout: Duration) -> Option<ServiceVersion> {
+        Self::detect_async(socket, timeout).await
+    }
+}
+
+/// Accepts any certificate but still checks handshake signatures
+#[derive(Debug)]
+struct AcceptAnyCertificate(Arc<CryptoProvider>);
+
+impl ServerCertVerifier for AcceptAnyCertificate {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, tokio_rustls::rustls::Error> {
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, tokio_rustls::rustls::Error> {
+        crypto::verify_tls12_signature(message, cert, dss, &self.0.signature_verification_algorithms)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, tokio_rustls::rustls::Error> {
+        crypto::verify_tls13_signature(message, cert, dss, &self.0.signature_verification_algorithms)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.0.signature_verification_algorithms.supported_schemes()
+    }
+}
@@ -0,0 +1,204 @@
+//! VNC service detection use case
+
+use std::net::SocketAddr;
+use std::time::Duration;
+use tokio::net::TcpStream as AsyncTcpStream;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::time::timeout as async_timeout;
+use tracing::{debug, trace, warn};
+
+use crate::domain::{Port, ServiceVersion};
+use crate::constants::*;
+use crate::infrastructure::record_attempt;
+use crate::scanning::Detector;
+
+const RFB_GREETING_LEN: usize = 12;
+const SECURITY_NONE: u8 = 1;
+const SECURITY_INVALID: u8 = 0;
+
+/// Parsed RFB greeting, e.g. `RFB 003.008\n`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RfbVersion {
+    pub major: u16,
+    pub minor: u16,
+}
+
+impl RfbVersion {
+    /// Greeting to send back; servers accept their own version or lower
+    fn greeting(&self) -> [u8; RFB_GREETING_LEN] {
+        let mut out = [0u8; RFB_GREETING_LEN];
+        out.copy_from_slice(format!("RFB {:03}.{:03}\n", self.major, self.minor).as_bytes());
+        out
+    }
+
+    /// RFB 3.7 and later send a list of security types instead of a single one
+    fn has_type_list(&self) -> bool {
+        (self.major, self.minor) >= (3, 7)
+    }
+}
+
+/// VNC detector: RFB version handshake and security type listing
+pub struct VncDetector;
+
+impl VncDetector {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Async VNC detection
+    ///
+    /// ```
+    /// use port_scanner::application::VncDetector;
+    /// use std::time::Duration;
+    /// use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    /// use tokio::net::TcpListener;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// // RFB 3.8 server offering no authentication and VNC authentication
+    /// let listener = TcpListener::bind("127.0.0.1:0").await?;
+    /// let addr = listener.local_addr()?;
+    /// tokio::spawn(async move {
+    ///     let (mut stream, _) = listener.accept().await.unwrap();
+    ///     stream.write_all(b"RFB 003.008\n").await.unwrap();
+    ///     let mut reply = [0; 12];
+    ///     stream.read_exact(&mut reply).await.unwrap();
+    ///     assert_eq!(&reply, b"RFB 003.008\n");
+    ///     stream.write_all(&[2, 1, 2]).await.unwrap();
+    /// });
+    ///
+    /// let vnc = VncDetector::detect_async(&addr, Duration::from_secs(2)).await.unwrap();
+    /// assert_eq!(vnc.service_name, "VNC");
+    /// assert_eq!(vnc.version.as_deref(), Some("RFB 3.8"));
+    /// assert_eq!(vnc.extra_info.as_deref(), Some("rfb=3.8; security=None/VNC Authentication; auth=none"));
+    ///
+    /// assert_eq!(VncDetector::parse_greeting(b"SSH-2.0-Open"), None);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn detect_async(socket: &SocketAddr, timeout: Duration) -> Option<ServiceVersion> {
+        debug!("Attempting VNC detection on {}", socket);
+
+        record_attempt();
+        let mut stream = match async_timeout(timeout, AsyncTcpStream::connect(socket)).await {
+            Ok(Ok(stream)) => stream,
+            _ => {
+                trace!("VNC connect to {} failed", socket);
+                return None;
+            }
+        };
+
+        let mut greeting = [0u8; RFB_GREETING_LEN];
+        async_timeout(timeout, stream.read_exact(&mut greeting)).await.ok()?.ok()?;
+        let Some(rfb) = Self::parse_greeting(&greeting) else {
+            debug!("Not an RFB greeting on {}", socket);
+            return None;
+        };
+
+        let mut extra = vec![format!("rfb={}.{}", rfb.major, rfb.minor)];
+        let mut version = ServiceVersion::new("VNC", "tcp")
+            .with_version(format!("RFB {}.{}", rfb.major, rfb.minor))
+            .with_banner(String::from_utf8_lossy(&greeting).trim().to_string())
+            .with_confidence(CONFIDENCE_HIGH);
+
+        // Security types are only sent after the client echoes a version
+        let security = match stream.write_all(&rfb.greeting()).await {
+            Ok(()) => async_timeout(timeout, Self::read_security_types(&mut stream, rfb)).await.ok().flatten(),
+            Err(_) => None,
+        };
+
+        match security {
+            Some(types) => {
+                let names: Vec<&str> = types.iter().map(|&t| Self::security_type_name(t)).collect();
+                extra.push(format!("security={}", names.join("/")));
+                if types.contains(&SECURITY_NONE) {
+                    warn!("VNC on {} allows unauthenticated access", socket);
+                    extra.push("auth=none".to_string());
+                } else {
+                    extra.push("auth=required".to_string());
+                }
+            }
+            None => trace!("No security types read from {}", socket),
+        }
+
+        version = version.with_extra_info(extra.join("; "));
+        Some(version)
+    }
+
+    /// Read the server's security types for the negotiated RFB version
+    async fn read_security_types(stream: &mut AsyncTcpStream, rfb: RfbVersion) -> Option<Vec<u8>> {
+        if rfb.has_type_list() {
+            let count = stream.read_u8().await.ok()?;
+            let mut types = vec![0u8; count as usize];
+            stream.read_exact(&mut types).await.ok()?;
+            Self::parse_security_types(count, &types)
+        } else {
+            // RFB 3.3: the server picks a single type as a u32
+            let chosen = stream.read_u32().await.ok()?;
+            match u8::try_from(chosen) {
+                Ok(SECURITY_INVALID) | Err(_) => None,
+                Ok(t) => Some(vec![t]),
+            }
+        }
+    }
+
+    /// Parse an `RFB xxx.yyy\n` greeting
+    pub fn parse_greeting(data: &[u8]) -> Option<RfbVersion> {
+        let text = std::str::from_utf8(data.get(..RFB_GREETING_LEN)?).ok()?;
+        let version = text.strip_prefix("RFB ")?.strip_suffix('\n')?;
+        let (major, minor) = version.split_once('.')?;
+        Some(RfbVersion {
+            major: major.parse().ok()?,
+            minor: minor.parse().ok()?,
+        })
+    }
+
+    /// Interpret a security type list; a count of zero means the server refused us
+    pub fn parse_security_types(count: u8, types: &[u8]) -> Option<Vec<u8>> {
+        if count == 0 || types.len() < count as usize {
+            return None;
+        }
+        Some(types[..count as usize].to_vec())
+    }
+
+    fn security_type_name(security_type: u8) -> &'static str {
+        match security_type {
+            1 => "None",
+            2 => "VNC Authentication",
+            5 => "RA2",
+            6 => "RA2ne",
+            16 => "Tight",
+            18 => "TLS",
+            19 => "VeNCrypt",
+            22 => "XVP",
+            30 => "Apple Remote Desktop",
+            _ => "unknown",
+        }
+    }
+}
+
+impl Default for VncDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait::async_trait]
+impl Detector for VncDetector {
+    fn name(&self) -> &str {
+        "VncDetector"
+    }
+
+    fn can_detect(&self, port: Port) -> bool {
+        // Display :0 through :9
+        (5900..=5909).contains(&port)
+    }
+
+    fn detect_service(&self, _socket: &SocketAddr, _timeout: Duration) -> Option<ServiceVersion> {
+        None // Async-only detector, see detect_service_async
+    }
+
+    async fn detect_service_async(&self, socket: &SocketAddr, timeout: Duration) -> Option<ServiceVersion> {
+        Self::detect_async(socket, timeout).await
+    }
+}
@@ -0,0 +1,174 @@
+//! Security findings with a severity, derived from detection results
+
+use crate::domain::{Finding, PortScanResult, ScanResults, ServiceVersion, Severity};
+
+/// Check of one open port for an issue worth reporting
+pub trait FindingRule: Send + Sync {
+    fn name(&self) -> &str;
+
+    /// The finding on `result`, an open port, if the rule applies
+    fn check(&self, result: &PortScanResult) -> Option<Finding>;
+}
+
+/// Applies finding rules to every open port of a scan.
+///
+/// Rules read what detection found (service names, `extra_info`, the SMB
+/// dialect, `unauthenticated`), so most of them need `detect_versions`.
+///
+/// ```
+/// use port_scanner::application::FindingEngine;
+/// use port_scanner::domain::{OSInfo, Severity};
+/// use port_scanner::prelude::*;
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let results = ScanResults::new(vec![
+///     PortScanResult::new(22, PortStatus::Open).with_version(ServiceVersion::new("SSH", "tcp")),
+///     PortScanResult::new(23, PortStatus::Open).with_version(ServiceVersion::new("Telnet", "tcp")),
+///     PortScanResult::new(445, PortStatus::Open)
+///         .with_os_info(OSInfo::new().with_smb_version("SMB 1.0")),
+///     PortScanResult::new(2323, PortStatus::Closed).with_version(ServiceVersion::new("Telnet", "tcp")),
+/// ]);
+///
+/// let findings = FindingEngine::with_defaults().evaluate(&results);
+/// let summary: Vec<_> = findings.iter().map(|f| (f.severity, f.title.as_str(), f.port)).collect();
+/// assert_eq!(summary, vec![
+///     (Severity::Critical, "SMBv1 enabled", 445),
+///     (Severity::High, "Telnet open", 23),
+/// ]);
+///
+/// // Reports list them in their own section
+/// let config = ScanConfigBuilder::new().target("10.0.0.5".parse()?).custom_ports(vec![22, 23, 445, 2323]).build()?;
+/// let report = ScanReport::new(&config, results, 1.0);
+/// assert_eq!(report.findings, findings);
+/// let text = OutputFormatterFactory::create(OutputFormat::Text, false).format(&report)?;
+/// assert!(text.contains("=== FINDINGS ===\n[CRITICAL] 445/tcp SMBv1 enabled"));
+/// let json: serde_json::Value = serde_json::from_str(&OutputFormatterFactory::create(OutputFormat::Json, false).format(&report)?)?;
+/// assert_eq!(json["findings"][1]["severity"], "high");
+/// # Ok(())
+/// # }
+/// ```
+pub struct FindingEngine {
+    rules: Vec<Box<dyn FindingRule>>,
+}
+
+impl FindingEngine {
+    pub fn new() -> Self {
+        Self { rules: Vec::new() }
+    }
+
+    /// Engine with the built-in rules: open Telnet, SMBv1, the default SNMP
+    /// community and unauthenticated services such as VNC and Redis
+    pub fn with_defaults() -> Self {
+        let mut engine = Self::new();
+        engine.register(Box::new(TelnetOpen));
+        engine.register(Box::new(Smb1Enabled));
+        engine.register(Box::new(SnmpDefaultCommunity));
+        engine.register(Box::new(NoAuthentication));
+        engine
+    }
+
+    pub fn register(&mut self, rule: Box<dyn FindingRule>) {
+        self.rules.push(rule);
+    }
+
+    /// Findings on the open ports of `results`, most severe first, then by port
+    pub fn evaluate(&self, results: &ScanResults) -> Vec<Finding> {
+        let mut findings: Vec<Finding> = results.results.iter()
+            .filter(|result| result.is_open())
+            .flat_map(|result| {
+                self.rules.iter()
+                    .filter_map(|rule| rule.check(result))
+                    .map(|finding| finding.with_protocol(result.protocol).with_host(result.host))
+            })
+            .collect();
+        findings.sort_by(|a, b| b.severity.cmp(&a.severity).then(a.host.cmp(&b.host)).then(a.port.cmp(&b.port)));
+        findings
+    }
+}
+
+impl Default for FindingEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Whether a detector reported `entry` (e.g. `auth=none`) in `extra_info`
+fn reported(version: &ServiceVersion, entry: &str) -> bool {
+    version.extra_info.as_deref()
+        .is_some_and(|extra| extra.split("; ").any(|item| item == entry))
+}
+
+struct TelnetOpen;
+
+impl FindingRule for TelnetOpen {
+    fn name(&self) -> &str {
+        "telnet-open"
+    }
+
+    fn check(&self, result: &PortScanResult) -> Option<Finding> {
+        let version = result.service_version.as_ref()?;
+        version.service_name.eq_ignore_ascii_case("telnet").then(|| Finding::new(
+            Severity::High,
+            "Telnet open",
+            result.port,
+            "Telnet sends credentials and sessions in cleartext",
+        ))
+    }
+}
+
+struct Smb1Enabled;
+
+impl FindingRule for Smb1Enabled {
+    fn name(&self) -> &str {
+        "smb1-enabled"
+    }
+
+    fn check(&self, result: &PortScanResult) -> Option<Finding> {
+        let smb1 = |version: Option<&String>| version.is_some_and(|v| v == "SMB 1.0");
+        let from_os = smb1(result.os_info.as_ref().and_then(|os| os.smb_version.as_ref()));
+        let from_service = result.service_version.as_ref()
+            .is_some_and(|v| v.service_name == "SMB" && smb1(v.version.as_ref()));
+        (from_os || from_service).then(|| Finding::new(
+            Severity::Critical,
+            "SMBv1 enabled",
+            result.port,
+            "Server negotiates SMB 1.0, which is deprecated and exposed to EternalBlue-class exploits",
+        ))
+    }
+}
+
+struct SnmpDefaultCommunity;
+
+impl FindingRule for SnmpDefaultCommunity {
+    fn name(&self) -> &str {
+        "snmp-default-community"
+    }
+
+    fn check(&self, result: &PortScanResult) -> Option<Finding> {
+        let version = result.service_version.as_ref()?;
+        reported(version, "default-community=open").then(|| Finding::new(
+            Severity::High,
+            "SNMP default community",
+            result.port,
+            "Agent answers the default community string, exposing device details to anyone",
+        ))
+    }
+}
+
+struct NoAuthentication;
+
+impl FindingRule for NoAuthentication {
+    fn name(&self) -> &str {
+        "no-authentication"
+    }
+
+    fn check(&self, result: &PortScanResult) -> Option<Finding> {
+        let version = result.service_version.as_ref()?;
+        (version.unauthenticated || reported(version, "auth=none")).then(|| Finding::new(
+            Severity::Critical,
+            format!("Unauthenticated {}", version.service_name),
+            result.port,
+            format!("{} accepts clients without credentials", version.service_name),
+        ))
+    }
+}
@@ -0,0 +1,105 @@
+//! Open-port expectations that decide the process exit status in CI
+
+use crate::constants::{EXIT_AS_EXPECTED, EXIT_ERROR, EXIT_FINDINGS};
+use crate::domain::{Port, ScanResults};
+
+/// What the open ports of a host are checked against
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum FindingsPolicy {
+    /// Open ports never count as findings
+    #[default]
+    Ignore,
+    /// Any open port is a finding
+    FailOnOpen,
+    /// Open ports must be exactly this set
+    ExpectPorts(Vec<Port>),
+}
+
+/// Deviation of one host's open ports from the policy
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Findings {
+    /// Open but not allowed
+    pub unexpected_open: Vec<Port>,
+    /// Expected open but not found open
+    pub missing: Vec<Port>,
+}
+
+impl Findings {
+    pub fn is_empty(&self) -> bool {
+        self.unexpected_open.is_empty() && self.missing.is_empty()
+    }
+
+    /// `EXIT_FINDINGS` if anything deviates, else `EXIT_AS_EXPECTED`
+    pub fn exit_code(&self) -> u8 {
+        if self.is_empty() {
+            EXIT_AS_EXPECTED
+        } else {
+            EXIT_FINDINGS
+        }
+    }
+}
+
+impl FindingsPolicy {
+    /// Compare the open ports in `results` with the policy
+    pub fn evaluate(&self, results: &ScanResults) -> Findings {
+        let mut open: Vec<Port> = results.get_open_results().iter().map(|r| r.port).collect();
+        open.sort_unstable();
+        open.dedup();
+
+        match self {
+            FindingsPolicy::Ignore => Findings::default(),
+            FindingsPolicy::FailOnOpen => Findings { unexpected_open: open, missing: Vec::new() },
+            FindingsPolicy::ExpectPorts(expected) => {
+                let mut missing: Vec<Port> = expected.iter()
+                    .copied()
+                    .filter(|port| !open.contains(port))
+                    .collect();
+                missing.sort_unstable();
+                missing.dedup();
+                Findings {
+                    unexpected_open: open.into_iter().filter(|port| !expected.contains(port)).collect(),
+                    missing,
+                }
+            }
+        }
+    }
+}
+
+/// Exit status of a run, given each host's findings or `None` where its scan
+/// failed: the highest per-host code, so findings outrank errors.
+///
+/// ```
+/// use port_scanner::application::{run_exit_code, FindingsPolicy};
+/// use port_scanner::constants::{EXIT_AS_EXPECTED, EXIT_ERROR, EXIT_FINDINGS};
+/// use port_scanner::prelude::*;
+///
+/// let results = |open: &[u16]| ScanResults::new(
+///     [22, 80, 443].into_iter()
+///         .map(|port| PortScanResult::new(port, if open.contains(&port) { PortStatus::Open } else { PortStatus::Closed }))
+///         .collect(),
+/// );
+/// let allowlist = FindingsPolicy::ExpectPorts(vec![22, 443]);
+///
+/// // Open ports match the allowlist
+/// let as_expected = allowlist.evaluate(&results(&[22, 443]));
+/// assert_eq!(run_exit_code(&[Some(as_expected.clone())]), EXIT_AS_EXPECTED);
+///
+/// // 80 open and 443 missing
+/// let found_open = allowlist.evaluate(&results(&[22, 80]));
+/// assert_eq!((found_open.unexpected_open.as_slice(), found_open.missing.as_slice()), (&[80][..], &[443][..]));
+/// assert_eq!(run_exit_code(&[Some(found_open.clone())]), EXIT_FINDINGS);
+///
+/// // --fail-on-open with nothing open
+/// let none_open = FindingsPolicy::FailOnOpen.evaluate(&results(&[]));
+/// assert_eq!(run_exit_code(&[Some(none_open)]), EXIT_AS_EXPECTED);
+///
+/// // A host that could not be scanned
+/// assert_eq!(run_exit_code(&[Some(as_expected), None]), EXIT_ERROR);
+/// assert_eq!(run_exit_code(&[None, Some(found_open)]), EXIT_FINDINGS);
+/// assert_eq!(run_exit_code(&[]), EXIT_AS_EXPECTED);
+/// ```
+pub fn run_exit_code(hosts: &[Option<Findings>]) -> u8 {
+    hosts.iter()
+        .map(|findings| findings.as_ref().map_or(EXIT_ERROR, Findings::exit_code))
+        .fold(EXIT_AS_EXPECTED, u8::max)
+}
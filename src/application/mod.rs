@@ -1,9 +1,39 @@
-/// Application layer module exports
+//! Application layer module exports
 
 pub mod scan_ports;
+pub mod scan;
+pub mod progress;
+pub mod findings;
+pub mod finding_engine;
 pub mod detect_service;
 pub mod detect_os;
+pub mod detect_dns;
+pub mod detect_rdp;
+pub mod detect_snmp;
+pub mod detect_vnc;
+pub mod detect_telnet;
+pub mod detect_smtp;
+pub mod detect_mail;
+pub mod detect_redis;
+pub mod detect_mongodb;
+pub mod detect_tls;
+pub mod os_guess;
 
 pub use scan_ports::PortScanner;
+pub use scan::{Scan, ScanBuilder, HostResults};
+pub use progress::{ScanProgress, ProgressSnapshot, PartialScanResults};
+pub use findings::{run_exit_code, Findings, FindingsPolicy};
+pub use finding_engine::{FindingEngine, FindingRule};
 pub use detect_service::VersionDetector;
 pub use detect_os::SMBFingerprinter;
+pub use detect_dns::DnsDetector;
+pub use detect_rdp::RdpDetector;
+pub use detect_snmp::SnmpDetector;
+pub use detect_vnc::VncDetector;
+pub use detect_telnet::TelnetDetector;
+pub use detect_smtp::SmtpDetector;
+pub use detect_mail::{ImapDetector, Pop3Detector};
+pub use detect_redis::RedisDetector;
+pub use detect_mongodb::MongoDbDetector;
+pub use detect_tls::TlsDetector;
+pub use os_guess::OsGuesser;
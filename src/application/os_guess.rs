@@ -0,0 +1,183 @@
+//! Host OS guessing from weak signals across a whole scan
+
+use tracing::debug;
+
+use crate::domain::{OSInfo, OsCandidate, PortScanResult, ScanResults};
+
+/// Distribution and vendor keywords found in service banners
+const BANNER_HINTS: [(&str, &str, Option<&str>); 12] = [
+    ("ubuntu", "Linux", Some("Ubuntu")),
+    ("debian", "Linux", Some("Debian")),
+    ("centos", "Linux", Some("CentOS")),
+    ("red hat", "Linux", Some("Red Hat")),
+    ("rhel", "Linux", Some("Red Hat")),
+    ("fedora", "Linux", Some("Fedora")),
+    ("alpine", "Linux", Some("Alpine")),
+    ("linux", "Linux", None),
+    ("freebsd", "FreeBSD", None),
+    ("openbsd", "OpenBSD", None),
+    ("windows", "Windows", None),
+    ("microsoft", "Windows", None),
+];
+
+/// Ports that are almost only open on Windows hosts
+const WINDOWS_PORTS: [u16; 4] = [135, 139, 445, 3389];
+
+// Evidence weights; each is the confidence that one signal alone warrants
+const WEIGHT_BANNER: f32 = 0.7;
+const WEIGHT_SMB_WINDOWS: f32 = 0.4;
+const WEIGHT_SMB1: f32 = 0.2;
+const WEIGHT_TTL: f32 = 0.3;
+const WEIGHT_WINDOWS_PORTS: f32 = 0.3;
+
+/// Combines SMB dialects, reply TTLs, banner keywords and open-port patterns
+/// into a ranked list of OS candidates.
+///
+/// Each signal is weak on its own (Samba speaks SMB 3.1.1, TTLs shrink per
+/// hop), so confidences are combined as independent evidence:
+/// `1 - Π(1 - weight)`. Banners that name a distribution outweigh the rest.
+pub struct OsGuesser;
+
+impl OsGuesser {
+    /// Host-level guess for `results`; `None` when nothing points to any OS
+    ///
+    /// ```
+    /// use port_scanner::application::OsGuesser;
+    /// use port_scanner::domain::{OSInfo, ServiceVersion};
+    /// use port_scanner::prelude::*;
+    ///
+    /// let ssh = PortScanResult::new(22, PortStatus::Open)
+    ///     .with_version(ServiceVersion::new("ssh", "tcp").with_banner("SSH-2.0-OpenSSH_8.9p1 Ubuntu-3ubuntu0.6"));
+    /// let smb = PortScanResult::new(445, PortStatus::Open)
+    ///     .with_os_info(OSInfo::new().with_os_name("Windows").with_smb_version("SMB 3.1.1"));
+    /// let guess = OsGuesser::guess(&ScanResults::new(vec![ssh, smb])).expect("guess");
+    ///
+    /// // The distribution named by the banner outweighs an SMB dialect Samba also speaks
+    /// let ranked: Vec<(&str, f32)> = guess.candidates.iter().map(|c| (c.name.as_str(), c.confidence)).collect();
+    /// assert_eq!(ranked.iter().map(|(name, _)| *name).collect::<Vec<_>>(), ["Linux", "Windows"]);
+    /// assert!((ranked[0].1 - 0.7).abs() < 1e-6);
+    /// assert!((ranked[1].1 - 0.4).abs() < 1e-6);
+    /// assert_eq!(guess.os_name.as_deref(), Some("Linux"));
+    /// assert_eq!(guess.os_version.as_deref(), Some("Ubuntu"));
+    /// assert_eq!(guess.smb_version.as_deref(), Some("SMB 3.1.1"));
+    /// assert!(guess.candidates[1].evidence[0].contains("SMB 3.1.1"));
+    ///
+    /// // Nothing to go on
+    /// assert!(OsGuesser::guess(&ScanResults::new(vec![PortScanResult::new(80, PortStatus::Open)])).is_none());
+    /// ```
+    pub fn guess(results: &ScanResults) -> Option<OSInfo> {
+        let mut evidence = Evidence::default();
+        let mut distribution = None;
+
+        for result in &results.results {
+            Self::smb_signal(result, &mut evidence);
+            if let Some(hint) = Self::banner_signal(result, &mut evidence) {
+                distribution.get_or_insert(hint);
+            }
+        }
+        Self::ttl_signal(&results.results, &mut evidence);
+        Self::port_signal(results, &mut evidence);
+
+        let candidates = evidence.rank();
+        let best = candidates.first()?;
+        debug!("OS guess: {} ({:.0}%) from {} candidates", best.name, best.confidence * 100.0, candidates.len());
+
+        let mut os_info = OSInfo::new().with_os_name(best.name.clone());
+        if best.name == "Linux" {
+            if let Some(distribution) = distribution {
+                os_info = os_info.with_os_version(distribution);
+            }
+        }
+        if let Some(smb) = results.results.iter().find_map(|r| r.os_info.as_ref()?.smb_version.clone()) {
+            os_info = os_info.with_smb_version(smb);
+        }
+        Some(os_info.with_candidates(candidates))
+    }
+
+    fn smb_signal(result: &PortScanResult, evidence: &mut Evidence) {
+        let Some(os_info) = &result.os_info else { return };
+        let smb = os_info.smb_version.as_deref().unwrap_or("SMB");
+        match os_info.os_name.as_deref() {
+            Some("Windows") => evidence.add("Windows", WEIGHT_SMB_WINDOWS, format!("{} negotiated on port {}", smb, result.port)),
+            Some("Windows/Samba") => {
+                evidence.add("Windows", WEIGHT_SMB1, format!("{} on port {}", smb, result.port));
+                evidence.add("Linux", WEIGHT_SMB1, format!("{} on port {} (Samba)", smb, result.port));
+            }
+            _ => {}
+        }
+    }
+
+    /// Returns the distribution named by the banner, if any
+    fn banner_signal(result: &PortScanResult, evidence: &mut Evidence) -> Option<&'static str> {
+        let version = result.service_version.as_ref()?;
+        let text = [&version.banner, &version.version, &version.extra_info]
+            .into_iter()
+            .flatten()
+            .map(|s| s.to_lowercase())
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        let (keyword, family, distribution) = BANNER_HINTS.iter().find(|(keyword, _, _)| text.contains(keyword))?;
+        evidence.add(family, WEIGHT_BANNER, format!("{} banner on port {} mentions {}", version.service_name, result.port, keyword));
+        *distribution
+    }
+
+    /// The initial TTL (64, 128 or 255) is what the host's stack sent before hops decremented it
+    fn ttl_signal(results: &[PortScanResult], evidence: &mut Evidence) {
+        let Some(ttl) = results.iter().filter_map(|r| r.ttl).max() else { return };
+        let family = match ttl {
+            0..=64 => "Linux",
+            65..=128 => "Windows",
+            _ => "Network device",
+        };
+        evidence.add(family, WEIGHT_TTL, format!("reply TTL {}", ttl));
+    }
+
+    fn port_signal(results: &ScanResults, evidence: &mut Evidence) {
+        let mut open: Vec<u16> = results.get_open_results()
+            .iter()
+            .map(|r| r.port)
+            .filter(|port| WINDOWS_PORTS.contains(port))
+            .collect();
+        open.sort_unstable();
+        open.dedup();
+        if open.len() >= 2 {
+            let ports: Vec<String> = open.iter().map(u16::to_string).collect();
+            evidence.add("Windows", WEIGHT_WINDOWS_PORTS, format!("Windows service ports open ({})", ports.join(", ")));
+        }
+    }
+}
+
+/// Signals collected per OS family, in first-seen order
+#[derive(Default)]
+struct Evidence {
+    families: Vec<(String, Vec<(f32, String)>)>,
+}
+
+impl Evidence {
+    fn add(&mut self, family: &str, weight: f32, reason: String) {
+        match self.families.iter_mut().find(|(name, _)| name == family) {
+            Some((_, signals)) => {
+                if !signals.iter().any(|(_, r)| *r == reason) {
+                    signals.push((weight, reason));
+                }
+            }
+            None => self.families.push((family.to_string(), vec![(weight, reason)])),
+        }
+    }
+
+    fn rank(self) -> Vec<OsCandidate> {
+        let mut candidates: Vec<OsCandidate> = self.families.into_iter()
+            .map(|(name, signals)| {
+                let doubt: f32 = signals.iter().map(|(weight, _)| 1.0 - weight).product();
+                OsCandidate {
+                    name,
+                    confidence: 1.0 - doubt,
+                    evidence: signals.into_iter().map(|(_, reason)| reason).collect(),
+                }
+            })
+            .collect();
+        candidates.sort_by(|a, b| b.confidence.total_cmp(&a.confidence));
+        candidates
+    }
+}
@@ -0,0 +1,179 @@
+//! Live scan progress that callers can poll while a scan runs
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::domain::PortStatus;
+
+/// Point-in-time view of a running scan
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ProgressSnapshot {
+    pub scanned: usize,
+    pub total: usize,
+    /// Ports per second since the scan started
+    pub rate: f64,
+    /// Time left at the current rate; `None` before the first result
+    pub eta: Option<Duration>,
+}
+
+impl ProgressSnapshot {
+    /// Fraction of ports scanned, 0.0..=1.0
+    pub fn ratio(&self) -> f64 {
+        if self.total == 0 {
+            return 1.0;
+        }
+        (self.scanned as f64 / self.total as f64).min(1.0)
+    }
+}
+
+/// Running counts of a scan in progress, passed to `PortScanner::on_progress` callbacks
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PartialScanResults {
+    pub scanned: usize,
+    pub total: usize,
+    pub open_ports: usize,
+    pub closed_ports: usize,
+    pub filtered_ports: usize,
+    pub reset_ports: usize,
+    pub open_filtered_ports: usize,
+    pub error_ports: usize,
+}
+
+impl PartialScanResults {
+    /// Percentage of probes finished, 0.0..=100.0
+    pub fn percent_complete(&self) -> f32 {
+        if self.total == 0 {
+            return 100.0;
+        }
+        (self.scanned as f32 / self.total as f32 * 100.0).min(100.0)
+    }
+}
+
+/// Counters updated as results arrive, shared via `PortScanner::progress()`
+#[derive(Debug, Default)]
+pub struct ScanProgress {
+    total: AtomicUsize,
+    scanned: AtomicUsize,
+    /// Results per status, indexed by `status_slot`
+    statuses: [AtomicUsize; 6],
+    started: Mutex<Option<Instant>>,
+}
+
+impl ScanProgress {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reset the counters for a scan of `total` ports
+    pub(crate) fn start(&self, total: usize) {
+        self.total.store(total, Ordering::Relaxed);
+        self.scanned.store(0, Ordering::Relaxed);
+        for count in &self.statuses {
+            count.store(0, Ordering::Relaxed);
+        }
+        *self.started.lock().unwrap_or_else(|e| e.into_inner()) = Some(Instant::now());
+    }
+
+    /// Count one result; returns how many have been scanned so far
+    pub(crate) fn record(&self, status: &PortStatus) -> usize {
+        self.statuses[status_slot(status)].fetch_add(1, Ordering::Relaxed);
+        self.scanned.fetch_add(1, Ordering::Relaxed) + 1
+    }
+
+    pub fn scanned(&self) -> usize {
+        self.scanned.load(Ordering::Relaxed)
+    }
+
+    pub fn total(&self) -> usize {
+        self.total.load(Ordering::Relaxed)
+    }
+
+    /// Time since the scan started (zero before it starts)
+    pub fn elapsed(&self) -> Duration {
+        self.started.lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .map(|started| started.elapsed())
+            .unwrap_or_default()
+    }
+
+    /// Scanned and total ports, rate and ETA as of now
+    ///
+    /// ```
+    /// use port_scanner::application::ProgressSnapshot;
+    /// use port_scanner::infrastructure::testing::{Reply, ScriptedConnector};
+    /// use port_scanner::prelude::*;
+    /// use port_scanner::scanning::StandardScan;
+    /// use std::sync::Arc;
+    /// use std::time::Duration;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// let config = ScanConfigBuilder::new()
+    ///     .target("10.0.0.5".parse()?)
+    ///     .range(1, 10)
+    ///     .parallel(false)
+    ///     .build()?;
+    /// let slow = ScriptedConnector::new(Reply::Refuse).delay(Duration::from_millis(50));
+    /// let scanner = PortScanner::new(config)?.with_strategy(Arc::new(StandardScan::new().with_connector(Arc::new(slow))));
+    /// let progress = scanner.progress();
+    ///
+    /// // A snapshot each time another result is in
+    /// let poll = async {
+    ///     let mut snapshots: Vec<ProgressSnapshot> = Vec::new();
+    ///     while snapshots.last().map_or(true, |s| s.scanned < s.total) {
+    ///         let snapshot = progress.snapshot();
+    ///         if snapshot.scanned > snapshots.last().map_or(0, |s| s.scanned) {
+    ///             snapshots.push(snapshot);
+    ///         }
+    ///         tokio::time::sleep(Duration::from_millis(2)).await;
+    ///     }
+    ///     snapshots
+    /// };
+    /// let (results, snapshots) = tokio::join!(scanner.scan_all(|_| {}), poll);
+    ///
+    /// assert_eq!(results.total_ports, 10);
+    /// assert!(snapshots.len() >= 5 && snapshots.iter().all(|s| s.total == 10 && s.eta.is_some()));
+    /// assert!(snapshots.windows(2).all(|w| w[0].ratio() < w[1].ratio()));
+    /// assert!(snapshots.windows(2).all(|w| w[0].eta > w[1].eta), "{snapshots:?}");
+    /// assert_eq!(snapshots.last().unwrap().eta, Some(Duration::ZERO));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn snapshot(&self) -> ProgressSnapshot {
+        let scanned = self.scanned();
+        let total = self.total();
+        let elapsed = self.elapsed().as_secs_f64();
+        let rate = if elapsed > 0.0 { scanned as f64 / elapsed } else { 0.0 };
+        let eta = (rate > 0.0).then(|| {
+            Duration::from_secs_f64(total.saturating_sub(scanned) as f64 / rate)
+        });
+        ProgressSnapshot { scanned, total, rate, eta }
+    }
+
+    /// Counts by status so far
+    pub fn partial(&self) -> PartialScanResults {
+        let count = |status: PortStatus| self.statuses[status_slot(&status)].load(Ordering::Relaxed);
+        PartialScanResults {
+            scanned: self.scanned(),
+            total: self.total(),
+            open_ports: count(PortStatus::Open),
+            closed_ports: count(PortStatus::Closed),
+            filtered_ports: count(PortStatus::Filtered),
+            reset_ports: count(PortStatus::Reset),
+            open_filtered_ports: count(PortStatus::OpenFiltered),
+            error_ports: count(PortStatus::Error(String::new())),
+        }
+    }
+}
+
+fn status_slot(status: &PortStatus) -> usize {
+    match status {
+        PortStatus::Open => 0,
+        PortStatus::Closed => 1,
+        PortStatus::Filtered => 2,
+        PortStatus::Reset => 3,
+        PortStatus::OpenFiltered => 4,
+        PortStatus::Error(_) => 5,
+    }
+}
@@ -0,0 +1,503 @@
+//! High-level scan facade for embedding the scanner as a library
+
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc;
+use tracing::{info, warn};
+
+use crate::application::PortScanner;
+use crate::constants::{RESULT_CHANNEL_CAPACITY, REVERSE_DNS_TIMEOUT};
+use crate::domain::{Port, ScanResults};
+use crate::errors::{ConfigError, ConfigResult, ScanResult};
+use crate::infrastructure::{network_utils, resolve_all, ReverseResolver, SystemResolver};
+use crate::presentation::ScanObserver;
+use crate::scanning::{
+    schedule, targets, DetectorRegistry, ParallelExecutor, ScanConfig, ScanConfigBuilder, ScanMode,
+    ScanStrategy, SequentialExecutor, Target,
+};
+
+type SharedObserver = Arc<Mutex<Box<dyn ScanObserver>>>;
+
+/// Results for one target of a `Scan`
+#[derive(Debug, Clone)]
+pub struct HostResults {
+    pub target: Target,
+    /// PTR name of the target, with `reverse_dns` and a successful lookup
+    pub hostname: Option<String>,
+    pub results: ScanResults,
+}
+
+/// A configured scan of one or more targets.
+///
+/// Wraps config building, strategy and executor selection, and observer
+/// wiring; targets are scanned one after another unless
+/// `interleave_hosts` or the config's `randomize_scan_order` is set.
+///
+/// ```no_run
+/// use port_scanner::application::Scan;
+///
+/// # async fn example() -> anyhow::Result<()> {
+/// let hosts = Scan::builder()
+///     .target_spec("192.168.1.0/30")?
+///     .ports(vec![22, 80, 443])
+///     .configure(|config| config.detect_versions(true))
+///     .run()
+///     .await?;
+/// for host in &hosts {
+///     println!("{}: {} open", host.target, host.results.open_ports);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct Scan {
+    targets: Vec<Target>,
+    config: ScanConfigBuilder,
+    detectors: Option<Arc<DetectorRegistry>>,
+    strategy: Option<Arc<dyn ScanStrategy>>,
+    observers: Vec<SharedObserver>,
+    interleave_hosts: bool,
+    resolver: Option<Arc<dyn ReverseResolver>>,
+}
+
+impl Scan {
+    pub fn builder() -> ScanBuilder {
+        ScanBuilder::new()
+    }
+
+    /// Scan every target in order, notifying the observers about each one.
+    ///
+    /// Results are sorted by port. Fails before scanning if the settings are
+    /// invalid or there are no targets, and with `ScanError::Unreachable`
+    /// when a target has no route.
+    ///
+    /// Each host is finished the same way whether scanned alone or
+    /// interleaved: filtered ports are verified, the host is classified, and
+    /// `connection_attempts` counts every connect made for it.
+    ///
+    /// ```
+    /// use port_scanner::application::Scan;
+    /// use port_scanner::domain::HostState;
+    /// use port_scanner::infrastructure::testing::{Reply, ScriptedConnector};
+    /// use port_scanner::scanning::StandardScan;
+    /// use std::net::IpAddr;
+    /// use std::sync::Arc;
+    /// use std::time::Duration;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// let hosts: Vec<IpAddr> = (1..=2).map(|i| IpAddr::from([127, 0, 0, i])).collect();
+    /// for interleave in [false, true] {
+    ///     // Nothing answers, so each host gets two closed-port samples
+    ///     let connector = Arc::new(ScriptedConnector::new(Reply::Silent));
+    ///     let results = Scan::builder()
+    ///         .targets(hosts.clone())
+    ///         .ports(vec![22, 80, 443])
+    ///         .configure(|b| b.timeout(Duration::from_millis(50)).closed_port_samples(2))
+    ///         .strategy(Arc::new(StandardScan::new().with_connector(connector.clone())))
+    ///         .interleave_hosts(interleave)
+    ///         .run()
+    ///         .await?;
+    ///
+    ///     for host in &results {
+    ///         let connects = connector.connects().iter().filter(|a| a.ip() == host.target.ip).count();
+    ///         assert_eq!(host.results.host_state, Some(HostState::HostLikelyDown));
+    ///         assert_eq!((host.results.connection_attempts, connects), (3 + 2, 3 + 2));
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn run(mut self) -> ScanResult<Vec<HostResults>> {
+        let config = self.build_config()?;
+        let hostnames = self.resolve_hostnames().await;
+        if self.interleaved(&config) {
+            return self.run_interleaved(config, hostnames).await;
+        }
+
+        let mut hosts = Vec::with_capacity(self.targets.len());
+        for (target, hostname) in self.targets.iter().zip(hostnames) {
+            info!("Scanning target {}", target);
+            let scanner = self.scanner(&config, target)?;
+            scanner.check_reachable()?;
+
+            for observer in &self.observers {
+                lock(observer).on_scan_started(scanner.config().probe_count());
+            }
+            let observers = self.observers.clone();
+            let results = scanner.scan_all(move |result| {
+                for observer in &observers {
+                    lock(observer).on_port_scanned(result);
+                }
+            }).await;
+            for observer in &self.observers {
+                lock(observer).on_scan_completed(&results);
+            }
+
+            hosts.push(HostResults { target: *target, hostname, results });
+        }
+        Ok(hosts)
+    }
+
+    /// Addresses `run` would probe, in the order it would probe them,
+    /// without any network activity. Random orders repeat only with a seed.
+    ///
+    /// ```
+    /// use port_scanner::application::Scan;
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// let probes = |interleave| Scan::builder()
+    ///     .target_spec("10.0.0.1-2")?
+    ///     .ports(vec![22, 80, 443])
+    ///     .interleave_hosts(interleave)
+    ///     .configure(|b| b.seed(Some(7)))
+    ///     .build()
+    ///     .dry_run();
+    ///
+    /// let one_by_one = probes(false)?;
+    /// assert_eq!(one_by_one[0], "10.0.0.1:22".parse()?);
+    /// assert_eq!(one_by_one[3], "10.0.0.2:22".parse()?);
+    ///
+    /// let interleaved = probes(true)?;
+    /// assert_eq!(interleaved.len(), 6);
+    /// assert!(interleaved.windows(2).all(|w| w[0].ip() != w[1].ip()));
+    /// assert_eq!(interleaved, probes(true)?);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn dry_run(mut self) -> ScanResult<Vec<SocketAddr>> {
+        let config = self.build_config()?;
+        let scanners = self.targets.iter()
+            .map(|target| self.scanner(&config, target))
+            .collect::<ScanResult<Vec<_>>>()?;
+        if !self.interleaved(&config) {
+            return Ok(scanners.iter().flat_map(PortScanner::dry_run).collect());
+        }
+        let probes = Self::schedule(&config, &scanners).into_iter()
+            .map(|(host, port)| {
+                let host = scanners[host].config();
+                network_utils::socket_addr(host.target_ip, port, host.scope_id)
+            })
+            .collect();
+        Ok(probes)
+    }
+
+    /// Config for the first target; `scanner` swaps in the others
+    fn build_config(&mut self) -> ScanResult<ScanConfig> {
+        let first = self.targets.first()
+            .ok_or_else(|| ConfigError::MissingField("targets".to_string()))?;
+        let builder = std::mem::take(&mut self.config);
+        Ok(builder.target(first.ip).build()?)
+    }
+
+    /// All targets are scanned from one schedule
+    fn interleaved(&self, config: &ScanConfig) -> bool {
+        (self.interleave_hosts || config.randomize_scan_order) && self.targets.len() > 1
+    }
+
+    /// `(scanner index, port)` pairs of an interleaved scan, in dispatch order
+    fn schedule(config: &ScanConfig, scanners: &[PortScanner]) -> Vec<(usize, Port)> {
+        let port_lists: Vec<_> = scanners.iter().map(|s| s.ports().to_vec()).collect();
+        if config.randomize_scan_order {
+            schedule::shuffle_all(&port_lists, &config.rng)
+        } else {
+            schedule::interleave(&port_lists, &config.rng)
+        }
+    }
+
+    /// PTR names for all targets, looked up concurrently; all `None`
+    /// without a resolver
+    async fn resolve_hostnames(&self) -> Vec<Option<String>> {
+        let Some(resolver) = &self.resolver else {
+            return vec![None; self.targets.len()];
+        };
+        let ips: Vec<_> = self.targets.iter().map(|target| target.ip).collect();
+        resolve_all(resolver.as_ref(), &ips, REVERSE_DNS_TIMEOUT).await
+    }
+
+    /// Scan all targets at once from one interleaved schedule, or one
+    /// shuffled work list with `randomize_scan_order`.
+    ///
+    /// Every observer hears `on_scan_started` for each host up front, then
+    /// results from all hosts as they complete, then one `on_scan_completed`
+    /// per host. `max_scan_duration` bounds the whole run.
+    async fn run_interleaved(self, config: ScanConfig, hostnames: Vec<Option<String>>) -> ScanResult<Vec<HostResults>> {
+        let scanners = self.targets.iter()
+            .map(|target| self.scanner(&config, target))
+            .collect::<ScanResult<Vec<_>>>()?;
+        for scanner in &scanners {
+            scanner.check_reachable()?;
+        }
+        let configs: Vec<ScanConfig> = scanners.iter().map(|s| s.config().clone()).collect();
+        let probes = Self::schedule(&config, &scanners);
+        info!("Scanning {} targets interleaved ({} probes)", configs.len(), probes.len());
+
+        for scanner in &scanners {
+            for observer in &self.observers {
+                lock(observer).on_scan_started(scanner.config().probe_count());
+            }
+        }
+
+        let (tx, mut rx) = mpsc::channel(RESULT_CHANNEL_CAPACITY);
+        let strategies = scanners[0].strategies(&config);
+        let parallel = config.parallel;
+        let concurrency = config.concurrency();
+        let scan = tokio::spawn(async move {
+            // One pass over the schedule per transport
+            for strategy in strategies {
+                if parallel {
+                    ParallelExecutor::new(concurrency).stream_hosts(probes.clone(), strategy, &configs, tx.clone()).await;
+                } else {
+                    SequentialExecutor::new().stream_hosts(probes.clone(), strategy, &configs, tx.clone()).await;
+                }
+            }
+        });
+
+        let mut deadline = config.max_scan_duration.map(|limit| tokio::time::Instant::now() + limit);
+        let mut cancelled = false;
+        let mut per_host = vec![Vec::new(); scanners.len()];
+        loop {
+            let next = match deadline {
+                Some(at) => match tokio::time::timeout_at(at, rx.recv()).await {
+                    Ok(next) => next,
+                    Err(_) if !cancelled => {
+                        warn!("Scan time limit reached, finishing detection on open ports");
+                        for scanner in &scanners {
+                            scanner.cancel();
+                        }
+                        cancelled = true;
+                        deadline = Some(at + config.drain_timeout);
+                        continue;
+                    }
+                    Err(_) => {
+                        warn!("Drain timeout reached, returning partial results");
+                        scan.abort();
+                        break;
+                    }
+                },
+                None => rx.recv().await,
+            };
+            let Some((host, result)) = next else {
+                break;
+            };
+            for observer in &self.observers {
+                lock(observer).on_port_scanned(&result);
+            }
+            per_host[host].push(result);
+        }
+
+        let mut hosts = Vec::with_capacity(scanners.len());
+        let hosts_iter = scanners.iter().zip(self.targets).zip(hostnames).zip(per_host);
+        for (((scanner, target), hostname), results) in hosts_iter {
+            let results = scanner.complete(results).await;
+            for observer in &self.observers {
+                lock(observer).on_scan_completed(&results);
+            }
+            hosts.push(HostResults { target, hostname, results });
+        }
+        Ok(hosts)
+    }
+
+    /// Scanner for one target, with the config's target swapped in
+    fn scanner(&self, config: &ScanConfig, target: &Target) -> ScanResult<PortScanner> {
+        let mut host_config = config.clone();
+        host_config.target_ip = target.ip;
+        host_config.scope_id = target.scope_id;
+
+        let mut scanner = PortScanner::new(host_config)?;
+        if let Some(detectors) = &self.detectors {
+            scanner = scanner.with_detectors(Arc::clone(detectors));
+        }
+        if let Some(strategy) = &self.strategy {
+            scanner = scanner.with_strategy(Arc::clone(strategy));
+        }
+        Ok(scanner)
+    }
+}
+
+fn lock(observer: &SharedObserver) -> std::sync::MutexGuard<'_, Box<dyn ScanObserver>> {
+    observer.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Builder for `Scan`.
+///
+/// Targets and ports have dedicated methods; every other setting goes
+/// through `configure`. Without `ports`, the common ports are scanned.
+pub struct ScanBuilder {
+    targets: Vec<Target>,
+    config: ScanConfigBuilder,
+    detectors: Option<Arc<DetectorRegistry>>,
+    strategy: Option<Arc<dyn ScanStrategy>>,
+    observers: Vec<SharedObserver>,
+    interleave_hosts: bool,
+    resolver: Option<Arc<dyn ReverseResolver>>,
+}
+
+impl ScanBuilder {
+    pub fn new() -> Self {
+        Self {
+            targets: Vec::new(),
+            config: ScanConfigBuilder::new().common_ports(),
+            detectors: None,
+            strategy: None,
+            observers: Vec::new(),
+            interleave_hosts: false,
+            resolver: None,
+        }
+    }
+
+    /// Add targets
+    pub fn targets<T: Into<Target>>(mut self, targets: impl IntoIterator<Item = T>) -> Self {
+        self.targets.extend(targets.into_iter().map(Into::into));
+        self
+    }
+
+    /// Add a host (optionally with an IPv6 zone), every host of a CIDR block,
+    /// or every host of an octet range such as `192.168.1.1-20`
+    pub fn target_spec(mut self, spec: &str) -> ConfigResult<Self> {
+        self.targets.extend(targets::expand_target(spec)?);
+        Ok(self)
+    }
+
+    /// Scan exactly these ports
+    pub fn ports(mut self, ports: Vec<Port>) -> Self {
+        self.config = self.config.custom_ports(ports);
+        self
+    }
+
+    /// Scan a port range, or any other `ScanMode`
+    pub fn scan_mode(mut self, mode: ScanMode) -> Self {
+        self.config = self.config.scan_mode(mode);
+        self
+    }
+
+    /// Adjust any other setting on the underlying config builder
+    pub fn configure(mut self, f: impl FnOnce(ScanConfigBuilder) -> ScanConfigBuilder) -> Self {
+        self.config = f(self.config);
+        self
+    }
+
+    /// Detect services with `detectors` instead of the built-in registry
+    pub fn detectors(mut self, detectors: DetectorRegistry) -> Self {
+        self.detectors = Some(Arc::new(detectors));
+        self
+    }
+
+    /// Probe every transport with `strategy` instead of the one the config selects
+    pub fn strategy(mut self, strategy: Arc<dyn ScanStrategy>) -> Self {
+        self.strategy = Some(strategy);
+        self
+    }
+
+    /// Notify `observer` of every result; pass an `Arc<Mutex<_>>` to read it afterwards
+    pub fn observer(mut self, observer: impl ScanObserver + 'static) -> Self {
+        self.observers.push(Arc::new(Mutex::new(Box::new(observer))));
+        self
+    }
+
+    /// Scan all targets together, each host's ports shuffled and hosts
+    /// taking turns, so that no host gets a rapid sequential sweep. Use
+    /// `randomize_scan_order` to shuffle all host:port pairs at once instead.
+    ///
+    /// ```
+    /// use port_scanner::application::Scan;
+    /// use port_scanner::infrastructure::testing::{Reply, ScriptedConnector};
+    /// use port_scanner::scanning::StandardScan;
+    /// use std::net::IpAddr;
+    /// use std::sync::Arc;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// let hosts: Vec<IpAddr> = (1..=3).map(|i| IpAddr::from([127, 0, 0, i])).collect();
+    /// let connector = Arc::new(ScriptedConnector::new(Reply::Refuse));
+    /// Scan::builder()
+    ///     .targets(hosts.clone())
+    ///     .ports((1..=30).collect())
+    ///     .configure(|b| b.parallel(false))
+    ///     .strategy(Arc::new(StandardScan::new().with_connector(connector.clone())))
+    ///     .interleave_hosts(true)
+    ///     .run()
+    ///     .await?;
+    ///
+    /// let connects = connector.connects();
+    /// for host in &hosts {
+    ///     let mut ports: Vec<u16> = connects.iter().filter(|a| a.ip() == *host).map(|a| a.port()).collect();
+    ///     assert_ne!(ports, (1..=30).collect::<Vec<u16>>());
+    ///     ports.sort_unstable();
+    ///     assert_eq!(ports, (1..=30).collect::<Vec<u16>>());
+    /// }
+    /// // Connects to the same host are dispatched three apart
+    /// assert!(connects.windows(3).all(|w| w[0].ip() != w[1].ip() && w[0].ip() != w[2].ip() && w[1].ip() != w[2].ip()));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn interleave_hosts(mut self, interleave: bool) -> Self {
+        self.interleave_hosts = interleave;
+        self
+    }
+
+    /// Look up each target's PTR name before scanning and attach it to its
+    /// `HostResults`. Lookups run concurrently, each bounded by
+    /// `REVERSE_DNS_TIMEOUT`; a failed lookup leaves the hostname `None`.
+    pub fn reverse_dns(mut self, enabled: bool) -> Self {
+        self.resolver = enabled.then(|| Arc::new(SystemResolver) as Arc<dyn ReverseResolver>);
+        self
+    }
+
+    /// Reverse DNS through `resolver` instead of the system resolver
+    ///
+    /// ```
+    /// use port_scanner::application::Scan;
+    /// use port_scanner::infrastructure::ReverseResolver;
+    /// use std::net::IpAddr;
+    ///
+    /// struct Stub;
+    ///
+    /// #[async_trait::async_trait]
+    /// impl ReverseResolver for Stub {
+    ///     async fn reverse(&self, ip: IpAddr) -> Option<String> {
+    ///         (ip == IpAddr::from([127, 0, 0, 1])).then(|| "localhost.test".to_string())
+    ///     }
+    /// }
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// let hosts = Scan::builder()
+    ///     .target_spec("127.0.0.1")?
+    ///     .target_spec("127.0.0.2")?
+    ///     .ports(vec![1])
+    ///     .resolver(Stub)
+    ///     .run()
+    ///     .await?;
+    /// assert_eq!(hosts[0].hostname.as_deref(), Some("localhost.test"));
+    /// assert_eq!(hosts[1].hostname, None);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn resolver(mut self, resolver: impl ReverseResolver + 'static) -> Self {
+        self.resolver = Some(Arc::new(resolver));
+        self
+    }
+
+    pub fn build(self) -> Scan {
+        Scan {
+            targets: self.targets,
+            config: self.config,
+            detectors: self.detectors,
+            strategy: self.strategy,
+            observers: self.observers,
+            interleave_hosts: self.interleave_hosts,
+            resolver: self.resolver,
+        }
+    }
+
+    /// Build and run the scan
+    pub async fn run(self) -> ScanResult<Vec<HostResults>> {
+        self.build().run().await
+    }
+}
+
+impl Default for ScanBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,61 +1,655 @@
-/// Main port scanning use case (async)
+//! Main port scanning use case (async)
 
+use futures::{Stream, StreamExt};
+use rand::Rng;
+use tokio::sync::mpsc;
+use tracing::{info, debug, warn};
+
+use std::collections::HashSet;
+use std::net::SocketAddr;
 use std::sync::Arc;
-use tracing::{info, debug};
 
-use crate::domain::{PortScanResult, ScanResults};
-use crate::scanning::{ScanConfig, ScanStrategyFactory, ParallelExecutor, SequentialExecutor};
-use crate::errors::ScanResult;
+use crate::constants::{MIN_PORT, RESULT_CHANNEL_CAPACITY, FILTERED_VERIFY_ATTEMPTS, FILTERED_VERIFY_TIMEOUT_FACTOR, CLOSED_PORT_SAMPLE_RANGE};
+use crate::domain::{HostState, Port, PortScanResult, Protocol, ScanResults};
+use crate::scanning::{privileges, check_privileges, ScanCancellation, ScanConfig, ScanStrategy, ScanStrategyFactory, DetectorRegistry, ParallelExecutor, SequentialExecutor};
+use crate::errors::{ScanError, ScanResult};
+use crate::infrastructure::{network_utils, AttemptCounter, ScanRng};
+use crate::application::{OsGuesser, PartialScanResults, ScanProgress};
+
+/// Callback given aggregate counts and percent complete
+type ProgressCallback = Arc<dyn Fn(&PartialScanResults, f32) + Send + Sync>;
 
 /// Port scanner orchestrator (async)
 pub struct PortScanner {
     config: ScanConfig,
+    progress: Arc<ScanProgress>,
+    /// Replaces the built-in detectors when set
+    detectors: Option<Arc<DetectorRegistry>>,
+    /// Called every `.0` results with the running counts
+    on_progress: Option<(usize, ProgressCallback)>,
+    /// Replaces the strategy picked from the config when set
+    strategy: Option<Arc<dyn ScanStrategy>>,
+    /// Ports to probe in probe order, drawn once so that a random order or
+    /// sample is the same for `dry_run` and every scan
+    ports: Vec<Port>,
 }
 
 impl PortScanner {
-    pub fn new(config: ScanConfig) -> ScanResult<Self> {
+    /// Fails on invalid settings, or with `ScanError::PermissionDenied` when
+    /// `require_privileges` is set and a requested feature cannot run.
+    /// Otherwise features that lack privileges are reported once and replaced
+    /// by their fallback.
+    pub fn new(mut config: ScanConfig) -> ScanResult<Self> {
         config.validate()?;
-        Ok(Self { config })
+        for warning in check_privileges(&config) {
+            if config.require_privileges {
+                return Err(ScanError::PermissionDenied(format!(
+                    "{} cannot run ({}); {}",
+                    warning.feature, warning.reason, warning.hint
+                )));
+            }
+            privileges::warn_once(&warning);
+        }
+        if let Some(warning) = config.port_clamp_warning() {
+            warn!("{}", warning);
+        }
+        // Clones of the caller's config must not count into or cancel this
+        // scanner, and a seeded scanner starts its random sequence afresh
+        config.connection_attempts = AttemptCounter::new();
+        config.cancellation = ScanCancellation::new();
+        config.rng = ScanRng::new(config.seed);
+        let ports = config.get_ports();
+        Ok(Self { config, progress: Arc::new(ScanProgress::new()), detectors: None, on_progress: None, strategy: None, ports })
+    }
+
+    /// Detect services with `detectors` instead of the built-in registry
+    pub fn with_detectors(mut self, detectors: Arc<DetectorRegistry>) -> Self {
+        self.detectors = Some(detectors);
+        self
+    }
+
+    /// Probe every transport with `strategy` instead of the one the config selects
+    pub fn with_strategy(mut self, strategy: Arc<dyn ScanStrategy>) -> Self {
+        self.strategy = Some(strategy);
+        self
+    }
+
+    /// Call `callback` with the running counts and percent complete after
+    /// every `every` results, and once more after the last result.
+    ///
+    /// ```
+    /// use port_scanner::prelude::*;
+    /// use std::net::{IpAddr, Ipv4Addr};
+    /// use std::sync::{Arc, Mutex};
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// let config = ScanConfigBuilder::new()
+    ///     .target(IpAddr::V4(Ipv4Addr::LOCALHOST))
+    ///     .range(1, 25)
+    ///     .timeout(std::time::Duration::from_millis(200))
+    ///     .build()?;
+    /// let calls = Arc::new(Mutex::new(Vec::new()));
+    /// let seen = Arc::clone(&calls);
+    /// let scanner = PortScanner::new(config)?
+    ///     .on_progress(10, move |partial, percent| seen.lock().unwrap().push((partial.scanned, percent)));
+    /// scanner.scan_all(|_| {}).await;
+    ///
+    /// let calls = calls.lock().unwrap();
+    /// assert_eq!(calls.iter().map(|c| c.0).collect::<Vec<_>>(), vec![10, 20, 25]);
+    /// assert_eq!(calls[2].1, 100.0);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn on_progress<F>(mut self, every: usize, callback: F) -> Self
+    where
+        F: Fn(&PartialScanResults, f32) + Send + Sync + 'static,
+    {
+        self.on_progress = Some((every.max(1), Arc::new(callback)));
+        self
+    }
+
+    /// Strategy probing over `protocol`, using the custom detectors if any
+    pub(crate) fn strategy(&self, config: &ScanConfig, protocol: Protocol) -> Arc<dyn ScanStrategy> {
+        if let Some(strategy) = &self.strategy {
+            return Arc::clone(strategy);
+        }
+        let detectors = match &self.detectors {
+            Some(detectors) => Arc::clone(detectors),
+            None => Arc::new(DetectorRegistry::for_config(config)),
+        };
+        ScanStrategyFactory::create_for_protocol(config, detectors, protocol)
+    }
+
+    /// One strategy per transport in `config.protocol`, TCP first
+    pub(crate) fn strategies(&self, config: &ScanConfig) -> Vec<Arc<dyn ScanStrategy>> {
+        config.protocol.protocols().iter()
+            .map(|&protocol| self.strategy(config, protocol))
+            .collect()
     }
 
     pub fn config(&self) -> &ScanConfig {
         &self.config
     }
 
+    /// Ports to probe, in the order they are probed
+    pub(crate) fn ports(&self) -> &[Port] {
+        &self.ports
+    }
+
+    /// Cancel the running scan: no new probes start, and ports already
+    /// found open get `drain_timeout` to finish detection before the scan
+    /// returns what it has
+    pub fn cancel(&self) {
+        self.config.cancellation.cancel();
+    }
+
+    /// Progress handle for the current scan; poll it while `scan_all` runs.
+    ///
+    /// Counters reset whenever a new scan starts on this scanner.
+    pub fn progress(&self) -> Arc<ScanProgress> {
+        Arc::clone(&self.progress)
+    }
+
     /// Scan all configured ports (async)
+    ///
+    /// `callback` sees results in completion order; the returned results
+    /// are sorted by port, TCP before UDP, however the probes finished.
+    ///
+    /// ```
+    /// use port_scanner::prelude::*;
+    /// use std::net::TcpListener;
+    /// use std::sync::{Arc, Mutex};
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// // Open ports answer after closed ones, and probes go out shuffled
+    /// let listeners = (0..4).map(|_| TcpListener::bind("127.0.0.1:0")).collect::<Result<Vec<_>, _>>()?;
+    /// let mut ports = vec![1, 2, 3];
+    /// for listener in &listeners {
+    ///     ports.push(listener.local_addr()?.port());
+    /// }
+    /// ports.reverse();
+    /// let config = ScanConfigBuilder::new()
+    ///     .target("127.0.0.1".parse()?)
+    ///     .custom_ports(ports)
+    ///     .randomize_scan_order(true)
+    ///     .build()?;
+    ///
+    /// let seen = Arc::new(Mutex::new(Vec::new()));
+    /// let log = Arc::clone(&seen);
+    /// let results = PortScanner::new(config)?
+    ///     .scan_all(move |result| log.lock().unwrap().push(result.port))
+    ///     .await;
+    ///
+    /// assert_eq!(results.total_ports, 7);
+    /// assert_eq!(seen.lock().unwrap().len(), 7);
+    /// assert!(results.results.windows(2).all(|pair| pair[0].port < pair[1].port));
+    /// # Ok(())
+    /// # }
+    /// ```
     pub async fn scan_all<F>(&self, callback: F) -> ScanResults
     where
         F: Fn(&PortScanResult) + Send + Sync + 'static,
     {
-        info!("Starting port scan on {}", self.config.target_ip);
-        info!("Scan mode: {:?}", self.config.scan_mode);
-        info!("Timeout: {:?}", self.config.timeout);
-        info!("Parallel: {}", self.config.parallel);
-        
-        let ports = self.config.get_ports();
-        info!("Total ports to scan: {}", ports.len());
+        let mut stream = Box::pin(self.scan_stream());
+        let mut results = Vec::new();
         
-        // Create the appropriate strategy
-        let strategy = ScanStrategyFactory::create(&self.config);
-        debug!("Using scan strategy: {}", strategy.name());
+        while let Some(result) = stream.next().await {
+            callback(&result);
+            results.push(result);
+        }
         
-        // Execute async scan
-        let results = if self.config.parallel {
-            let executor = ParallelExecutor::new(self.config.thread_count * 4); // More concurrent tasks
-            executor.scan_ports(ports, strategy, &self.config, callback).await
+        self.complete(results).await
+    }
+
+    /// `scan_all` for callers without an async runtime: runs the scan on a
+    /// runtime of its own and blocks until it finishes.
+    ///
+    /// Fails with `ScanError::Runtime` when called from inside a tokio
+    /// runtime (blocking there would stall it; use `scan_all` instead) or
+    /// when the runtime cannot be started.
+    ///
+    /// ```
+    /// use port_scanner::prelude::*;
+    /// use std::net::TcpListener;
+    ///
+    /// fn main() -> anyhow::Result<()> {
+    ///     let listener = TcpListener::bind("127.0.0.1:0")?;
+    ///     let open = listener.local_addr()?.port();
+    ///     let config = ScanConfigBuilder::new()
+    ///         .target("127.0.0.1".parse()?)
+    ///         .custom_ports(vec![open, 1])
+    ///         .build()?;
+    ///     let scanner = PortScanner::new(config)?;
+    ///
+    ///     let results = scanner.scan_all_blocking(|_| {})?;
+    ///     assert_eq!(results.total_ports, 2);
+    ///     assert_eq!(results.open_ports, 1);
+    ///
+    ///     // Not from async code
+    ///     let runtime = tokio::runtime::Runtime::new()?;
+    ///     let nested = runtime.block_on(async { scanner.scan_all_blocking(|_| {}) });
+    ///     assert!(matches!(nested, Err(ScanError::Runtime(_))));
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn scan_all_blocking<F>(&self, callback: F) -> ScanResult<ScanResults>
+    where
+        F: Fn(&PortScanResult) + Send + Sync + 'static,
+    {
+        if tokio::runtime::Handle::try_current().is_ok() {
+            return Err(ScanError::Runtime(
+                "scan_all_blocking called from within a tokio runtime; use scan_all".to_string(),
+            ));
+        }
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .build()
+            .map_err(|e| ScanError::Runtime(e.to_string()))?;
+        Ok(runtime.block_on(self.scan_all(callback)))
+    }
+
+    /// Like `scan_all`, but fails with `ScanError::Unreachable` before probing
+    /// when the target has no route, instead of reporting every port as an error.
+    ///
+    /// ```
+    /// use port_scanner::prelude::*;
+    /// use port_scanner::scanning::ScanStrategy;
+    /// use std::io;
+    /// use std::net::{IpAddr, SocketAddr};
+    /// use std::sync::Arc;
+    /// use std::sync::atomic::{AtomicUsize, Ordering};
+    ///
+    /// struct NoRoute(AtomicUsize);
+    ///
+    /// #[async_trait::async_trait]
+    /// impl ScanStrategy for NoRoute {
+    ///     async fn scan_async(&self, port: Port, _: IpAddr, _: &ScanConfig) -> PortScanResult {
+    ///         self.0.fetch_add(1, Ordering::Relaxed);
+    ///         PortScanResult::new(port, PortStatus::Error("Network is unreachable".to_string()))
+    ///     }
+    ///     fn name(&self) -> &'static str { "no route" }
+    ///     fn check_route(&self, _: &SocketAddr) -> io::Result<()> {
+    ///         Err(io::ErrorKind::NetworkUnreachable.into())
+    ///     }
+    /// }
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// let config = ScanConfigBuilder::new()
+    ///     .target("192.0.2.1".parse::<IpAddr>()?)
+    ///     .range(1, 1000)
+    ///     .build()?;
+    /// let strategy = Arc::new(NoRoute(AtomicUsize::new(0)));
+    /// let scanner = PortScanner::new(config)?.with_strategy(strategy.clone());
+    ///
+    /// let error = scanner.try_scan_all(|_| {}).await.unwrap_err();
+    /// assert!(matches!(error, ScanError::Unreachable { .. }));
+    /// assert_eq!(strategy.0.load(Ordering::Relaxed), 0);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn try_scan_all<F>(&self, callback: F) -> ScanResult<ScanResults>
+    where
+        F: Fn(&PortScanResult) + Send + Sync + 'static,
+    {
+        self.check_reachable()?;
+        Ok(self.scan_all(callback).await)
+    }
+
+    /// Fail with `ScanError::Unreachable` if no route leads to the target.
+    ///
+    /// Other route-check failures are logged and ignored; the scan itself
+    /// will report them per port.
+    pub fn check_reachable(&self) -> ScanResult<()> {
+        let addr = network_utils::socket_addr(self.config.target_ip, MIN_PORT, self.config.scope_id);
+        for strategy in self.strategies(&self.config) {
+            match strategy.check_route(&addr) {
+                Err(e) if network_utils::is_unreachable(&e) => {
+                    return Err(ScanError::Unreachable {
+                        target: self.config.target_ip.to_string(),
+                        reason: e.to_string(),
+                    });
+                }
+                Err(e) => debug!("Route check for {} failed: {}", self.config.target_ip, e),
+                Ok(()) => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Re-probe filtered TCP ports with a longer timeout.
+    ///
+    /// A port stays filtered only if it is silent on every attempt; any
+    /// answer (open, closed, reset) replaces the original result. Probes
+    /// run concurrently under the scan's concurrency limit.
+    async fn verify_filtered(&self, results: &mut [PortScanResult]) {
+        let filtered: Vec<usize> = results.iter()
+            .enumerate()
+            .filter(|(_, r)| r.status.is_filtered() && r.protocol == Protocol::Tcp)
+            .map(|(i, _)| i)
+            .collect();
+        if filtered.is_empty() {
+            return;
+        }
+        info!("Verifying {} filtered ports", filtered.len());
+
+        let mut config = self.config.clone();
+        config.timeout = self.config.timeout * FILTERED_VERIFY_TIMEOUT_FACTOR;
+        let strategy = self.strategy(&config, Protocol::Tcp);
+        let config = &config;
+
+        let verified: Vec<(usize, PortScanResult)> = futures::stream::iter(filtered)
+            .map(|index| {
+                let strategy = Arc::clone(&strategy);
+                let port = results[index].port;
+                async move {
+                    for attempt in 1..=FILTERED_VERIFY_ATTEMPTS {
+                        let result = config.scope(strategy.scan_async(port, config.target_ip, config)).await;
+                        if !result.status.is_filtered() {
+                            debug!("Port {} answered on verification attempt {}", port, attempt);
+                            return Some((index, result));
+                        }
+                    }
+                    None
+                }
+            })
+            .buffer_unordered(self.config.concurrency())
+            .filter_map(|verified| async move { verified })
+            .collect()
+            .await;
+
+        info!("{} filtered ports reclassified after verification", verified.len());
+        for (index, result) in verified {
+            results[index] = result;
+        }
+    }
+
+    /// Tell a firewalled host from a down one when most ports were filtered.
+    ///
+    /// Any TCP answer in the results already shows the host is up.
+    /// Otherwise `closed_port_samples` random high ports are probed: an
+    /// answer there means a firewall drops the scanned ports, silence means
+    /// the host is likely down. Inconclusive probes (errors) give `None`.
+    ///
+    /// ```
+    /// use port_scanner::prelude::*;
+    /// use port_scanner::domain::HostState;
+    /// use port_scanner::scanning::ScanStrategy;
+    /// use std::net::IpAddr;
+    /// use std::sync::Arc;
+    ///
+    /// /// Drops ports below 1024; above that, answers RST if the host is up
+    /// struct Firewall { host_up: bool }
+    ///
+    /// #[async_trait::async_trait]
+    /// impl ScanStrategy for Firewall {
+    ///     async fn scan_async(&self, port: Port, _: IpAddr, _: &ScanConfig) -> PortScanResult {
+    ///         let status = if self.host_up && port >= 1024 { PortStatus::Closed } else { PortStatus::Filtered };
+    ///         PortScanResult::new(port, status)
+    ///     }
+    ///     fn name(&self) -> &'static str { "firewall" }
+    /// }
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// for (host_up, expected) in [(true, HostState::HostUpFirewalled), (false, HostState::HostLikelyDown)] {
+    ///     let config = ScanConfigBuilder::new()
+    ///         .target("192.0.2.1".parse::<IpAddr>()?)
+    ///         .range(1, 100)
+    ///         .build()?;
+    ///     let scanner = PortScanner::new(config)?.with_strategy(Arc::new(Firewall { host_up }));
+    ///     let results = scanner.scan_all(|_| {}).await;
+    ///     assert_eq!(results.filtered_ports, 100);
+    ///     assert_eq!(results.host_state, Some(expected));
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    async fn classify_host(&self, results: &ScanResults) -> Option<HostState> {
+        let samples = self.config.closed_port_samples;
+        if samples == 0 || results.filtered_ports * 2 <= results.total_ports {
+            return None;
+        }
+        let answered = |result: &PortScanResult| result.protocol == Protocol::Tcp
+            && (result.status.is_open() || result.status.is_closed() || result.status.is_reset());
+        if results.results.iter().any(answered) {
+            return Some(HostState::HostUpFirewalled);
+        }
+        if !self.config.protocol.protocols().contains(&Protocol::Tcp) {
+            return None;
+        }
+
+        let scanned: HashSet<Port> = results.results.iter().map(|r| r.port).collect();
+        let (low, high) = CLOSED_PORT_SAMPLE_RANGE;
+        let ports: Vec<Port> = self.config.rng.with(|rng| {
+            (0..samples * 8)
+                .map(|_| rng.gen_range(low..=high))
+                .filter(|port| !scanned.contains(port))
+                .take(samples)
+                .collect()
+        });
+        debug!("{} of {} ports filtered; sampling closed ports {:?}", results.filtered_ports, results.total_ports, ports);
+
+        let strategy = self.strategy(&self.config, Protocol::Tcp);
+        let config = &self.config;
+        let probes = ports.into_iter().map(|port| {
+            let strategy = Arc::clone(&strategy);
+            async move { config.scope(strategy.scan_async(port, config.target_ip, config)).await }
+        });
+        let sampled = futures::future::join_all(probes).await;
+
+        let state = if sampled.iter().any(answered) {
+            Some(HostState::HostUpFirewalled)
+        } else if !sampled.is_empty() && sampled.iter().all(|r| r.status.is_filtered()) {
+            Some(HostState::HostLikelyDown)
         } else {
-            let executor = SequentialExecutor::new();
-            executor.scan_ports(ports, strategy, &self.config, callback).await
+            None
         };
-        
+        info!("Host {} state from closed-port sampling: {:?}", self.config.target_ip, state);
+        state
+    }
+
+    /// Turn one host's streamed results into `ScanResults`: verify filtered
+    /// ports, summarize, and classify the host
+    pub(crate) async fn complete(&self, mut results: Vec<PortScanResult>) -> ScanResults {
+        if self.config.verify_filtered {
+            self.verify_filtered(&mut results).await;
+        }
+        let mut results = self.finish(results);
+        results.host_state = self.classify_host(&results).await;
+        // Verification and closed-port sampling connect too
+        results.connection_attempts = self.config.connection_attempts.get();
+        results
+    }
+
+    /// Collect streamed results into `ScanResults` sorted by port, flagging
+    /// a tripped open-port limit and guessing the host OS from what was found
+    fn finish(&self, results: Vec<PortScanResult>) -> ScanResults {
         info!("Scan completed. Total results: {}", results.len());
-        ScanResults::from(results)
+        let mut results = ScanResults::from(results);
+        // Completion order depends on timing; reports want a stable order
+        results.sort_by_port();
+        if let Some(limit) = self.config.max_open_ports {
+            results.honeypot_suspected = results.open_ports > limit;
+        }
+        results.os_guess = OsGuesser::guess(&results);
+        results.connection_attempts = self.config.connection_attempts.get();
+        results
+    }
+
+    /// Scan all configured ports, yielding each result as soon as it completes.
+    ///
+    /// The scan runs on a spawned task, so this must be called from within a
+    /// tokio runtime. Dropping the stream stops the scan: probes in flight
+    /// are aborted and no new ones start.
+    ///
+    /// With `max_scan_duration` set, the scan is cancelled at the deadline
+    /// (see `cancel`) and the stream ends at most `drain_timeout` later;
+    /// unscanned ports are omitted.
+    ///
+    /// ```
+    /// use futures::StreamExt;
+    /// use port_scanner::infrastructure::testing::{Reply, ScriptedConnector};
+    /// use port_scanner::prelude::*;
+    /// use port_scanner::scanning::StandardScan;
+    /// use std::collections::HashSet;
+    /// use std::sync::Arc;
+    /// use std::time::Duration;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// let scanner = |last: Port| -> anyhow::Result<PortScanner> {
+    ///     let config = ScanConfigBuilder::new()
+    ///         .target("127.0.0.1".parse()?)
+    ///         .range(1, last)
+    ///         .max_concurrent(Some(10))
+    ///         .build()?;
+    ///     let slow = ScriptedConnector::new(Reply::Refuse).delay(Duration::from_millis(20));
+    ///     let strategy = StandardScan::new().with_connector(Arc::new(slow));
+    ///     Ok(PortScanner::new(config)?.with_strategy(Arc::new(strategy)))
+    /// };
+    ///
+    /// // Every port arrives exactly once
+    /// let ports: Vec<Port> = scanner(50)?.scan_stream().map(|result| result.port).collect().await;
+    /// assert_eq!(ports.len(), 50);
+    /// assert_eq!(ports.iter().collect::<HashSet<_>>().len(), 50);
+    ///
+    /// // Dropping the stream early stops probing
+    /// let scanner = scanner(1000)?;
+    /// let first: Vec<_> = scanner.scan_stream().take(5).collect().await;
+    /// assert_eq!(first.len(), 5);
+    /// tokio::time::sleep(Duration::from_millis(500)).await;
+    /// assert!(scanner.config().connection_attempts.get() < 40);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn scan_stream(&self) -> impl Stream<Item = PortScanResult> + Send + 'static {
+        let (tx, rx) = mpsc::channel(RESULT_CHANNEL_CAPACITY);
+        let config = self.config.clone();
+        let strategies = self.strategies(&config);
+        let deadline = config.max_scan_duration.map(|limit| tokio::time::Instant::now() + limit);
+        let cancellation = config.cancellation.clone();
+        let drain_timeout = config.drain_timeout;
+        let progress = Arc::clone(&self.progress);
+        progress.start(config.probe_count());
+        config.connection_attempts.reset();
+        config.cancellation.reset();
+        let on_progress = self.on_progress.clone();
+        let ports = self.ports.clone();
+        
+        let scan = ScanTask(tokio::spawn(async move {
+            Self::execute(&config, ports, strategies, tx).await;
+        }));
+        
+        let state = (rx, scan, progress, on_progress, deadline, cancellation);
+        futures::stream::unfold(state, move |(mut rx, scan, progress, on_progress, mut deadline, cancellation)| async move {
+            let next = loop {
+                let Some(at) = deadline else {
+                    break rx.recv().await;
+                };
+                match tokio::time::timeout_at(at, rx.recv()).await {
+                    Ok(next) => break next,
+                    Err(_) if !cancellation.is_cancelled() => {
+                        warn!("Scan time limit reached, finishing detection on open ports");
+                        cancellation.cancel();
+                        deadline = Some(at + drain_timeout);
+                    }
+                    Err(_) => {
+                        warn!("Drain timeout reached, returning partial results");
+                        // Aborting the scan task drops its JoinSet, cancelling in-flight probes
+                        scan.0.abort();
+                        break None;
+                    }
+                }
+            };
+            if let Some(result) = &next {
+                let scanned = progress.record(&result.status);
+                if let Some((every, callback)) = &on_progress {
+                    if scanned % every == 0 || scanned == progress.total() {
+                        let partial = progress.partial();
+                        callback(&partial, partial.percent_complete());
+                    }
+                }
+            }
+            next.map(|result| (result, (rx, scan, progress, on_progress, deadline, cancellation)))
+        })
+    }
+
+    /// Addresses `scan_all` would probe, in the order it would probe them,
+    /// without any network activity. A random order or sample is drawn once
+    /// per scanner, so the scan probes exactly these.
+    ///
+    /// ```
+    /// use port_scanner::infrastructure::testing::{Reply, ScriptedConnector};
+    /// use port_scanner::prelude::*;
+    /// use port_scanner::scanning::StandardScan;
+    /// use std::sync::Arc;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// let config = ScanConfigBuilder::new()
+    ///     .target("10.0.0.7".parse()?)
+    ///     .range(1, 1024)
+    ///     .ordering(ScanOrder::Random)
+    ///     .parallel(false)
+    ///     .build()?;
+    /// let connector = Arc::new(ScriptedConnector::new(Reply::Refuse));
+    /// let scanner = PortScanner::new(config)?
+    ///     .with_strategy(Arc::new(StandardScan::new().with_connector(connector.clone())));
+    ///
+    /// let probes = scanner.dry_run();
+    /// assert_eq!(probes.len(), 1024);
+    /// assert!(connector.connects().is_empty());
+    ///
+    /// scanner.scan_all(|_| {}).await;
+    /// assert_eq!(connector.connects(), probes);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn dry_run(&self) -> Vec<SocketAddr> {
+        self.ports.iter().copied()
+            .map(|port| network_utils::socket_addr(self.config.target_ip, port, self.config.scope_id))
+            .collect()
     }
 
-    /// Scan a single port (async)
+    /// Scan a single port over the first configured transport (async)
     pub async fn scan_port(&self, port: u16) -> PortScanResult {
-        let strategy = ScanStrategyFactory::create(&self.config);
-        strategy.scan_async(port, self.config.target_ip, &self.config).await
+        let strategy = self.strategy(&self.config, self.config.protocol.protocols()[0]);
+        self.config.scope(strategy.scan_async(port, self.config.target_ip, &self.config)).await
+    }
+
+    /// Probe every port with each strategy in turn, one pass per transport
+    async fn execute(config: &ScanConfig, ports: Vec<Port>, strategies: Vec<Arc<dyn ScanStrategy>>, tx: mpsc::Sender<PortScanResult>) {
+        info!("Starting port scan on {}", config.target_ip);
+        info!("Scan mode: {:?}", config.scan_mode);
+        info!("Timeout: {:?}", config.timeout);
+        info!("Parallel: {}", config.parallel);
+        
+        info!("Total ports to scan: {}", ports.len());
+        
+        for strategy in strategies {
+            debug!("Using scan strategy: {}", strategy.name());
+            
+            // Execute async scan
+            if config.parallel {
+                let executor = ParallelExecutor::for_config(config);
+                debug!("Parallel executor limited to {} concurrent probes", executor.max_concurrent());
+                executor.stream_ports(ports.clone(), strategy, config, tx.clone()).await;
+            } else {
+                let executor = SequentialExecutor::new();
+                executor.stream_ports(ports.clone(), strategy, config, tx.clone()).await;
+            }
+        }
     }
 }
 
+/// Scan task behind `scan_stream`, aborted when the stream is dropped
+struct ScanTask(tokio::task::JoinHandle<()>);
+
+impl Drop for ScanTask {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
@@ -1,10 +1,17 @@
-/// Application constants
+//! Application constants
 
 use std::time::Duration;
 
+// Report format; bump REPORT_SCHEMA_VERSION on any breaking JSON field change
+pub const REPORT_SCHEMA_VERSION: &str = "1.0";
+pub const TOOL_VERSION: &str = env!("CARGO_PKG_VERSION");
+
 // Port ranges
 pub const MIN_PORT: u16 = 1;
 pub const MAX_PORT: u16 = 65535;
+
+// Largest CIDR block expanded into individual targets (a /16)
+pub const MAX_EXPANDED_TARGETS: usize = 65536;
 pub const HIGH_PORT_START: u16 = 1024;
 
 // Default configuration values
@@ -15,15 +22,153 @@ pub const DEFAULT_DETECT_VERSIONS: bool = false;
 pub const DEFAULT_DETECT_OS: bool = false;
 pub const DEFAULT_PARALLEL: bool = true;
 pub const DEFAULT_RANDOMIZE_SOURCE: bool = false;
+pub const DEFAULT_REUSE_CONNECTION: bool = false;
+pub const DEFAULT_GRACEFUL_CLOSE: bool = true;
+pub const DEFAULT_VERIFY_FILTERED: bool = false;
+pub const DEFAULT_RETRY_ON_RESET: bool = false;
+pub const DEFAULT_PROBE_RETRIES: usize = 0;
+pub const DEFAULT_UDP_ICMP_CLOSED: bool = true;
+pub const DEFAULT_RAMP_UP: bool = false;
+pub const DEFAULT_REQUIRE_PRIVILEGES: bool = false;
+
+// Async task limits (ParallelExecutor clamps to MIN..=MAX)
+pub const MIN_CONCURRENCY: usize = 10;
+pub const MAX_CONCURRENCY: usize = 2000;
+pub const TASKS_PER_THREAD: usize = 4;
+
+// Concurrency ramp-up: start small, double every interval up to the limit
+pub const RAMP_UP_INITIAL_CONCURRENCY: usize = 10;
+pub const RAMP_UP_INTERVAL: Duration = Duration::from_millis(500);
 
 // Timeout durations
 pub const DEFAULT_TIMEOUT: Duration = Duration::from_millis(DEFAULT_TIMEOUT_MS);
 pub const BANNER_READ_TIMEOUT_MS: u64 = 2000;
 pub const SMB_TIMEOUT_MS: u64 = 3000;
+pub const DEFAULT_DRAIN_TIMEOUT: Duration = Duration::from_secs(2);
+
+// Probes for silent ports without a port-specific probe, tried in order until
+// one is answered; each waits up to the read timeout, all within the budget
+pub const GENERIC_PROBES: &[&[u8]] = &[b"\r\n", b"GET / HTTP/1.0\r\n\r\n", b"HELP\r\n"];
+
+// Response headers an HTTP service is checked for; absent ones are reported
+pub const HTTP_SECURITY_HEADERS: &[&str] = &[
+    "Strict-Transport-Security",
+    "Content-Security-Policy",
+    "X-Frame-Options",
+    "X-Content-Type-Options",
+];
+pub const GENERIC_PROBE_READ_TIMEOUT: Duration = Duration::from_millis(750);
+pub const GENERIC_PROBE_BUDGET: Duration = Duration::from_secs(3);
+
+// Reverse DNS: each PTR lookup is bounded, and only so many run at once
+pub const REVERSE_DNS_TIMEOUT: Duration = Duration::from_secs(2);
+pub const REVERSE_DNS_CONCURRENCY: usize = 32;
+
+// Top 100 ports ranked by how often they are found open, most common first
+pub const TOP_PORTS_BY_FREQUENCY: &[u16] = &[
+    80, 23, 443, 21, 22, 25, 3389, 110, 445, 139, 143, 53, 135, 3306, 8080,
+    1723, 111, 995, 993, 5900, 1025, 587, 8888, 199, 1720, 465, 548, 113, 81, 6001,
+    10000, 514, 5060, 179, 1026, 2000, 8443, 8000, 32768, 554, 26, 1433, 49152, 2001, 515,
+    8008, 49154, 1027, 5666, 646, 5000, 5631, 631, 49153, 8081, 2049, 88, 79, 5800, 106,
+    2121, 1110, 49155, 6000, 513, 990, 5357, 427, 49156, 543, 544, 5101, 144, 7, 389,
+    5432, 6379, 27017, 9090, 37, 873, 1028, 1029, 1900, 2717, 3000, 3128, 3986, 4899, 5009,
+    5051, 5190, 6646, 7070, 8009, 8031, 9100, 9999, 1755, 2103,
+];
+
+// SNMP community tried when none are configured
+pub const DEFAULT_SNMP_COMMUNITY: &str = "public";
+
+// Scan presets
+pub const FAST_PRESET_PORTS: usize = 100;
+pub const FAST_PRESET_TIMEOUT_MS: u64 = 200;
+pub const FAST_PRESET_CONCURRENCY: usize = 1000;
+pub const THOROUGH_PRESET_TIMEOUT_MS: u64 = 2000;
+
+// Compliance profiles. PCI DSS: cleartext/remote-admin services, web,
+// mail, DNS, Windows file sharing and databases that must not be exposed
+pub const PCI_DSS_PROFILE_PORTS: &[u16] = &[
+    21, 22, 23, 25, 53, 80, 110, 135, 139, 143, 443, 445,
+    1433, 1521, 3306, 3389, 5432, 5900, 8080, 8443, 27017,
+];
+// Services behind frequently exploited CVEs (SMB, RMI, Docker API,
+// unauthenticated data stores, remote shells)
+pub const COMMON_VULNS_PROFILE_PORTS: &[u16] = &[
+    21, 22, 23, 80, 111, 135, 139, 443, 445, 512, 513, 514, 1099,
+    1433, 2049, 2375, 3306, 3389, 5432, 5900, 5985, 6379, 8080, 9200, 11211, 27017,
+];
+
+// Filtered-port verification: extra probes, each with the timeout scaled up
+pub const FILTERED_VERIFY_ATTEMPTS: usize = 2;
+pub const FILTERED_VERIFY_TIMEOUT_FACTOR: u32 = 4;
+
+// Reset connects re-probed (with `retry_on_reset`) before reporting the port reset
+pub const RESET_RETRY_ATTEMPTS: usize = 2;
+
+// Scan time estimate: the expected case assumes this share of probes time
+// out and the rest are answered (open or closed) after a LAN-ish round trip
+pub const ESTIMATE_SILENT_SHARE: f64 = 0.1;
+pub const ESTIMATE_ANSWER_TIME: Duration = Duration::from_millis(20);
+
+// Closed-port sampling: when more than half the ports are filtered, probe a
+// few random high ports that should answer RST if the host is up
+pub const DEFAULT_CLOSED_PORT_SAMPLES: usize = 2;
+pub const CLOSED_PORT_SAMPLE_RANGE: (u16, u16) = (49152, 65535);
+
+// Widest bar in the services-by-category histogram
+pub const HISTOGRAM_BAR_WIDTH: usize = 40;
+
+// Console progress: per-port lines before switching to a running count,
+// printed every PROGRESS_SUMMARY_INTERVAL open ports
+pub const DEFAULT_PROGRESS_LINE_LIMIT: usize = 1000;
+pub const PROGRESS_SUMMARY_INTERVAL: usize = 1000;
+
+// Live dashboard redraw rate
+pub const TUI_REFRESH_INTERVAL: Duration = Duration::from_millis(100);
+
+// Process exit status: 0 = as expected, 1 = error, 2 = findings (--fail-on-open / --expect-ports)
+pub const EXIT_AS_EXPECTED: u8 = 0;
+pub const EXIT_ERROR: u8 = 1;
+pub const EXIT_FINDINGS: u8 = 2;
+
+// Webhook delivery
+pub const WEBHOOK_TIMEOUT_MS: u64 = 5000;
 
 // Stealth settings
+// Random source ports to try before giving up when the drawn port is taken
+pub const SOURCE_PORT_BIND_ATTEMPTS: usize = 3;
 pub const DELAY_JITTER_PERCENT: u64 = 50;
 
+// Service detection confidence levels
+pub const CONFIDENCE_HIGH: f32 = 0.9;
+pub const CONFIDENCE_MEDIUM: f32 = 0.6;
+pub const CONFIDENCE_LOW: f32 = 0.3;
+
+// z-score of the 95% confidence interval for sampled open-port estimates
+pub const ESTIMATE_Z_SCORE: f64 = 1.96;
+
+// Banner fingerprints: SHA-256 over this many leading bytes, shown as this many hex digits
+pub const FINGERPRINT_BANNER_BYTES: usize = 256;
+pub const FINGERPRINT_HEX_LEN: usize = 16;
+
+// Raw banner bytes kept as hex with --include-raw-banner
+pub const RAW_BANNER_HEX_BYTES: usize = 256;
+
+// Replaces whatever a redaction pattern matched in a shared report
+pub const REDACTION_MASK: &str = "[REDACTED]";
+
+// Capacity of the channel carrying results from scan tasks to consumers
+pub const RESULT_CHANNEL_CAPACITY: usize = 1024;
+
 // Buffer sizes
 pub const BANNER_BUFFER_SIZE: usize = 1024;
 pub const SMB_BUFFER_SIZE: usize = 4096;
+pub const DNS_BUFFER_SIZE: usize = 4096;
+pub const RDP_BUFFER_SIZE: usize = 1024;
+pub const SNMP_BUFFER_SIZE: usize = 2048;
+pub const TELNET_BUFFER_SIZE: usize = 1024;
+pub const MONGODB_MAX_MESSAGE_SIZE: usize = 1 << 20;
+
+// Ceilings for everything a detector reads from one connection
+pub const DEFAULT_MAX_READ_BYTES: usize = 64 * 1024;
+pub const DEFAULT_MAX_READ_DURATION: Duration = Duration::from_secs(10);
+pub const DEFAULT_MAX_READ_IDLE: Duration = Duration::from_millis(500);
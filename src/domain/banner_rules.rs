@@ -0,0 +1,184 @@
+//! Banner fingerprints: regex rules with named captures, so new services are data, not code
+
+use regex::Regex;
+use std::sync::OnceLock;
+
+use crate::constants::{CONFIDENCE_HIGH, CONFIDENCE_MEDIUM};
+use crate::errors::{ConfigError, ConfigResult};
+use super::service::ServiceVersion;
+
+/// Rule matching an HTTP response whose `Server` header starts with `$server`
+macro_rules! http_server {
+    ($server:literal) => {
+        concat!(r"(?s)^HTTP/[\d.]+ .*?\n(?i:server):[ \t]*(?P<banner>", $server, r"[^\r\n]*)")
+    };
+}
+
+/// `(service, pattern, confidence)`; earlier rules win ties, so specific
+/// fingerprints come before the generic ones for the same service
+const BUILTIN_RULES: &[(&str, &str, f32)] = &[
+    // SSH: SSH-<protocol>-<software> [comments]
+    ("SSH", r"^SSH-[\d.]+-(?P<banner>(?P<product>OpenSSH)_for_(?P<os>Windows)_(?P<version>[\w.]+)[^\r\n]*)", CONFIDENCE_HIGH),
+    ("SSH", r"^SSH-[\d.]+-(?P<banner>(?P<product>OpenSSH)_(?P<version>[\w.]+)(?:[ \t](?P<os>Ubuntu|Debian|Raspbian|FreeBSD|NetBSD))?[^\r\n]*)", CONFIDENCE_HIGH),
+    ("SSH", r"^SSH-[\d.]+-(?P<banner>(?P<product>dropbear)_(?P<version>[\w.]+)[^\r\n]*)", CONFIDENCE_HIGH),
+    ("SSH", r"^SSH-[\d.]+-(?P<banner>(?P<product>[^\s_]+)(?:_(?P<version>\S+))?[^\r\n]*)", CONFIDENCE_HIGH),
+    // HTTP: product, version and OS from the Server header
+    ("HTTP", http_server!(r"(?P<product>nginx)(?:/(?P<version>[\d.]+))?(?:[ \t]*\((?P<os>[^)\r\n]+)\))?"), CONFIDENCE_HIGH),
+    ("HTTP", http_server!(r"(?P<product>Apache)(?:/(?P<version>[\d.]+))?(?:[ \t]*\((?P<os>[^)\r\n]+)\))?"), CONFIDENCE_HIGH),
+    ("HTTP", http_server!(r"(?P<product>Microsoft-IIS)/(?P<version>[\d.]+)"), CONFIDENCE_HIGH),
+    ("HTTP", http_server!(r"(?P<product>lighttpd)(?:/(?P<version>[\d.]+))?"), CONFIDENCE_HIGH),
+    ("HTTP", http_server!(r"(?P<product>[^/\s]+)(?:/(?P<version>\S+))?"), CONFIDENCE_HIGH),
+    ("HTTP", r"^(?P<banner>HTTP)/[\d.]+ \d{3}", CONFIDENCE_MEDIUM),
+    // SMTP greetings name the MTA after the host name
+    ("SMTP", r"^220[ -]\S+ E?SMTP (?P<product>Postfix)(?: \((?P<os>[^)\r\n]+)\))?", CONFIDENCE_HIGH),
+    ("SMTP", r"^220[ -]\S+ E?SMTP (?P<product>Exim) (?P<version>[\d.]+)", CONFIDENCE_HIGH),
+    ("SMTP", r"^220[ -]\S+ E?SMTP (?P<product>Sendmail) (?P<version>[\d.]+)", CONFIDENCE_HIGH),
+    ("SMTP", r"^220[ -]\S+ (?P<product>Microsoft ESMTP MAIL Service)(?:, Version: (?P<version>[\d.]+))?", CONFIDENCE_HIGH),
+    ("SMTP", r"(?i)^220[ -][^\r\n]*\b(?:e?smtp|mail)\b", CONFIDENCE_MEDIUM),
+    // FTP
+    ("FTP", r"^220[ -][^\r\n]*\((?P<product>vsFTPd) (?P<version>[\d.]+)\)", CONFIDENCE_HIGH),
+    ("FTP", r"^220[ -][^\r\n]*(?P<product>ProFTPD) (?P<version>[\d.]+\w*)", CONFIDENCE_HIGH),
+    ("FTP", r"^220[ -][^\r\n]*(?P<product>Pure-FTPd)", CONFIDENCE_HIGH),
+    ("FTP", r"^220[ -][^\r\n]*(?P<product>FileZilla Server)(?: version)? (?P<version>[\d.]+\w*)", CONFIDENCE_HIGH),
+    ("FTP", r"^220[ -][^\r\n]*(?P<product>Microsoft FTP Service)", CONFIDENCE_HIGH),
+    ("FTP", r"(?i)ftp", CONFIDENCE_MEDIUM),
+    ("FTP", r"^220", CONFIDENCE_MEDIUM),
+    // Mail access
+    ("POP3", r"^\+OK [^\r\n]*(?P<product>Dovecot)", CONFIDENCE_HIGH),
+    ("POP3", r"^\+OK ", CONFIDENCE_MEDIUM),
+    ("IMAP", r"^\* OK [^\r\n]*(?P<product>Dovecot|Courier-IMAP|Cyrus)", CONFIDENCE_HIGH),
+    ("IMAP", r"(?i)^\* OK [^\r\n]*\bimap", CONFIDENCE_MEDIUM),
+    // Remote desktop
+    ("VNC", r"^RFB (?P<version>\d{3}\.\d{3})", CONFIDENCE_HIGH),
+];
+
+/// One fingerprint: banners matching `pattern` belong to `service`.
+///
+/// The named groups `product`, `version` and `os` fill in the matching
+/// fields; `banner`, if present, is the part of the response kept as the
+/// banner (the whole response otherwise).
+#[derive(Debug, Clone)]
+pub struct BannerRule {
+    pub service: String,
+    pub pattern: Regex,
+    pub confidence: f32,
+}
+
+impl BannerRule {
+    pub fn new(service: impl Into<String>, pattern: &str, confidence: f32) -> ConfigResult<Self> {
+        let pattern = Regex::new(pattern)
+            .map_err(|e| ConfigError::InvalidPattern(e.to_string()))?;
+        Ok(Self { service: service.into(), pattern, confidence })
+    }
+
+    /// Fields captured from `banner`, if the rule matches
+    pub fn apply(&self, banner: &str) -> Option<BannerMatch> {
+        let captures = self.pattern.captures(banner)?;
+        let group = |name| captures.name(name)
+            .map(|m| m.as_str().trim().to_string())
+            .filter(|s| !s.is_empty());
+        Some(BannerMatch {
+            service: self.service.clone(),
+            product: group("product"),
+            version: group("version"),
+            os: group("os"),
+            banner: group("banner").unwrap_or_else(|| banner.to_string()),
+            confidence: self.confidence,
+        })
+    }
+}
+
+/// What a rule read from a banner
+#[derive(Debug, Clone, PartialEq)]
+pub struct BannerMatch {
+    pub service: String,
+    pub product: Option<String>,
+    pub version: Option<String>,
+    pub os: Option<String>,
+    pub banner: String,
+    pub confidence: f32,
+}
+
+impl From<BannerMatch> for ServiceVersion {
+    fn from(found: BannerMatch) -> Self {
+        let mut version = ServiceVersion::new(found.service, "tcp")
+            .with_banner(found.banner)
+            .with_confidence(found.confidence);
+        version.product = found.product;
+        version.version = found.version;
+        version.os = found.os;
+        version
+    }
+}
+
+/// Ordered set of banner rules.
+///
+/// ```
+/// use port_scanner::domain::{BannerRule, BannerRuleSet};
+///
+/// let rules = BannerRuleSet::builtin();
+/// let found = rules.best_match("HTTP/1.1 200 OK\r\nServer: nginx/1.18.0 (Ubuntu)\r\n\r\n").unwrap();
+/// assert_eq!(found.service, "HTTP");
+/// assert_eq!(found.product.as_deref(), Some("nginx"));
+/// assert_eq!(found.version.as_deref(), Some("1.18.0"));
+/// assert_eq!(found.os.as_deref(), Some("Ubuntu"));
+/// assert_eq!(found.banner, "nginx/1.18.0 (Ubuntu)");
+///
+/// let found = rules.best_match("HTTP/1.1 403 Forbidden\r\nDate: today\r\nServer: Apache/2.4.41 (Debian)\r\n\r\n").unwrap();
+/// assert_eq!((found.product.as_deref(), found.version.as_deref()), (Some("Apache"), Some("2.4.41")));
+///
+/// let found = rules.best_match("SSH-2.0-OpenSSH_8.2p1 Ubuntu-4ubuntu0.5\r\n").unwrap();
+/// assert_eq!(found.service, "SSH");
+/// assert_eq!((found.product.as_deref(), found.version.as_deref()), (Some("OpenSSH"), Some("8.2p1")));
+/// assert_eq!(found.os.as_deref(), Some("Ubuntu"));
+///
+/// // Custom rules are data too
+/// let rules = BannerRuleSet::new()
+///     .with_rule(BannerRule::new("Gopher", r"^(?P<product>gophernicus)/(?P<version>[\d.]+)", 0.9)?);
+/// assert_eq!(rules.best_match("gophernicus/3.1\r\n").unwrap().version.as_deref(), Some("3.1"));
+/// assert!(rules.best_match("SSH-2.0-OpenSSH_9.6\r\n").is_none());
+/// # Ok::<(), port_scanner::errors::ConfigError>(())
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct BannerRuleSet {
+    rules: Vec<BannerRule>,
+}
+
+impl BannerRuleSet {
+    /// Empty rule set
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Rules for the services the scanner recognizes out of the box
+    pub fn builtin() -> &'static Self {
+        static BUILTIN: OnceLock<BannerRuleSet> = OnceLock::new();
+        BUILTIN.get_or_init(|| Self {
+            rules: BUILTIN_RULES.iter()
+                .map(|&(service, pattern, confidence)| {
+                    BannerRule::new(service, pattern, confidence).expect("built-in banner rule compiles")
+                })
+                .collect(),
+        })
+    }
+
+    pub fn with_rule(mut self, rule: BannerRule) -> Self {
+        self.rules.push(rule);
+        self
+    }
+
+    pub fn rules(&self) -> &[BannerRule] {
+        &self.rules
+    }
+
+    /// Run every rule and keep the highest-confidence match, the earliest
+    /// rule winning ties
+    pub fn best_match(&self, banner: &str) -> Option<BannerMatch> {
+        self.rules.iter()
+            .filter_map(|rule| rule.apply(banner))
+            .fold(None, |best: Option<BannerMatch>, found| match best {
+                Some(best) if best.confidence >= found.confidence => Some(best),
+                _ => Some(found),
+            })
+    }
+}
@@ -0,0 +1,72 @@
+//! Domain model for security findings derived from scan results
+
+use serde::{Deserialize, Serialize};
+use std::net::IpAddr;
+
+use crate::domain::{Port, Protocol};
+
+/// How urgently a finding needs attention, lowest first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Info,
+    Low,
+    Medium,
+    High,
+    Critical,
+}
+
+impl std::fmt::Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Severity::Info => write!(f, "INFO"),
+            Severity::Low => write!(f, "LOW"),
+            Severity::Medium => write!(f, "MEDIUM"),
+            Severity::High => write!(f, "HIGH"),
+            Severity::Critical => write!(f, "CRITICAL"),
+        }
+    }
+}
+
+/// One actionable issue on a port, e.g. "Telnet open"
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Finding {
+    pub severity: Severity,
+    pub title: String,
+    pub port: Port,
+    #[serde(default)]
+    pub protocol: Protocol,
+    /// Host of the port, for reports covering several hosts
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub host: Option<IpAddr>,
+    /// What was observed and why it matters
+    pub detail: String,
+}
+
+impl Finding {
+    pub fn new(severity: Severity, title: impl Into<String>, port: Port, detail: impl Into<String>) -> Self {
+        Self {
+            severity,
+            title: title.into(),
+            port,
+            protocol: Protocol::Tcp,
+            host: None,
+            detail: detail.into(),
+        }
+    }
+
+    pub fn with_protocol(mut self, protocol: Protocol) -> Self {
+        self.protocol = protocol;
+        self
+    }
+
+    pub fn with_host(mut self, host: Option<IpAddr>) -> Self {
+        self.host = host;
+        self
+    }
+
+    /// `port/protocol`, e.g. `23/tcp`
+    pub fn label(&self) -> String {
+        format!("{}/{}", self.port, self.protocol)
+    }
+}
@@ -1,11 +1,15 @@
-/// Domain layer module exports
+//! Domain layer module exports
 
 pub mod port;
 pub mod service;
+pub mod banner_rules;
 pub mod scan_result;
 pub mod os;
+pub mod finding;
 
-pub use port::{Port, PortStatus};
-pub use service::{ServiceInfo, ServiceVersion, ServiceRepository, StaticServiceRepository};
-pub use scan_result::{PortScanResult, ScanResults};
-pub use os::OSInfo;
+pub use port::{Port, PortStatus, Protocol};
+pub use service::{CommonPortSet, ServiceCategory, ServiceInfo, ServiceVersion, ServiceRepository, StaticServiceRepository, normalize_banner, banner_fingerprint};
+pub use banner_rules::{BannerMatch, BannerRule, BannerRuleSet};
+pub use scan_result::{HostState, LatencySummary, OpenPortEstimate, PortScanResult, ScanResults};
+pub use os::{OSInfo, OsCandidate};
+pub use finding::{Finding, Severity};
@@ -1,9 +1,9 @@
-/// Domain model for operating system detection
+//! Domain model for operating system detection
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 /// Operating system information detected from network fingerprinting
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct OSInfo {
     pub os_name: Option<String>,
     pub os_version: Option<String>,
@@ -11,6 +11,20 @@ pub struct OSInfo {
     pub computer_name: Option<String>,
     pub domain: Option<String>,
     pub smb_version: Option<String>,
+    /// Ranked OS guesses from aggregated weak signals, best first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub candidates: Vec<OsCandidate>,
+}
+
+/// One OS guess with the signals that support it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OsCandidate {
+    /// OS family, e.g. "Linux" or "Windows"
+    pub name: String,
+    /// Combined confidence of all evidence (0.0–1.0)
+    pub confidence: f32,
+    /// Human-readable signals, e.g. "SSH banner mentions Ubuntu"
+    pub evidence: Vec<String>,
 }
 
 impl OSInfo {
@@ -22,6 +36,7 @@ impl OSInfo {
             computer_name: None,
             domain: None,
             smb_version: None,
+            candidates: Vec::new(),
         }
     }
 
@@ -55,6 +70,16 @@ impl OSInfo {
         self
     }
 
+    pub fn with_candidates(mut self, candidates: Vec<OsCandidate>) -> Self {
+        self.candidates = candidates;
+        self
+    }
+
+    /// Highest-ranked guess, if any
+    pub fn best_candidate(&self) -> Option<&OsCandidate> {
+        self.candidates.first()
+    }
+
     pub fn is_detected(&self) -> bool {
         self.os_name.is_some() 
             || self.os_version.is_some() 
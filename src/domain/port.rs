@@ -1,16 +1,49 @@
-/// Domain model for ports and port status
+//! Domain model for ports and port status
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 /// Type alias for port numbers
 pub type Port = u16;
 
+/// Transport protocol a port is probed over
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Protocol {
+    #[default]
+    Tcp,
+    Udp,
+}
+
+impl Protocol {
+    /// Parse `tcp` or `udp`, ignoring case
+    pub fn from_label(label: &str) -> Option<Self> {
+        match label.trim().to_ascii_lowercase().as_str() {
+            "tcp" => Some(Protocol::Tcp),
+            "udp" => Some(Protocol::Udp),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for Protocol {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Protocol::Tcp => write!(f, "tcp"),
+            Protocol::Udp => write!(f, "udp"),
+        }
+    }
+}
+
 /// Represents the status of a scanned port
-#[derive(Debug, Clone, PartialEq, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum PortStatus {
     Open,
     Closed,
     Filtered,
+    /// Connection reset/aborted mid-handshake (often an actively rejecting firewall)
+    Reset,
+    /// No reply to a FIN/NULL/Xmas probe: open, or dropped by a filter
+    OpenFiltered,
     Error(String),
 }
 
@@ -27,9 +60,61 @@ impl PortStatus {
         matches!(self, PortStatus::Filtered)
     }
 
+    /// A connect reset or aborted mid-handshake, counted apart from closed
+    /// ports.
+    ///
+    /// ```
+    /// use port_scanner::infrastructure::testing::{Reply, ScriptedConnector};
+    /// use port_scanner::prelude::*;
+    /// use port_scanner::scanning::StandardScan;
+    /// use std::sync::Arc;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// let config = ScanConfigBuilder::new()
+    ///     .target("127.0.0.1".parse()?)
+    ///     .custom_ports(vec![1, 2])
+    ///     .build()?;
+    /// let rejecting = ScriptedConnector::new(Reply::Refuse).on_port(1, [Reply::Reset]);
+    /// let strategy = StandardScan::new().with_connector(Arc::new(rejecting));
+    /// let results = PortScanner::new(config.clone())?.with_strategy(Arc::new(strategy)).scan_all(|_| {}).await;
+    ///
+    /// assert!(results.results[0].status.is_reset());
+    /// assert_eq!((results.reset_ports, results.closed_ports), (1, 1));
+    ///
+    /// let report = ScanReport::new(&config, results, 1.0);
+    /// let text = OutputFormatterFactory::create(OutputFormat::Text, false).format(&report)?;
+    /// assert!(text.contains("Reset Ports:         1"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn is_reset(&self) -> bool {
+        matches!(self, PortStatus::Reset)
+    }
+
+    pub fn is_open_filtered(&self) -> bool {
+        matches!(self, PortStatus::OpenFiltered)
+    }
+
     pub fn is_error(&self) -> bool {
         matches!(self, PortStatus::Error(_))
     }
+
+    /// Parse the `Display` form back, e.g. `OPEN|FILTERED` or `ERROR: timed out`
+    pub fn from_label(label: &str) -> Option<Self> {
+        let label = label.trim();
+        let status = match label.to_ascii_uppercase().as_str() {
+            "OPEN" => PortStatus::Open,
+            "CLOSED" => PortStatus::Closed,
+            "FILTERED" => PortStatus::Filtered,
+            "RESET" => PortStatus::Reset,
+            "OPEN|FILTERED" => PortStatus::OpenFiltered,
+            "ERROR" => PortStatus::Error(String::new()),
+            upper if upper.starts_with("ERROR:") => PortStatus::Error(label["ERROR:".len()..].trim().to_string()),
+            _ => return None,
+        };
+        Some(status)
+    }
 }
 
 impl std::fmt::Display for PortStatus {
@@ -38,6 +123,8 @@ impl std::fmt::Display for PortStatus {
             PortStatus::Open => write!(f, "OPEN"),
             PortStatus::Closed => write!(f, "CLOSED"),
             PortStatus::Filtered => write!(f, "FILTERED"),
+            PortStatus::Reset => write!(f, "RESET"),
+            PortStatus::OpenFiltered => write!(f, "OPEN|FILTERED"),
             PortStatus::Error(e) => write!(f, "ERROR: {}", e),
         }
     }
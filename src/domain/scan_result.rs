@@ -1,43 +1,178 @@
-/// Domain model for scan results
+//! Domain model for scan results
 
-use serde::Serialize;
-use super::port::{Port, PortStatus};
-use super::service::ServiceVersion;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::net::IpAddr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use super::port::{Port, PortStatus, Protocol};
+use super::service::{ServiceCategory, ServiceInfo, ServiceRepository, ServiceVersion, StaticServiceRepository};
 use super::os::OSInfo;
 
 /// Result of scanning a single port
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct PortScanResult {
+    /// Host the port belongs to, set when one report covers several hosts
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub host: Option<IpAddr>,
     pub port: Port,
+    /// Transport the port was probed over
+    #[serde(default)]
+    pub protocol: Protocol,
     pub status: PortStatus,
     pub service_version: Option<ServiceVersion>,
+    /// Structured product, version and details of the detected service
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub service_info: Option<ServiceInfo>,
     pub os_info: Option<OSInfo>,
+    /// IP TTL of the reply, known only for raw-socket scans
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ttl: Option<u8>,
+    /// OS error number behind an `Error` status (`errno`, or the Winsock
+    /// code on Windows), for diagnosing platform-specific failures
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error_code: Option<i32>,
+    /// Time from starting the connect to the completed handshake, for open
+    /// TCP ports; serialized as fractional milliseconds
+    #[serde(rename = "connect_latency_ms", with = "opt_float_millis", default, skip_serializing_if = "Option::is_none")]
+    pub connect_latency: Option<Duration>,
+    /// When the port status was recorded, serialized as Unix epoch milliseconds
+    #[serde(with = "epoch_millis", default = "unix_epoch")]
+    pub scanned_at: SystemTime,
 }
 
 impl PortScanResult {
     pub fn new(port: Port, status: PortStatus) -> Self {
         Self { 
+            host: None,
             port, 
+            protocol: Protocol::Tcp,
             status,
             service_version: None,
+            service_info: None,
             os_info: None,
+            ttl: None,
+            error_code: None,
+            connect_latency: None,
+            scanned_at: SystemTime::now(),
         }
     }
 
+    /// Result of a probe that failed with `error`: an `Error` status with
+    /// the message, keeping the OS error code if there is one.
+    ///
+    /// ```
+    /// use port_scanner::domain::{PortScanResult, PortStatus};
+    /// use port_scanner::presentation::ReportField;
+    /// use std::io;
+    ///
+    /// let error = io::Error::from_raw_os_error(13);
+    /// let result = PortScanResult::from_error(443, &error);
+    /// assert_eq!(result.status, PortStatus::Error(error.to_string()));
+    /// assert_eq!(result.error_code, Some(13));
+    ///
+    /// let json = serde_json::to_value(&result)?;
+    /// assert_eq!(json["error_code"], 13);
+    /// let parsed: PortScanResult = serde_json::from_value(json)?;
+    /// assert_eq!(parsed.error_code, Some(13));
+    /// assert_eq!(ReportField::ErrorCode.csv_value(&result), "13");
+    ///
+    /// // Errors made up by the scanner have no code
+    /// let timeout = io::Error::new(io::ErrorKind::TimedOut, "connect timed out");
+    /// assert_eq!(PortScanResult::from_error(443, &timeout).error_code, None);
+    /// # Ok::<(), serde_json::Error>(())
+    /// ```
+    pub fn from_error(port: Port, error: &std::io::Error) -> Self {
+        let mut result = Self::new(port, PortStatus::Error(error.to_string()));
+        result.error_code = error.raw_os_error();
+        result
+    }
+
+    /// `scanned_at` as Unix epoch milliseconds
+    ///
+    /// ```
+    /// use port_scanner::infrastructure::testing::{Reply, ScriptedConnector};
+    /// use port_scanner::prelude::*;
+    /// use port_scanner::scanning::StandardScan;
+    /// use std::sync::Arc;
+    /// use std::time::{Duration, SystemTime};
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// let config = ScanConfigBuilder::new()
+    ///     .target("10.0.0.5".parse()?)
+    ///     .range(1, 5)
+    ///     .parallel(false)
+    ///     .build()?;
+    /// let refuse = ScriptedConnector::new(Reply::Refuse).delay(Duration::from_millis(5));
+    /// let strategy = StandardScan::new().with_connector(Arc::new(refuse));
+    /// let started = SystemTime::now();
+    /// let mut results = PortScanner::new(config)?.with_strategy(Arc::new(strategy)).scan_all(|_| {}).await.results;
+    /// results.sort_by_key(|r| r.port);
+    ///
+    /// // A sequential scan probes in port order, so the stamps increase with the port
+    /// let stamps: Vec<u64> = results.iter().map(|r| r.scanned_at_millis()).collect();
+    /// assert_eq!(stamps.len(), 5);
+    /// assert!(results.iter().all(|r| r.scanned_at >= started && r.scanned_at <= SystemTime::now()));
+    /// assert!(stamps.windows(2).all(|w| w[0] < w[1]), "{stamps:?}");
+    ///
+    /// let json = serde_json::to_value(&results[0])?;
+    /// assert_eq!(json["scanned_at"], stamps[0]);
+    /// let parsed: PortScanResult = serde_json::from_value(json)?;
+    /// assert_eq!(parsed.scanned_at_millis(), stamps[0]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn scanned_at_millis(&self) -> u64 {
+        epoch_millis::to_millis(self.scanned_at)
+    }
+
+    /// Set the detected service; `service_info` is derived from it
     pub fn with_version(mut self, version: ServiceVersion) -> Self {
+        self.service_info = Some(ServiceInfo::from(&version));
         self.service_version = Some(version);
         self
     }
 
+    /// Replace the structured service information derived by `with_version`
+    pub fn with_service_info(mut self, info: ServiceInfo) -> Self {
+        self.service_info = Some(info);
+        self
+    }
+
+    pub fn with_protocol(mut self, protocol: Protocol) -> Self {
+        self.protocol = protocol;
+        self
+    }
+
     pub fn with_os_info(mut self, os_info: OSInfo) -> Self {
         self.os_info = Some(os_info);
         self
     }
 
+    pub fn with_ttl(mut self, ttl: u8) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+
+    pub fn with_connect_latency(mut self, latency: Duration) -> Self {
+        self.connect_latency = Some(latency);
+        self
+    }
+
+    pub fn with_host(mut self, host: IpAddr) -> Self {
+        self.host = Some(host);
+        self
+    }
+
     pub fn is_open(&self) -> bool {
         self.status.is_open()
     }
 
+    /// Port and transport as shown to users, e.g. `53/udp`
+    pub fn label(&self) -> String {
+        format!("{}/{}", self.port, self.protocol)
+    }
+
     pub fn has_service_info(&self) -> bool {
         self.service_version.is_some()
     }
@@ -47,15 +182,49 @@ impl PortScanResult {
     }
 }
 
+/// What closed-port sampling says about a host whose ports mostly timed out
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HostState {
+    /// A port that should be closed answered: the host is up and a
+    /// firewall drops the silent ports
+    HostUpFirewalled,
+    /// The sampled ports were silent too: the host is down or drops everything
+    HostLikelyDown,
+}
+
+impl std::fmt::Display for HostState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HostState::HostUpFirewalled => write!(f, "up, firewalled"),
+            HostState::HostLikelyDown => write!(f, "likely down"),
+        }
+    }
+}
+
 /// Collection of scan results with statistics
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ScanResults {
     pub results: Vec<PortScanResult>,
     pub total_ports: usize,
     pub open_ports: usize,
     pub closed_ports: usize,
     pub filtered_ports: usize,
+    pub reset_ports: usize,
+    pub open_filtered_ports: usize,
     pub error_ports: usize,
+    /// Host exceeded the open-port threshold and was not fully scanned
+    #[serde(default)]
+    pub honeypot_suspected: bool,
+    /// Host-level OS guess aggregated from all results after the scan
+    #[serde(default)]
+    pub os_guess: Option<OSInfo>,
+    /// Host state from closed-port sampling, when most ports were filtered
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub host_state: Option<HostState>,
+    /// TCP connects and UDP probes made, including retries and detection
+    #[serde(default)]
+    pub connection_attempts: u64,
 }
 
 impl ScanResults {
@@ -64,6 +233,8 @@ impl ScanResults {
         let open = results.iter().filter(|r| r.status.is_open()).count();
         let closed = results.iter().filter(|r| r.status.is_closed()).count();
         let filtered = results.iter().filter(|r| r.status.is_filtered()).count();
+        let reset = results.iter().filter(|r| r.status.is_reset()).count();
+        let open_filtered = results.iter().filter(|r| r.status.is_open_filtered()).count();
         let error = results.iter().filter(|r| r.status.is_error()).count();
 
         Self {
@@ -72,7 +243,13 @@ impl ScanResults {
             open_ports: open,
             closed_ports: closed,
             filtered_ports: filtered,
+            reset_ports: reset,
+            open_filtered_ports: open_filtered,
             error_ports: error,
+            honeypot_suspected: false,
+            os_guess: None,
+            host_state: None,
+            connection_attempts: 0,
         }
     }
 
@@ -84,9 +261,291 @@ impl ScanResults {
         }
     }
 
+    /// Sort results by port number, TCP before UDP (probe order may differ)
+    pub fn sort_by_port(&mut self) {
+        self.results.sort_by_key(|r| (r.port, r.protocol));
+    }
+
+    /// Results split by `host`, ordered by address; results without a host
+    /// come first under `None`
+    pub fn by_host(&self) -> BTreeMap<Option<IpAddr>, ScanResults> {
+        let mut hosts: BTreeMap<Option<IpAddr>, Vec<PortScanResult>> = BTreeMap::new();
+        for result in &self.results {
+            hosts.entry(result.host).or_default().push(result.clone());
+        }
+        hosts.into_iter().map(|(host, results)| (host, ScanResults::new(results))).collect()
+    }
+
+    /// Open ports per transport present in the results, for scans covering
+    /// both TCP and UDP
+    pub fn open_ports_by_protocol(&self) -> BTreeMap<Protocol, usize> {
+        let mut counts = BTreeMap::new();
+        for result in &self.results {
+            *counts.entry(result.protocol).or_insert(0) += usize::from(result.is_open());
+        }
+        counts
+    }
+
+    /// Open ports per service category, from the detected service or else
+    /// the port's well-known one. Categories without open ports are left out.
+    ///
+    /// ```
+    /// use port_scanner::domain::{PortScanResult, PortStatus, ScanResults, ServiceCategory, ServiceVersion};
+    ///
+    /// let results = ScanResults::new(vec![
+    ///     PortScanResult::new(80, PortStatus::Open),
+    ///     PortScanResult::new(8081, PortStatus::Open).with_version(ServiceVersion::new("HTTP", "tcp")),
+    ///     PortScanResult::new(3306, PortStatus::Open),
+    ///     PortScanResult::new(22, PortStatus::Open),
+    ///     PortScanResult::new(2222, PortStatus::Open).with_version(ServiceVersion::new("SSH", "tcp")),
+    ///     PortScanResult::new(5432, PortStatus::Closed),
+    ///     PortScanResult::new(31337, PortStatus::Open),
+    /// ]);
+    /// let summary = results.service_summary();
+    /// assert_eq!(summary[&ServiceCategory::Web], 2);
+    /// assert_eq!(summary[&ServiceCategory::Database], 1);
+    /// assert_eq!(summary[&ServiceCategory::RemoteAccess], 2);
+    /// assert_eq!(summary[&ServiceCategory::Other], 1);
+    /// assert!(!summary.contains_key(&ServiceCategory::Mail));
+    /// ```
+    pub fn service_summary(&self) -> BTreeMap<ServiceCategory, usize> {
+        let repository = StaticServiceRepository::new();
+        let mut counts = BTreeMap::new();
+        for result in self.results.iter().filter(|r| r.is_open()) {
+            let detected = result.service_version.as_ref()
+                .map(|v| v.service_name.as_str())
+                .filter(|name| *name != "unknown");
+            let name = detected.or_else(|| repository.get_service_name(result.port)).unwrap_or_default();
+            *counts.entry(ServiceCategory::of_service(name)).or_insert(0) += 1;
+        }
+        counts
+    }
+
     pub fn get_open_results(&self) -> Vec<&PortScanResult> {
         self.results.iter().filter(|r| r.is_open()).collect()
     }
+
+    /// Combine two result sets, recomputing the statistics.
+    ///
+    /// Duplicates are matched by host, port and protocol: the entry from
+    /// `other` wins unless only the earlier one is open.
+    ///
+    /// ```
+    /// use port_scanner::prelude::*;
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// let host = "10.0.0.5".parse()?;
+    /// let first = ScanResults::new(vec![
+    ///     PortScanResult::new(22, PortStatus::Open),
+    ///     PortScanResult::new(80, PortStatus::Closed),
+    ///     PortScanResult::new(443, PortStatus::Filtered),
+    /// ]);
+    /// let second = ScanResults::new(vec![
+    ///     // A later timeout does not hide an earlier open port
+    ///     PortScanResult::new(22, PortStatus::Filtered),
+    ///     // The later answer wins otherwise
+    ///     PortScanResult::new(80, PortStatus::Open),
+    ///     PortScanResult::new(443, PortStatus::Closed),
+    ///     // Another transport or host is not a duplicate
+    ///     PortScanResult::new(53, PortStatus::Open).with_protocol(Protocol::Udp),
+    ///     PortScanResult::new(22, PortStatus::Closed).with_host(host),
+    /// ]);
+    ///
+    /// let merged = first.merge(second);
+    /// let status = |port, host| merged.results.iter().find(|r| r.port == port && r.host == host).map(|r| r.status.clone());
+    /// assert_eq!(status(22, None), Some(PortStatus::Open));
+    /// assert_eq!(status(80, None), Some(PortStatus::Open));
+    /// assert_eq!(status(443, None), Some(PortStatus::Closed));
+    /// assert_eq!(status(22, Some(host)), Some(PortStatus::Closed));
+    /// // Counts are recomputed, not added up
+    /// assert_eq!((merged.total_ports, merged.open_ports, merged.closed_ports, merged.filtered_ports), (5, 3, 2, 0));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn merge(self, other: ScanResults) -> ScanResults {
+        let honeypot_suspected = self.honeypot_suspected || other.honeypot_suspected;
+        let os_guess = other.os_guess.or(self.os_guess);
+        let host_state = other.host_state.or(self.host_state);
+        let connection_attempts = self.connection_attempts + other.connection_attempts;
+        let mut merged: Vec<PortScanResult> = Vec::with_capacity(self.results.len() + other.results.len());
+        let mut index: HashMap<(Option<IpAddr>, Port, Protocol), usize> = HashMap::new();
+
+        for result in self.results.into_iter().chain(other.results) {
+            match index.get(&(result.host, result.port, result.protocol)) {
+                Some(&i) => {
+                    if result.is_open() || !merged[i].is_open() {
+                        merged[i] = result;
+                    }
+                }
+                None => {
+                    index.insert((result.host, result.port, result.protocol), merged.len());
+                    merged.push(result);
+                }
+            }
+        }
+
+        let mut results = Self::new(merged);
+        results.honeypot_suspected = honeypot_suspected;
+        results.os_guess = os_guess;
+        results.host_state = host_state;
+        results.connection_attempts = connection_attempts;
+        results
+    }
+}
+
+/// Open-port count extrapolated from a random sample of a port range
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct OpenPortEstimate {
+    /// Ports probed
+    pub sampled: usize,
+    /// Open ports among those probed
+    pub open: usize,
+    /// Ports in the range the sample was drawn from
+    pub population: usize,
+    /// Expected open ports in the whole range
+    pub estimate: f64,
+    /// Bounds of the 95% confidence interval
+    pub low: f64,
+    pub high: f64,
+}
+
+impl std::fmt::Display for OpenPortEstimate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "~{:.0} of {} ports open (95% CI {:.0}-{:.0}), from {} open in {} sampled",
+            self.estimate, self.population, self.low, self.high, self.open, self.sampled
+        )
+    }
+}
+
+/// Spread of the connect latencies of a scan's open ports, in milliseconds
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct LatencySummary {
+    /// Open ports with a recorded latency
+    pub samples: usize,
+    pub min_ms: f64,
+    pub avg_ms: f64,
+    pub max_ms: f64,
+}
+
+impl std::fmt::Display for LatencySummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "min {:.2}ms / avg {:.2}ms / max {:.2}ms over {} open ports",
+            self.min_ms, self.avg_ms, self.max_ms, self.samples
+        )
+    }
+}
+
+impl ScanResults {
+    /// Min, average and max connect latency of the open ports, `None` when
+    /// no open port has one (e.g. raw-socket or UDP scans).
+    ///
+    /// ```
+    /// use port_scanner::prelude::*;
+    /// use std::net::TcpListener;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// let listener = TcpListener::bind("127.0.0.1:0")?;
+    /// let open = listener.local_addr()?.port();
+    /// let closed = TcpListener::bind("127.0.0.1:0")?.local_addr()?.port();
+    /// let config = ScanConfigBuilder::new()
+    ///     .target("127.0.0.1".parse()?)
+    ///     .custom_ports(vec![open, closed])
+    ///     .build()?;
+    ///
+    /// let results = PortScanner::new(config)?.scan_all(|_| {}).await;
+    /// let port = |port| results.results.iter().find(|r| r.port == port).unwrap();
+    /// assert!(port(open).connect_latency.is_some());
+    /// assert_eq!(port(closed).status, PortStatus::Closed);
+    /// assert_eq!(port(closed).connect_latency, None);
+    ///
+    /// let summary = results.connect_latency_summary().unwrap();
+    /// assert_eq!(summary.samples, 1);
+    /// assert!(summary.min_ms >= 0.0 && summary.min_ms == summary.max_ms);
+    ///
+    /// let json = serde_json::to_value(port(open))?;
+    /// assert!(json["connect_latency_ms"].as_f64().unwrap() >= 0.0);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn connect_latency_summary(&self) -> Option<LatencySummary> {
+        let latencies: Vec<f64> = self.results.iter()
+            .filter(|r| r.is_open())
+            .filter_map(|r| r.connect_latency)
+            .map(|latency| latency.as_secs_f64() * 1000.0)
+            .collect();
+        if latencies.is_empty() {
+            return None;
+        }
+        Some(LatencySummary {
+            samples: latencies.len(),
+            min_ms: latencies.iter().copied().fold(f64::INFINITY, f64::min),
+            avg_ms: latencies.iter().sum::<f64>() / latencies.len() as f64,
+            max_ms: latencies.iter().copied().fold(0.0, f64::max),
+        })
+    }
+
+    /// Extrapolate the open-port count of a `population`-port range from
+    /// these results, taken as a uniform random sample of it.
+    ///
+    /// The interval is a Wilson score interval with a finite population
+    /// correction, so it narrows to the exact count as the sample approaches
+    /// the whole range, and never goes below the open ports actually seen.
+    ///
+    /// ```
+    /// use port_scanner::domain::{PortScanResult, PortStatus, ScanResults};
+    ///
+    /// // 10 of 100 sampled ports open, drawn from 1000 ports
+    /// let results = ScanResults::new((1..=100)
+    ///     .map(|port| PortScanResult::new(port, if port <= 10 { PortStatus::Open } else { PortStatus::Closed }))
+    ///     .collect());
+    /// let estimate = results.estimate_open(1000);
+    /// assert_eq!(estimate.estimate, 100.0);
+    /// assert!(estimate.low >= 10.0 && estimate.low < 100.0);
+    /// assert!(estimate.high > 100.0 && estimate.high <= 910.0);
+    ///
+    /// // Sampling the whole range gives the exact count
+    /// let exact = results.estimate_open(100);
+    /// assert_eq!((exact.low, exact.estimate, exact.high), (10.0, 10.0, 10.0));
+    /// ```
+    pub fn estimate_open(&self, population: usize) -> OpenPortEstimate {
+        let sampled = self.total_ports;
+        let open = self.open_ports;
+        let population = population.max(sampled);
+        if sampled == 0 {
+            return OpenPortEstimate { sampled, open, population, estimate: 0.0, low: 0.0, high: population as f64 };
+        }
+
+        let n = sampled as f64;
+        let big_n = population as f64;
+        let p = open as f64 / n;
+        let fpc = if population > 1 { ((big_n - n) / (big_n - 1.0)).sqrt() } else { 0.0 };
+        let z = crate::constants::ESTIMATE_Z_SCORE * fpc;
+        let denominator = 1.0 + z * z / n;
+        let center = (p + z * z / (2.0 * n)) / denominator;
+        let half_width = z / denominator * (p * (1.0 - p) / n + z * z / (4.0 * n * n)).sqrt();
+
+        // Unsampled ports can add to, but never take away from, what was seen
+        let unseen_closed = (sampled - open) as f64;
+        OpenPortEstimate {
+            sampled,
+            open,
+            population,
+            estimate: open as f64 * big_n / n,
+            low: ((center - half_width) * big_n).max(open as f64),
+            high: ((center + half_width) * big_n).min(big_n - unseen_closed),
+        }
+    }
+}
+
+impl FromIterator<ScanResults> for ScanResults {
+    fn from_iter<I: IntoIterator<Item = ScanResults>>(iter: I) -> Self {
+        iter.into_iter().fold(Self::new(Vec::new()), Self::merge)
+    }
 }
 
 impl From<Vec<PortScanResult>> for ScanResults {
@@ -94,3 +553,44 @@ impl From<Vec<PortScanResult>> for ScanResults {
         Self::new(results)
     }
 }
+
+fn unix_epoch() -> SystemTime {
+    UNIX_EPOCH
+}
+
+/// Serde adapter storing an optional `Duration` as fractional milliseconds
+mod opt_float_millis {
+    use super::*;
+    use serde::{Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(duration: &Option<Duration>, serializer: S) -> Result<S::Ok, S::Error> {
+        match duration {
+            Some(duration) => serializer.serialize_f64(duration.as_secs_f64() * 1000.0),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Duration>, D::Error> {
+        let millis = Option::<f64>::deserialize(deserializer)?;
+        Ok(millis.filter(|ms| ms.is_finite() && *ms >= 0.0).map(|ms| Duration::from_secs_f64(ms / 1000.0)))
+    }
+}
+
+/// Serde adapter storing a `SystemTime` as integer milliseconds since the Unix epoch
+mod epoch_millis {
+    use super::*;
+    use serde::{Deserializer, Serializer};
+
+    pub fn to_millis(time: SystemTime) -> u64 {
+        time.duration_since(UNIX_EPOCH).map(|d| d.as_millis() as u64).unwrap_or(0)
+    }
+
+    pub fn serialize<S: Serializer>(time: &SystemTime, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(to_millis(*time))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<SystemTime, D::Error> {
+        let millis = u64::deserialize(deserializer)?;
+        Ok(UNIX_EPOCH + Duration::from_millis(millis))
+    }
+}
@@ -1,10 +1,10 @@
-/// Domain model for services and service detection
+//! Domain model for services and service detection
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 /// Service information detected from a port
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ServiceInfo {
     pub name: Option<String>,
     pub version: Option<String>,
@@ -45,33 +45,184 @@ impl ServiceInfo {
     pub fn is_detected(&self) -> bool {
         self.name.is_some() || self.version.is_some() || self.product.is_some()
     }
+
+    /// Split a product banner into product, version and extra info.
+    ///
+    /// Accepts `<product>/<version>` or `<product>_<version>` followed by
+    /// optional details, with a leading parenthesized group unwrapped; other
+    /// banners yield `None`.
+    ///
+    /// ```
+    /// use port_scanner::domain::ServiceInfo;
+    ///
+    /// let info = ServiceInfo::parse_product("nginx/1.18.0 (Ubuntu)").unwrap();
+    /// assert_eq!(info.product.as_deref(), Some("nginx"));
+    /// assert_eq!(info.version.as_deref(), Some("1.18.0"));
+    /// assert_eq!(info.extra_info.as_deref(), Some("Ubuntu"));
+    ///
+    /// let info = ServiceInfo::parse_product("OpenSSH_8.2p1 Ubuntu-4ubuntu0.5").unwrap();
+    /// assert_eq!(info.product.as_deref(), Some("OpenSSH"));
+    /// assert_eq!(info.version.as_deref(), Some("8.2p1"));
+    /// assert_eq!(info.extra_info.as_deref(), Some("Ubuntu-4ubuntu0.5"));
+    ///
+    /// assert!(ServiceInfo::parse_product("220 ProFTPD Server ready").is_none());
+    /// ```
+    pub fn parse_product(banner: &str) -> Option<Self> {
+        let banner = banner.trim();
+        let (token, rest) = banner.split_once(char::is_whitespace).unwrap_or((banner, ""));
+        let split = token.find(['/', '_'])?;
+        let (product, version) = (&token[..split], &token[split + 1..]);
+        if product.is_empty() || !version.starts_with(|c: char| c.is_ascii_digit()) {
+            return None;
+        }
+
+        let rest = rest.trim();
+        let extra = match rest.strip_prefix('(').and_then(|r| r.split_once(')')) {
+            Some((group, after)) => format!("{} {}", group.trim(), after.trim()),
+            None => rest.to_string(),
+        };
+        let mut info = Self::new().with_product(product).with_version(version);
+        if !extra.trim().is_empty() {
+            info = info.with_extra_info(extra.trim());
+        }
+        Some(info)
+    }
+}
+
+/// Structured view of a detection result: the product, version and details
+/// come from the matched banner rule, or are split out of the banner where
+/// it names a product. The banner's OS and the detector's findings make up
+/// the extra info.
+///
+/// ```
+/// use port_scanner::domain::{BannerRuleSet, PortScanResult, PortStatus, ServiceVersion};
+///
+/// let found = BannerRuleSet::builtin().best_match("HTTP/1.1 200 OK\r\nServer: nginx/1.18.0 (Ubuntu)").unwrap();
+/// let result = PortScanResult::new(80, PortStatus::Open).with_version(ServiceVersion::from(found));
+/// let info = result.service_info.clone().unwrap();
+/// assert_eq!(info.product.as_deref(), Some("nginx"));
+/// assert_eq!(info.version.as_deref(), Some("1.18.0"));
+/// assert_eq!(info.extra_info.as_deref(), Some("Ubuntu"));
+///
+/// let version = result.service_version.unwrap().with_extra_info("tls=1.3");
+/// let result = PortScanResult::new(443, PortStatus::Open).with_version(version);
+/// let info = result.service_info.unwrap();
+/// assert_eq!(info.extra_info.as_deref(), Some("Ubuntu; tls=1.3"));
+///
+/// // And back again
+/// let version = ServiceVersion::from(info);
+/// assert_eq!(version.product.as_deref(), Some("nginx"));
+/// assert_eq!(version.version.as_deref(), Some("1.18.0"));
+/// ```
+impl From<&ServiceVersion> for ServiceInfo {
+    fn from(version: &ServiceVersion) -> Self {
+        let mut info = match &version.product {
+            Some(product) => Self::new().with_product(product.clone()),
+            None => version.banner.as_deref()
+                .and_then(Self::parse_product)
+                .unwrap_or_default(),
+        };
+        if version.service_name != "unknown" {
+            info.name = Some(version.service_name.clone());
+        }
+        info.version = info.version.or_else(|| version.version.clone());
+        let details: Vec<&str> = [version.os.as_deref(), version.extra_info.as_deref()]
+            .into_iter()
+            .flatten()
+            .collect();
+        if !details.is_empty() {
+            info.extra_info = Some(details.join("; "));
+        }
+        info
+    }
+}
+
+impl Default for ServiceInfo {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 /// Service version information (legacy compatibility)
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ServiceVersion {
     pub service_name: String,
+    /// Software named by the banner, e.g. `nginx`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub product: Option<String>,
     pub version: Option<String>,
+    /// Operating system the banner mentions, e.g. `Ubuntu`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub os: Option<String>,
+    /// Printable banner, see `normalize_banner`
     pub banner: Option<String>,
+    /// Banner as received, kept only when normalization changed it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub raw_banner: Option<String>,
+    /// Hex of the leading bytes as received, for binary greetings that do
+    /// not survive the text banner (only with `include_raw_banner`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub banner_hex: Option<String>,
+    /// Reading stopped at the configured byte or time cap
+    #[serde(default)]
+    pub banner_truncated: bool,
+    /// Hash of the normalized banner, equal for identical unidentified services
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fingerprint: Option<String>,
     pub protocol: String,
+    /// How strongly the banner supports the service guess (0.0–1.0)
+    pub confidence: f32,
+    /// Protocol-specific findings reported by detectors
+    pub extra_info: Option<String>,
+    /// Service name comes from the port number, not from a banner
+    #[serde(default)]
+    pub port_based: bool,
+    /// The service answered privileged requests without credentials
+    #[serde(default)]
+    pub unauthenticated: bool,
+    /// Parts of the banner or version were masked, see `Redactor`
+    #[serde(default)]
+    pub redacted: bool,
 }
 
 impl ServiceVersion {
     pub fn unknown() -> Self {
         Self {
             service_name: "unknown".to_string(),
+            product: None,
             version: None,
+            os: None,
             banner: None,
+            raw_banner: None,
+            banner_hex: None,
+            banner_truncated: false,
+            fingerprint: None,
             protocol: "tcp".to_string(),
+            confidence: 0.0,
+            extra_info: None,
+            port_based: false,
+            unauthenticated: false,
+            redacted: false,
         }
     }
 
     pub fn new(service: impl Into<String>, protocol: impl Into<String>) -> Self {
         Self {
             service_name: service.into(),
+            product: None,
             version: None,
+            os: None,
             banner: None,
+            raw_banner: None,
+            banner_hex: None,
+            banner_truncated: false,
+            fingerprint: None,
             protocol: protocol.into(),
+            confidence: 0.0,
+            extra_info: None,
+            port_based: false,
+            unauthenticated: false,
+            redacted: false,
         }
     }
 
@@ -80,12 +231,159 @@ impl ServiceVersion {
         self
     }
 
+    pub fn with_product(mut self, product: impl Into<String>) -> Self {
+        self.product = Some(product.into());
+        self
+    }
+
+    pub fn with_os(mut self, os: impl Into<String>) -> Self {
+        self.os = Some(os.into());
+        self
+    }
+
+    /// Service guessed from the port's well-known assignment
+    pub fn port_guess(service: impl Into<String>, protocol: impl Into<String>) -> Self {
+        let mut version = Self::new(service, protocol).with_confidence(crate::constants::CONFIDENCE_LOW);
+        version.port_based = true;
+        version
+    }
+
+    /// Set the banner, normalized for safe display; the original is kept in
+    /// `raw_banner` if it differs. Also sets `fingerprint` from the normalized
+    /// banner.
+    ///
+    /// ```
+    /// use port_scanner::domain::ServiceVersion;
+    ///
+    /// let a = ServiceVersion::unknown().with_banner("XYZZY ready\r\n");
+    /// let b = ServiceVersion::unknown().with_banner("XYZZY ready\r\n");
+    /// let c = ServiceVersion::unknown().with_banner("PLUGH ready\r\n");
+    /// assert!(a.fingerprint.is_some());
+    /// assert_eq!(a.fingerprint, b.fingerprint);
+    /// assert_ne!(a.fingerprint, c.fingerprint);
+    ///
+    /// // ANSI colours and a NUL byte are stripped; the original is kept
+    /// let raw = "\x1b[31mSSH-2.0-OpenSSH_9.6\x00\x1b[0m\r\n";
+    /// let v = ServiceVersion::unknown().with_banner(raw);
+    /// let banner = v.banner.as_deref().unwrap();
+    /// assert_eq!(banner, "SSH-2.0-OpenSSH_9.6");
+    /// assert!(!banner.chars().any(char::is_control));
+    /// assert_eq!(v.raw_banner.as_deref(), Some(raw));
+    ///
+    /// // A clean banner has no raw copy
+    /// assert_eq!(ServiceVersion::unknown().with_banner("SSH-2.0-OpenSSH_9.6").raw_banner, None);
+    /// ```
     pub fn with_banner(mut self, banner: impl Into<String>) -> Self {
-        self.banner = Some(banner.into());
+        let raw = banner.into();
+        let clean = normalize_banner(&raw);
+        self.raw_banner = (clean != raw).then_some(raw);
+        self.fingerprint = Some(banner_fingerprint(&clean));
+        self.banner = Some(clean);
+        self
+    }
+
+    /// Keep the first `RAW_BANNER_HEX_BYTES` of `bytes` hex-encoded in `banner_hex`
+    pub fn with_banner_bytes(mut self, bytes: &[u8]) -> Self {
+        let bytes = &bytes[..bytes.len().min(crate::constants::RAW_BANNER_HEX_BYTES)];
+        self.banner_hex = Some(to_hex(bytes));
+        self
+    }
+
+    pub fn with_banner_truncated(mut self, truncated: bool) -> Self {
+        self.banner_truncated = truncated;
+        self
+    }
+
+    pub fn with_extra_info(mut self, info: impl Into<String>) -> Self {
+        self.extra_info = Some(info.into());
+        self
+    }
+
+    pub fn with_unauthenticated(mut self, unauthenticated: bool) -> Self {
+        self.unauthenticated = unauthenticated;
+        self
+    }
+
+    pub fn with_confidence(mut self, confidence: f32) -> Self {
+        self.confidence = confidence.clamp(0.0, 1.0);
         self
     }
 }
 
+/// Compatibility view of structured service information
+impl From<ServiceInfo> for ServiceVersion {
+    fn from(info: ServiceInfo) -> Self {
+        let name = info.name.or_else(|| info.product.clone()).unwrap_or_else(|| "unknown".to_string());
+        let mut version = Self::new(name, "tcp");
+        version.product = info.product;
+        version.version = info.version;
+        version.extra_info = info.extra_info;
+        version
+    }
+}
+
+/// Make a banner safe to print and store.
+///
+/// Removes ANSI escape sequences and control characters (NUL, bell, C1
+/// codes, ...), turns CR/LF/tab into spaces and collapses runs of whitespace.
+pub fn normalize_banner(raw: &str) -> String {
+    let mut out = String::with_capacity(raw.len());
+    let mut chars = raw.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\x1b' => match chars.next() {
+                // CSI: parameters and intermediates up to a final byte in @..~
+                Some('[') => {
+                    for c in chars.by_ref() {
+                        if ('@'..='~').contains(&c) {
+                            break;
+                        }
+                    }
+                }
+                // OSC: terminated by BEL or ESC \
+                Some(']') => {
+                    while let Some(c) = chars.next() {
+                        if c == '\x07' || (c == '\x1b' && chars.next_if_eq(&'\\').is_some()) {
+                            break;
+                        }
+                    }
+                }
+                // Two-character escape
+                _ => {}
+            },
+            c if c.is_whitespace() => out.push(' '),
+            c if c.is_control() => {}
+            c => out.push(c),
+        }
+    }
+    out.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Stable hash of a normalized banner for grouping unidentified services.
+///
+/// SHA-256 of the first `FINGERPRINT_BANNER_BYTES` bytes, as the leading
+/// `FINGERPRINT_HEX_LEN` hex digits.
+pub fn banner_fingerprint(banner: &str) -> String {
+    use crate::constants::{FINGERPRINT_BANNER_BYTES, FINGERPRINT_HEX_LEN};
+
+    let bytes = &banner.as_bytes()[..banner.len().min(FINGERPRINT_BANNER_BYTES)];
+    let digest = ring::digest::digest(&ring::digest::SHA256, bytes);
+    let mut hex = to_hex(digest.as_ref());
+    hex.truncate(FINGERPRINT_HEX_LEN);
+    hex
+}
+
+/// Lowercase hex, two digits per byte
+fn to_hex(bytes: &[u8]) -> String {
+    use std::fmt::Write;
+
+    let mut hex = String::with_capacity(bytes.len() * 2);
+    for byte in bytes {
+        let _ = write!(hex, "{:02x}", byte);
+    }
+    hex
+}
+
 /// Repository trait for service information
 pub trait ServiceRepository: Send + Sync {
     fn get_service_info(&self, port: u16) -> Option<ServiceInfo>;
@@ -113,6 +411,8 @@ impl StaticServiceRepository {
         services.insert(143, "IMAP");
         services.insert(443, "HTTPS");
         services.insert(445, "SMB");
+        services.insert(993, "IMAPS");
+        services.insert(995, "POP3S");
         services.insert(3306, "MySQL");
         services.insert(3389, "RDP");
         services.insert(5432, "PostgreSQL");
@@ -150,3 +450,95 @@ impl Default for StaticServiceRepository {
         Self::new()
     }
 }
+
+/// Themed alternative to the general common-port list, for targeted scans
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CommonPortSet {
+    /// HTTP(S) servers, proxies and app servers
+    Web,
+    /// SQL, NoSQL, cache and search engines
+    Db,
+    /// Remote shells and desktops
+    Remote,
+}
+
+impl CommonPortSet {
+    pub const ALL: [CommonPortSet; 3] = [CommonPortSet::Web, CommonPortSet::Db, CommonPortSet::Remote];
+
+    /// Ports of the set, ascending
+    pub fn ports(self) -> &'static [u16] {
+        match self {
+            CommonPortSet::Web => &[80, 443, 3000, 5000, 8000, 8008, 8080, 8081, 8443, 8888, 9000, 9443],
+            CommonPortSet::Db => &[1433, 1521, 3306, 5432, 5984, 6379, 9042, 9200, 11211, 27017],
+            CommonPortSet::Remote => &[22, 23, 512, 513, 514, 3389, 5900, 5901, 5985, 5986],
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            CommonPortSet::Web => "web",
+            CommonPortSet::Db => "db",
+            CommonPortSet::Remote => "remote",
+        }
+    }
+
+    /// Parse a set name such as `web`, ignoring case
+    pub fn from_label(label: &str) -> Option<Self> {
+        let label = label.trim();
+        Self::ALL.into_iter().find(|set| set.name().eq_ignore_ascii_case(label))
+    }
+}
+
+impl std::fmt::Display for CommonPortSet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Attack-surface category of a service, for at-a-glance summaries
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ServiceCategory {
+    Web,
+    Database,
+    Mail,
+    RemoteAccess,
+    FileSharing,
+    Other,
+}
+
+/// Service names per category, matched case-insensitively on the name up
+/// to the first `-` (so `HTTP-Proxy` is `HTTP`)
+const SERVICE_CATEGORIES: &[(ServiceCategory, &[&str])] = &[
+    (ServiceCategory::Web, &["HTTP", "HTTPS"]),
+    (ServiceCategory::Database, &["MySQL", "PostgreSQL", "MongoDB", "Redis", "MSSQL", "Oracle", "Memcached", "Elasticsearch", "CouchDB", "Cassandra"]),
+    (ServiceCategory::Mail, &["SMTP", "SMTPS", "Submission", "POP3", "POP3S", "IMAP", "IMAPS"]),
+    (ServiceCategory::RemoteAccess, &["SSH", "Telnet", "RDP", "VNC", "WinRM"]),
+    (ServiceCategory::FileSharing, &["FTP", "FTPS", "SFTP", "TFTP", "SMB", "NetBIOS", "NFS", "AFP", "rsync"]),
+];
+
+impl ServiceCategory {
+    /// Category of a service name as reported by the detectors or the
+    /// service repository
+    pub fn of_service(name: &str) -> Self {
+        let base = name.split('-').next().unwrap_or(name);
+        SERVICE_CATEGORIES.iter()
+            .find(|(_, names)| names.iter().any(|n| n.eq_ignore_ascii_case(base)))
+            .map_or(ServiceCategory::Other, |&(category, _)| category)
+    }
+}
+
+impl std::fmt::Display for ServiceCategory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            ServiceCategory::Web => "Web",
+            ServiceCategory::Database => "Database",
+            ServiceCategory::Mail => "Mail",
+            ServiceCategory::RemoteAccess => "Remote-Access",
+            ServiceCategory::FileSharing => "File-Sharing",
+            ServiceCategory::Other => "Other",
+        };
+        f.pad(name)
+    }
+}
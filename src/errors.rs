@@ -1,4 +1,4 @@
-/// Custom error types for the port scanner
+//! Custom error types for the port scanner
 
 use thiserror::Error;
 use std::io;
@@ -16,8 +16,11 @@ pub enum ScanError {
     #[error("Timeout after {0:?}")]
     Timeout(Duration),
     
-    #[error("Permission denied for port {0}")]
-    PermissionDenied(u16),
+    #[error("Permission denied: {0}")]
+    PermissionDenied(String),
+
+    #[error("Host {target} is unreachable: {reason}")]
+    Unreachable { target: String, reason: String },
     
     #[error("Invalid port number: {0}")]
     InvalidPort(u16),
@@ -30,6 +33,9 @@ pub enum ScanError {
     
     #[error("Thread pool error: {0}")]
     ThreadPool(String),
+
+    #[error("Async runtime error: {0}")]
+    Runtime(String),
     
     #[error("Configuration error: {0}")]
     Config(#[from] ConfigError),
@@ -47,8 +53,47 @@ pub enum ConfigError {
     #[error("Invalid thread count: {0}")]
     InvalidThreadCount(usize),
     
+    #[error("Invalid concurrency: {0}")]
+    InvalidConcurrency(usize),
+
+    #[error("Invalid read limit: {0} bytes")]
+    InvalidReadLimit(usize),
+
+    #[error("Invalid packet field: {0}")]
+    InvalidPacketField(String),
+
+    #[error("Invalid banner pattern: {0}")]
+    InvalidPattern(String),
+
+    #[error("Invalid confidence threshold: {0} (expected 0.0-1.0)")]
+    InvalidConfidence(f32),
+    
     #[error("Invalid scan mode")]
     InvalidScanMode,
+
+    #[error("Invalid port clamp: {0}")]
+    InvalidPortClamp(String),
+
+    #[error("Unknown port set: {0} (expected web, db or remote)")]
+    UnknownPortSet(String),
+    
+    #[error("Invalid target: {0}")]
+    InvalidTarget(String),
+    
+    #[error("Unknown network interface: {0}")]
+    UnknownInterface(String),
+
+    #[error("Binding to interface {0} is not supported on this platform")]
+    InterfaceBindUnsupported(String),
+    
+    #[error("Line {line}: {reason}")]
+    InvalidInputLine { line: usize, reason: String },
+    
+    #[error("Cannot read input file {0}")]
+    InputFile(String),
+
+    #[error("All targets are excluded")]
+    AllTargetsExcluded,
 }
 
 /// Detection errors
@@ -78,6 +123,12 @@ pub enum FormatterError {
     
     #[error("Unsupported format")]
     UnsupportedFormat,
+
+    #[error("Unknown output field '{0}' (expected one of: {1})")]
+    UnknownField(String, String),
+
+    #[error("Invalid report at line {line}: {reason}")]
+    InvalidReport { line: usize, reason: String },
 }
 
 /// Result type alias for scan operations
@@ -0,0 +1,93 @@
+//! Counting of the connections a scan opens
+//!
+//! Detection and retries can multiply the nominal port count several-fold.
+//! Every TCP connect and UDP probe calls `record_attempt`, which counts
+//! into the `AttemptCounter` of the scan running the current task; the
+//! executor scopes each probe to its scan's counter.
+
+use std::future::Future;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+tokio::task_local! {
+    static CURRENT: AttemptCounter;
+}
+
+/// Connection attempts made by one scan, shared by its clones.
+///
+/// ```
+/// use port_scanner::application::VersionDetector;
+/// use port_scanner::infrastructure::testing::{Reply, ScriptedConnector};
+/// use port_scanner::prelude::*;
+/// use port_scanner::scanning::{DetectorRegistry, StandardScan};
+/// use std::sync::Arc;
+/// use std::time::Duration;
+///
+/// # #[tokio::main]
+/// # async fn main() -> anyhow::Result<()> {
+/// let mut listeners = Vec::new();
+/// for _ in 0..3 {
+///     listeners.push(tokio::net::TcpListener::bind("127.0.0.1:0").await?);
+/// }
+/// let ports: Vec<u16> = listeners.iter().map(|l| l.local_addr().unwrap().port()).collect();
+/// let config = ScanConfigBuilder::new()
+///     .target("127.0.0.1".parse()?)
+///     .custom_ports(ports.clone())
+///     .detect_versions(true)
+///     .max_read_duration(Duration::from_millis(50))
+///     .probe_retries(1)
+///     .build()?;
+///
+/// // Only the banner grabber, which reconnects once per open port
+/// let mut detectors = DetectorRegistry::new();
+/// detectors.register(Box::new(VersionDetector::new()));
+/// // The first connect to the first port times out
+/// let slow_once = ScriptedConnector::new(Reply::Accept).on_port(ports[0], [Reply::Timeout, Reply::Accept]);
+/// let strategy = StandardScan::with_detectors(Arc::new(detectors)).with_connector(Arc::new(slow_once));
+/// let results = PortScanner::new(config.clone())?
+///     .with_strategy(Arc::new(strategy))
+///     .scan_all(|_| {})
+///     .await;
+///
+/// assert_eq!(results.open_ports, 3);
+/// // One connect per port, one retry, one detection reconnect per open port
+/// assert_eq!(results.connection_attempts, 3 + 1 + 3);
+///
+/// let report = ScanReport::new(&config, results, 1.0);
+/// assert_eq!(report.statistics.connection_attempts, 7);
+/// let text = OutputFormatterFactory::create(OutputFormat::Text, false).format(&report)?;
+/// assert!(text.contains("Connection Attempts: 7"));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct AttemptCounter(Arc<AtomicU64>);
+
+impl AttemptCounter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&self) {
+        self.0.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn get(&self) -> u64 {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    pub fn reset(&self) {
+        self.0.store(0, Ordering::Relaxed);
+    }
+
+    /// Run `future` with `record_attempt` counting into this counter
+    pub async fn scope<F: Future>(&self, future: F) -> F::Output {
+        CURRENT.scope(self.clone(), future).await
+    }
+}
+
+/// Count one TCP connect or UDP probe against the scan running this task;
+/// does nothing outside a scan
+pub fn record_attempt() {
+    let _ = CURRENT.try_with(AttemptCounter::record);
+}
@@ -0,0 +1,65 @@
+//! Time source abstraction, so time-dependent code can run against a fixed clock
+
+use std::fmt::Debug;
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime};
+
+/// Source of wall-clock and monotonic time
+pub trait Clock: Send + Sync + Debug {
+    /// Current wall-clock time
+    fn now(&self) -> SystemTime;
+    /// Current monotonic time
+    fn instant(&self) -> Instant;
+}
+
+/// The system clock
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> SystemTime {
+        SystemTime::now()
+    }
+
+    fn instant(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// Clock that only moves when told to
+#[derive(Debug)]
+pub struct MockClock {
+    start_time: SystemTime,
+    start_instant: Instant,
+    offset: Mutex<Duration>,
+}
+
+impl MockClock {
+    /// Clock reading `start` until advanced
+    pub fn new(start: SystemTime) -> Self {
+        Self {
+            start_time: start,
+            start_instant: Instant::now(),
+            offset: Mutex::new(Duration::ZERO),
+        }
+    }
+
+    /// Move both wall-clock and monotonic time forward by `by`
+    pub fn advance(&self, by: Duration) {
+        *self.offset.lock().unwrap_or_else(|e| e.into_inner()) += by;
+    }
+
+    fn offset(&self) -> Duration {
+        *self.offset.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&self) -> SystemTime {
+        self.start_time + self.offset()
+    }
+
+    fn instant(&self) -> Instant {
+        self.start_instant + self.offset()
+    }
+}
@@ -0,0 +1,65 @@
+//! Tracing subscriber setup for the human and JSON log formats
+
+use tracing::{Level, Subscriber};
+use tracing_subscriber::fmt::MakeWriter;
+
+/// How log events are rendered
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LogFormat {
+    /// Human-readable lines
+    #[default]
+    Text,
+    /// One JSON object per line with `timestamp`, `level`, `message` and
+    /// the event's fields at the top level, for log aggregators
+    Json,
+}
+
+/// Subscriber writing events at `level` and above to `writer`
+///
+/// ```
+/// use port_scanner::infrastructure::{log_subscriber, LogFormat};
+/// use std::io::Write;
+/// use std::sync::{Arc, Mutex};
+/// use tracing::Level;
+///
+/// /// Collects log output in memory
+/// #[derive(Clone, Default)]
+/// struct Buffer(Arc<Mutex<Vec<u8>>>);
+///
+/// impl Write for Buffer {
+///     fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+///         self.0.lock().unwrap().write(buf)
+///     }
+///     fn flush(&mut self) -> std::io::Result<()> {
+///         Ok(())
+///     }
+/// }
+///
+/// let buffer = Buffer::default();
+/// let writer = buffer.clone();
+/// let subscriber = log_subscriber(LogFormat::Json, Level::INFO, move || writer.clone());
+/// tracing::subscriber::with_default(subscriber, || {
+///     tracing::warn!(port = 23, "Telnet is open");
+///     tracing::debug!("below the level, not written");
+///     tracing::info!("Scan finished");
+/// });
+///
+/// let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+/// let lines: Vec<serde_json::Value> = output.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+/// assert_eq!(lines.len(), 2);
+/// assert_eq!((&lines[0]["level"], &lines[0]["message"], &lines[0]["port"]), (&"WARN".into(), &"Telnet is open".into(), &23.into()));
+/// assert_eq!((&lines[1]["level"], &lines[1]["message"]), (&"INFO".into(), &"Scan finished".into()));
+/// ```
+pub fn log_subscriber<W>(format: LogFormat, level: Level, writer: W) -> Box<dyn Subscriber + Send + Sync>
+where
+    W: for<'w> MakeWriter<'w> + Send + Sync + 'static,
+{
+    let subscriber = tracing_subscriber::fmt()
+        .with_max_level(level)
+        .with_target(false)
+        .with_writer(writer);
+    match format {
+        LogFormat::Text => Box::new(subscriber.finish()),
+        LogFormat::Json => Box::new(subscriber.json().flatten_event(true).finish()),
+    }
+}
@@ -1,5 +1,19 @@
-/// Infrastructure layer module exports
+//! Infrastructure layer module exports
 
 pub mod network;
+pub mod clock;
+pub mod read_limits;
+pub mod resolver;
+pub mod attempts;
+pub mod rng;
+pub mod logging;
+#[doc(hidden)]
+pub mod testing;
 
-pub use network::{NetworkConnector, TcpConnector, network_utils};
+pub use network::{NetworkConnector, TcpConnector, AsyncNetworkConnector, AsyncTcpConnector, AsyncDatagramProber, AsyncUdpProber, network_utils};
+pub use clock::{Clock, SystemClock, MockClock};
+pub use read_limits::{ReadLimits, CappedRead, read_limits, read_capped};
+pub use resolver::{ReverseResolver, SystemResolver, resolve_all};
+pub use logging::{LogFormat, log_subscriber};
+pub use attempts::{AttemptCounter, record_attempt};
+pub use rng::{ScanRng, with_rng};
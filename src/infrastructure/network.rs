@@ -1,8 +1,12 @@
-/// Network connectivity abstractions
+//! Network connectivity abstractions
 
 use std::io;
-use std::net::{SocketAddr, TcpStream};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV6, TcpStream};
 use std::time::Duration;
+use socket2::{Domain, Protocol, Socket, TcpKeepalive, Type};
+use tokio::net::{TcpSocket, TcpStream as AsyncTcpStream, UdpSocket};
+
+use crate::constants::SOURCE_PORT_BIND_ATTEMPTS;
 
 /// Trait for network connectivity to enable testing and mocking
 pub trait NetworkConnector: Send + Sync {
@@ -31,52 +35,363 @@ impl NetworkConnector for TcpConnector {
     }
 }
 
+/// Async counterpart of `NetworkConnector`, used by the scan strategies
+///
+/// A mock connector lets a strategy scan without touching the network:
+///
+/// ```
+/// use port_scanner::infrastructure::AsyncNetworkConnector;
+/// use port_scanner::prelude::*;
+/// use port_scanner::scanning::StandardScan;
+/// use std::io;
+/// use std::net::SocketAddr;
+/// use std::sync::{Arc, Mutex};
+/// use std::time::Duration;
+/// use tokio::net::TcpStream;
+///
+/// /// Times out on port 22, refuses the rest, and records every address
+/// #[derive(Default)]
+/// struct Mock(Mutex<Vec<SocketAddr>>);
+///
+/// #[async_trait::async_trait]
+/// impl AsyncNetworkConnector for Mock {
+///     async fn connect(&self, addr: &SocketAddr, _: Duration) -> io::Result<TcpStream> {
+///         self.0.lock().unwrap().push(*addr);
+///         Err(if addr.port() == 22 { io::ErrorKind::TimedOut } else { io::ErrorKind::ConnectionRefused }.into())
+///     }
+/// }
+///
+/// # #[tokio::main]
+/// # async fn main() -> anyhow::Result<()> {
+/// let config = ScanConfigBuilder::new()
+///     .target("192.0.2.7".parse()?)
+///     .range(20, 25)
+///     .build()?;
+/// let mock = Arc::new(Mock::default());
+/// let strategy = StandardScan::new().with_connector(mock.clone());
+/// let mut results = PortScanner::new(config)?.with_strategy(Arc::new(strategy)).scan_all(|_| {}).await.results;
+/// results.sort_by_key(|r| r.port);
+///
+/// let statuses: Vec<_> = results.iter().map(|r| (r.port, r.status.clone())).collect();
+/// assert_eq!(statuses, [
+///     (20, PortStatus::Closed), (21, PortStatus::Closed), (22, PortStatus::Filtered),
+///     (23, PortStatus::Closed), (24, PortStatus::Closed), (25, PortStatus::Closed),
+/// ]);
+/// let mut seen: Vec<String> = mock.0.lock().unwrap().iter().map(SocketAddr::to_string).collect();
+/// seen.sort();
+/// assert_eq!(seen, (20..=25).map(|port| format!("192.0.2.7:{port}")).collect::<Vec<_>>());
+/// # Ok(())
+/// # }
+/// ```
+#[async_trait::async_trait]
+pub trait AsyncNetworkConnector: Send + Sync {
+    /// Connect to `addr`, failing with `ErrorKind::TimedOut` after `timeout`
+    async fn connect(&self, addr: &SocketAddr, timeout: Duration) -> io::Result<AsyncTcpStream>;
+}
+
+/// Real async TCP connector with control over the local end of the socket
+#[derive(Debug, Clone, Default)]
+pub struct AsyncTcpConnector {
+    source_port: Option<u16>,
+    randomize_source_port: bool,
+    reuse_address: bool,
+    bind_device: Option<String>,
+    linger: Option<Duration>,
+    keepalive: Option<Duration>,
+}
+
+impl AsyncTcpConnector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Connect from this local port
+    pub fn source_port(mut self, port: Option<u16>) -> Self {
+        self.source_port = port;
+        self
+    }
+
+    /// Connect from a random high port, drawn per connection
+    pub fn randomize_source_port(mut self, randomize: bool) -> Self {
+        self.randomize_source_port = randomize;
+        self
+    }
+
+    /// Set `SO_REUSEADDR` so a fixed source port can be reused right away
+    pub fn reuse_address(mut self, reuse: bool) -> Self {
+        self.reuse_address = reuse;
+        self
+    }
+
+    /// Send through this interface (`SO_BINDTODEVICE`, Linux only; needs
+    /// CAP_NET_RAW before Linux 5.7). The socket is bound before connecting:
+    ///
+    /// ```
+    /// # #[cfg(target_os = "linux")]
+    /// # #[tokio::main]
+    /// # async fn main() -> std::io::Result<()> {
+    /// use port_scanner::infrastructure::{AsyncNetworkConnector, AsyncTcpConnector};
+    /// use std::time::Duration;
+    ///
+    /// let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+    /// let connector = AsyncTcpConnector::new().bind_device(Some("lo".to_string()));
+    /// let stream = connector.connect(&listener.local_addr()?, Duration::from_secs(1)).await?;
+    /// assert_eq!(socket2::SockRef::from(&stream).device()?.as_deref(), Some(&b"lo"[..]));
+    /// # Ok(())
+    /// # }
+    /// # #[cfg(not(target_os = "linux"))]
+    /// # fn main() {}
+    /// ```
+    pub fn bind_device(mut self, device: Option<String>) -> Self {
+        self.bind_device = device;
+        self
+    }
+
+    /// Set `SO_LINGER`; zero makes closing send RST and skip TIME_WAIT, so
+    /// the local port is free again at once
+    ///
+    /// ```
+    /// use port_scanner::infrastructure::{AsyncNetworkConnector, AsyncTcpConnector};
+    /// use std::time::Duration;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> std::io::Result<()> {
+    /// let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+    /// let connector = AsyncTcpConnector::new()
+    ///     .linger(Some(Duration::ZERO))
+    ///     .keepalive(Some(Duration::from_secs(30)));
+    /// let stream = connector.connect(&listener.local_addr()?, Duration::from_secs(1)).await?;
+    ///
+    /// let socket = socket2::SockRef::from(&stream);
+    /// assert_eq!(socket.linger()?, Some(Duration::ZERO));
+    /// assert!(socket.keepalive()?);
+    /// # #[cfg(target_os = "linux")]
+    /// assert_eq!(socket.tcp_keepalive_time()?, Duration::from_secs(30));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn linger(mut self, linger: Option<Duration>) -> Self {
+        self.linger = linger;
+        self
+    }
+
+    /// Enable TCP keepalive, idle for `idle` before the first probe
+    pub fn keepalive(mut self, idle: Option<Duration>) -> Self {
+        self.keepalive = idle;
+        self
+    }
+
+    fn local_port(&self) -> Option<u16> {
+        self.source_port.or_else(|| self.randomize_source_port.then(network_utils::random_source_port))
+    }
+
+    /// Create a socket for `addr` with the configured options and local port
+    fn socket(&self, addr: &SocketAddr, local_port: Option<u16>) -> io::Result<Socket> {
+        let socket = Socket::new(Domain::for_address(*addr), Type::STREAM, Some(Protocol::TCP))?;
+        socket.set_nonblocking(true)?;
+        if self.reuse_address {
+            socket.set_reuse_address(true)?;
+        }
+        // Set before connecting so they also cover a connect that fails
+        if let Some(linger) = self.linger {
+            socket.set_linger(Some(linger))?;
+        }
+        if let Some(idle) = self.keepalive {
+            socket.set_tcp_keepalive(&TcpKeepalive::new().with_time(idle))?;
+        }
+        if let Some(device) = &self.bind_device {
+            #[cfg(any(target_os = "android", target_os = "fuchsia", target_os = "linux"))]
+            socket.bind_device(Some(device.as_bytes()))?;
+            #[cfg(not(any(target_os = "android", target_os = "fuchsia", target_os = "linux")))]
+            return Err(io::Error::new(io::ErrorKind::Unsupported, format!("cannot bind to device {} on this platform", device)));
+        }
+        if let Some(port) = local_port {
+            let unspecified = match addr {
+                SocketAddr::V4(_) => IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+                SocketAddr::V6(_) => IpAddr::V6(Ipv6Addr::UNSPECIFIED),
+            };
+            socket.bind(&SocketAddr::new(unspecified, port).into())?;
+        }
+        Ok(socket)
+    }
+
+    /// Socket bound as configured; random source ports are redrawn when taken
+    fn bound_socket(&self, addr: &SocketAddr) -> io::Result<Socket> {
+        let mut attempts = if self.source_port.is_none() && self.randomize_source_port { SOURCE_PORT_BIND_ATTEMPTS } else { 1 };
+        loop {
+            match self.socket(addr, self.local_port()) {
+                Err(e) if e.kind() == io::ErrorKind::AddrInUse && attempts > 1 => attempts -= 1,
+                result => return result,
+            }
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl AsyncNetworkConnector for AsyncTcpConnector {
+    async fn connect(&self, addr: &SocketAddr, timeout: Duration) -> io::Result<AsyncTcpStream> {
+        let socket = TcpSocket::from_std_stream(self.bound_socket(addr)?.into());
+        match tokio::time::timeout(timeout, socket.connect(*addr)).await {
+            Ok(result) => result,
+            Err(_) => Err(io::Error::new(io::ErrorKind::TimedOut, "connect timed out")),
+        }
+    }
+}
+
+/// Sends single UDP probes; a trait so UDP scans can be tested and mocked
+#[async_trait::async_trait]
+pub trait AsyncDatagramProber: Send + Sync {
+    /// Send `payload` to `addr` and wait up to `timeout` for the first reply.
+    ///
+    /// Returns `Ok(None)` on silence. An ICMP port-unreachable reply
+    /// surfaces as an error that `network_utils::is_port_unreachable` accepts.
+    async fn probe(&self, addr: &SocketAddr, payload: &[u8], timeout: Duration) -> io::Result<Option<Vec<u8>>>;
+}
+
+/// Real UDP prober using a connected socket per probe
+#[derive(Debug, Clone, Default)]
+pub struct AsyncUdpProber {
+    bind_device: Option<String>,
+}
+
+impl AsyncUdpProber {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Send through this interface, as `AsyncTcpConnector::bind_device`
+    pub fn bind_device(mut self, device: Option<String>) -> Self {
+        self.bind_device = device;
+        self
+    }
+}
+
+#[async_trait::async_trait]
+impl AsyncDatagramProber for AsyncUdpProber {
+    async fn probe(&self, addr: &SocketAddr, payload: &[u8], timeout: Duration) -> io::Result<Option<Vec<u8>>> {
+        let local: SocketAddr = match addr {
+            SocketAddr::V4(_) => (Ipv4Addr::UNSPECIFIED, 0).into(),
+            SocketAddr::V6(_) => (Ipv6Addr::UNSPECIFIED, 0).into(),
+        };
+        let socket = UdpSocket::bind(local).await?;
+        if let Some(device) = &self.bind_device {
+            #[cfg(any(target_os = "android", target_os = "fuchsia", target_os = "linux"))]
+            socket2::SockRef::from(&socket).bind_device(Some(device.as_bytes()))?;
+            #[cfg(not(any(target_os = "android", target_os = "fuchsia", target_os = "linux")))]
+            return Err(io::Error::new(io::ErrorKind::Unsupported, format!("cannot bind to device {} on this platform", device)));
+        }
+        // Connecting lets the kernel report ICMP unreachable on the next receive
+        socket.connect(addr).await?;
+        socket.send(payload).await?;
+
+        let mut buf = vec![0u8; crate::infrastructure::read_limits().max_bytes.min(u16::MAX as usize)];
+        match tokio::time::timeout(timeout, socket.recv(&mut buf)).await {
+            Ok(Ok(n)) => {
+                buf.truncate(n);
+                Ok(Some(buf))
+            }
+            Ok(Err(e)) => Err(e),
+            Err(_) => Ok(None),
+        }
+    }
+}
+
 /// Helper functions for network operations
 pub mod network_utils {
     use super::*;
-    use std::net::{IpAddr, TcpListener};
     use std::io::ErrorKind;
-    
-    /// Generate a random high port number (1024-65535)
+    use rand::Rng;
+    use crate::domain::Port;
+    use crate::errors::{ConfigError, ConfigResult};
+    use crate::infrastructure::with_rng;
+
+    /// Generate a random high port number (1024-65535) from the scan's RNG
     pub fn random_source_port() -> u16 {
-        use std::time::{SystemTime, UNIX_EPOCH};
-        
-        let timestamp = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_nanos();
-        
-        let port = (timestamp % (65535 - 1024) as u128 + 1024) as u16;
-        port
-    }
-
-    /// Calculate random delay with jitter
+        with_rng(|rng| rng.gen_range(1024..=65535))
+    }
+
+    /// Calculate random delay with jitter drawn from the scan's RNG
     pub fn random_delay_jitter(base_delay: Duration, jitter_percent: u64) -> Duration {
-        use std::time::{SystemTime, UNIX_EPOCH};
-        
-        let timestamp = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_nanos();
-        
-        let jitter_range = (base_delay.as_millis() * jitter_percent as u128) / 100;
-        let jitter = (timestamp % (jitter_range * 2)) as i128 - jitter_range as i128;
-        
-        let new_delay_ms = base_delay.as_millis() as i128 + jitter;
-        let new_delay_ms = new_delay_ms.max(0) as u64;
-        
-        Duration::from_millis(new_delay_ms)
-    }
-
-    /// Attempt to connect from a specific source port
+        let base_ms = base_delay.as_millis() as i128;
+        let jitter_range = base_ms * jitter_percent as i128 / 100;
+        if jitter_range == 0 {
+            return base_delay;
+        }
+        let jitter = with_rng(|rng| rng.gen_range(-jitter_range..=jitter_range));
+
+        Duration::from_millis((base_ms + jitter).max(0) as u64)
+    }
+
+    /// Connect to `remote_addr` from `local_addr` (blocking)
     pub fn connect_from_port(
         local_addr: SocketAddr,
         remote_addr: SocketAddr,
         timeout: Duration,
     ) -> io::Result<TcpStream> {
-        // This is a simplified version - full implementation would use socket2 crate
-        // For now, fall back to standard connection
-        TcpStream::connect_timeout(&remote_addr, timeout)
+        let socket = Socket::new(Domain::for_address(remote_addr), Type::STREAM, Some(Protocol::TCP))?;
+        socket.set_reuse_address(true)?;
+        socket.bind(&local_addr.into())?;
+        socket.connect_timeout(&remote_addr.into(), timeout)?;
+        Ok(socket.into())
+    }
+
+    /// Parse an IP address with an optional IPv6 zone (`fe80::1%eth0`).
+    ///
+    /// Returns the address and its scope id (0 when no zone was given).
+    pub fn parse_scoped_ip(s: &str) -> ConfigResult<(IpAddr, u32)> {
+        let (addr, zone) = match s.trim().split_once('%') {
+            Some((addr, zone)) => (addr, Some(zone)),
+            None => (s.trim(), None),
+        };
+        let ip: IpAddr = addr.parse()
+            .map_err(|_| ConfigError::InvalidTarget(s.to_string()))?;
+
+        let scope_id = match zone {
+            None => 0,
+            Some(_) if ip.is_ipv4() => return Err(ConfigError::InvalidTarget(s.to_string())),
+            Some(zone) => interface_index(zone)
+                .ok_or_else(|| ConfigError::UnknownInterface(zone.to_string()))?,
+        };
+        Ok((ip, scope_id))
+    }
+
+    /// Resolve an interface name or numeric zone to its interface index
+    pub fn interface_index(zone: &str) -> Option<u32> {
+        if let Ok(index) = zone.parse::<u32>() {
+            return Some(index);
+        }
+        #[cfg(unix)]
+        {
+            let name = std::ffi::CString::new(zone).ok()?;
+            // SAFETY: `name` is a valid NUL-terminated string for the duration of the call
+            let index = unsafe { libc::if_nametoindex(name.as_ptr()) };
+            (index != 0).then_some(index)
+        }
+        #[cfg(not(unix))]
+        {
+            None
+        }
+    }
+
+    /// Check that `name` is an interface probes can be bound to: it must
+    /// exist, and the platform must support `SO_BINDTODEVICE`
+    pub fn check_bind_interface(name: &str) -> ConfigResult<()> {
+        if !cfg!(any(target_os = "android", target_os = "fuchsia", target_os = "linux")) {
+            return Err(ConfigError::InterfaceBindUnsupported(name.to_string()));
+        }
+        // A numeric zone is an index, not a device name
+        if name.parse::<u32>().is_ok() || interface_index(name).is_none() {
+            return Err(ConfigError::UnknownInterface(name.to_string()));
+        }
+        Ok(())
+    }
+
+    /// Build the socket address for a probe, keeping the IPv6 scope id
+    pub fn socket_addr(ip: IpAddr, port: Port, scope_id: u32) -> SocketAddr {
+        match ip {
+            IpAddr::V6(v6) => SocketAddr::V6(SocketAddrV6::new(v6, port, 0, scope_id)),
+            IpAddr::V4(_) => SocketAddr::new(ip, port),
+        }
     }
 
     /// Get number of CPU cores for parallel processing
@@ -91,8 +406,57 @@ pub mod network_utils {
         error.kind() == ErrorKind::ConnectionRefused
     }
 
+    /// Check if a UDP probe error is an ICMP port unreachable: Linux and the
+    /// BSDs report it on the connected socket as `ECONNREFUSED`, Windows as
+    /// `WSAECONNRESET`
+    pub fn is_port_unreachable(error: &io::Error) -> bool {
+        is_connection_refused(error) || (cfg!(windows) && error.kind() == ErrorKind::ConnectionReset)
+    }
+
+    /// Check if a port scan result indicates the connection was reset or aborted
+    pub fn is_connection_reset(error: &io::Error) -> bool {
+        matches!(error.kind(), ErrorKind::ConnectionReset | ErrorKind::ConnectionAborted)
+    }
+
     /// Check if a port scan result indicates timeout
     pub fn is_timeout(error: &io::Error) -> bool {
         error.kind() == ErrorKind::TimedOut
     }
+
+    /// Check if an error means the host cannot be reached at all (no route,
+    /// or no local address of its family), so every port would fail alike
+    pub fn is_unreachable(error: &io::Error) -> bool {
+        matches!(
+            error.kind(),
+            ErrorKind::AddrNotAvailable | ErrorKind::NetworkUnreachable | ErrorKind::HostUnreachable
+        )
+    }
+
+    /// Ask the kernel for a route to `addr` without sending anything
+    pub fn check_route(addr: &SocketAddr) -> io::Result<()> {
+        let local: SocketAddr = match addr {
+            SocketAddr::V4(_) => (Ipv4Addr::UNSPECIFIED, 0).into(),
+            SocketAddr::V6(_) => (Ipv6Addr::UNSPECIFIED, 0).into(),
+        };
+        std::net::UdpSocket::bind(local)?.connect(addr)
+    }
+
+    /// Close `stream` with a FIN rather than a reset.
+    ///
+    /// Closing a socket with unread data makes the kernel send RST, which
+    /// services log as an aborted session, so data that has already arrived
+    /// (up to the read limit) is discarded before shutting down.
+    pub async fn close_gracefully(mut stream: AsyncTcpStream) {
+        let mut buf = [0u8; 1024];
+        let mut drained = 0;
+        while drained < crate::infrastructure::read_limits().max_bytes {
+            match stream.try_read(&mut buf) {
+                Ok(n) if n > 0 => drained += n,
+                _ => break,
+            }
+        }
+        if let Err(e) = tokio::io::AsyncWriteExt::shutdown(&mut stream).await {
+            tracing::trace!("Shutdown failed, dropping connection: {}", e);
+        }
+    }
 }
@@ -0,0 +1,138 @@
+//! Caps on how much detectors read from a single connection
+//!
+//! A hostile or broken service can stream forever or drip one byte at a
+//! time. Every detector read loop goes through `read_capped`, which stops at
+//! the configured byte and duration ceilings and gives up on a reply that
+//! stalls mid-way for longer than the idle window. The limits are those of
+//! the scan running the current task; the executor scopes each probe to its
+//! scan's `ReadLimits`.
+
+use std::future::Future;
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncReadExt};
+use tokio::time::{timeout_at, Instant};
+
+use crate::constants::{BANNER_BUFFER_SIZE, DEFAULT_MAX_READ_BYTES, DEFAULT_MAX_READ_DURATION, DEFAULT_MAX_READ_IDLE};
+
+/// Byte and time ceilings for one read
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReadLimits {
+    pub max_bytes: usize,
+    pub max_duration: Duration,
+    /// Longest gap between chunks once a reply has started arriving
+    pub max_idle: Duration,
+}
+
+impl Default for ReadLimits {
+    fn default() -> Self {
+        Self {
+            max_bytes: DEFAULT_MAX_READ_BYTES,
+            max_duration: DEFAULT_MAX_READ_DURATION,
+            max_idle: DEFAULT_MAX_READ_IDLE,
+        }
+    }
+}
+
+tokio::task_local! {
+    static CURRENT: ReadLimits;
+}
+
+impl ReadLimits {
+    /// Run `future` with `read_limits` returning these limits
+    pub async fn scope<F: Future>(self, future: F) -> F::Output {
+        CURRENT.scope(self, future).await
+    }
+}
+
+/// Limits of the scan running this task; outside a scan, the defaults
+pub fn read_limits() -> ReadLimits {
+    CURRENT.try_with(|limits| *limits).unwrap_or_default()
+}
+
+/// Data from a capped read
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CappedRead {
+    pub data: Vec<u8>,
+    /// Reading stopped at a limit while the peer was still sending
+    pub truncated: bool,
+}
+
+/// Read until `complete(data)` holds, the peer closes or stays quiet, or a
+/// limit from `read_limits()` is hit.
+///
+/// The first chunk may take up to `idle_timeout`; after that each chunk must
+/// follow the previous one within `max_idle`, so a peer trickling a byte at
+/// a time cannot hold the connection for the whole `max_duration`.
+///
+/// I/O errors end the read; whatever arrived before is returned.
+///
+/// ```
+/// use port_scanner::infrastructure::{read_capped, ReadLimits};
+/// use std::time::{Duration, Instant};
+/// use tokio::io::AsyncWriteExt;
+///
+/// # #[tokio::main]
+/// # async fn main() -> anyhow::Result<()> {
+/// let limits = ReadLimits {
+///     max_bytes: 1024,
+///     max_duration: Duration::from_secs(10),
+///     max_idle: Duration::from_millis(100),
+/// };
+///
+/// // Sends one byte, then stalls with the connection open
+/// let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+/// let addr = listener.local_addr()?;
+/// tokio::spawn(async move {
+///     let (mut peer, _) = listener.accept().await.unwrap();
+///     peer.write_all(b"S").await.unwrap();
+///     tokio::time::sleep(Duration::from_secs(30)).await;
+/// });
+///
+/// let mut stream = tokio::net::TcpStream::connect(addr).await?;
+/// let started = Instant::now();
+/// let read = limits.scope(read_capped(&mut stream, Duration::from_secs(5), |data| data.contains(&b'\n'))).await;
+///
+/// // Given up after the idle window, not the 5s wait or the 10s budget
+/// assert_eq!(read.data, b"S");
+/// assert!(!read.truncated);
+/// assert!(started.elapsed() < Duration::from_secs(1));
+/// # Ok(())
+/// # }
+/// ```
+pub async fn read_capped<S, F>(stream: &mut S, idle_timeout: Duration, mut complete: F) -> CappedRead
+where
+    S: AsyncRead + Unpin,
+    F: FnMut(&[u8]) -> bool,
+{
+    let limits = read_limits();
+    let deadline = Instant::now() + limits.max_duration;
+    let mut buffer = [0u8; BANNER_BUFFER_SIZE];
+    let mut read = CappedRead::default();
+
+    loop {
+        // A peer that never pauses keeps `read` ready, so the timeout alone
+        // would not enforce the deadline
+        let remaining = limits.max_bytes.saturating_sub(read.data.len());
+        if remaining == 0 || Instant::now() >= deadline {
+            read.truncated = true;
+            break;
+        }
+        let chunk = buffer.len().min(remaining);
+        let idle = if read.data.is_empty() { idle_timeout } else { idle_timeout.min(limits.max_idle) };
+        let wait = deadline.min(Instant::now() + idle);
+        match timeout_at(wait, stream.read(&mut buffer[..chunk])).await {
+            Ok(Ok(n)) if n > 0 => {
+                read.data.extend_from_slice(&buffer[..n]);
+                if complete(&read.data) {
+                    break;
+                }
+            }
+            Err(_) if Instant::now() >= deadline => {
+                read.truncated = !read.data.is_empty();
+                break;
+            }
+            _ => break,
+        }
+    }
+    read
+}
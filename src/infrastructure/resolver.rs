@@ -0,0 +1,77 @@
+//! Reverse DNS lookups, behind a trait so results can be enriched against a stub
+
+use std::net::IpAddr;
+use std::time::Duration;
+use futures::stream::{self, StreamExt};
+use tracing::{debug, trace};
+
+use crate::constants::REVERSE_DNS_CONCURRENCY;
+
+/// Maps an IP address to its PTR hostname
+#[async_trait::async_trait]
+pub trait ReverseResolver: Send + Sync {
+    /// PTR name for `ip`, or `None` if it has none or the lookup failed
+    async fn reverse(&self, ip: IpAddr) -> Option<String>;
+}
+
+/// Resolver using the system's `getnameinfo`, so `/etc/hosts` and the
+/// configured name servers are honoured
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemResolver;
+
+#[async_trait::async_trait]
+impl ReverseResolver for SystemResolver {
+    async fn reverse(&self, ip: IpAddr) -> Option<String> {
+        tokio::task::spawn_blocking(move || name_info(ip)).await.ok().flatten()
+    }
+}
+
+#[cfg(unix)]
+fn name_info(ip: IpAddr) -> Option<String> {
+    let addr = socket2::SockAddr::from(std::net::SocketAddr::new(ip, 0));
+    let mut host = [0 as libc::c_char; libc::NI_MAXHOST as usize];
+    // SAFETY: `addr` is a valid sockaddr of the given length and `host` is a
+    // writable buffer of the given size; no service buffer is requested
+    let rc = unsafe {
+        libc::getnameinfo(
+            addr.as_ptr() as *const libc::sockaddr,
+            addr.len(),
+            host.as_mut_ptr(),
+            host.len() as libc::socklen_t,
+            std::ptr::null_mut(),
+            0,
+            libc::NI_NAMEREQD,
+        )
+    };
+    if rc != 0 {
+        trace!("No PTR record for {} (getnameinfo error {})", ip, rc);
+        return None;
+    }
+    // SAFETY: on success getnameinfo wrote a NUL-terminated string into `host`
+    let name = unsafe { std::ffi::CStr::from_ptr(host.as_ptr()) };
+    Some(name.to_string_lossy().into_owned()).filter(|name| !name.is_empty())
+}
+
+#[cfg(not(unix))]
+fn name_info(_ip: IpAddr) -> Option<String> {
+    None
+}
+
+/// Reverse-resolve every address concurrently, each lookup bounded by
+/// `timeout`. The result lines up with `ips`; failed or slow lookups are
+/// `None` and never fail the batch.
+pub async fn resolve_all(resolver: &dyn ReverseResolver, ips: &[IpAddr], timeout: Duration) -> Vec<Option<String>> {
+    stream::iter(ips.iter().copied())
+        .map(|ip| async move {
+            match tokio::time::timeout(timeout, resolver.reverse(ip)).await {
+                Ok(name) => name,
+                Err(_) => {
+                    debug!("Reverse lookup of {} timed out", ip);
+                    None
+                }
+            }
+        })
+        .buffered(REVERSE_DNS_CONCURRENCY)
+        .collect()
+        .await
+}
@@ -0,0 +1,82 @@
+//! Per-scan random number generator
+//!
+//! Source ports, jitter, port sampling and random scan order draw from the
+//! `ScanRng` of the scan they belong to, seeded from `ScanConfig::seed` so a
+//! seeded scan repeats its choices. Code that has no config at hand calls
+//! `with_rng`, which uses the RNG of the scan running the current task; the
+//! executor scopes each probe to its scan's RNG.
+
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+tokio::task_local! {
+    static CURRENT: ScanRng;
+}
+
+/// Random source of one scan, shared by its clones.
+///
+/// ```
+/// use port_scanner::infrastructure::{network_utils, ScanRng};
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let source_ports = |seed| async move {
+///     let rng = ScanRng::new(Some(seed));
+///     rng.scope(async { (0..100).map(|_| network_utils::random_source_port()).collect::<Vec<u16>>() }).await
+/// };
+///
+/// // Same seed, same sequence
+/// assert_eq!(source_ports(42).await, source_ports(42).await);
+/// assert_ne!(source_ports(42).await, source_ports(43).await);
+///
+/// // Draws spread evenly over 1024..=65535: eight equal buckets, each
+/// // within 5% of its share
+/// let mut buckets = [0usize; 8];
+/// ScanRng::new(Some(7)).scope(async {
+///     for _ in 0..80_000 {
+///         buckets[(network_utils::random_source_port() as usize - 1024) / 8064] += 1;
+///     }
+/// }).await;
+/// assert!(buckets.iter().all(|&n| (9_500..=10_500).contains(&n)), "{buckets:?}");
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct ScanRng(Arc<Mutex<StdRng>>);
+
+impl ScanRng {
+    /// RNG seeded with `seed`, or from entropy when `None`
+    pub fn new(seed: Option<u64>) -> Self {
+        let rng = match seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+        Self(Arc::new(Mutex::new(rng)))
+    }
+
+    /// Run `f` with this RNG
+    pub fn with<T>(&self, f: impl FnOnce(&mut StdRng) -> T) -> T {
+        f(&mut self.0.lock().unwrap_or_else(|e| e.into_inner()))
+    }
+
+    /// Run `future` with `with_rng` drawing from this RNG
+    pub async fn scope<F: Future>(&self, future: F) -> F::Output {
+        CURRENT.scope(self.clone(), future).await
+    }
+}
+
+impl Default for ScanRng {
+    fn default() -> Self {
+        Self::new(None)
+    }
+}
+
+/// Run `f` with the RNG of the scan running this task; outside a scan,
+/// with a fresh entropy-seeded one
+pub fn with_rng<T>(f: impl FnOnce(&mut StdRng) -> T) -> T {
+    match CURRENT.try_with(ScanRng::clone) {
+        Ok(rng) => rng.with(f),
+        Err(_) => f(&mut StdRng::from_entropy()),
+    }
+}
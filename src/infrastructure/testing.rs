@@ -0,0 +1,159 @@
+//! Scripted connector for tests and examples
+//!
+//! Not part of the stable API: it exists so doctests can drive a scan
+//! without a network, and may change with them.
+
+use std::collections::HashMap;
+use std::io;
+use std::net::SocketAddr;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+use tokio::net::TcpStream;
+
+use crate::infrastructure::AsyncNetworkConnector;
+
+/// What one scripted connect does
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Reply {
+    /// Fail with `ConnectionRefused`
+    Refuse,
+    /// Fail with `ConnectionReset`
+    Reset,
+    /// Fail with `TimedOut` at once
+    Timeout,
+    /// Fail with an unclassified error carrying this message
+    Fail(&'static str),
+    /// Never answer; the connect runs into its timeout
+    Silent,
+    /// Connect to the requested address for real
+    Accept,
+    /// Connect to this address instead, e.g. a local listener standing in
+    /// for an open port
+    AcceptAt(SocketAddr),
+}
+
+/// Connector answering from a script and recording every connect.
+///
+/// Each port follows its own list of replies, one per connect, repeating
+/// the last; other ports get the default reply.
+///
+/// ```
+/// use port_scanner::infrastructure::testing::{Reply, ScriptedConnector};
+/// use port_scanner::infrastructure::AsyncNetworkConnector;
+/// use std::io::ErrorKind;
+/// use std::net::SocketAddr;
+/// use std::time::Duration;
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let connector = ScriptedConnector::new(Reply::Refuse).on_port(22, [Reply::Timeout, Reply::Reset]);
+/// let mut kinds = Vec::new();
+/// for port in [80, 22, 22, 22] {
+///     let addr = SocketAddr::from(([192, 0, 2, 1], port));
+///     kinds.push(connector.connect(&addr, Duration::from_secs(1)).await.unwrap_err().kind());
+/// }
+///
+/// assert_eq!(kinds, [ErrorKind::ConnectionRefused, ErrorKind::TimedOut, ErrorKind::ConnectionReset, ErrorKind::ConnectionReset]);
+/// assert_eq!(connector.attempts(22), 3);
+/// assert_eq!(connector.connects().len(), 4);
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct ScriptedConnector {
+    default: Reply,
+    scripts: HashMap<u16, Vec<Reply>>,
+    delay: Duration,
+    connects: Mutex<Vec<SocketAddr>>,
+    in_flight: AtomicUsize,
+    peak: AtomicUsize,
+}
+
+impl ScriptedConnector {
+    /// Connector giving every port `default`
+    pub fn new(default: Reply) -> Self {
+        Self {
+            default,
+            scripts: HashMap::new(),
+            delay: Duration::ZERO,
+            connects: Mutex::new(Vec::new()),
+            in_flight: AtomicUsize::new(0),
+            peak: AtomicUsize::new(0),
+        }
+    }
+
+    /// Answer successive connects to `port` with `replies`, then keep
+    /// repeating the last one
+    pub fn on_port(mut self, port: u16, replies: impl IntoIterator<Item = Reply>) -> Self {
+        self.scripts.insert(port, replies.into_iter().collect());
+        self
+    }
+
+    /// Wait this long before every reply (within the connect timeout)
+    pub fn delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    /// Addresses connected to, in the order the connects started
+    pub fn connects(&self) -> Vec<SocketAddr> {
+        self.connects.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Connects made to `port`
+    pub fn attempts(&self, port: u16) -> usize {
+        self.connects().iter().filter(|addr| addr.port() == port).count()
+    }
+
+    /// Most connects that were pending at the same time
+    pub fn peak_in_flight(&self) -> usize {
+        self.peak.load(Ordering::SeqCst)
+    }
+
+    /// Record a connect to `addr` and pick its reply
+    fn next_reply(&self, addr: &SocketAddr) -> Reply {
+        let mut connects = self.connects.lock().unwrap_or_else(|e| e.into_inner());
+        let attempt = connects.iter().filter(|earlier| earlier.port() == addr.port()).count();
+        connects.push(*addr);
+        match self.scripts.get(&addr.port()) {
+            Some(replies) => replies.get(attempt).or(replies.last()).unwrap_or(&self.default).clone(),
+            None => self.default.clone(),
+        }
+    }
+}
+
+/// Counts a connect as pending until dropped, also when it is cancelled
+struct InFlight<'a>(&'a AtomicUsize);
+
+impl Drop for InFlight<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+#[async_trait::async_trait]
+impl AsyncNetworkConnector for ScriptedConnector {
+    async fn connect(&self, addr: &SocketAddr, timeout: Duration) -> io::Result<TcpStream> {
+        let reply = self.next_reply(addr);
+        let pending = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+        self.peak.fetch_max(pending, Ordering::SeqCst);
+        let _in_flight = InFlight(&self.in_flight);
+
+        let answer = async {
+            if !self.delay.is_zero() {
+                tokio::time::sleep(self.delay).await;
+            }
+            match reply {
+                Reply::Refuse => Err(io::ErrorKind::ConnectionRefused.into()),
+                Reply::Reset => Err(io::ErrorKind::ConnectionReset.into()),
+                Reply::Timeout => Err(io::Error::new(io::ErrorKind::TimedOut, "connect timed out")),
+                Reply::Fail(message) => Err(io::Error::other(message)),
+                Reply::Silent => std::future::pending().await,
+                Reply::Accept => TcpStream::connect(addr).await,
+                Reply::AcceptAt(target) => TcpStream::connect(target).await,
+            }
+        };
+        tokio::time::timeout(timeout, answer).await
+            .unwrap_or_else(|_| Err(io::Error::new(io::ErrorKind::TimedOut, "connect timed out")))
+    }
+}
@@ -1,39 +1,67 @@
-/// Port Scanner Library
-/// 
-/// A modular and extensible port scanner with clean architecture.
-/// 
-/// # Architecture
-/// 
-/// The codebase follows a layered architecture:
-/// - **Domain**: Core business logic and entities
-/// - **Infrastructure**: External dependencies (network, IO)
-/// - **Application**: Use cases and orchestration
-/// - **Presentation**: User interfaces and output formatting
-/// - **Scanning**: Scanning strategies and execution
-/// 
-/// # Example
-/// 
-/// ```no_run
-/// use port_scanner::prelude::*;
-/// use std::net::IpAddr;
-/// 
-/// # fn main() -> anyhow::Result<()> {
-/// let config = ScanConfigBuilder::new()
-///     .target("127.0.0.1".parse::<IpAddr>()?)
-///     .common_ports()
-///     .timeout(std::time::Duration::from_millis(500))
-///     .parallel(true)
-///     .build()?;
-/// 
-/// let scanner = PortScanner::new(config)?;
-/// let results = scanner.scan_all(|result| {
-///     println!("Port {}: {:?}", result.port, result.status);
-/// });
-/// 
-/// println!("Found {} open ports", results.open_ports);
-/// # Ok(())
-/// # }
-/// ```
+//! Port Scanner Library
+//! 
+//! A modular and extensible port scanner with clean architecture.
+//! 
+//! # Architecture
+//! 
+//! The codebase follows a layered architecture:
+//! - **Domain**: Core business logic and entities
+//! - **Infrastructure**: External dependencies (network, IO)
+//! - **Application**: Use cases and orchestration
+//! - **Presentation**: User interfaces and output formatting
+//! - **Scanning**: Scanning strategies and execution
+//! 
+//! # Example
+//! 
+//! ```no_run
+//! use port_scanner::prelude::*;
+//! use std::net::IpAddr;
+//! 
+//! # #[tokio::main]
+//! # async fn main() -> anyhow::Result<()> {
+//! let config = ScanConfigBuilder::new()
+//!     .target("127.0.0.1".parse::<IpAddr>()?)
+//!     .common_ports()
+//!     .timeout(std::time::Duration::from_millis(500))
+//!     .parallel(true)
+//!     .build()?;
+//! 
+//! let scanner = PortScanner::new(config)?;
+//! let results = scanner.scan_all(|result| {
+//!     println!("Port {}: {:?}", result.port, result.status);
+//! }).await;
+//! 
+//! println!("Found {} open ports", results.open_ports);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! # Scan facade
+//!
+//! `Scan` wraps config building, strategy selection and observers for
+//! embedding; each target's results come back separately.
+//!
+//! ```
+//! use port_scanner::prelude::*;
+//! use std::net::{IpAddr, TcpListener};
+//!
+//! # #[tokio::main]
+//! # async fn main() -> anyhow::Result<()> {
+//! let listener = TcpListener::bind("127.0.0.1:0")?;
+//! let port = listener.local_addr()?.port();
+//!
+//! let hosts = Scan::builder()
+//!     .targets(["127.0.0.1".parse::<IpAddr>()?])
+//!     .ports(vec![port])
+//!     .configure(|config| config.timeout(std::time::Duration::from_millis(500)))
+//!     .run()
+//!     .await?;
+//!
+//! assert_eq!(hosts.len(), 1);
+//! assert_eq!(hosts[0].results.open_ports, 1);
+//! # Ok(())
+//! # }
+//! ```
 
 // Core modules
 pub mod constants;
@@ -48,14 +76,14 @@ pub mod presentation;
 pub use errors::{ScanError, ConfigError, DetectionError, FormatterError};
 pub use domain::{Port, PortStatus, PortScanResult, ScanResults, ServiceInfo, ServiceVersion, OSInfo};
 pub use scanning::{ScanConfig, ScanConfigBuilder, ScanMode};
-pub use application::{PortScanner, VersionDetector, SMBFingerprinter};
+pub use application::{PortScanner, Scan, VersionDetector, SMBFingerprinter};
 pub use presentation::{OutputFormat, OutputFormatter, OutputFormatterFactory, ScanReport};
 
 /// Prelude module for convenient imports
 pub mod prelude {
-    pub use crate::domain::{Port, PortStatus, PortScanResult, ScanResults, ServiceVersion, OSInfo};
-    pub use crate::scanning::{ScanConfig, ScanConfigBuilder, ScanMode};
-    pub use crate::application::{PortScanner, VersionDetector, SMBFingerprinter};
+    pub use crate::domain::{Port, PortStatus, PortScanResult, Protocol, ScanResults, ServiceVersion, OSInfo};
+    pub use crate::scanning::{ScanConfig, ScanConfigBuilder, ScanMode, ScanOrder, ScanPreset, ScanProfile, ScanProtocol, ScanType};
+    pub use crate::application::{PortScanner, Scan, VersionDetector, SMBFingerprinter};
     pub use crate::presentation::{OutputFormat, OutputFormatterFactory, ScanReport};
     pub use crate::errors::{ScanError, ConfigError};
     pub use anyhow;
@@ -1,18 +1,26 @@
-/// Modernized main entry point using new architecture
+//! Modernized main entry point using new architecture
 
 use port_scanner::prelude::*;
+use port_scanner::scanning::{targets, ParallelExecutor, Target, TargetExclusions};
+use port_scanner::infrastructure::{log_subscriber, network_utils, resolve_all, LogFormat, SystemResolver};
+use port_scanner::application::{run_exit_code, Findings, FindingsPolicy, ScanBuilder};
+use port_scanner::constants::{DEFAULT_CLOSED_PORT_SAMPLES, DEFAULT_PROGRESS_LINE_LIMIT, MAX_PORT, MIN_PORT, REVERSE_DNS_TIMEOUT};
 use port_scanner::presentation::{
-    OutputFormatter, OutputFormatterFactory, OutputFormat,
-    JsonFormatter, TextFormatter, CsvFormatter,
-    ProgressObserver, MetricsCollector, ScanObserver
+    OutputFormat, OutputFormatterFactory, JsonFormatter, category_histogram, port_based_note, ResultSink, FileSink, MultiFormatter, ReportField,
+    Capabilities, ProgressObserver, MetricsCollector, Redactor, ScanObserver, WebhookObserver
 };
+#[cfg(feature = "tui")]
+use port_scanner::presentation::{run_dashboard, TuiState};
+use std::borrow::Cow;
+use std::net::{IpAddr, SocketAddr};
+use std::process::ExitCode;
 use std::time::Instant;
 use std::io::{self, Write};
 use std::path::Path;
 use std::sync::{Arc, Mutex};
-use tracing::{info, debug, Level};
-use tracing_subscriber;
+use tracing::{info, debug, warn, Level};
 use clap::{Parser, ValueEnum, ArgGroup};
+use tracing_subscriber::util::SubscriberInitExt;
 
 /// A fast and modular port scanner written in Rust
 #[derive(Parser, Debug)]
@@ -25,18 +33,67 @@ use clap::{Parser, ValueEnum, ArgGroup};
         .required(false)
         .args(["ports", "common"])
 ))]
+#[command(group(
+    ArgGroup::new("preset")
+        .required(false)
+        .args(["fast", "normal", "thorough", "profile"])
+))]
 struct Cli {
-    /// Target IP address to scan
-    #[arg(short, long, value_name = "IP")]
+    /// Target IP address, CIDR block (e.g. 192.168.1.0/24) or octet range (e.g. 192.168.1.1-20) to scan
+    #[arg(short, long, value_name = "IP", conflicts_with = "targets_file")]
     target: Option<String>,
 
-    /// Ports to scan (e.g., "80,443,8080" or "1-1000")
+    /// Read target IPs, CIDR blocks or octet ranges from a file (one per line or comma-separated, # comments)
+    #[arg(long, value_name = "PATH")]
+    targets_file: Option<String>,
+
+    /// Hosts to skip (comma-separated)
+    #[arg(long, value_delimiter = ',', value_name = "IPS")]
+    exclude_hosts: Vec<String>,
+
+    /// Address blocks to skip (comma-separated CIDRs)
+    #[arg(long, value_delimiter = ',', value_name = "CIDRS")]
+    exclude_cidr: Vec<String>,
+
+    /// Ports to scan (e.g., "80,443,8080", "1-1000" or "22,8000-8100")
     #[arg(short, long, value_name = "PORTS", group = "port-spec")]
     ports: Option<String>,
 
-    /// Use common ports preset
-    #[arg(short, long, group = "port-spec")]
-    common: bool,
+    /// Read ports from a file (one per line or comma-separated, ranges and # comments allowed)
+    #[arg(long, value_name = "PATH", group = "port-spec")]
+    ports_file: Option<String>,
+
+    /// Use common ports preset, or a themed set: web, db or remote
+    #[arg(short, long, value_name = "SET", num_args = 0..=1, group = "port-spec")]
+    common: Option<Option<String>>,
+
+    /// Scan only N random ports from the --ports range (default 1-65535) and estimate the rest
+    #[arg(long, value_name = "N", conflicts_with_all = ["common", "ports_file"])]
+    sample: Option<usize>,
+
+    /// Never scan ports below this, whatever --ports, --common or a preset select
+    #[arg(long, value_name = "PORT")]
+    min_port: Option<u16>,
+
+    /// Never scan ports above this, whatever --ports, --common or a preset select
+    #[arg(long, value_name = "PORT")]
+    max_port: Option<u16>,
+
+    /// Preset: top 100 ports, 200ms timeout, high concurrency, no detection
+    #[arg(long)]
+    fast: bool,
+
+    /// Preset: common ports with default timeout and concurrency
+    #[arg(long)]
+    normal: bool,
+
+    /// Preset: all ports, 2s timeout, service and OS detection
+    #[arg(long)]
+    thorough: bool,
+
+    /// Compliance profile: its port set with version detection; explicit flags override it
+    #[arg(long, value_enum, value_name = "PROFILE")]
+    profile: Option<ProfileArg>,
 
     /// Enable service version detection
     #[arg(short = 'v', long)]
@@ -46,6 +103,10 @@ struct Cli {
     #[arg(short = 'o', long)]
     detect_os: bool,
 
+    /// Only run service and OS detection on these ports (e.g. "22,443" or "8000-8100")
+    #[arg(long, value_name = "PORTS", value_parser = parse_detect_ports)]
+    detect_ports: Option<Vec<Port>>,
+
     /// Enable parallel scanning
     #[arg(long, default_value = "true")]
     parallel: bool,
@@ -54,9 +115,73 @@ struct Cli {
     #[arg(short = 'T', long, value_name = "NUM")]
     threads: Option<usize>,
 
-    /// Connection timeout in milliseconds
-    #[arg(long, default_value = "500", value_name = "MS")]
-    timeout: u64,
+    /// Maximum concurrent probes in parallel mode (default: threads x 4, clamped to 10-2000)
+    #[arg(long, value_name = "NUM")]
+    concurrency: Option<usize>,
+
+    /// Connection timeout in milliseconds (default: 500)
+    #[arg(long, value_name = "MS")]
+    timeout: Option<u64>,
+
+    /// Stop after this long and report partial results (e.g. "60s", "2m", "500ms")
+    #[arg(long, value_name = "DURATION", value_parser = parse_duration)]
+    max_time: Option<std::time::Duration>,
+
+    /// When --max-time hits, how long ports already found open may finish detection (default: 2s)
+    #[arg(long, value_name = "DURATION", value_parser = parse_duration, requires = "max_time")]
+    drain_timeout: Option<std::time::Duration>,
+
+    /// Most bytes read from one connection during service detection (default: 65536)
+    #[arg(long, value_name = "BYTES")]
+    max_read_bytes: Option<usize>,
+
+    /// Longest time spent reading from one connection during service detection (default: 10s)
+    #[arg(long, value_name = "DURATION", value_parser = parse_duration)]
+    max_read_time: Option<std::time::Duration>,
+
+    /// Give up on a detection reply that stalls this long between chunks (default: 500ms)
+    #[arg(long, value_name = "DURATION", value_parser = parse_duration)]
+    max_read_idle: Option<std::time::Duration>,
+
+    /// Wait this long after connecting before reading a banner, for services that greet late (e.g. "300ms")
+    #[arg(long, value_name = "DURATION", value_parser = parse_duration)]
+    banner_delay: Option<std::time::Duration>,
+
+    /// How long to wait for a banner once connected (default: 2s); --timeout only bounds the connect
+    #[arg(long, value_name = "DURATION", value_parser = parse_duration)]
+    banner_timeout: Option<std::time::Duration>,
+
+    /// Include the hex of the first raw banner bytes (`banner_hex`) in JSON output
+    #[arg(long)]
+    include_raw_banner: bool,
+
+    /// Stop scanning a host once more than N ports are open (honeypot/tarpit guard)
+    #[arg(long, value_name = "N")]
+    honeypot_threshold: Option<usize>,
+
+    /// Random high ports probed to tell a firewalled host from a down one when most ports are filtered (0 disables, default: 2)
+    #[arg(long, value_name = "N")]
+    closed_port_samples: Option<usize>,
+
+    /// Half-open SYN scan (needs the syn-scan feature and root/CAP_NET_RAW; falls back to connect scan)
+    #[arg(long)]
+    syn: bool,
+
+    /// Probe type; raw types need the syn-scan feature and root/CAP_NET_RAW
+    #[arg(long, value_enum, value_name = "TYPE", conflicts_with = "syn")]
+    scan_type: Option<ScanTypeArg>,
+
+    /// Initial IP TTL of raw-socket probes, e.g. 128 to look like Windows (raw scan types only)
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u8).range(1..))]
+    ttl: Option<u8>,
+
+    /// TCP window size of raw-socket probes, e.g. 65535 (raw scan types only)
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u16).range(1..))]
+    window: Option<u16>,
+
+    /// Exit with an error instead of falling back when the scan type needs privileges this process lacks
+    #[arg(long)]
+    require_privileges: bool,
 
     /// Randomize source port (stealth)
     #[arg(long)]
@@ -70,10 +195,19 @@ struct Cli {
     #[arg(short = 'f', long, value_enum)]
     format: Option<OutputFormatArg>,
 
-    /// Output file path (auto-generated if not specified)
+    /// Output file path (auto-generated if not specified, "-" for stdout)
     #[arg(short = 'F', long, value_name = "PATH")]
     output_file: Option<String>,
 
+    /// Add to the --output-file instead of replacing it: CSV rows without a second header, NDJSON lines, JSON reports collected in an array
+    #[arg(long, requires = "output_file")]
+    append: bool,
+
+    /// Write the report to stdout in this format instead of a file (suppresses console output).
+    /// With several targets, json prints one report per line (JSON Lines)
+    #[arg(long, value_enum, value_name = "FORMAT", conflicts_with = "output_file")]
+    stdout: Option<OutputFormatArg>,
+
     /// Enable verbose output
     #[arg(long)]
     verbose: bool,
@@ -86,9 +220,137 @@ struct Cli {
     #[arg(long)]
     open_only: bool,
 
+    /// Columns for CSV/JSON output, in order (e.g. port,service,version)
+    #[arg(long, value_delimiter = ',', value_name = "FIELDS")]
+    fields: Option<Vec<String>>,
+
+    /// Mask credentials, private IPv4 addresses and email addresses in banners before writing reports
+    #[arg(long)]
+    redact: bool,
+
+    /// Also mask matches of this regex in reports, or only its `value` group (repeatable, implies --redact)
+    #[arg(long, value_name = "REGEX")]
+    redact_pattern: Vec<String>,
+
     /// Enable debug logging (shows detailed trace information)
     #[arg(short = 'd', long)]
     debug: bool,
+
+    /// Log line format on stderr
+    #[arg(long, value_enum, value_name = "FORMAT", default_value = "text")]
+    log_format: LogFormatArg,
+
+    /// List the host:port pairs that would be scanned and exit without connecting
+    #[arg(long)]
+    dry_run: bool,
+
+    /// Look up each target's PTR hostname and show it in the output and reports
+    #[arg(long)]
+    reverse_dns: bool,
+
+    /// Print each target's PTR hostname and exit without scanning
+    #[arg(long)]
+    resolve_only: bool,
+
+    /// Re-probe filtered ports with a longer timeout before reporting them
+    #[arg(long)]
+    verify_filtered: bool,
+
+    /// Re-probe ports whose connect was reset (up to 2 more tries); refused ports stay closed
+    #[arg(long)]
+    retry_on_reset: bool,
+
+    /// Re-probe ports that time out or error up to N more times (default: 0)
+    #[arg(long, value_name = "N")]
+    retries: Option<usize>,
+
+    /// Stop retrying a port after it errored N times (e.g. behind a flaky proxy); timeouts keep every retry
+    #[arg(long, value_name = "N", requires = "retries")]
+    max_port_errors: Option<usize>,
+
+    /// Start with 10 concurrent probes and double every 500ms up to the limit
+    #[arg(long)]
+    ramp_up: bool,
+
+    /// Live dashboard with progress, open ports and rates (requires the `tui` feature)
+    #[arg(long)]
+    tui: bool,
+
+    /// Port lines to print before switching to a running count of open ports (0 = no limit)
+    #[arg(long, value_name = "N", default_value_t = DEFAULT_PROGRESS_LINE_LIMIT)]
+    progress_lines: usize,
+
+    /// Grab banners over the scan connection instead of opening a second one
+    #[arg(long)]
+    reuse_connection: bool,
+
+    /// Drop probe connections without draining and shutting them down first
+    #[arg(long)]
+    no_graceful_close: bool,
+
+    /// SO_LINGER for probe sockets; 0 resets connections on close so large scans don't pile up TIME_WAIT sockets
+    #[arg(long, value_name = "DURATION", value_parser = parse_duration)]
+    linger: Option<std::time::Duration>,
+
+    /// Enable TCP keepalive on probe sockets after this idle time (e.g. 30s)
+    #[arg(long, value_name = "DURATION", value_parser = parse_duration)]
+    keepalive: Option<std::time::Duration>,
+
+    /// Send probes through this network interface, e.g. a VPN tunnel (Linux only)
+    #[arg(long, value_name = "NAME")]
+    interface: Option<String>,
+
+    /// Exit with status 2 if any port is open (CI gate)
+    #[arg(long, conflicts_with = "expect_ports")]
+    fail_on_open: bool,
+
+    /// Exit with status 2 unless the open ports are exactly these (e.g. 22,443)
+    #[arg(long, value_name = "PORTS", value_delimiter = ',')]
+    expect_ports: Option<Vec<Port>>,
+
+    /// Seed for source-port randomization, jitter and random order (reproducible runs)
+    #[arg(long, value_name = "N")]
+    seed: Option<u64>,
+
+    /// Order in which ports are probed (default: numeric)
+    #[arg(long, value_enum, value_name = "ORDER")]
+    order: Option<ScanOrderArg>,
+
+    /// Shuffle every host:port pair into one work list, so probes jump between hosts and ports (overrides --order)
+    #[arg(long)]
+    randomize_scan_order: bool,
+
+    /// Transport to probe each port over (default: tcp)
+    #[arg(long, value_enum, value_name = "PROTOCOL")]
+    protocol: Option<ProtocolArg>,
+
+    /// Don't read ICMP port-unreachable as closed in UDP scans (for platforms or networks that drop or forge it)
+    #[arg(long)]
+    no_icmp_closed: bool,
+
+    /// SNMP community string to try on UDP port 161 (repeatable, default: public; needs --protocol udp or both)
+    #[arg(long = "snmp-community", value_name = "COMMUNITY")]
+    snmp_communities: Vec<String>,
+
+    /// Stop probing a port once a detector is at least this confident (0.0-1.0)
+    #[arg(long, value_name = "CONFIDENCE")]
+    detection_early_exit: Option<f32>,
+
+    /// POST scan events as JSON to this URL
+    #[arg(long, value_name = "URL")]
+    webhook_url: Option<String>,
+
+    /// Attempt a DNS zone transfer (AXFR) of this domain when port 53 is open
+    #[arg(long, value_name = "DOMAIN")]
+    axfr_domain: Option<String>,
+
+    /// Compare two JSON or CSV reports and print what changed instead of scanning
+    #[arg(long, num_args = 2, value_names = ["OLD", "NEW"])]
+    diff: Option<Vec<String>>,
+
+    /// Print the scan types, detectors, output formats and features of this build as JSON and exit
+    #[arg(long)]
+    capabilities: bool,
 }
 
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum, Debug)]
@@ -99,12 +361,105 @@ enum OutputFormatArg {
     Csv,
     /// Text format
     Text,
+    /// Newline-delimited JSON, one result per line as it completes
+    Ndjson,
     /// All formats
     All,
 }
 
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum, Debug)]
+enum LogFormatArg {
+    /// Human-readable lines
+    Text,
+    /// One JSON object per line, for log aggregators
+    Json,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum, Debug)]
+enum ProfileArg {
+    /// PCI DSS: remote admin, web, mail, DNS, SMB and database ports (21 ports), version detection
+    PciDss,
+    /// Services behind frequently exploited CVEs (26 ports), version and OS detection
+    CommonVulns,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum, Debug)]
+enum ScanOrderArg {
+    /// Ascending port number
+    Numeric,
+    /// Most frequently open ports first
+    Frequency,
+    /// Random order
+    Random,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum, Debug)]
+enum ProtocolArg {
+    /// TCP only
+    Tcp,
+    /// UDP only: a reply is open, ICMP unreachable closed, silence open|filtered
+    Udp,
+    /// One TCP and one UDP probe per port
+    Both,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum, Debug)]
+enum ScanTypeArg {
+    /// Full TCP connect (default)
+    Connect,
+    /// Half-open SYN, same as --syn
+    Syn,
+    /// Bare FIN: RST means closed, silence open|filtered
+    Fin,
+    /// No flags: RST means closed, silence open|filtered
+    Null,
+    /// FIN+PSH+URG: RST means closed, silence open|filtered
+    Xmas,
+}
+
+impl From<ScanTypeArg> for ScanType {
+    fn from(arg: ScanTypeArg) -> Self {
+        match arg {
+            ScanTypeArg::Connect => ScanType::Connect,
+            ScanTypeArg::Syn => ScanType::Syn,
+            ScanTypeArg::Fin => ScanType::Fin,
+            ScanTypeArg::Null => ScanType::Null,
+            ScanTypeArg::Xmas => ScanType::Xmas,
+        }
+    }
+}
+
+impl From<ProfileArg> for ScanProfile {
+    fn from(arg: ProfileArg) -> Self {
+        match arg {
+            ProfileArg::PciDss => ScanProfile::PciDss,
+            ProfileArg::CommonVulns => ScanProfile::CommonVulns,
+        }
+    }
+}
+
+impl From<ScanOrderArg> for ScanOrder {
+    fn from(arg: ScanOrderArg) -> Self {
+        match arg {
+            ScanOrderArg::Numeric => ScanOrder::Numeric,
+            ScanOrderArg::Frequency => ScanOrder::Frequency,
+            ScanOrderArg::Random => ScanOrder::Random,
+        }
+    }
+}
+
+impl From<ProtocolArg> for ScanProtocol {
+    fn from(arg: ProtocolArg) -> Self {
+        match arg {
+            ProtocolArg::Tcp => ScanProtocol::Tcp,
+            ProtocolArg::Udp => ScanProtocol::Udp,
+            ProtocolArg::Both => ScanProtocol::Both,
+        }
+    }
+}
+
 #[tokio::main]
-async fn main() -> anyhow::Result<()> {
+async fn main() -> anyhow::Result<ExitCode> {
     // Parse CLI args early to get debug flag
     let cli = Cli::parse();
     
@@ -115,99 +470,381 @@ async fn main() -> anyhow::Result<()> {
         Level::WARN  // Only show warnings and errors by default
     };
     
-    tracing_subscriber::fmt()
-        .with_max_level(log_level)
-        .with_target(false)
-        .init();
+    // Logs go to stderr so they never mix with a report written to stdout
+    let log_format = match cli.log_format {
+        LogFormatArg::Text => LogFormat::Text,
+        LogFormatArg::Json => LogFormat::Json,
+    };
+    log_subscriber(log_format, log_level, io::stderr).init();
 
     info!("Port Scanner v2.0 - Refactored Architecture");
 
+    if cli.capabilities {
+        println!("{}", serde_json::to_string_pretty(&Capabilities::current())?);
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    // Report comparison needs no target and never touches the network
+    if let Some(paths) = &cli.diff {
+        return display_diff(&paths[0], &paths[1], cli.stdout).map(|_| ExitCode::SUCCESS);
+    }
+
+    // Report on stdout: either --stdout FORMAT or --format FORMAT -F -
+    let stdout_format = match (cli.stdout, cli.format, cli.output_file.as_deref()) {
+        (Some(fmt), _, _) | (None, Some(fmt), Some("-")) => Some(single_output_format(fmt)?),
+        _ => None,
+    };
+    let quiet = stdout_format.is_some();
+
     // Display banner
-    println!("╔════════════════════════════════════╗");
-    println!("║   Rust Port Scanner v2.0          ║");
-    println!("║   Clean Architecture              ║");
-    println!("╚════════════════════════════════════╝\n");
+    if !quiet {
+        println!("╔════════════════════════════════════╗");
+        println!("║   Rust Port Scanner v2.0          ║");
+        println!("║   Clean Architecture              ║");
+        println!("╚════════════════════════════════════╝\n");
+    }
 
     // Store output preferences (cli already parsed above)
     let output_format = cli.format;
     let output_file = cli.output_file.clone();
+    let append = cli.append;
     let open_only = cli.open_only;
+    let dry_run = cli.dry_run;
+    let reverse_dns = cli.reverse_dns || cli.resolve_only;
+    let resolve_only = cli.resolve_only;
+    let webhook_url = cli.webhook_url.clone();
+    let progress_lines = Some(cli.progress_lines).filter(|&lines| lines > 0);
+    let fields = cli.fields.as_deref()
+        .map(ReportField::parse_list)
+        .transpose()?;
+    let redactor = if cli.redact || !cli.redact_pattern.is_empty() {
+        let redactor = cli.redact_pattern.iter()
+            .try_fold(Redactor::builtin(), |redactor, pattern| redactor.with_pattern(pattern))
+            .map_err(|e| anyhow::anyhow!("Invalid --redact-pattern: {}", e))?;
+        Some(redactor)
+    } else {
+        None
+    };
+    if cli.tui && !cfg!(feature = "tui") {
+        warn!("--tui requires building with the `tui` feature; using console output");
+    }
+    let tui = cli.tui && cfg!(feature = "tui") && !quiet;
+    let policy = match (&cli.expect_ports, cli.fail_on_open) {
+        (Some(ports), _) => FindingsPolicy::ExpectPorts(ports.clone()),
+        (None, true) => FindingsPolicy::FailOnOpen,
+        (None, false) => FindingsPolicy::Ignore,
+    };
 
-    // Build config from CLI args or interactive mode
-    let config = if cli.target.is_some() || cli.non_interactive {
-        build_config_from_cli(cli)?
+    let exclusions = TargetExclusions::parse(&cli.exclude_hosts, &cli.exclude_cidr)
+        .map_err(|e| anyhow::anyhow!("Invalid exclusion: {}", e))?;
+    let targets = match (&cli.targets_file, &cli.target) {
+        (Some(path), _) => Some(targets::read_target_list(Path::new(path))
+            .map_err(|e| anyhow::anyhow!("Invalid targets file {}: {}", path, e))?),
+        (None, Some(spec)) if spec.contains(['/', '-']) => Some(targets::expand_target(spec)
+            .map_err(|e| anyhow::anyhow!("Invalid target '{}': {}", spec, e))?),
+        _ => None,
+    };
+
+    // Build config from CLI args or interactive mode (stdout mode never prompts)
+    let config = if cli.target.is_some() || targets.is_some() || cli.non_interactive || quiet {
+        build_config_from_cli(cli, targets.as_deref())?
     } else {
         build_config_interactive()?
     };
+    if (config.ttl.is_some() || config.tcp_window.is_some()) && !config.scan_type.is_raw() {
+        warn!("--ttl and --window only apply to raw-socket scans (--syn, --scan-type fin|null|xmas); ignoring them");
+    }
+
+    // One scan per host; hosts from --targets-file or a CIDR reuse the same settings
+    let targets = match targets {
+        Some(targets) => targets,
+        None => vec![Target { ip: config.target_ip, scope_id: config.scope_id }],
+    };
+    let targets = exclusions.apply(targets)?;
+    if targets.len() > 1 && !append && output_file.as_deref().is_some_and(|path| path != "-") {
+        return Err(anyhow::anyhow!("--output-file cannot be used with several targets unless --append is given; reports are named per host"));
+    }
 
-    // Display scan info
-    display_scan_info(&config);
+    // PTR names for all hosts at once; a failed lookup just leaves the name out
+    let hostnames = if reverse_dns {
+        let ips: Vec<IpAddr> = targets.iter().map(|target| target.ip).collect();
+        resolve_all(&SystemResolver, &ips, REVERSE_DNS_TIMEOUT).await
+    } else {
+        vec![None; targets.len()]
+    };
+    if resolve_only {
+        for (target, hostname) in targets.iter().zip(&hostnames) {
+            println!("{}\t{}", target, hostname.as_deref().unwrap_or("-"));
+        }
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    let several_targets = targets.len() > 1;
+    let output = OutputOptions { stdout_format, several_targets, output_format, output_file, append, open_only, fields, webhook_url, tui, progress_lines, redactor };
+    let mut outcomes = Vec::new();
+    if config.randomize_scan_order && targets.len() > 1 && !dry_run {
+        for (target, results) in run_shuffled_scan(config, targets, hostnames, &output).await? {
+            let findings = policy.evaluate(&results);
+            report_findings(target.ip, &findings);
+            outcomes.push(Some(findings));
+        }
+        return Ok(ExitCode::from(run_exit_code(&outcomes)));
+    }
+    for (target, hostname) in targets.into_iter().zip(hostnames) {
+        let mut host_config = config.clone();
+        host_config.target_ip = target.ip;
+        host_config.scope_id = target.scope_id;
+
+        // Dry run: show the expanded work list and stop before any network activity
+        if dry_run {
+            display_dry_run(&host_config, &cli_scan(&config, vec![target]).build().dry_run()?);
+            continue;
+        }
+        let results = match run_scan(host_config, target, hostname, &output).await {
+            Ok(results) => results,
+            // One line for the host instead of an error on every port
+            Err(e) if matches!(e.downcast_ref(), Some(ScanError::Unreachable { .. })) => {
+                eprintln!("✗ {}", e);
+                outcomes.push(None);
+                continue;
+            }
+            Err(e) => return Err(e),
+        };
+        let findings = policy.evaluate(&results);
+        report_findings(target.ip, &findings);
+        outcomes.push(Some(findings));
+    }
+
+    Ok(ExitCode::from(run_exit_code(&outcomes)))
+}
+
+/// Print open-port policy violations to stderr so stdout reports stay clean
+fn report_findings(target: IpAddr, findings: &Findings) {
+    let list = |ports: &[Port]| ports.iter().map(Port::to_string).collect::<Vec<_>>().join(", ");
+    if !findings.unexpected_open.is_empty() {
+        eprintln!("✗ Unexpected open ports on {}: {}", target, list(&findings.unexpected_open));
+    }
+    if !findings.missing.is_empty() {
+        eprintln!("✗ Expected ports not open on {}: {}", target, list(&findings.missing));
+    }
+}
+
+/// Where and how a finished scan is reported
+struct OutputOptions {
+    stdout_format: Option<OutputFormat>,
+    /// Reports of several hosts share stdout
+    several_targets: bool,
+    output_format: Option<OutputFormatArg>,
+    output_file: Option<String>,
+    /// Add to `output_file` instead of replacing it (`--append`)
+    append: bool,
+    open_only: bool,
+    fields: Option<Vec<ReportField>>,
+    webhook_url: Option<String>,
+    tui: bool,
+    /// Cap on per-port console lines, see `ProgressObserver::with_line_limit`
+    progress_lines: Option<usize>,
+    /// Masks sensitive banner content in reports (`--redact`); the console
+    /// still shows results as found
+    redactor: Option<Redactor>,
+}
+
+/// `result` as it goes into reports, masked when `--redact` is on
+fn report_result<'a>(result: &'a PortScanResult, redactor: Option<&Redactor>) -> Cow<'a, PortScanResult> {
+    match redactor {
+        Some(redactor) => {
+            let mut result = result.clone();
+            redactor.redact_result(&mut result);
+            Cow::Owned(result)
+        }
+        None => Cow::Borrowed(result),
+    }
+}
+
+/// Feeds each result to the report outputs as it arrives, masked when
+/// `--redact` is on
+struct ReportFeed {
+    outputs: Arc<Mutex<MultiFormatter>>,
+    redactor: Option<Redactor>,
+}
+
+impl ScanObserver for ReportFeed {
+    fn on_port_scanned(&mut self, result: &PortScanResult) {
+        if let Ok(mut outputs) = self.outputs.lock() {
+            outputs.on_result(&report_result(result, self.redactor.as_ref()));
+        }
+    }
+
+    fn on_scan_started(&mut self, _total_ports: usize) {}
+
+    fn on_scan_completed(&mut self, _results: &ScanResults) {}
+}
+
+/// `Scan` of `targets` with the settings in `config`
+fn cli_scan(config: &ScanConfig, targets: Vec<Target>) -> ScanBuilder {
+    let template = config.clone();
+    Scan::builder()
+        .targets(targets)
+        .configure(move |_| ScanConfigBuilder::from(template))
+}
 
-    // Create scanner
-    let scanner = PortScanner::new(config.clone())?;
+/// Scan one host, report the results and return them
+async fn run_scan(config: ScanConfig, target: Target, hostname: Option<String>, output: &OutputOptions) -> anyhow::Result<ScanResults> {
+    let quiet = output.stdout_format.is_some();
+    // Per-port console lines would scroll under the dashboard
+    let live_console = !quiet && !output.tui;
 
-    // Create observers wrapped in Arc<Mutex<>> for thread safety
-    let progress_observer = Arc::new(Mutex::new(ProgressObserver::new(config.verbose)));
+    // Display scan info
+    if !quiet {
+        display_scan_info(&config, hostname.as_deref());
+    }
+
+    // Every requested output is fed in the same pass; NDJSON streams results as they arrive
+    let outputs = Arc::new(Mutex::new(build_outputs(&config, output)?));
+    let progress_observer = ProgressObserver::new(config.verbose).with_line_limit(output.progress_lines);
+    let progress_observer = Arc::new(Mutex::new(progress_observer));
     let metrics_collector = Arc::new(Mutex::new(MetricsCollector::new()));
-    
-    // Clone Arc references for the closure
-    let progress_obs_clone = Arc::clone(&progress_observer);
-    let metrics_clone = Arc::clone(&metrics_collector);
-    
-    // Start timing
-    let start_time = Instant::now();
-    
-    // Notify observers scan is starting
-    progress_observer.lock().unwrap().on_scan_started(config.port_count());
-    
+    let webhook_observer = output.webhook_url.clone().map(|url| Arc::new(Mutex::new(WebhookObserver::new(url))));
+
+    let mut scan = cli_scan(&config, vec![target])
+        .observer(Arc::clone(&metrics_collector))
+        .observer(ReportFeed { outputs: Arc::clone(&outputs), redactor: output.redactor.clone() });
+    // Console progress would corrupt a report written to stdout
+    if live_console {
+        scan = scan.observer(Arc::clone(&progress_observer));
+    }
+    if let Some(webhook) = &webhook_observer {
+        scan = scan.observer(Arc::clone(webhook));
+    }
+    #[cfg(feature = "tui")]
+    let dashboard = output.tui.then(|| Arc::new(Mutex::new(TuiState::new(config.probe_count()))));
+    #[cfg(feature = "tui")]
+    if let Some(state) = &dashboard {
+        scan = scan.observer(Arc::clone(state));
+    }
+
     info!("Starting parallel scan with observers enabled");
+    let start_time = Instant::now();
+    #[cfg(feature = "tui")]
+    let hosts = match dashboard {
+        Some(state) => run_dashboard(scan.run(), state).await?
+            .ok_or_else(|| anyhow::anyhow!("Scan cancelled"))??,
+        None => scan.run().await?,
+    };
+    #[cfg(not(feature = "tui"))]
+    let hosts = scan.run().await?;
+    let duration = start_time.elapsed();
+    let results = hosts.into_iter().next()
+        .map(|host| host.results)
+        .ok_or_else(|| anyhow::anyhow!("Scan of {} returned no results", target))?;
 
-    // Perform scan with observer callbacks
-    let results = scanner.scan_all(move |result| {
-        if let Ok(mut obs) = progress_obs_clone.lock() {
-            obs.on_port_scanned(&result);
-        }
-        if let Ok(mut metrics) = metrics_clone.lock() {
-            metrics.on_port_scanned(&result);
+    // Wait for queued webhook deliveries
+    let pending = webhook_observer.and_then(|webhook| webhook.lock().unwrap().close());
+    if let Some(handle) = pending {
+        let _ = handle.await;
+    }
+
+    if !quiet {
+        // The dashboard took the place of the console progress
+        if output.tui {
+            progress_observer.lock().unwrap().on_scan_completed(&results);
         }
-    }).await;
 
-    // Calculate duration
+        // Display performance metrics
+        let metrics = metrics_collector.lock().unwrap();
+        println!("\n=== PERFORMANCE METRICS ===");
+        println!("Total time: {:.2}s", metrics.elapsed().as_secs_f64());
+        println!("Ports/second: {:.2}", metrics.ports_per_second());
+        println!("Ports scanned: {}", metrics.ports_scanned);
+        println!("Result spread: {:.2}s", metrics.scan_spread().as_secs_f64());
+    }
+
+    report_results(&config, hostname, &results, duration, &mut outputs.lock().unwrap(), output)?;
+    Ok(results)
+}
+
+/// Scan every target from one shuffled host×port work list, then report
+/// each host as its own scan
+async fn run_shuffled_scan(
+    config: ScanConfig,
+    targets: Vec<Target>,
+    hostnames: Vec<Option<String>>,
+    output: &OutputOptions,
+) -> anyhow::Result<Vec<(Target, ScanResults)>> {
+    let quiet = output.stdout_format.is_some();
+    if output.tui {
+        warn!("--tui follows a single host; ignoring it for a shuffled multi-target scan");
+    }
+    if !quiet {
+        display_scan_info(&config, None);
+        println!("Targets:         {} (one shuffled host×port work list)", targets.len());
+    }
+
+    let webhook = output.webhook_url.clone().map(|url| Arc::new(Mutex::new(WebhookObserver::new(url))));
+    let mut scan = cli_scan(&config, targets);
+    if !quiet {
+        scan = scan.observer(ProgressObserver::new(config.verbose).with_line_limit(output.progress_lines));
+    }
+    if let Some(webhook) = &webhook {
+        scan = scan.observer(Arc::clone(webhook));
+    }
+
+    let start_time = Instant::now();
+    let hosts = scan.run().await?;
     let duration = start_time.elapsed();
-    let duration_seconds = duration.as_secs_f64();
 
-    // Notify observers of completion
-    progress_observer.lock().unwrap().on_scan_completed(&results);
-    
-    // Display performance metrics
-    let metrics = metrics_collector.lock().unwrap();
-    println!("\n=== PERFORMANCE METRICS ===");
-    println!("Total time: {:.2}s", metrics.elapsed().as_secs_f64());
-    println!("Ports/second: {:.2}", metrics.ports_per_second());
-    println!("Ports scanned: {}", metrics.ports_scanned);
-    drop(metrics); // Release lock
-
-    // Get metrics from results
-    let total_ports = results.total_ports;
-    let open_ports = results.open_ports;
-    let closed_ports = results.closed_ports;
-
-    // Create report for export
-    let report = ScanReport::new(&config, results.clone(), duration_seconds);
+    // Wait for queued webhook deliveries
+    let pending = webhook.and_then(|webhook| webhook.lock().unwrap().close());
+    if let Some(handle) = pending {
+        let _ = handle.await;
+    }
 
-    // Handle output based on CLI args or interactive prompt
-    if let Some(fmt) = output_format {
-        // CLI-specified format
-        match fmt {
-            OutputFormatArg::Json => save_report(&report, OutputFormat::Json, &config.target_ip.to_string(), output_file.as_deref(), open_only)?,
-            OutputFormatArg::Csv => save_report(&report, OutputFormat::Csv, &config.target_ip.to_string(), output_file.as_deref(), open_only)?,
-            OutputFormatArg::Text => save_report(&report, OutputFormat::Text, &config.target_ip.to_string(), output_file.as_deref(), open_only)?,
-            OutputFormatArg::All => {
-                save_report(&report, OutputFormat::Json, &config.target_ip.to_string(), None, open_only)?;
-                save_report(&report, OutputFormat::Csv, &config.target_ip.to_string(), None, open_only)?;
-                save_report(&report, OutputFormat::Text, &config.target_ip.to_string(), None, open_only)?;
-            }
+    let mut scanned = Vec::with_capacity(hosts.len());
+    for (host, hostname) in hosts.into_iter().zip(hostnames) {
+        let mut host_config = config.clone();
+        host_config.target_ip = host.target.ip;
+        host_config.scope_id = host.target.scope_id;
+
+        let mut outputs = build_outputs(&host_config, output)?;
+        for result in &host.results.results {
+            outputs.on_result(&report_result(result, output.redactor.as_ref()));
         }
+        report_results(&host_config, hostname, &host.results, duration, &mut outputs, output)?;
+        scanned.push((host.target, host.results));
+    }
+    Ok(scanned)
+}
+
+/// Write the reports for one host's finished scan and, unless a report
+/// goes to stdout, show the results on the console
+fn report_results(
+    config: &ScanConfig,
+    hostname: Option<String>,
+    results: &ScanResults,
+    duration: std::time::Duration,
+    outputs: &mut MultiFormatter,
+    output: &OutputOptions,
+) -> anyhow::Result<()> {
+    let quiet = output.stdout_format.is_some();
+    let open_only = output.open_only;
+    let fields = output.fields.as_deref();
+    let mut shared = results.clone();
+    if let Some(redactor) = &output.redactor {
+        let masked = redactor.redact(&mut shared);
+        debug!("Redacted sensitive content in {} results", masked);
+    }
+    let report = ScanReport::new(config, shared, duration.as_secs_f64())
+        .with_command_line(std::env::args())
+        .with_hostname(hostname);
+
+    // Stdout mode: emit only the machine-readable report
+    if quiet {
+        return complete_outputs(outputs, &report, quiet);
+    }
+
+    // Handle output based on CLI args or interactive prompt
+    if output.output_format.is_some() {
+        complete_outputs(outputs, &report, quiet)?;
     } else {
         // Interactive format selection
         println!("\n=== OUTPUT OPTIONS ===");
@@ -226,13 +863,13 @@ async fn main() -> anyhow::Result<()> {
         let choice = line.trim();
         
         match choice {
-            "1" => save_report(&report, OutputFormat::Json, &config.target_ip.to_string(), None, open_only)?,
-            "2" => save_report(&report, OutputFormat::Csv, &config.target_ip.to_string(), None, open_only)?,
-            "3" => save_report(&report, OutputFormat::Text, &config.target_ip.to_string(), None, open_only)?,
+            "1" => save_report(&report, OutputFormat::Json, &config.target_ip.to_string(), None, open_only, fields)?,
+            "2" => save_report(&report, OutputFormat::Csv, &config.target_ip.to_string(), None, open_only, fields)?,
+            "3" => save_report(&report, OutputFormat::Text, &config.target_ip.to_string(), None, open_only, fields)?,
             "4" => {
-                save_report(&report, OutputFormat::Json, &config.target_ip.to_string(), None, open_only)?;
-                save_report(&report, OutputFormat::Csv, &config.target_ip.to_string(), None, open_only)?;
-                save_report(&report, OutputFormat::Text, &config.target_ip.to_string(), None, open_only)?;
+                save_report(&report, OutputFormat::Json, &config.target_ip.to_string(), None, open_only, fields)?;
+                save_report(&report, OutputFormat::Csv, &config.target_ip.to_string(), None, open_only, fields)?;
+                save_report(&report, OutputFormat::Text, &config.target_ip.to_string(), None, open_only, fields)?;
             }
             "0" => debug!("Skipping file export"),
             _ => println!("Invalid option, skipping export"),
@@ -240,58 +877,164 @@ async fn main() -> anyhow::Result<()> {
     }
 
     // Output results to console
-    display_text_results(&results, duration, total_ports, open_ports, closed_ports);
+    display_text_results(results, duration, results.total_ports, results.open_ports, results.closed_ports);
+    if let Some(population) = config.scan_mode.sample_population() {
+        println!("\n=== SAMPLE ESTIMATE ===");
+        println!("{}", results.estimate_open(population));
+    }
 
     Ok(())
 }
 
 /// Build configuration from command-line arguments
-fn build_config_from_cli(cli: Cli) -> anyhow::Result<ScanConfig> {
-    // Parse target IP
-    let target_ip = if let Some(target) = cli.target {
-        target.parse()
+fn build_config_from_cli(cli: Cli, targets: Option<&[Target]>) -> anyhow::Result<ScanConfig> {
+    // Parse target IP (IPv6 link-local targets may carry a zone, e.g. fe80::1%eth0)
+    let (target_ip, scope_id) = if let Some(first) = targets.and_then(|t| t.first()) {
+        (first.ip, first.scope_id)
+    } else if let Some(target) = cli.target {
+        network_utils::parse_scoped_ip(&target)
             .map_err(|e| anyhow::anyhow!("Invalid IP address '{}': {}", target, e))?
     } else {
         return Err(anyhow::anyhow!("Target IP is required. Use --target or run without arguments for interactive mode."));
     };
 
-    // Parse scan mode
-    let scan_mode = if cli.common {
-        ScanMode::CommonPorts
-    } else if let Some(ports_str) = cli.ports {
-        parse_ports_string(&ports_str)?
+    // Presets and profiles seed the builder; explicit flags below take precedence
+    let preset = if cli.fast {
+        Some(ScanPreset::Fast)
+    } else if cli.thorough {
+        Some(ScanPreset::Thorough)
+    } else if cli.normal {
+        Some(ScanPreset::Normal)
     } else {
+        None
+    };
+    let mut builder = match (preset, cli.profile) {
+        (Some(preset), _) => preset.builder(),
+        (None, Some(profile)) => ScanProfile::from(profile).builder(),
         // Default to common ports if nothing specified
-        ScanMode::CommonPorts
+        (None, None) => ScanConfigBuilder::new().common_ports(),
     };
 
-    // Determine thread count
-    let thread_count = cli.threads
-        .unwrap_or_else(|| port_scanner::infrastructure::network_utils::num_cpus())
-        .max(1)
-        .min(256);
+    // Parse scan mode
+    if let Some(set) = &cli.common {
+        builder = match set {
+            Some(set) => builder.scan_mode(ScanMode::common_set(set)?),
+            None => builder.common_ports(),
+        };
+    } else if let Some(ports_str) = &cli.ports {
+        builder = builder.scan_mode(parse_ports_string(ports_str)?);
+    } else if let Some(path) = cli.ports_file {
+        let ports = targets::read_port_list(Path::new(&path))
+            .map_err(|e| anyhow::anyhow!("Invalid ports file {}: {}", path, e))?;
+        builder = builder.scan_mode(ScanMode::CustomList(ports));
+    }
+    if let Some(count) = cli.sample {
+        let range = match cli.ports.as_deref().map(parse_ports_string).transpose()? {
+            Some(ScanMode::Range { start, end }) => (start, end),
+            Some(_) => return Err(anyhow::anyhow!("--sample needs a port range (START-END), not a list")),
+            None => (MIN_PORT, MAX_PORT),
+        };
+        builder = builder.scan_mode(ScanMode::Sample { range, count });
+    }
+    builder = builder.min_port(cli.min_port).max_port(cli.max_port);
+
+    if let Some(timeout_ms) = cli.timeout {
+        builder = builder.timeout(std::time::Duration::from_millis(timeout_ms));
+    }
+    if let Some(threads) = cli.threads {
+        builder = builder.thread_count(threads.clamp(1, 256));
+    }
+    if cli.concurrency.is_some() {
+        builder = builder.max_concurrent(cli.concurrency);
+    }
+    if let Some(bytes) = cli.max_read_bytes {
+        builder = builder.max_read_bytes(bytes);
+    }
+    if let Some(duration) = cli.max_read_time {
+        builder = builder.max_read_duration(duration);
+    }
+    if let Some(idle) = cli.max_read_idle {
+        builder = builder.max_read_idle(idle);
+    }
+    if let Some(timeout) = cli.drain_timeout {
+        builder = builder.drain_timeout(timeout);
+    }
+    builder = builder.banner_initial_delay(cli.banner_delay);
+    builder = builder.banner_timeout(cli.banner_timeout);
+    builder = builder.include_raw_banner(cli.include_raw_banner);
+    if let Some(order) = cli.order {
+        builder = builder.ordering(order.into());
+    }
+    if let Some(protocol) = cli.protocol {
+        builder = builder.protocol(protocol.into());
+    }
+    // Detection flags can only switch detection on
+    if cli.detect_versions {
+        builder = builder.detect_versions(true);
+    }
+    if cli.detect_os {
+        builder = builder.detect_os(true);
+    }
+    builder = builder.detect_ports(cli.detect_ports);
+    if cli.verify_filtered {
+        builder = builder.verify_filtered(true);
+    }
+    if cli.retry_on_reset {
+        builder = builder.retry_on_reset(true);
+    }
+    if let Some(retries) = cli.retries {
+        builder = builder.probe_retries(retries);
+    }
+    builder = builder.max_port_errors(cli.max_port_errors);
+    if cli.ramp_up {
+        builder = builder.ramp_up(true);
+    }
+    if cli.require_privileges {
+        builder = builder.require_privileges(true);
+    }
+    if let Some(scan_type) = cli.scan_type {
+        builder = builder.scan_type(scan_type.into());
+    }
 
     // Build delay option
     let delay_between_probes = cli.delay.map(std::time::Duration::from_millis);
 
     // Build configuration
-    Ok(ScanConfigBuilder::new()
+    Ok(builder
         .target(target_ip)
-        .scan_mode(scan_mode)
-        .timeout(std::time::Duration::from_millis(cli.timeout))
+        .scope_id(scope_id)
         .verbose(cli.verbose)
-        .detect_versions(cli.detect_versions)
-        .detect_os(cli.detect_os)
         .parallel(cli.parallel)
-        .thread_count(thread_count)
         .randomize_source_port(cli.randomize_port)
+        .seed(cli.seed)
         .delay_between_probes(delay_between_probes)
+        .dns_axfr_domain(cli.axfr_domain)
+        .randomize_scan_order(cli.randomize_scan_order)
+        .snmp_communities(cli.snmp_communities)
+        .detection_early_exit(cli.detection_early_exit)
+        .max_scan_duration(cli.max_time)
+        .max_open_ports(cli.honeypot_threshold)
+        .closed_port_samples(cli.closed_port_samples.unwrap_or(DEFAULT_CLOSED_PORT_SAMPLES))
+        .reuse_connection(cli.reuse_connection)
+        .graceful_close(!cli.no_graceful_close)
+        .udp_icmp_closed(!cli.no_icmp_closed)
+        .linger(cli.linger)
+        .keepalive(cli.keepalive)
+        .bind_interface(cli.interface)
+        .syn_scan(cli.syn)
+        .ttl(cli.ttl)
+        .tcp_window(cli.window)
         .build()?)
 }
 
+/// `--detect-ports` list, same syntax as `-p` lists
+fn parse_detect_ports(s: &str) -> Result<Vec<Port>, String> {
+    targets::parse_port_spec(s)
+}
+
 /// Parse ports string (e.g., "80,443,8080" or "1-1000")
 fn parse_ports_string(s: &str) -> anyhow::Result<ScanMode> {
-    if s.contains('-') {
+    if s.contains('-') && !s.contains(',') {
         // Port range
         let parts: Vec<&str> = s.split('-').collect();
         if parts.len() != 2 {
@@ -303,25 +1046,119 @@ fn parse_ports_string(s: &str) -> anyhow::Result<ScanMode> {
             .map_err(|_| anyhow::anyhow!("Invalid end port: {}", parts[1]))?;
         Ok(ScanMode::Range { start, end })
     } else {
-        // Custom port list
-        let ports: Result<Vec<u16>, _> = s.split(',')
-            .map(|p| p.trim().parse())
-            .collect();
-        Ok(ScanMode::CustomList(ports?))
+        // Custom port list, which may mix in ranges
+        let ports = targets::parse_port_spec(s).map_err(|e| anyhow::anyhow!("Invalid port list: {}", e))?;
+        Ok(ScanMode::CustomList(ports))
+    }
+}
+
+/// Parse a duration such as "60s", "2m", "500ms" or bare seconds
+fn parse_duration(s: &str) -> Result<std::time::Duration, String> {
+    let s = s.trim();
+    let split = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    let (value, unit) = s.split_at(split);
+    let value: u64 = value.parse().map_err(|_| format!("Invalid duration: {}", s))?;
+    match unit {
+        "ms" => Ok(std::time::Duration::from_millis(value)),
+        "" | "s" => Ok(std::time::Duration::from_secs(value)),
+        "m" => Ok(std::time::Duration::from_secs(value * 60)),
+        "h" => Ok(std::time::Duration::from_secs(value * 3600)),
+        _ => Err(format!("Invalid duration unit '{}' (use ms, s, m or h)", unit)),
+    }
+}
+
+/// Map a CLI format to a single output format (stdout takes exactly one)
+fn single_output_format(fmt: OutputFormatArg) -> anyhow::Result<OutputFormat> {
+    match fmt {
+        OutputFormatArg::Json => Ok(OutputFormat::Json),
+        OutputFormatArg::Csv => Ok(OutputFormat::Csv),
+        OutputFormatArg::Text => Ok(OutputFormat::Text),
+        OutputFormatArg::Ndjson => Ok(OutputFormat::Ndjson),
+        OutputFormatArg::All => Err(anyhow::anyhow!("Writing to stdout requires a single format (json, csv, ndjson or text)")),
+    }
+}
+
+/// Load two JSON or CSV reports and print their differences (JSON with `--stdout json`)
+fn display_diff(old_path: &str, new_path: &str, format: Option<OutputFormatArg>) -> anyhow::Result<()> {
+    let old = ScanReport::from_file(Path::new(old_path))
+        .map_err(|e| anyhow::anyhow!("Failed to load report '{}': {}", old_path, e))?;
+    let new = ScanReport::from_file(Path::new(new_path))
+        .map_err(|e| anyhow::anyhow!("Failed to load report '{}': {}", new_path, e))?;
+    let diff = old.diff(&new);
+
+    match format {
+        None | Some(OutputFormatArg::Text) => print!("{}", diff),
+        Some(OutputFormatArg::Json) => println!("{}", serde_json::to_string_pretty(&diff)?),
+        Some(_) => return Err(anyhow::anyhow!("Report diffs are available as text or json")),
+    }
+    Ok(())
+}
+
+/// Outputs for one host: the `--stdout` report and the `--format` files
+fn build_outputs(config: &ScanConfig, output: &OutputOptions) -> anyhow::Result<MultiFormatter> {
+    let fields = output.fields.as_deref();
+    let formatter = |format| OutputFormatterFactory::create_with_fields(format, output.open_only, fields);
+    let mut outputs = MultiFormatter::new();
+    if let Some(format) = output.stdout_format {
+        let stdout = match format {
+            // One report per line, so several hosts' reports stay parseable
+            OutputFormat::Json if output.several_targets => {
+                let mut json = JsonFormatter::new(output.open_only).compact(true);
+                json.fields = output.fields.clone();
+                Box::new(json)
+            }
+            format => formatter(format),
+        };
+        outputs = outputs.with_writer(stdout, io::stdout());
+    }
+    // `-F -` already routed --format to stdout
+    let Some(fmt) = output.output_format.filter(|_| output.output_file.as_deref() != Some("-")) else {
+        return Ok(outputs);
+    };
+    let target_ip = config.target_ip.to_string();
+    let formats = match fmt {
+        OutputFormatArg::All => vec![OutputFormat::Json, OutputFormat::Csv, OutputFormat::Text],
+        single => vec![single_output_format(single)?],
+    };
+    for format in formats {
+        let path = match (&output.output_file, fmt) {
+            (Some(path), fmt) if fmt != OutputFormatArg::All => path.clone(),
+            _ => ScanReport::default_filename(&target_ip, format),
+        };
+        let outputs_with = if output.append { MultiFormatter::with_appended_file } else { MultiFormatter::with_file };
+        outputs = outputs_with(outputs, formatter(format), path)
+            .map_err(|e| anyhow::anyhow!("Failed to create {:?} report: {}", format, e))?;
+    }
+    Ok(outputs)
+}
+
+/// Finish every output and list the files written
+fn complete_outputs(outputs: &mut MultiFormatter, report: &ScanReport, quiet: bool) -> anyhow::Result<()> {
+    if let Err(e) = outputs.on_complete(report) {
+        eprintln!("✗ Failed to save report: {}", e);
+        return Err(e.into());
+    }
+    for path in outputs.paths() {
+        // Stdout may be carrying a report
+        if quiet {
+            eprintln!("✓ Report saved to: {}", path.display());
+        } else {
+            println!("✓ Report saved to: {}", path.display());
+        }
     }
+    Ok(())
 }
 
 /// Save report in specified format
-fn save_report(report: &ScanReport, format: OutputFormat, target_ip: &str, custom_path: Option<&str>, open_only: bool) -> anyhow::Result<()> {
-    let filename = custom_path
-        .map(|p| p.to_string())
-        .unwrap_or_else(|| ScanReport::default_filename(target_ip, format));
-    let path = Path::new(&filename);
-    let formatter = OutputFormatterFactory::create(format, open_only);
+fn save_report(report: &ScanReport, format: OutputFormat, target_ip: &str, custom_path: Option<&str>, open_only: bool, fields: Option<&[ReportField]>) -> anyhow::Result<()> {
+    let mut sink = match custom_path {
+        Some(path) => FileSink::new(format, path, open_only),
+        None => FileSink::with_default_name(format, target_ip, open_only),
+    }.with_fields(fields.map(<[_]>::to_vec));
     
-    match formatter.write_to_file(report, path) {
+    match sink.on_complete(report) {
         Ok(_) => {
-            println!("✓ {:?} report saved to: {}", format, filename);
+            println!("✓ {:?} report saved to: {}", format, sink.path().display());
             Ok(())
         }
         Err(e) => {
@@ -343,7 +1180,7 @@ fn build_config_interactive() -> anyhow::Result<ScanConfig> {
     io::stdout().flush()?;
     let target_input = lines.next()
         .ok_or_else(|| anyhow::anyhow!("No input provided"))??;
-    let target_ip = target_input.trim().parse()
+    let (target_ip, scope_id) = network_utils::parse_scoped_ip(&target_input)
         .map_err(|e| anyhow::anyhow!("Invalid IP address format: {}", e))?;
 
     // Get scan mode
@@ -419,7 +1256,7 @@ fn build_config_interactive() -> anyhow::Result<ScanConfig> {
         if thread_input.trim().is_empty() {
             default_threads
         } else {
-            thread_input.trim().parse::<usize>()?.min(256).max(1)
+            thread_input.trim().parse::<usize>()?.clamp(1, 256)
         }
     } else {
         1
@@ -433,7 +1270,7 @@ fn build_config_interactive() -> anyhow::Result<ScanConfig> {
     let timeout_ms = if timeout_input.trim().is_empty() {
         500
     } else {
-        timeout_input.trim().parse::<u64>()?.max(100).min(10000)
+        timeout_input.trim().parse::<u64>()?.clamp(100, 10000)
     };
 
     // Ask for stealth options
@@ -457,7 +1294,7 @@ fn build_config_interactive() -> anyhow::Result<ScanConfig> {
         let delay_ms = if delay_ms_input.trim().is_empty() {
             100
         } else {
-            delay_ms_input.trim().parse::<u64>()?.max(10).min(5000)
+            delay_ms_input.trim().parse::<u64>()?.clamp(10, 5000)
         };
         Some(std::time::Duration::from_millis(delay_ms))
     } else {
@@ -475,6 +1312,7 @@ fn build_config_interactive() -> anyhow::Result<ScanConfig> {
     // Build config
     Ok(ScanConfigBuilder::new()
         .target(target_ip)
+        .scope_id(scope_id)
         .scan_mode(mode)
         .timeout(std::time::Duration::from_millis(timeout_ms))
         .verbose(verbose)
@@ -487,46 +1325,140 @@ fn build_config_interactive() -> anyhow::Result<ScanConfig> {
         .build()?)
 }
 
+/// Display the host:port pairs a scan would probe
+fn display_dry_run(config: &ScanConfig, probes: &[SocketAddr]) {
+
+    println!("=== DRY RUN (no connections will be made) ===");
+    for addr in probes {
+        println!("{}", addr);
+    }
+    println!("\nTotal: {} probe(s) against {}", probes.len(), config.target_display());
+    println!("Estimated time: {}", config.estimated_duration());
+}
+
 /// Display scan configuration info
-fn display_scan_info(config: &ScanConfig) {
+fn display_scan_info(config: &ScanConfig, hostname: Option<&str>) {
     println!("\n╔══════════════════════════════════════════════════════════╗");
     println!("║              SCAN CONFIGURATION SUMMARY                  ║");
     println!("╚══════════════════════════════════════════════════════════╝");
     
     println!("\n=== TARGET & SCOPE ===");
-    println!("Target IP:       {}", config.target_ip);
+    println!("Target IP:       {}", config.target_display());
+    if let Some(hostname) = hostname {
+        println!("Hostname:        {}", hostname);
+    }
     match &config.scan_mode {
         ScanMode::Range { start, end } => {
             println!("Scan Mode:       Port Range");
             println!("Port Range:      {}-{}", start, end);
-            println!("Total Ports:     {}", end - start + 1);
+            println!("Total Ports:     {}", config.port_count());
         }
         ScanMode::CommonPorts => {
             println!("Scan Mode:       Common Ports");
             println!("Total Ports:     {} well-known ports", config.port_count());
         }
+        ScanMode::CommonSet(set) => {
+            println!("Scan Mode:       Common Ports ({})", set);
+            println!("Total Ports:     {}", config.port_count());
+        }
+        ScanMode::Sample { range: (start, end), .. } => {
+            println!("Scan Mode:       Random Sample");
+            println!("Port Range:      {}-{}", start, end);
+            println!("Total Ports:     {} sampled", config.port_count());
+        }
         ScanMode::CustomList(ports) => {
             println!("Scan Mode:       Custom Port List");
-            println!("Total Ports:     {}", ports.len());
+            println!("Total Ports:     {}", config.port_count());
             if ports.len() <= 10 {
                 println!("Ports:           {:?}", ports);
             }
         }
     }
+    if config.min_port.is_some() || config.max_port.is_some() {
+        let (min, max) = config.port_clamp();
+        println!("Port Clamp:      {}-{}", min, max);
+    }
+    
+    if config.protocol != ScanProtocol::Tcp {
+        let protocols: Vec<String> = config.protocol.protocols().iter().map(ToString::to_string).collect();
+        println!("Protocols:       {}", protocols.join(", "));
+        if !config.udp_icmp_closed {
+            println!("UDP Unreachable: open|filtered (ICMP not trusted)");
+        }
+    }
     
     println!("\n=== DETECTION SETTINGS ===");
     println!("Service Detection:    {}", if config.detect_versions { "✓ Enabled" } else { "✗ Disabled" });
     println!("OS Detection (SMB):   {}", if config.detect_os { "✓ Enabled" } else { "✗ Disabled" });
+    if let Some(ports) = &config.detect_ports {
+        let ports: Vec<String> = ports.iter().map(Port::to_string).collect();
+        println!("Detection Ports:      {}", ports.join(", "));
+    }
     
     println!("\n=== PERFORMANCE SETTINGS ===");
     println!("Parallel Scanning:    {}", if config.parallel { "✓ Enabled" } else { "✗ Disabled" });
     if config.parallel {
         println!("Thread Count:         {}", config.thread_count);
+        println!("Concurrency:          {}", ParallelExecutor::for_config(config).max_concurrent());
     }
     println!("Connection Timeout:   {:?}", config.timeout);
+    println!("Estimated Time:       {}", config.estimated_duration());
+    if let Some(limit) = config.max_scan_duration {
+        println!("Scan Time Limit:      {:?}", limit);
+        println!("Drain Timeout:        {:?}", config.drain_timeout);
+    }
+    if config.randomize_scan_order {
+        println!("Probe Order:          Shuffled across hosts and ports");
+    } else {
+        println!("Probe Order:          {:?}", config.ordering);
+    }
+    println!("Read Limit:           {} bytes / {:?} per connection, {:?} idle", config.max_read_bytes, config.max_read_duration, config.max_read_idle);
+    if let Some(delay) = config.banner_initial_delay {
+        println!("Banner Delay:         {:?} after connect", delay);
+    }
+    if let Some(timeout) = config.banner_timeout {
+        println!("Banner Timeout:       {:?}", timeout);
+    }
+    if config.include_raw_banner {
+        println!("Raw Banner Hex:       ✓ Enabled");
+    }
+    if config.verify_filtered {
+        println!("Verify Filtered:      ✓ Enabled");
+    }
+    if config.retry_on_reset {
+        println!("Retry On Reset:       ✓ Enabled");
+    }
+    if config.probe_retries > 0 {
+        match config.max_port_errors {
+            Some(limit) => println!("Probe Retries:        {} (at most {} errors per port)", config.probe_retries, limit),
+            None => println!("Probe Retries:        {}", config.probe_retries),
+        }
+    }
+    if config.ramp_up {
+        println!("Concurrency Ramp-Up:  ✓ Enabled");
+    }
+    if let Some(linger) = config.linger {
+        println!("Socket Linger:        {:?}{}", linger, if linger.is_zero() { " (reset on close)" } else { "" });
+    }
+    if let Some(idle) = config.keepalive {
+        println!("TCP Keepalive:        after {:?} idle", idle);
+    }
+    if let Some(interface) = &config.bind_interface {
+        println!("Interface:            {}", interface);
+    }
     
     println!("\n=== STEALTH SETTINGS ===");
+    println!("Scan Type:            {}", config.scan_type);
+    if let Some(ttl) = config.ttl {
+        println!("Probe TTL:            {}", ttl);
+    }
+    if let Some(window) = config.tcp_window {
+        println!("Probe TCP Window:     {}", window);
+    }
     println!("Source Port Randomization: {}", if config.randomize_source_port { "✓ Enabled" } else { "✗ Disabled" });
+    if let Some(seed) = config.seed {
+        println!("Random Seed:          {}", seed);
+    }
     if let Some(delay) = config.delay_between_probes {
         println!("Probe Delay:          {:?} (Stealth mode)", delay);
     } else {
@@ -559,9 +1491,39 @@ fn display_text_results(
     println!("\n=== SUMMARY STATISTICS ===");
     println!("Total Ports Scanned: {}", total_ports);
     println!("Open Ports:          {} ({:.1}%)", open_ports, results.open_percentage());
+    let by_protocol = results.open_ports_by_protocol();
+    if by_protocol.len() > 1 {
+        for (protocol, open) in by_protocol {
+            println!("  {}:                {}", protocol, open);
+        }
+    }
     println!("Closed Ports:        {}", closed_ports);
     println!("Filtered Ports:      {}", results.filtered_ports);
+    println!("Reset Ports:         {}", results.reset_ports);
+    if results.open_filtered_ports > 0 {
+        println!("Open|Filtered Ports: {}", results.open_filtered_ports);
+    }
     println!("Error Ports:         {}", results.error_ports);
+    if let Some(latency) = results.connect_latency_summary() {
+        println!("Connect Latency:     {}", latency);
+    }
+    if let Some(state) = results.host_state {
+        println!("Host State:          {}", state);
+    }
+    if results.honeypot_suspected {
+        println!("⚠ Host exceeded the open-port threshold (possible honeypot); scan stopped early");
+    }
+    let by_category = results.service_summary();
+    if !by_category.is_empty() {
+        println!("\n=== SERVICES BY CATEGORY ===");
+        print!("{}", category_histogram(&by_category));
+    }
+    if let Some(os_guess) = &results.os_guess {
+        println!("\n=== OS GUESS ===");
+        for candidate in &os_guess.candidates {
+            println!("{:<20} {:.0}%  ({})", candidate.name, candidate.confidence * 100.0, candidate.evidence.join("; "));
+        }
+    }
     
     // Display open ports with FULL details
     if open_ports > 0 {
@@ -571,18 +1533,28 @@ fn display_text_results(
         
         for result in &results.results {
             if result.status.is_open() {
-                println!("\n┌─ Port {} ────────────────────", result.port);
+                println!("\n┌─ Port {} ────────────────────", result.label());
                 println!("│ Status: OPEN");
                 
                 // Display service version if available
                 if let Some(ref version) = result.service_version {
                     println!("│");
                     println!("│ ┌─ Service Detection ─────");
-                    println!("│ │ Service:     {}", version.service_name);
+                    println!("│ │ Service:     {}{}", version.service_name, port_based_note(version));
+                    if let Some(ref product) = version.product {
+                        println!("│ │ Product:     {}", product);
+                    }
                     if let Some(ref ver) = version.version {
                         println!("│ │ Version:     {}", ver);
                     }
+                    if let Some(ref os) = version.os {
+                        println!("│ │ Host OS:     {}", os);
+                    }
                     println!("│ │ Protocol:    {}", version.protocol);
+                    println!("│ │ Confidence:  {:.0}%", version.confidence * 100.0);
+                    if let Some(ref extra) = version.extra_info {
+                        println!("│ │ Extra Info:  {}", extra);
+                    }
                     if let Some(ref banner) = version.banner {
                         if !banner.is_empty() {
                             println!("│ │ Banner:      {}", banner.lines().next().unwrap_or(banner));
@@ -591,6 +1563,9 @@ fn display_text_results(
                                     println!("│ │              {}", line);
                                 }
                             }
+                            if version.banner_truncated {
+                                println!("│ │              [truncated at read limit]");
+                            }
                         }
                     }
                     println!("│ └─────────────────────────");
@@ -0,0 +1,53 @@
+//! What this build of the scanner supports, for scripts choosing options
+
+use serde::Serialize;
+
+use crate::constants::TOOL_VERSION;
+use crate::domain::Protocol;
+use crate::presentation::formatter::{OutputFormat, OutputFormatterFactory};
+use crate::scanning::{DetectorRegistry, ScanStrategyFactory, ScanType};
+
+/// Scan types, transports, detectors, output formats and cargo features
+/// available in this build; serialized by `--capabilities`.
+///
+/// ```
+/// use port_scanner::presentation::Capabilities;
+///
+/// let json = serde_json::to_value(Capabilities::current())?;
+/// let listed = |key: &str, name: &str| json[key].as_array().unwrap().iter().any(|v| v == name);
+/// for format in ["text", "json", "csv", "ndjson"] {
+///     assert!(listed("output_formats", format));
+/// }
+/// assert!(listed("detectors", "VersionDetector"));
+/// assert!(listed("detectors", "SMBFingerprinter"));
+/// assert!(listed("scan_types", "connect"));
+/// assert_eq!(listed("scan_types", "syn"), cfg!(all(feature = "syn-scan", unix)));
+/// # Ok::<(), serde_json::Error>(())
+/// ```
+#[derive(Debug, Clone, Serialize)]
+pub struct Capabilities {
+    pub tool_version: &'static str,
+    pub scan_types: Vec<ScanType>,
+    pub protocols: Vec<Protocol>,
+    /// Built-in detectors, in the order they are tried
+    pub detectors: Vec<String>,
+    pub output_formats: Vec<OutputFormat>,
+    /// Optional cargo features compiled in
+    pub features: Vec<&'static str>,
+}
+
+impl Capabilities {
+    /// Capabilities of the running binary
+    pub fn current() -> Self {
+        let detectors = DetectorRegistry::with_defaults();
+        let features = [("syn-scan", cfg!(feature = "syn-scan")), ("tui", cfg!(feature = "tui"))];
+        Self {
+            tool_version: TOOL_VERSION,
+            scan_types: ScanStrategyFactory::supported_scan_types(),
+            protocols: vec![Protocol::Tcp, Protocol::Udp],
+            detectors: detectors.detector_names().into_iter().map(String::from).collect(),
+            output_formats: OutputFormatterFactory::supported_formats(),
+            features: features.into_iter().filter(|&(_, enabled)| enabled).map(|(name, _)| name).collect(),
+        }
+    }
+}
@@ -0,0 +1,204 @@
+//! Differences between two scan reports
+
+use std::collections::BTreeMap;
+use std::fmt;
+use serde::{Deserialize, Serialize};
+
+use crate::domain::{PortScanResult, Protocol};
+use crate::presentation::ScanReport;
+
+/// An open port as seen in one of the two reports
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PortChange {
+    pub port: u16,
+    #[serde(default)]
+    pub protocol: Protocol,
+    pub service: Option<String>,
+    pub version: Option<String>,
+}
+
+/// A port open in both reports whose detected service changed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VersionChange {
+    pub port: u16,
+    #[serde(default)]
+    pub protocol: Protocol,
+    pub old_service: Option<String>,
+    pub old_version: Option<String>,
+    pub new_service: Option<String>,
+    pub new_version: Option<String>,
+}
+
+/// Changes for a single host
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HostDiff {
+    pub host: String,
+    /// Open now, not open in the baseline
+    pub opened: Vec<PortChange>,
+    /// Open in the baseline, not open now
+    pub closed: Vec<PortChange>,
+    pub version_changed: Vec<VersionChange>,
+}
+
+impl HostDiff {
+    pub fn is_empty(&self) -> bool {
+        self.opened.is_empty() && self.closed.is_empty() && self.version_changed.is_empty()
+    }
+}
+
+/// Structured difference between a baseline report and a newer one
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScanDiff {
+    pub hosts: Vec<HostDiff>,
+}
+
+impl ScanDiff {
+    pub fn is_empty(&self) -> bool {
+        self.hosts.iter().all(HostDiff::is_empty)
+    }
+}
+
+impl ScanReport {
+    /// Compare this (baseline) report with a newer one.
+    ///
+    /// Results are matched by port number and protocol within each host. A port missing
+    /// from a report counts as not open, so `--open-only` reports compare
+    /// the same as full ones.
+    ///
+    /// ```
+    /// use port_scanner::prelude::*;
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// let config = ScanConfigBuilder::new().target("10.0.0.5".parse()?).common_ports().build()?;
+    /// let ssh = |version: &str| ServiceVersion::new("SSH", "tcp").with_version(version);
+    /// let report = |results| ScanReport::new(&config, ScanResults::new(results), 1.0);
+    ///
+    /// let baseline = report(vec![
+    ///     PortScanResult::new(22, PortStatus::Open).with_version(ssh("8.9p1")),
+    ///     PortScanResult::new(80, PortStatus::Open),
+    ///     PortScanResult::new(443, PortStatus::Closed),
+    /// ]);
+    /// let latest = report(vec![
+    ///     PortScanResult::new(22, PortStatus::Open).with_version(ssh("9.6p1")),
+    ///     PortScanResult::new(80, PortStatus::Filtered),
+    ///     PortScanResult::new(443, PortStatus::Open),
+    /// ]);
+    ///
+    /// let diff = baseline.diff(&latest);
+    /// let host = &diff.hosts[0];
+    /// assert_eq!(host.host, "10.0.0.5");
+    /// assert_eq!(host.opened.iter().map(|p| p.port).collect::<Vec<_>>(), [443]);
+    /// assert_eq!(host.closed.iter().map(|p| p.port).collect::<Vec<_>>(), [80]);
+    /// assert_eq!(host.version_changed.len(), 1);
+    /// let change = &host.version_changed[0];
+    /// assert_eq!((change.port, change.old_version.as_deref(), change.new_version.as_deref()), (22, Some("8.9p1"), Some("9.6p1")));
+    ///
+    /// assert!(latest.diff(&latest).is_empty());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn diff(&self, other: &ScanReport) -> ScanDiff {
+        let mut hosts: BTreeMap<&str, (&[PortScanResult], &[PortScanResult])> = BTreeMap::new();
+        hosts.entry(&self.scan_info.target_ip).or_default().0 = &self.results;
+        hosts.entry(&other.scan_info.target_ip).or_default().1 = &other.results;
+
+        let hosts = hosts.into_iter()
+            .map(|(host, (old, new))| diff_host(host, old, new))
+            .collect();
+        ScanDiff { hosts }
+    }
+}
+
+fn diff_host(host: &str, old: &[PortScanResult], new: &[PortScanResult]) -> HostDiff {
+    let old_open = open_ports(old);
+    let new_open = open_ports(new);
+
+    let mut diff = HostDiff {
+        host: host.to_string(),
+        opened: Vec::new(),
+        closed: Vec::new(),
+        version_changed: Vec::new(),
+    };
+
+    for (key, result) in &new_open {
+        match old_open.get(key) {
+            None => diff.opened.push(port_change(result)),
+            Some(previous) => {
+                let (old_service, old_version) = service_fields(previous);
+                let (new_service, new_version) = service_fields(result);
+                if (&old_service, &old_version) != (&new_service, &new_version) {
+                    diff.version_changed.push(VersionChange {
+                        port: result.port,
+                        protocol: result.protocol,
+                        old_service,
+                        old_version,
+                        new_service,
+                        new_version,
+                    });
+                }
+            }
+        }
+    }
+
+    for (key, result) in &old_open {
+        if !new_open.contains_key(key) {
+            diff.closed.push(port_change(result));
+        }
+    }
+
+    diff
+}
+
+fn open_ports(results: &[PortScanResult]) -> BTreeMap<(u16, Protocol), &PortScanResult> {
+    results.iter()
+        .filter(|r| r.status.is_open())
+        .map(|r| ((r.port, r.protocol), r))
+        .collect()
+}
+
+fn service_fields(result: &PortScanResult) -> (Option<String>, Option<String>) {
+    match &result.service_version {
+        Some(sv) => (Some(sv.service_name.clone()), sv.version.clone()),
+        None => (None, None),
+    }
+}
+
+fn port_change(result: &PortScanResult) -> PortChange {
+    let (service, version) = service_fields(result);
+    PortChange { port: result.port, protocol: result.protocol, service, version }
+}
+
+fn describe(service: &Option<String>, version: &Option<String>) -> String {
+    match (service, version) {
+        (Some(s), Some(v)) => format!("{} {}", s, v),
+        (Some(s), None) => s.clone(),
+        (None, _) => "unknown".to_string(),
+    }
+}
+
+impl fmt::Display for ScanDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return writeln!(f, "No changes");
+        }
+        for host in self.hosts.iter().filter(|h| !h.is_empty()) {
+            writeln!(f, "=== {} ===", host.host)?;
+            for change in &host.opened {
+                writeln!(f, "  + {:<9} {}", format!("{}/{}", change.port, change.protocol), describe(&change.service, &change.version))?;
+            }
+            for change in &host.closed {
+                writeln!(f, "  - {:<9} {}", format!("{}/{}", change.port, change.protocol), describe(&change.service, &change.version))?;
+            }
+            for change in &host.version_changed {
+                writeln!(
+                    f,
+                    "  ~ {:<9} {} -> {}",
+                    format!("{}/{}", change.port, change.protocol),
+                    describe(&change.old_service, &change.old_version),
+                    describe(&change.new_service, &change.new_version),
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,208 @@
+//! Per-result columns selectable for CSV and JSON output
+
+use serde_json::{json, Value};
+
+use crate::domain::PortScanResult;
+use crate::errors::{FormatterError, FormatterResult};
+
+/// A column of per-port output
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportField {
+    Port,
+    Status,
+    Service,
+    Version,
+    Protocol,
+    Confidence,
+    Banner,
+    ExtraInfo,
+    OsName,
+    OsVersion,
+    OsBuild,
+    SmbVersion,
+    ComputerName,
+    Domain,
+    ScannedAt,
+    Fingerprint,
+    ErrorCode,
+    ConnectLatency,
+}
+
+impl ReportField {
+    /// Every field, in default CSV column order
+    pub const ALL: [ReportField; 18] = [
+        ReportField::Port,
+        ReportField::Status,
+        ReportField::Service,
+        ReportField::Version,
+        ReportField::Protocol,
+        ReportField::Confidence,
+        ReportField::Banner,
+        ReportField::ExtraInfo,
+        ReportField::OsName,
+        ReportField::OsVersion,
+        ReportField::OsBuild,
+        ReportField::SmbVersion,
+        ReportField::ComputerName,
+        ReportField::Domain,
+        ReportField::ScannedAt,
+        ReportField::Fingerprint,
+        ReportField::ErrorCode,
+        ReportField::ConnectLatency,
+    ];
+
+    /// Field name as accepted by `--fields` and used as the JSON key
+    pub fn name(&self) -> &'static str {
+        match self {
+            ReportField::Port => "port",
+            ReportField::Status => "status",
+            ReportField::Service => "service",
+            ReportField::Version => "version",
+            ReportField::Protocol => "protocol",
+            ReportField::Confidence => "confidence",
+            ReportField::Banner => "banner",
+            ReportField::ExtraInfo => "extra_info",
+            ReportField::OsName => "os_name",
+            ReportField::OsVersion => "os_version",
+            ReportField::OsBuild => "os_build",
+            ReportField::SmbVersion => "smb_version",
+            ReportField::ComputerName => "computer_name",
+            ReportField::Domain => "domain",
+            ReportField::ScannedAt => "scanned_at",
+            ReportField::Fingerprint => "fingerprint",
+            ReportField::ErrorCode => "error_code",
+            ReportField::ConnectLatency => "connect_latency_ms",
+        }
+    }
+
+    pub fn csv_header(&self) -> &'static str {
+        match self {
+            ReportField::Port => "Port",
+            ReportField::Status => "Status",
+            ReportField::Service => "Service",
+            ReportField::Version => "Version",
+            ReportField::Protocol => "Protocol",
+            ReportField::Confidence => "Confidence",
+            ReportField::Banner => "Banner",
+            ReportField::ExtraInfo => "Extra_Info",
+            ReportField::OsName => "OS_Name",
+            ReportField::OsVersion => "OS_Version",
+            ReportField::OsBuild => "OS_Build",
+            ReportField::SmbVersion => "SMB_Version",
+            ReportField::ComputerName => "Computer_Name",
+            ReportField::Domain => "Domain",
+            ReportField::ScannedAt => "Scanned_At",
+            ReportField::Fingerprint => "Fingerprint",
+            ReportField::ErrorCode => "Error_Code",
+            ReportField::ConnectLatency => "Connect_Latency_Ms",
+        }
+    }
+
+    /// Parse a field name (case-insensitive; CSV header spellings also accepted)
+    pub fn parse(name: &str) -> FormatterResult<Self> {
+        let name = name.trim();
+        Self::ALL.iter()
+            .copied()
+            .find(|f| f.name().eq_ignore_ascii_case(name) || f.csv_header().eq_ignore_ascii_case(name))
+            .ok_or_else(|| {
+                let known: Vec<_> = Self::ALL.iter().map(ReportField::name).collect();
+                FormatterError::UnknownField(name.to_string(), known.join(", "))
+            })
+    }
+
+    /// Parse a list of field names, keeping the given order
+    ///
+    /// ```
+    /// use port_scanner::presentation::{OutputFormat, OutputFormatterFactory, ReportField};
+    /// use port_scanner::prelude::*;
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// let fields = ReportField::parse_list(&["service", "PORT", "version"])?;
+    /// assert_eq!(fields, [ReportField::Service, ReportField::Port, ReportField::Version]);
+    ///
+    /// let config = ScanConfigBuilder::new()
+    ///     .target("127.0.0.1".parse()?)
+    ///     .custom_ports(vec![22])
+    ///     .build()?;
+    /// let ssh = ServiceVersion::new("ssh", "tcp").with_version("9.6p1");
+    /// let results = ScanResults::new(vec![PortScanResult::new(22, PortStatus::Open).with_version(ssh)]);
+    /// let report = ScanReport::new(&config, results, 1.0);
+    ///
+    /// let csv = OutputFormatterFactory::create_with_fields(OutputFormat::Csv, false, Some(&fields)).format(&report)?;
+    /// assert_eq!(csv, "Service,Port,Version\nssh,22,9.6p1\n");
+    /// let json = OutputFormatterFactory::create_with_fields(OutputFormat::Json, false, Some(&fields)).format(&report)?;
+    /// let json: serde_json::Value = serde_json::from_str(&json)?;
+    /// assert_eq!(json["results"][0], serde_json::json!({"service": "ssh", "port": 22, "version": "9.6p1"}));
+    ///
+    /// let error = ReportField::parse_list(&["port", "servce"]).unwrap_err();
+    /// assert!(error.to_string().starts_with("Unknown output field 'servce' (expected one of: port, status, service,"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn parse_list<S: AsRef<str>>(names: &[S]) -> FormatterResult<Vec<Self>> {
+        names.iter().map(|n| Self::parse(n.as_ref())).collect()
+    }
+
+    /// Value as a CSV cell; free-text fields are quoted with commas and newlines replaced
+    pub fn csv_value(&self, result: &PortScanResult) -> String {
+        match self {
+            ReportField::Port => result.port.to_string(),
+            ReportField::Protocol => result.protocol.to_string(),
+            ReportField::Status => csv_text(&result.status.to_string()),
+            ReportField::Confidence => result.service_version.as_ref()
+                .map(|v| format!("{:.2}", v.confidence))
+                .unwrap_or_default(),
+            ReportField::ScannedAt => result.scanned_at_millis().to_string(),
+            ReportField::ErrorCode => result.error_code.map(|code| code.to_string()).unwrap_or_default(),
+            ReportField::ConnectLatency => result.connect_latency
+                .map(|latency| format!("{:.3}", latency.as_secs_f64() * 1000.0))
+                .unwrap_or_default(),
+            ReportField::Banner | ReportField::ExtraInfo => {
+                let text = self.text(result).unwrap_or("");
+                format!("\"{}\"", csv_text(text).replace('"', "\"\""))
+            }
+            _ => self.text(result).unwrap_or("").to_string(),
+        }
+    }
+
+    /// Value for a pruned JSON result object
+    pub fn json_value(&self, result: &PortScanResult) -> Value {
+        match self {
+            ReportField::Port => json!(result.port),
+            ReportField::Protocol => json!(result.protocol),
+            ReportField::Status => serde_json::to_value(&result.status).unwrap_or(Value::Null),
+            ReportField::Confidence => result.service_version.as_ref()
+                .map_or(Value::Null, |v| json!(v.confidence)),
+            ReportField::ScannedAt => json!(result.scanned_at_millis()),
+            ReportField::ErrorCode => result.error_code.map_or(Value::Null, |code| json!(code)),
+            ReportField::ConnectLatency => result.connect_latency
+                .map_or(Value::Null, |latency| json!(latency.as_secs_f64() * 1000.0)),
+            _ => self.text(result).map_or(Value::Null, |text| json!(text)),
+        }
+    }
+
+    fn text<'a>(&self, result: &'a PortScanResult) -> Option<&'a str> {
+        let service = result.service_version.as_ref();
+        let os = result.os_info.as_ref();
+        match self {
+            ReportField::Service => service.map(|v| v.service_name.as_str()),
+            ReportField::Version => service.and_then(|v| v.version.as_deref()),
+            ReportField::Banner => service.and_then(|v| v.banner.as_deref()),
+            ReportField::ExtraInfo => service.and_then(|v| v.extra_info.as_deref()),
+            ReportField::Fingerprint => service.and_then(|v| v.fingerprint.as_deref()),
+            ReportField::OsName => os.and_then(|o| o.os_name.as_deref()),
+            ReportField::OsVersion => os.and_then(|o| o.os_version.as_deref()),
+            ReportField::OsBuild => os.and_then(|o| o.os_build.as_deref()),
+            ReportField::SmbVersion => os.and_then(|o| o.smb_version.as_deref()),
+            ReportField::ComputerName => os.and_then(|o| o.computer_name.as_deref()),
+            ReportField::Domain => os.and_then(|o| o.domain.as_deref()),
+            ReportField::Port | ReportField::Protocol | ReportField::Status | ReportField::Confidence
+            | ReportField::ScannedAt | ReportField::ErrorCode | ReportField::ConnectLatency => None,
+        }
+    }
+}
+
+/// Free text made safe for an unescaped CSV cell
+fn csv_text(text: &str) -> String {
+    text.replace(",", ";").replace("\n", " ").replace("\r", "")
+}
@@ -1,34 +1,111 @@
-/// Output formatter factory pattern
+//! Output formatter factory pattern
 
+use std::collections::{BTreeMap, HashMap};
 use std::path::Path;
-use std::fs::File;
-use std::io::Write;
-use serde::Serialize;
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::net::IpAddr;
+use std::sync::{Arc, OnceLock, RwLock};
+use serde::{Deserialize, Serialize};
 
-use crate::domain::{PortScanResult, ScanResults};
+use crate::application::{FindingEngine, HostResults};
+use crate::domain::{Finding, HostState, LatencySummary, OSInfo, OpenPortEstimate, Port, PortScanResult, Protocol, ScanResults, ServiceCategory};
 use crate::scanning::ScanConfig;
-use crate::errors::FormatterResult;
+use crate::errors::{FormatterError, FormatterResult};
+use crate::infrastructure::{Clock, SystemClock};
+use crate::constants::{HISTOGRAM_BAR_WIDTH, REPORT_SCHEMA_VERSION, TOOL_VERSION};
+use crate::presentation::fields::ReportField;
 
 /// Output format enum
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
 pub enum OutputFormat {
     Text,
     Json,
     Csv,
     Xml,
+    /// One JSON object per result and line, written as results complete
+    Ndjson,
 }
 
-/// Scan report for serialization
-#[derive(Debug, Serialize)]
+impl OutputFormat {
+    /// Lowercase name, as serialized and as registered with
+    /// `OutputFormatterFactory::create_by_name`
+    pub fn name(self) -> &'static str {
+        match self {
+            OutputFormat::Text => "text",
+            OutputFormat::Json => "json",
+            OutputFormat::Csv => "csv",
+            OutputFormat::Xml => "xml",
+            OutputFormat::Ndjson => "ndjson",
+        }
+    }
+}
+
+/// Scan report for serialization.
+///
+/// `schema_version` identifies the JSON layout so consumers can detect
+/// format changes across releases.
+///
+/// ```
+/// use port_scanner::prelude::*;
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let config = ScanConfigBuilder::new().target("10.0.0.5".parse()?).common_ports().build()?;
+/// let report = ScanReport::new(&config, ScanResults::new(vec![PortScanResult::new(22, PortStatus::Open)]), 1.0);
+/// let json = serde_json::to_value(&report)?;
+/// assert_eq!(json["schema_version"], "1.0");
+/// assert_eq!(json["tool_version"], env!("CARGO_PKG_VERSION"));
+///
+/// // A report written by the first 1.0 release still loads
+/// let v1_0 = r#"{
+///     "schema_version": "1.0",
+///     "tool_version": "2.0.0",
+///     "scan_info": {
+///         "target_ip": "10.0.0.5", "scan_mode": "CommonPorts", "timeout_ms": 1000,
+///         "parallel_enabled": true, "thread_count": 100, "version_detection": false,
+///         "os_detection": false, "stealth_enabled": false
+///     },
+///     "results": [{ "port": 22, "status": "Open", "service_version": null, "os_info": null }],
+///     "statistics": {
+///         "total_ports": 1, "open_ports": 1, "closed_ports": 0, "filtered_ports": 0,
+///         "reset_ports": 0, "open_filtered_ports": 0, "error_ports": 0,
+///         "open_percentage": 100.0, "scan_duration_seconds": 1.0, "ports_per_second": 1.0
+///     }
+/// }"#;
+/// let old: ScanReport = serde_json::from_str(v1_0)?;
+/// assert_eq!(old.schema_version, "1.0");
+/// assert_eq!(old.scan_info.target_ip, "10.0.0.5");
+/// assert_eq!((old.results[0].port, &old.results[0].status), (22, &PortStatus::Open));
+/// assert_eq!(old.results[0].protocol, Protocol::Tcp);
+/// assert_eq!(old.statistics.open_ports, 1);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Serialize, Deserialize)]
 pub struct ScanReport {
+    pub schema_version: String,
+    pub tool_version: String,
     pub scan_info: ScanInfo,
     pub results: Vec<PortScanResult>,
     pub statistics: ScanStatistics,
+    /// Host OS guessed from all results, see `OsGuesser`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub os_guess: Option<OSInfo>,
+    /// Open ports extrapolated to the whole range, for sampled scans
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub open_port_estimate: Option<OpenPortEstimate>,
+    /// Security issues on the open ports, most severe first, see `FindingEngine`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub findings: Vec<Finding>,
 }
 
-#[derive(Debug, Serialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ScanInfo {
     pub target_ip: String,
+    /// PTR name of the target, when reverse DNS was requested and found one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hostname: Option<String>,
     pub scan_mode: String,
     pub timeout_ms: u64,
     pub parallel_enabled: bool,
@@ -36,49 +113,194 @@ pub struct ScanInfo {
     pub version_detection: bool,
     pub os_detection: bool,
     pub stealth_enabled: bool,
+    /// Version of the scanner that produced the report
+    #[serde(default)]
+    pub scanner_version: String,
+    /// Command line the scan was run with; empty for library scans
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub command_line: Vec<String>,
+    /// When the scan started and finished (RFC 3339, UTC)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub started_at: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub finished_at: Option<String>,
 }
 
-#[derive(Debug, Serialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ScanStatistics {
     pub total_ports: usize,
     pub open_ports: usize,
     pub closed_ports: usize,
     pub filtered_ports: usize,
+    pub reset_ports: usize,
+    pub open_filtered_ports: usize,
     pub error_ports: usize,
     pub open_percentage: f32,
     pub scan_duration_seconds: f64,
     pub ports_per_second: f64,
+    /// TCP connects and UDP probes made, including retries and detection
+    #[serde(default)]
+    pub connection_attempts: u64,
+    /// Scan stopped early because the host answered on too many ports
+    #[serde(default)]
+    pub honeypot_suspected: bool,
+    /// Firewalled or down, from closed-port sampling when most ports were filtered
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub host_state: Option<HostState>,
+    /// Open ports per transport, when results cover both TCP and UDP
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub open_ports_by_protocol: Option<BTreeMap<Protocol, usize>>,
+    /// Open ports per service category, see `ScanResults::service_summary`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub services_by_category: BTreeMap<ServiceCategory, usize>,
+    /// Handshake times of the open ports, see `ScanResults::connect_latency_summary`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub connect_latency: Option<LatencySummary>,
 }
 
-impl ScanReport {
-    pub fn new(config: &ScanConfig, results: ScanResults, duration_seconds: f64) -> Self {
-        let total = results.total_ports;
-        let open = results.open_ports;
-        let closed = results.closed_ports;
-        let filtered = results.filtered_ports;
-        let error = results.error_ports;
-        
-        let open_percentage = results.open_percentage();
-        
+impl ScanStatistics {
+    pub fn new(results: &ScanResults, duration_seconds: f64) -> Self {
         let ports_per_second = if duration_seconds > 0.0 {
-            total as f64 / duration_seconds
+            results.total_ports as f64 / duration_seconds
         } else {
             0.0
         };
 
+        Self {
+            total_ports: results.total_ports,
+            open_ports: results.open_ports,
+            closed_ports: results.closed_ports,
+            filtered_ports: results.filtered_ports,
+            reset_ports: results.reset_ports,
+            open_filtered_ports: results.open_filtered_ports,
+            error_ports: results.error_ports,
+            open_percentage: results.open_percentage(),
+            scan_duration_seconds: duration_seconds,
+            ports_per_second,
+            connection_attempts: results.connection_attempts,
+            honeypot_suspected: results.honeypot_suspected,
+            host_state: results.host_state,
+            open_ports_by_protocol: Some(results.open_ports_by_protocol())
+                .filter(|by_protocol| by_protocol.len() > 1),
+            services_by_category: results.service_summary(),
+            connect_latency: results.connect_latency_summary(),
+        }
+    }
+}
+
+impl ScanReport {
+    /// Report for a scan that finished just now after `duration_seconds`
+    pub fn new(config: &ScanConfig, results: ScanResults, duration_seconds: f64) -> Self {
+        Self::new_at(config, results, duration_seconds, &SystemClock)
+    }
+
+    /// One report for several hosts of a `Scan`. Each result is tagged with
+    /// its host, and the text report gets a section per host followed by a
+    /// cross-host summary.
+    ///
+    /// ```
+    /// use port_scanner::application::HostResults;
+    /// use port_scanner::prelude::*;
+    /// use port_scanner::scanning::Target;
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// let config = ScanConfigBuilder::new()
+    ///     .target("10.0.0.1".parse()?)
+    ///     .custom_ports(vec![22, 80, 443])
+    ///     .build()?;
+    /// let ssh = || ServiceVersion::new("SSH", "tcp").with_product("OpenSSH").with_version("9.6");
+    /// let host = |ip: &str, results| -> anyhow::Result<HostResults> {
+    ///     Ok(HostResults { target: Target { ip: ip.parse()?, scope_id: 0 }, hostname: None, results: ScanResults::new(results) })
+    /// };
+    /// let hosts = [
+    ///     host("10.0.0.1", vec![
+    ///         PortScanResult::new(22, PortStatus::Open).with_version(ssh()),
+    ///         PortScanResult::new(80, PortStatus::Closed),
+    ///     ])?,
+    ///     host("10.0.0.2", vec![
+    ///         PortScanResult::new(22, PortStatus::Open).with_version(ssh()),
+    ///         PortScanResult::new(443, PortStatus::Open),
+    ///     ])?,
+    /// ];
+    ///
+    /// let report = ScanReport::for_hosts(&config, &hosts, 1.0);
+    /// assert_eq!(report.results[2].host, Some("10.0.0.2".parse()?));
+    ///
+    /// let text = OutputFormatterFactory::create(OutputFormat::Text, false).format(&report)?;
+    /// let line = |label: &str| text.lines().find(|line| line.starts_with(label)).map(str::trim_end);
+    /// assert!(text.contains("=== HOST 10.0.0.1 ===") && text.contains("=== HOST 10.0.0.2 ==="));
+    /// assert!(text.lines().any(|line| line.starts_with("22/tcp") && line.ends_with("OpenSSH 9.6")));
+    /// assert_eq!(line("Hosts Up:"), Some("Hosts Up:              2"));
+    /// assert_eq!(line("Total Open Ports:"), Some("Total Open Ports:      3"));
+    /// assert_eq!(line("Most Common Open Port:"), Some("Most Common Open Port: 22/tcp (2 hosts)"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn for_hosts(config: &ScanConfig, hosts: &[HostResults], duration_seconds: f64) -> Self {
+        let results = hosts.iter()
+            .flat_map(|host| host.results.results.iter().map(|result| result.clone().with_host(host.target.ip)))
+            .collect();
+        let mut report = Self::new(config, ScanResults::new(results), duration_seconds);
+        let targets: Vec<String> = hosts.iter().map(|host| host.target.ip.to_string()).collect();
+        report.scan_info.target_ip = targets.join(", ");
+        report
+    }
+
+    /// `new` with the finish time read from `clock`
+    ///
+    /// ```
+    /// use port_scanner::constants::TOOL_VERSION;
+    /// use port_scanner::infrastructure::MockClock;
+    /// use port_scanner::prelude::*;
+    /// use std::time::{Duration, UNIX_EPOCH};
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// let config = ScanConfigBuilder::new()
+    ///     .target("127.0.0.1".parse()?)
+    ///     .custom_ports(vec![22])
+    ///     .build()?;
+    /// let clock = MockClock::new(UNIX_EPOCH + Duration::from_secs(1_700_000_000));
+    /// let report = ScanReport::new_at(&config, ScanResults::new(Vec::new()), 2.0, &clock)
+    ///     .with_command_line(["port-scanner", "-t", "127.0.0.1"]);
+    ///
+    /// let json: serde_json::Value = serde_json::to_value(&report)?;
+    /// assert_eq!(json["scan_info"]["scanner_version"], TOOL_VERSION);
+    /// assert_eq!(json["scan_info"]["started_at"], "2023-11-14T22:13:18Z");
+    /// assert_eq!(json["scan_info"]["finished_at"], "2023-11-14T22:13:20Z");
+    /// assert_eq!(json["scan_info"]["command_line"][1], "-t");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn new_at(config: &ScanConfig, results: ScanResults, duration_seconds: f64, clock: &dyn Clock) -> Self {
+        let finished = clock.now();
+        let started = finished.checked_sub(std::time::Duration::from_secs_f64(duration_seconds.max(0.0)))
+            .unwrap_or(finished);
+        let statistics = ScanStatistics::new(&results, duration_seconds);
+        let os_guess = results.os_guess.clone();
+        let findings = FindingEngine::with_defaults().evaluate(&results);
+        let open_port_estimate = config.scan_mode.sample_population()
+            .map(|population| results.estimate_open(population));
+
         let scan_mode = match &config.scan_mode {
             crate::scanning::ScanMode::Range { start, end } => {
                 format!("Range: {}-{}", start, end)
             }
             crate::scanning::ScanMode::CommonPorts => "CommonPorts".to_string(),
+            crate::scanning::ScanMode::CommonSet(set) => format!("CommonSet: {}", set),
             crate::scanning::ScanMode::CustomList(ports) => {
                 format!("Custom: {} ports", ports.len())
             }
+            crate::scanning::ScanMode::Sample { range: (start, end), count } => {
+                format!("Sample: {} of {}-{}", count, start, end)
+            }
         };
 
         Self {
+            schema_version: REPORT_SCHEMA_VERSION.to_string(),
+            tool_version: TOOL_VERSION.to_string(),
             scan_info: ScanInfo {
                 target_ip: config.target_ip.to_string(),
+                hostname: None,
                 scan_mode,
                 timeout_ms: config.timeout.as_millis() as u64,
                 parallel_enabled: config.parallel,
@@ -90,27 +312,54 @@ impl ScanReport {
                 version_detection: config.detect_versions,
                 os_detection: config.detect_os,
                 stealth_enabled: config.is_stealth_enabled(),
+                scanner_version: TOOL_VERSION.to_string(),
+                command_line: Vec::new(),
+                started_at: Some(rfc3339(started)),
+                finished_at: Some(rfc3339(finished)),
             },
             results: results.results,
-            statistics: ScanStatistics {
-                total_ports: total,
-                open_ports: open,
-                closed_ports: closed,
-                filtered_ports: filtered,
-                error_ports: error,
-                open_percentage,
-                scan_duration_seconds: duration_seconds,
-                ports_per_second,
-            },
+            statistics,
+            os_guess,
+            open_port_estimate,
+            findings,
         }
     }
 
+    /// Record the command line the scan was run with
+    pub fn with_command_line<I, S>(mut self, args: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.scan_info.command_line = args.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Record the target's reverse DNS name
+    pub fn with_hostname(mut self, hostname: Option<String>) -> Self {
+        self.scan_info.hostname = hostname;
+        self
+    }
+
     pub fn default_filename(target_ip: &str, format: OutputFormat) -> String {
-        use std::time::{SystemTime, UNIX_EPOCH};
-        
-        let timestamp = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
+        Self::default_filename_at(target_ip, format, &SystemClock)
+    }
+
+    /// `default_filename` with the timestamp read from `clock`
+    ///
+    /// ```
+    /// use port_scanner::infrastructure::MockClock;
+    /// use port_scanner::prelude::*;
+    /// use std::time::{Duration, UNIX_EPOCH};
+    ///
+    /// let clock = MockClock::new(UNIX_EPOCH + Duration::from_secs(1_700_000_000));
+    /// assert_eq!(ScanReport::default_filename_at("10.0.0.1", OutputFormat::Json, &clock), "scan_10_0_0_1_1700000000.json");
+    /// assert_eq!(ScanReport::default_filename_at("::1", OutputFormat::Csv, &clock), "scan___1_1700000000.csv");
+    /// ```
+    pub fn default_filename_at(target_ip: &str, format: OutputFormat, clock: &dyn Clock) -> String {
+        let timestamp = clock.now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
             .as_secs();
         
         let safe_ip = target_ip.replace(".", "_").replace(":", "_");
@@ -119,32 +368,326 @@ impl ScanReport {
             OutputFormat::Xml => "xml",
             OutputFormat::Csv => "csv",
             OutputFormat::Text => "txt",
+            OutputFormat::Ndjson => "ndjson",
         };
         
         format!("scan_{}_{}.{}", safe_ip, timestamp, extension)
     }
 }
 
+/// `time` as an RFC 3339 UTC timestamp with whole seconds
+fn rfc3339(time: std::time::SystemTime) -> String {
+    let secs = time.duration_since(std::time::UNIX_EPOCH).unwrap_or_default().as_secs();
+    let (days, secs_of_day) = ((secs / 86_400) as i64, secs % 86_400);
+    // Civil date from days since the epoch (Howard Hinnant's algorithm)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year, month, day, secs_of_day / 3_600, secs_of_day % 3_600 / 60, secs_of_day % 60
+    )
+}
+
+/// Write `bytes` to `path` atomically.
+///
+/// Data goes to a temporary file in the same directory which is renamed
+/// over `path` only after a successful write, so a failure never leaves a
+/// truncated report and an existing file stays intact.
+pub fn atomic_write(path: &Path, bytes: &[u8]) -> io::Result<()> {
+    atomic_write_with(path, |file| file.write_all(bytes))
+}
+
+/// `atomic_write` with the contents produced by `write`; any error from
+/// it is returned and leaves `path` untouched.
+///
+/// ```
+/// use port_scanner::presentation::atomic_write_with;
+/// use std::io::{self, Write};
+///
+/// /// Fails like a full disk once `room` bytes are written
+/// struct DiskFull<'a> {
+///     inner: &'a mut dyn Write,
+///     room: usize,
+/// }
+///
+/// impl Write for DiskFull<'_> {
+///     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+///         if self.room == 0 {
+///             return Err(io::ErrorKind::StorageFull.into());
+///         }
+///         let n = self.inner.write(&buf[..buf.len().min(self.room)])?;
+///         self.room -= n;
+///         Ok(n)
+///     }
+///
+///     fn flush(&mut self) -> io::Result<()> {
+///         self.inner.flush()
+///     }
+/// }
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let dir = std::env::temp_dir().join(format!("atomic-write-doc-{}", std::process::id()));
+/// std::fs::create_dir_all(&dir)?;
+/// let path = dir.join("report.json");
+///
+/// // Nothing there yet: a failed write leaves nothing behind
+/// let error = atomic_write_with(&path, |file| DiskFull { inner: file, room: 10 }.write_all(b"{\"results\": []}")).unwrap_err();
+/// assert_eq!(error.kind(), io::ErrorKind::StorageFull);
+/// assert!(!path.exists());
+///
+/// // An earlier report survives a failed overwrite
+/// atomic_write_with(&path, |file| file.write_all(b"previous"))?;
+/// let error = atomic_write_with(&path, |file| DiskFull { inner: file, room: 4 }.write_all(b"replacement")).unwrap_err();
+/// assert_eq!(error.kind(), io::ErrorKind::StorageFull);
+/// assert_eq!(std::fs::read_to_string(&path)?, "previous");
+///
+/// // No temporary files are left over
+/// assert_eq!(std::fs::read_dir(&dir)?.count(), 1);
+/// std::fs::remove_dir_all(&dir)?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn atomic_write_with<F>(path: &Path, write: F) -> io::Result<()>
+where
+    F: FnOnce(&mut dyn Write) -> io::Result<()>,
+{
+    let file_name = path.file_name()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "output path has no file name"))?;
+    let mut tmp_name = std::ffi::OsString::from(".");
+    tmp_name.push(file_name);
+    tmp_name.push(format!(".{}.tmp", std::process::id()));
+    let tmp_path = path.with_file_name(tmp_name);
+
+    let result = File::create(&tmp_path).and_then(|mut file| {
+        write(&mut file)?;
+        file.sync_all()
+    });
+
+    match result.and_then(|_| fs::rename(&tmp_path, path)) {
+        Ok(()) => Ok(()),
+        Err(e) => {
+            let _ = fs::remove_file(&tmp_path);
+            Err(e)
+        }
+    }
+}
+
+/// Contents of `path`, empty if it does not exist yet
+fn existing_contents(path: &Path) -> io::Result<String> {
+    match fs::read_to_string(path) {
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(String::new()),
+        result => result,
+    }
+}
+
+/// `existing` followed by `lines`, with a line break between them if
+/// `existing` lacks its last one
+fn join_lines(mut existing: String, lines: &str) -> String {
+    if !existing.is_empty() && !existing.ends_with('\n') {
+        existing.push('\n');
+    }
+    existing.push_str(lines);
+    existing
+}
+
 /// Trait for output formatters
 pub trait OutputFormatter: Send + Sync {
     fn format(&self, report: &ScanReport) -> FormatterResult<String>;
     fn write_to_file(&self, report: &ScanReport, path: &Path) -> FormatterResult<()>;
     fn extension(&self) -> &'static str;
+
+    /// Add the report to what `path` already holds, so successive scans
+    /// build up one history file; creates the file if it does not exist.
+    /// The file is rewritten atomically, as by `write_to_file`.
+    ///
+    /// Formats that cannot be concatenated return `UnsupportedFormat`.
+    ///
+    /// ```
+    /// use port_scanner::domain::PortStatus;
+    /// use port_scanner::presentation::{CsvFormatter, JsonFormatter, NdjsonFormatter, OutputFormatter};
+    /// use port_scanner::prelude::*;
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// let config = ScanConfigBuilder::new()
+    ///     .target("127.0.0.1".parse()?)
+    ///     .custom_ports(vec![22, 80])
+    ///     .build()?;
+    /// let scan = |ports: [(u16, PortStatus); 2]| ScanReport::new(
+    ///     &config,
+    ///     ScanResults::new(ports.into_iter().map(|(port, status)| PortScanResult::new(port, status)).collect()),
+    ///     1.0,
+    /// );
+    /// let first = scan([(22, PortStatus::Open), (80, PortStatus::Closed)]);
+    /// let second = scan([(22, PortStatus::Open), (80, PortStatus::Open)]);
+    ///
+    /// let dir = std::env::temp_dir().join(format!("append-to-file-{}", std::process::id()));
+    /// std::fs::create_dir_all(&dir)?;
+    ///
+    /// let csv = CsvFormatter::new(false);
+    /// csv.append_to_file(&first, &dir.join("history.csv"))?;
+    /// csv.append_to_file(&second, &dir.join("history.csv"))?;
+    /// let history = std::fs::read_to_string(dir.join("history.csv"))?;
+    /// let mut lines = history.lines();
+    /// assert!(lines.next().unwrap().starts_with("Port,"));
+    /// let rows: Vec<&str> = lines.collect();
+    /// assert_eq!(rows.len(), 4);
+    /// assert!(rows.iter().all(|row| !row.starts_with("Port,")));
+    /// assert!(rows[3].starts_with("80,OPEN,"));
+    ///
+    /// let ndjson = NdjsonFormatter::new(false);
+    /// ndjson.append_to_file(&first, &dir.join("history.ndjson"))?;
+    /// ndjson.append_to_file(&second, &dir.join("history.ndjson"))?;
+    /// let lines: Vec<serde_json::Value> = std::fs::read_to_string(dir.join("history.ndjson"))?
+    ///     .lines()
+    ///     .map(serde_json::from_str)
+    ///     .collect::<Result<_, _>>()?;
+    /// let ports: Vec<_> = lines.iter().map(|line| (line["port"].as_u64().unwrap(), line["status"].as_str().unwrap())).collect();
+    /// assert_eq!(ports, [(22, "Open"), (80, "Closed"), (22, "Open"), (80, "Open")]);
+    ///
+    /// // JSON reports are collected in an array
+    /// let json = JsonFormatter::new(false);
+    /// json.append_to_file(&first, &dir.join("history.json"))?;
+    /// json.append_to_file(&second, &dir.join("history.json"))?;
+    /// let reports: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(dir.join("history.json"))?)?;
+    /// assert_eq!(reports.as_array().unwrap().len(), 2);
+    /// assert_eq!(reports[1]["results"][1]["status"], "Open");
+    /// # std::fs::remove_dir_all(&dir)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn append_to_file(&self, _report: &ScanReport, _path: &Path) -> FormatterResult<()> {
+        Err(FormatterError::UnsupportedFormat)
+    }
+
+    /// Write the formatted report to any sink (stdout, socket, buffer).
+    ///
+    /// `--stdout json` writes the report this way, and nothing else goes to
+    /// stdout, so the output parses as one JSON document:
+    ///
+    /// ```
+    /// use port_scanner::prelude::*;
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// let config = ScanConfigBuilder::new().target("10.0.0.5".parse()?).custom_ports(vec![22, 80]).build()?;
+    /// let results = ScanResults::new(vec![
+    ///     PortScanResult::new(22, PortStatus::Open),
+    ///     PortScanResult::new(80, PortStatus::Closed),
+    /// ]);
+    /// let report = ScanReport::new(&config, results, 1.0);
+    ///
+    /// let mut stdout = Vec::new();
+    /// OutputFormatterFactory::create(OutputFormat::Json, false).write_to(&report, &mut stdout)?;
+    /// let json: serde_json::Value = serde_json::from_slice(&stdout)?;
+    /// assert_eq!(json["scan_info"]["target_ip"], "10.0.0.5");
+    /// assert_eq!(json["results"][0]["port"], 22);
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn write_to(&self, report: &ScanReport, writer: &mut dyn Write) -> FormatterResult<()> {
+        writer.write_all(self.format(report)?.as_bytes())?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Whether results are written one by one as they complete, through
+    /// `write_result`, instead of as a whole report at the end
+    fn streams(&self) -> bool {
+        false
+    }
+
+    /// Write one completed result; only called when `streams` is true
+    fn write_result(&self, _result: &PortScanResult, _writer: &mut dyn Write) -> FormatterResult<()> {
+        Ok(())
+    }
 }
 
 /// JSON formatter
 pub struct JsonFormatter {
     pub open_only: bool,
+    /// Keys kept in each result object; `None` keeps the full result
+    pub fields: Option<Vec<ReportField>>,
+    /// Print each report on a single line
+    pub compact: bool,
 }
 
 impl JsonFormatter {
     pub fn new(open_only: bool) -> Self {
-        Self { open_only }
+        Self { open_only, fields: None, compact: false }
+    }
+
+    pub fn with_fields(mut self, fields: Vec<ReportField>) -> Self {
+        self.fields = Some(fields);
+        self
+    }
+
+    /// Print each report on a single line, so reports written one after
+    /// another to the same stream read as JSON Lines
+    ///
+    /// ```
+    /// use port_scanner::presentation::{JsonFormatter, OutputFormatter};
+    /// use port_scanner::prelude::*;
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// let report = |target: &str| -> anyhow::Result<ScanReport> {
+    ///     let config = ScanConfigBuilder::new()
+    ///         .target(target.parse()?)
+    ///         .custom_ports(vec![22])
+    ///         .build()?;
+    ///     Ok(ScanReport::new(&config, ScanResults::new(vec![PortScanResult::new(22, PortStatus::Open)]), 1.0))
+    /// };
+    ///
+    /// let json = JsonFormatter::new(false).compact(true);
+    /// let mut stdout = Vec::new();
+    /// for target in ["10.0.0.1", "10.0.0.2"] {
+    ///     json.write_to(&report(target)?, &mut stdout)?;
+    /// }
+    ///
+    /// let lines: Vec<serde_json::Value> = String::from_utf8(stdout)?
+    ///     .lines()
+    ///     .map(serde_json::from_str)
+    ///     .collect::<Result<_, _>>()?;
+    /// assert_eq!(lines.len(), 2);
+    /// assert_eq!(lines[1]["scan_info"]["target_ip"], "10.0.0.2");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn compact(mut self, compact: bool) -> Self {
+        self.compact = compact;
+        self
+    }
+
+    fn to_json<T: Serialize>(&self, value: &T) -> serde_json::Result<String> {
+        if self.compact {
+            serde_json::to_string(value)
+        } else {
+            serde_json::to_string_pretty(value)
+        }
     }
 }
 
 impl OutputFormatter for JsonFormatter {
     fn format(&self, report: &ScanReport) -> FormatterResult<String> {
+        if let Some(fields) = &self.fields {
+            let pruned = PrunedReport {
+                schema_version: &report.schema_version,
+                tool_version: &report.tool_version,
+                scan_info: &report.scan_info,
+                results: report.results.iter()
+                    .filter(|r| !self.open_only || matches!(r.status, crate::domain::PortStatus::Open))
+                    .map(|result| SelectedFields { fields, result })
+                    .collect(),
+                statistics: &report.statistics,
+            };
+            return Ok(self.to_json(&pruned)?);
+        }
+
         if self.open_only {
             // Create filtered report with only open ports
             let filtered_results: Vec<_> = report.results.iter()
@@ -153,21 +696,56 @@ impl OutputFormatter for JsonFormatter {
                 .collect();
             
             let filtered_report = ScanReport {
+                schema_version: report.schema_version.clone(),
+                tool_version: report.tool_version.clone(),
                 scan_info: report.scan_info.clone(),
                 results: filtered_results,
                 statistics: report.statistics.clone(),
+                os_guess: report.os_guess.clone(),
+                open_port_estimate: report.open_port_estimate,
+                findings: report.findings.clone(),
             };
             
-            Ok(serde_json::to_string_pretty(&filtered_report)?)
+            Ok(self.to_json(&filtered_report)?)
         } else {
-            Ok(serde_json::to_string_pretty(report)?)
+            Ok(self.to_json(report)?)
         }
     }
 
+    /// The report followed by a newline
+    fn write_to(&self, report: &ScanReport, writer: &mut dyn Write) -> FormatterResult<()> {
+        writer.write_all(self.format(report)?.as_bytes())?;
+        writer.write_all(b"\n")?;
+        writer.flush()?;
+        Ok(())
+    }
+
     fn write_to_file(&self, report: &ScanReport, path: &Path) -> FormatterResult<()> {
         let json = self.format(report)?;
-        let mut file = File::create(path)?;
-        file.write_all(json.as_bytes())?;
+        atomic_write(path, json.as_bytes())?;
+        Ok(())
+    }
+
+    /// The file becomes an array of reports; a single report already in it
+    /// turns into the array's first element
+    fn append_to_file(&self, report: &ScanReport, path: &Path) -> FormatterResult<()> {
+        let existing = existing_contents(path)?;
+        let report: serde_json::Value = serde_json::from_str(&self.format(report)?)?;
+        let reports = match existing.trim() {
+            "" => vec![report],
+            text => match serde_json::from_str(text)? {
+                serde_json::Value::Array(mut reports) => {
+                    reports.push(report);
+                    reports
+                }
+                previous @ serde_json::Value::Object(_) => vec![previous, report],
+                _ => return Err(FormatterError::InvalidReport {
+                    line: 1,
+                    reason: "expected a report or an array of reports".to_string(),
+                }),
+            },
+        };
+        atomic_write(path, serde_json::to_string_pretty(&reports)?.as_bytes())?;
         Ok(())
     }
 
@@ -176,6 +754,105 @@ impl OutputFormatter for JsonFormatter {
     }
 }
 
+/// Report with each result reduced to the selected fields
+#[derive(Serialize)]
+struct PrunedReport<'a> {
+    schema_version: &'a str,
+    tool_version: &'a str,
+    scan_info: &'a ScanInfo,
+    results: Vec<SelectedFields<'a>>,
+    statistics: &'a ScanStatistics,
+}
+
+/// One result serialized as an object with only `fields`, in that order
+struct SelectedFields<'a> {
+    fields: &'a [ReportField],
+    result: &'a PortScanResult,
+}
+
+impl Serialize for SelectedFields<'_> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeMap;
+        let mut map = serializer.serialize_map(Some(self.fields.len()))?;
+        for field in self.fields {
+            map.serialize_entry(field.name(), &field.json_value(self.result))?;
+        }
+        map.end()
+    }
+}
+
+/// Newline-delimited JSON formatter: one result object per line.
+///
+/// Streams results as they complete, so another program can follow a
+/// scan while it runs; the statistics are left to the other formats.
+pub struct NdjsonFormatter {
+    pub open_only: bool,
+    /// Keys kept in each result object; `None` keeps the full result
+    pub fields: Option<Vec<ReportField>>,
+}
+
+impl NdjsonFormatter {
+    pub fn new(open_only: bool) -> Self {
+        Self { open_only, fields: None }
+    }
+
+    pub fn with_fields(mut self, fields: Vec<ReportField>) -> Self {
+        self.fields = Some(fields);
+        self
+    }
+
+    fn line(&self, result: &PortScanResult) -> FormatterResult<Option<String>> {
+        if self.open_only && !result.status.is_open() {
+            return Ok(None);
+        }
+        let line = match &self.fields {
+            Some(fields) => serde_json::to_string(&SelectedFields { fields, result })?,
+            None => serde_json::to_string(result)?,
+        };
+        Ok(Some(line))
+    }
+}
+
+impl OutputFormatter for NdjsonFormatter {
+    fn format(&self, report: &ScanReport) -> FormatterResult<String> {
+        let mut output = String::new();
+        for result in &report.results {
+            if let Some(line) = self.line(result)? {
+                output.push_str(&line);
+                output.push('\n');
+            }
+        }
+        Ok(output)
+    }
+
+    fn write_to_file(&self, report: &ScanReport, path: &Path) -> FormatterResult<()> {
+        atomic_write(path, self.format(report)?.as_bytes())?;
+        Ok(())
+    }
+
+    fn append_to_file(&self, report: &ScanReport, path: &Path) -> FormatterResult<()> {
+        let joined = join_lines(existing_contents(path)?, &self.format(report)?);
+        atomic_write(path, joined.as_bytes())?;
+        Ok(())
+    }
+
+    fn extension(&self) -> &'static str {
+        "ndjson"
+    }
+
+    fn streams(&self) -> bool {
+        true
+    }
+
+    fn write_result(&self, result: &PortScanResult, writer: &mut dyn Write) -> FormatterResult<()> {
+        if let Some(line) = self.line(result)? {
+            writeln!(writer, "{}", line)?;
+            writer.flush()?;
+        }
+        Ok(())
+    }
+}
+
 /// Text formatter
 pub struct TextFormatter {
     pub open_only: bool,
@@ -197,6 +874,9 @@ impl OutputFormatter for TextFormatter {
         
         output.push_str("=== SCAN CONFIGURATION ===\n");
         output.push_str(&format!("Target IP:          {}\n", report.scan_info.target_ip));
+        if let Some(hostname) = &report.scan_info.hostname {
+            output.push_str(&format!("Hostname:           {}\n", hostname));
+        }
         output.push_str(&format!("Scan Mode:          {}\n", report.scan_info.scan_mode));
         output.push_str(&format!("Timeout:            {} ms\n", report.scan_info.timeout_ms));
         output.push_str(&format!("Parallel Scan:      {}\n", if report.scan_info.parallel_enabled { "Yes" } else { "No" }));
@@ -206,16 +886,77 @@ impl OutputFormatter for TextFormatter {
         output.push_str(&format!("Version Detection:  {}\n", if report.scan_info.version_detection { "Enabled" } else { "Disabled" }));
         output.push_str(&format!("OS Detection:       {}\n", if report.scan_info.os_detection { "Enabled" } else { "Disabled" }));
         output.push_str(&format!("Stealth Mode:       {}\n", if report.scan_info.stealth_enabled { "Enabled" } else { "Disabled" }));
+        if let (Some(started), Some(finished)) = (&report.scan_info.started_at, &report.scan_info.finished_at) {
+            output.push_str(&format!("Started:            {}\n", started));
+            output.push_str(&format!("Finished:           {}\n", finished));
+        }
+        if !report.scan_info.scanner_version.is_empty() {
+            output.push_str(&format!("Scanner Version:    {}\n", report.scan_info.scanner_version));
+        }
+        if !report.scan_info.command_line.is_empty() {
+            output.push_str(&format!("Command Line:       {}\n", report.scan_info.command_line.join(" ")));
+        }
         
         output.push_str("\n=== SCAN STATISTICS ===\n");
         output.push_str(&format!("Total Ports Scanned: {}\n", report.statistics.total_ports));
         output.push_str(&format!("Open Ports:          {}\n", report.statistics.open_ports));
+        if let Some(by_protocol) = &report.statistics.open_ports_by_protocol {
+            for (protocol, open) in by_protocol {
+                output.push_str(&format!("  {}:                {}\n", protocol, open));
+            }
+        }
         output.push_str(&format!("Closed Ports:        {}\n", report.statistics.closed_ports));
         output.push_str(&format!("Filtered Ports:      {}\n", report.statistics.filtered_ports));
+        output.push_str(&format!("Reset Ports:         {}\n", report.statistics.reset_ports));
+        output.push_str(&format!("Open|Filtered Ports: {}\n", report.statistics.open_filtered_ports));
         output.push_str(&format!("Error Ports:         {}\n", report.statistics.error_ports));
         output.push_str(&format!("Open Percentage:     {:.1}%\n", report.statistics.open_percentage));
         output.push_str(&format!("Scan Duration:       {:.2} seconds\n", report.statistics.scan_duration_seconds));
         output.push_str(&format!("Scan Speed:          {:.2} ports/sec\n", report.statistics.ports_per_second));
+        output.push_str(&format!("Connection Attempts: {}\n", report.statistics.connection_attempts));
+        if let Some(latency) = &report.statistics.connect_latency {
+            output.push_str(&format!("Connect Latency:     {}\n", latency));
+        }
+        if let Some(state) = report.statistics.host_state {
+            output.push_str(&format!("Host State:          {}\n", state));
+        }
+        if report.statistics.honeypot_suspected {
+            output.push_str("WARNING: Host exceeded the open-port threshold (possible honeypot); scan stopped early\n");
+        }
+        if let Some(estimate) = &report.open_port_estimate {
+            output.push_str(&format!("Sample Estimate:     {}\n", estimate));
+        }
+
+        if !report.statistics.services_by_category.is_empty() {
+            output.push_str("\n=== SERVICES BY CATEGORY ===\n");
+            output.push_str(&category_histogram(&report.statistics.services_by_category));
+        }
+
+        if !report.findings.is_empty() {
+            output.push_str("\n=== FINDINGS ===\n");
+            for finding in &report.findings {
+                let host = finding.host.map(|host| format!("{} ", host)).unwrap_or_default();
+                output.push_str(&format!("[{}] {}{} {}: {}\n", finding.severity, host, finding.label(), finding.title, finding.detail));
+            }
+        }
+
+        if let Some(os_guess) = &report.os_guess {
+            output.push_str("\n=== OS GUESS ===\n");
+            for candidate in &os_guess.candidates {
+                output.push_str(&format!("{:<20} {:.0}%\n", candidate.name, candidate.confidence * 100.0));
+                for evidence in &candidate.evidence {
+                    output.push_str(&format!("  - {}\n", evidence));
+                }
+            }
+        }
+
+        // Reports covering several hosts list each host on its own
+        let hosts = ScanResults::new(report.results.clone()).by_host();
+        if hosts.len() > 1 {
+            output.push_str(&host_sections(&hosts));
+            output.push_str(REPORT_FOOTER);
+            return Ok(output);
+        }
 
         output.push_str("\n=== DETAILED PORT RESULTS ===\n");
         
@@ -223,12 +964,16 @@ impl OutputFormatter for TextFormatter {
         let mut open_ports = Vec::new();
         let mut closed_ports = Vec::new();
         let mut filtered_ports = Vec::new();
+        let mut reset_ports = Vec::new();
+        let mut open_filtered_ports = Vec::new();
         
         for result in &report.results {
             match &result.status {
                 crate::domain::PortStatus::Open => open_ports.push(result),
                 crate::domain::PortStatus::Closed => closed_ports.push(result),
                 crate::domain::PortStatus::Filtered => filtered_ports.push(result),
+                crate::domain::PortStatus::Reset => reset_ports.push(result),
+                crate::domain::PortStatus::OpenFiltered => open_filtered_ports.push(result),
                 _ => {}
             }
         }
@@ -236,18 +981,35 @@ impl OutputFormatter for TextFormatter {
         if !open_ports.is_empty() {
             output.push_str("\n--- OPEN PORTS (VERBOSE) ---\n");
             for result in open_ports {
-                output.push_str(&format!("\nPort {}:\n", result.port));
+                output.push_str(&format!("\nPort {}:\n", result.label()));
                 output.push_str("  Status: OPEN\n");
                 
                 if let Some(version) = &result.service_version {
-                    output.push_str(&format!("  Service Name:    {}\n", version.service_name));
+                    output.push_str(&format!("  Service Name:    {}{}\n", version.service_name, port_based_note(version)));
+                    if let Some(product) = &version.product {
+                        output.push_str(&format!("  Product:         {}\n", product));
+                    }
                     if let Some(ver) = &version.version {
                         output.push_str(&format!("  Version:         {}\n", ver));
                     }
+                    if let Some(os) = &version.os {
+                        output.push_str(&format!("  Host OS:         {}\n", os));
+                    }
                     if let Some(banner) = &version.banner {
-                        output.push_str(&format!("  Banner:          {}\n", banner));
+                        let note = if version.banner_truncated { " [truncated]" } else { "" };
+                        output.push_str(&format!("  Banner:          {}{}\n", banner, note));
+                    }
+                    if version.redacted {
+                        output.push_str("  Note:            Sensitive content redacted\n");
                     }
                     output.push_str(&format!("  Protocol:        {}\n", version.protocol));
+                    output.push_str(&format!("  Confidence:      {:.0}%\n", version.confidence * 100.0));
+                    if let Some(extra) = &version.extra_info {
+                        output.push_str(&format!("  Extra Info:      {}\n", extra));
+                    }
+                    if version.unauthenticated {
+                        output.push_str("  WARNING:         Accessible without authentication\n");
+                    }
                 } else {
                     output.push_str("  Service:         Unknown (no banner detected)\n");
                 }
@@ -285,9 +1047,29 @@ impl OutputFormatter for TextFormatter {
             output.push_str("Ports: ");
             for (i, result) in filtered_ports.iter().enumerate() {
                 if i > 0 { output.push_str(", "); }
-                output.push_str(&result.port.to_string());
+                output.push_str(&result.label());
+            }
+            output.push('\n');
+        }
+        
+        if !open_filtered_ports.is_empty() {
+            output.push_str(&format!("\n--- OPEN|FILTERED PORTS ({}) ---\n", open_filtered_ports.len()));
+            output.push_str("Ports: ");
+            for (i, result) in open_filtered_ports.iter().enumerate() {
+                if i > 0 { output.push_str(", "); }
+                output.push_str(&result.label());
+            }
+            output.push('\n');
+        }
+        
+        if !reset_ports.is_empty() {
+            output.push_str(&format!("\n--- RESET PORTS ({}) ---\n", reset_ports.len()));
+            output.push_str("Ports: ");
+            for (i, result) in reset_ports.iter().enumerate() {
+                if i > 0 { output.push_str(", "); }
+                output.push_str(&result.label());
             }
-            output.push_str("\n");
+            output.push('\n');
         }
         
         if !closed_ports.is_empty() {
@@ -299,15 +1081,21 @@ impl OutputFormatter for TextFormatter {
             }
         }
 
-        output.push_str("\n╚═══════════════════════════════════════════════════════════════════╝\n");
-        output.push_str("                    End of Report\n");
-        output.push_str("╚═══════════════════════════════════════════════════════════════════╝\n");
+        output.push_str(REPORT_FOOTER);
 
         Ok(output)
-    }    fn write_to_file(&self, report: &ScanReport, path: &Path) -> FormatterResult<()> {
+    }
+
+    fn write_to_file(&self, report: &ScanReport, path: &Path) -> FormatterResult<()> {
         let text = self.format(report)?;
-        let mut file = File::create(path)?;
-        file.write_all(text.as_bytes())?;
+        atomic_write(path, text.as_bytes())?;
+        Ok(())
+    }
+
+    /// Reports follow one another, each with its own header and footer
+    fn append_to_file(&self, report: &ScanReport, path: &Path) -> FormatterResult<()> {
+        let joined = join_lines(existing_contents(path)?, &self.format(report)?);
+        atomic_write(path, joined.as_bytes())?;
         Ok(())
     }
 
@@ -319,86 +1107,66 @@ impl OutputFormatter for TextFormatter {
 /// CSV formatter
 pub struct CsvFormatter {
     pub open_only: bool,
+    /// Columns to emit, in order
+    pub fields: Vec<ReportField>,
 }
 
 impl CsvFormatter {
     pub fn new(open_only: bool) -> Self {
-        Self { open_only }
+        Self { open_only, fields: ReportField::ALL.to_vec() }
+    }
+
+    pub fn with_fields(mut self, fields: Vec<ReportField>) -> Self {
+        self.fields = fields;
+        self
     }
 }
 
 impl OutputFormatter for CsvFormatter {
     fn format(&self, report: &ScanReport) -> FormatterResult<String> {
         let mut output = String::new();
-        
-        // Header with all columns
-        output.push_str("Port,Status,Service,Version,Protocol,Banner,OS_Name,OS_Version,OS_Build,SMB_Version,Computer_Name,Domain\n");
+
+        let header: Vec<_> = self.fields.iter().map(ReportField::csv_header).collect();
+        output.push_str(&header.join(","));
+        output.push('\n');
 
         for result in &report.results {
             // Skip non-open ports if open_only is enabled
             if self.open_only && !matches!(result.status, crate::domain::PortStatus::Open) {
                 continue;
             }
-            let status = match result.status {
-                crate::domain::PortStatus::Open => "OPEN",
-                crate::domain::PortStatus::Closed => "CLOSED",
-                crate::domain::PortStatus::Filtered => "FILTERED",
-                crate::domain::PortStatus::Error(_) => "ERROR",
-            };
-
-            let service = result.service_version.as_ref()
-                .map(|v| v.service_name.as_str())
-                .unwrap_or("");
-
-            let version = result.service_version.as_ref()
-                .and_then(|v| v.version.as_deref())
-                .unwrap_or("");
-            
-            let protocol = result.service_version.as_ref()
-                .map(|v| v.protocol.as_str())
-                .unwrap_or("");
-            
-            let banner = result.service_version.as_ref()
-                .and_then(|v| v.banner.as_deref())
-                .map(|b| b.replace(",", ";").replace("\n", " ").replace("\r", ""))
-                .unwrap_or_default();
-            
-            let os_name = result.os_info.as_ref()
-                .and_then(|os| os.os_name.as_deref())
-                .unwrap_or("");
-            
-            let os_version = result.os_info.as_ref()
-                .and_then(|os| os.os_version.as_deref())
-                .unwrap_or("");
-            
-            let os_build = result.os_info.as_ref()
-                .and_then(|os| os.os_build.as_deref())
-                .unwrap_or("");
-            
-            let smb_version = result.os_info.as_ref()
-                .and_then(|os| os.smb_version.as_deref())
-                .unwrap_or("");
-            
-            let computer_name = result.os_info.as_ref()
-                .and_then(|os| os.computer_name.as_deref())
-                .unwrap_or("");
-            
-            let domain = result.os_info.as_ref()
-                .and_then(|os| os.domain.as_deref())
-                .unwrap_or("");
-
-            output.push_str(&format!(
-                "{},{},{},{},{},\"{}\",{},{},{},{},{},{}\n",
-                result.port, status, service, version, protocol, banner, 
-                os_name, os_version, os_build, smb_version, computer_name, domain
-            ));
+            let row: Vec<_> = self.fields.iter().map(|field| field.csv_value(result)).collect();
+            output.push_str(&row.join(","));
+            output.push('\n');
         }
 
         Ok(output)
-    }    fn write_to_file(&self, report: &ScanReport, path: &Path) -> FormatterResult<()> {
+    }
+
+    fn write_to_file(&self, report: &ScanReport, path: &Path) -> FormatterResult<()> {
         let csv = self.format(report)?;
-        let mut file = File::create(path)?;
-        file.write_all(csv.as_bytes())?;
+        atomic_write(path, csv.as_bytes())?;
+        Ok(())
+    }
+
+    /// Only the rows are added to a non-empty file, whose header must
+    /// name the same columns
+    fn append_to_file(&self, report: &ScanReport, path: &Path) -> FormatterResult<()> {
+        let existing = existing_contents(path)?;
+        let csv = self.format(report)?;
+        let Some(existing_header) = existing.lines().next() else {
+            atomic_write(path, csv.as_bytes())?;
+            return Ok(());
+        };
+        let (header, rows) = csv.split_once('\n').unwrap_or((&csv, ""));
+        if existing_header.trim_end() != header {
+            return Err(FormatterError::InvalidReport {
+                line: 1,
+                reason: format!("existing columns '{}' differ from '{}'", existing_header.trim_end(), header),
+            });
+        }
+        let joined = join_lines(existing, rows);
+        atomic_write(path, joined.as_bytes())?;
         Ok(())
     }
 
@@ -407,16 +1175,250 @@ impl OutputFormatter for CsvFormatter {
     }
 }
 
+/// Builds a formatter from the `open_only` setting
+pub type FormatterConstructor = Arc<dyn Fn(bool) -> Box<dyn OutputFormatter> + Send + Sync>;
+
+/// Formatters by name, for `OutputFormatterFactory::create_by_name`;
+/// starts out with the built-in formats
+fn formatter_registry() -> &'static RwLock<HashMap<String, FormatterConstructor>> {
+    static REGISTRY: OnceLock<RwLock<HashMap<String, FormatterConstructor>>> = OnceLock::new();
+    REGISTRY.get_or_init(|| {
+        let builtins = OutputFormatterFactory::supported_formats().into_iter().map(|format| {
+            let constructor: FormatterConstructor = Arc::new(move |open_only| OutputFormatterFactory::create(format, open_only));
+            (format.name().to_string(), constructor)
+        });
+        RwLock::new(builtins.collect())
+    })
+}
+
 /// Factory for creating output formatters
 pub struct OutputFormatterFactory;
 
 impl OutputFormatterFactory {
+    /// Make `create_by_name(name, ..)` build formatters with `constructor`,
+    /// replacing any formatter registered under `name` before (built-ins
+    /// included). Registrations are process-wide.
+    ///
+    /// ```
+    /// use port_scanner::errors::FormatterResult;
+    /// use port_scanner::presentation::{OutputFormatter, OutputFormatterFactory};
+    /// use port_scanner::domain::PortStatus;
+    /// use port_scanner::prelude::*;
+    /// use std::path::Path;
+    ///
+    /// /// One `port=status` pair per line
+    /// struct PairsFormatter {
+    ///     open_only: bool,
+    /// }
+    ///
+    /// impl OutputFormatter for PairsFormatter {
+    ///     fn format(&self, report: &ScanReport) -> FormatterResult<String> {
+    ///         Ok(report.results.iter()
+    ///             .filter(|r| !self.open_only || r.status.is_open())
+    ///             .map(|r| format!("{}={}\n", r.port, r.status))
+    ///             .collect())
+    ///     }
+    ///     fn write_to_file(&self, report: &ScanReport, path: &Path) -> FormatterResult<()> {
+    ///         Ok(std::fs::write(path, self.format(report)?)?)
+    ///     }
+    ///     fn extension(&self) -> &'static str {
+    ///         "pairs"
+    ///     }
+    /// }
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// OutputFormatterFactory::register("pairs", |open_only| Box::new(PairsFormatter { open_only }));
+    ///
+    /// let config = ScanConfigBuilder::new()
+    ///     .target("127.0.0.1".parse()?)
+    ///     .custom_ports(vec![22, 80])
+    ///     .build()?;
+    /// let results = ScanResults::new(vec![
+    ///     PortScanResult::new(22, PortStatus::Open),
+    ///     PortScanResult::new(80, PortStatus::Closed),
+    /// ]);
+    /// let report = ScanReport::new(&config, results, 1.0);
+    ///
+    /// let formatter = OutputFormatterFactory::create_by_name("pairs", true).unwrap();
+    /// assert_eq!(formatter.format(&report)?, "22=OPEN\n");
+    /// assert_eq!(OutputFormatterFactory::create_by_name("pairs", false).unwrap().format(&report)?, "22=OPEN\n80=CLOSED\n");
+    ///
+    /// // Built-ins are registered too
+    /// assert_eq!(OutputFormatterFactory::create_by_name("csv", false).unwrap().extension(), "csv");
+    /// assert!(OutputFormatterFactory::create_by_name("yaml", false).is_none());
+    /// assert!(OutputFormatterFactory::registered_names().contains(&"pairs".to_string()));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn register<F>(name: impl Into<String>, constructor: F)
+    where
+        F: Fn(bool) -> Box<dyn OutputFormatter> + Send + Sync + 'static,
+    {
+        formatter_registry()
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(name.into(), Arc::new(constructor));
+    }
+
+    /// Formatter registered under `name` (built-ins are `text`, `json`,
+    /// `csv` and `ndjson`), or `None` if there is none
+    pub fn create_by_name(name: &str, open_only: bool) -> Option<Box<dyn OutputFormatter>> {
+        let constructor = formatter_registry()
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(name)
+            .cloned()?;
+        Some(constructor(open_only))
+    }
+
+    /// Names `create_by_name` accepts, sorted
+    pub fn registered_names() -> Vec<String> {
+        let mut names: Vec<String> = formatter_registry()
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .keys()
+            .cloned()
+            .collect();
+        names.sort();
+        names
+    }
+
+    /// Formats with a formatter of their own (XML still falls back to text)
+    pub fn supported_formats() -> Vec<OutputFormat> {
+        vec![OutputFormat::Text, OutputFormat::Json, OutputFormat::Csv, OutputFormat::Ndjson]
+    }
+
     pub fn create(format: OutputFormat, open_only: bool) -> Box<dyn OutputFormatter> {
         match format {
             OutputFormat::Json => Box::new(JsonFormatter::new(open_only)),
             OutputFormat::Text => Box::new(TextFormatter::new(open_only)),
             OutputFormat::Csv => Box::new(CsvFormatter::new(open_only)),
             OutputFormat::Xml => Box::new(TextFormatter::new(open_only)), // XML not implemented yet
+            OutputFormat::Ndjson => Box::new(NdjsonFormatter::new(open_only)),
+        }
+    }
+
+    /// Like `create`, restricting CSV/JSON/NDJSON results to `fields` (ignored for text)
+    pub fn create_with_fields(format: OutputFormat, open_only: bool, fields: Option<&[ReportField]>) -> Box<dyn OutputFormatter> {
+        let Some(fields) = fields else {
+            return Self::create(format, open_only);
+        };
+        match format {
+            OutputFormat::Json => Box::new(JsonFormatter::new(open_only).with_fields(fields.to_vec())),
+            OutputFormat::Csv => Box::new(CsvFormatter::new(open_only).with_fields(fields.to_vec())),
+            OutputFormat::Ndjson => Box::new(NdjsonFormatter::new(open_only).with_fields(fields.to_vec())),
+            _ => Self::create(format, open_only),
+        }
+    }
+}
+
+const REPORT_FOOTER: &str = "\n╚═══════════════════════════════════════════════════════════════════╝\n                    End of Report\n╚═══════════════════════════════════════════════════════════════════╝\n";
+
+/// A section per host with its counts and open-port table, then totals
+/// across all hosts
+fn host_sections(hosts: &BTreeMap<Option<IpAddr>, ScanResults>) -> String {
+    let mut output = String::new();
+    let mut hosts_up = 0;
+    let mut open_ports = 0;
+    let mut hosts_per_port: BTreeMap<(Port, Protocol), usize> = BTreeMap::new();
+
+    for (host, results) in hosts {
+        let name = host.map_or_else(|| "(unattributed)".to_string(), |ip| ip.to_string());
+        output.push_str(&format!("\n=== HOST {} ===\n", name));
+        output.push_str(&format!(
+            "Ports: {} scanned, {} open, {} closed, {} filtered\n",
+            results.total_ports, results.open_ports, results.closed_ports, results.filtered_ports,
+        ));
+        // Any answer, even a refusal, shows the host is up
+        if results.results.iter().any(|r| r.is_open() || r.status.is_closed() || r.status.is_reset()) {
+            hosts_up += 1;
+        }
+
+        let open: Vec<_> = results.results.iter().filter(|r| r.is_open()).collect();
+        if open.is_empty() {
+            output.push_str("No open ports\n");
+            continue;
         }
+        output.push_str(&format!("{:<12} {:<16} {}\n", "PORT", "SERVICE", "VERSION"));
+        for result in open {
+            open_ports += 1;
+            *hosts_per_port.entry((result.port, result.protocol)).or_insert(0) += 1;
+            let service = result.service_version.as_ref();
+            let name = service.map_or("unknown", |v| v.service_name.as_str());
+            let version: Vec<&str> = service
+                .map(|v| [v.product.as_deref(), v.version.as_deref()].into_iter().flatten().collect())
+                .unwrap_or_default();
+            let row = format!("{:<12} {:<16} {}", result.label(), name, version.join(" "));
+            output.push_str(row.trim_end());
+            output.push('\n');
+        }
+    }
+
+    // Most hosts wins; the lowest port breaks ties
+    let most_common = hosts_per_port.iter()
+        .max_by(|(a_port, a_hosts), (b_port, b_hosts)| a_hosts.cmp(b_hosts).then(b_port.cmp(a_port)));
+    output.push_str("\n=== MULTI-HOST SUMMARY ===\n");
+    output.push_str(&format!("Hosts Scanned:         {}\n", hosts.len()));
+    output.push_str(&format!("Hosts Up:              {}\n", hosts_up));
+    output.push_str(&format!("Total Open Ports:      {}\n", open_ports));
+    match most_common {
+        Some(((port, protocol), count)) => output.push_str(&format!(
+            "Most Common Open Port: {}/{} ({} host{})\n", port, protocol, count, if *count == 1 { "" } else { "s" },
+        )),
+        None => output.push_str("Most Common Open Port: none\n"),
+    }
+    output
+}
+
+/// One line per category with its open-port count and a bar scaled to
+/// the largest count
+pub fn category_histogram(counts: &BTreeMap<ServiceCategory, usize>) -> String {
+    let max = counts.values().copied().max().unwrap_or(0).max(1);
+    let mut output = String::new();
+    for (category, &count) in counts {
+        let bar = (count * HISTOGRAM_BAR_WIDTH).div_ceil(max);
+        output.push_str(&format!("{:<14} {:>5}  {}\n", category, count, "█".repeat(bar)));
+    }
+    output
+}
+
+/// Suffix marking service names guessed from the port number
+///
+/// Open ports get a guess even with detection off:
+///
+/// ```
+/// use port_scanner::infrastructure::testing::{Reply, ScriptedConnector};
+/// use port_scanner::prelude::*;
+/// use port_scanner::presentation::port_based_note;
+/// use port_scanner::scanning::{ScanStrategy, StandardScan};
+/// use std::sync::Arc;
+/// use tokio::net::TcpListener;
+///
+/// # #[tokio::main]
+/// # async fn main() -> anyhow::Result<()> {
+/// let listener = TcpListener::bind("127.0.0.1:0").await?;
+/// let config = ScanConfigBuilder::new()
+///     .target("10.0.0.5".parse()?)
+///     .custom_ports(vec![22])
+///     .detect_versions(false)
+///     .build()?;
+/// // Port 22 connects to a local listener, as if it were open
+/// let redirect = ScriptedConnector::new(Reply::AcceptAt(listener.local_addr()?));
+/// let scan = StandardScan::new().with_connector(Arc::new(redirect));
+/// let result = scan.scan_async(22, config.target_ip, &config).await;
+///
+/// assert_eq!(result.status, PortStatus::Open);
+/// let version = result.service_version.as_ref().unwrap();
+/// assert_eq!(version.service_name, "SSH");
+/// assert!(version.port_based);
+/// assert_eq!(port_based_note(version), " (port-based guess)");
+/// # Ok(())
+/// # }
+/// ```
+pub fn port_based_note(version: &crate::domain::ServiceVersion) -> &'static str {
+    if version.port_based {
+        " (port-based guess)"
+    } else {
+        ""
     }
 }
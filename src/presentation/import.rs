@@ -0,0 +1,243 @@
+//! Loading previously written reports for diffing, re-formatting and resume
+
+use std::path::Path;
+use std::time::{Duration, UNIX_EPOCH};
+
+use crate::application::FindingEngine;
+use crate::constants::{REPORT_SCHEMA_VERSION, TOOL_VERSION};
+use crate::domain::{OSInfo, PortScanResult, PortStatus, Protocol, ScanResults, ServiceVersion};
+use crate::errors::{FormatterError, FormatterResult};
+use crate::presentation::{ReportField, ScanInfo, ScanReport, ScanStatistics};
+
+/// Target recorded for reports whose format does not store one
+pub const UNKNOWN_TARGET: &str = "unknown";
+
+impl ScanReport {
+    /// Load a JSON report written by `JsonFormatter`
+    ///
+    /// ```
+    /// use port_scanner::domain::{OSInfo, ServiceVersion};
+    /// use port_scanner::prelude::*;
+    /// use port_scanner::presentation::{JsonFormatter, OutputFormatter};
+    /// use std::time::{Duration, UNIX_EPOCH};
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// let config = ScanConfigBuilder::new()
+    ///     .target("192.0.2.10".parse()?)
+    ///     .custom_ports(vec![22, 161, 445])
+    ///     .build()?;
+    /// // Timestamps are stored as whole milliseconds
+    /// let at = |ms| UNIX_EPOCH + Duration::from_millis(1_700_000_000_000 + ms);
+    /// let mut results = vec![
+    ///     PortScanResult::new(22, PortStatus::Open)
+    ///         .with_version(ServiceVersion::new("ssh", "tcp").with_banner("SSH-2.0-OpenSSH_9.6\x00"))
+    ///         .with_connect_latency(Duration::from_millis(3)),
+    ///     PortScanResult::new(161, PortStatus::OpenFiltered).with_protocol(Protocol::Udp),
+    ///     PortScanResult::new(445, PortStatus::Error("Connection reset by peer".into()))
+    ///         .with_os_info(OSInfo::new().with_os_name("Windows").with_smb_version("SMB 3.1.1")),
+    /// ];
+    /// for (ms, result) in results.iter_mut().enumerate() {
+    ///     result.scanned_at = at(ms as u64);
+    /// }
+    /// let report = ScanReport::new(&config, ScanResults::new(results.clone()), 1.5);
+    ///
+    /// let path = std::env::temp_dir().join(format!("port-scanner-roundtrip-{}.json", std::process::id()));
+    /// JsonFormatter::new(false).write_to_file(&report, &path)?;
+    /// let loaded = ScanReport::from_json_file(&path);
+    /// std::fs::remove_file(&path)?;
+    /// let loaded = loaded?;
+    ///
+    /// assert_eq!(loaded.results, results);
+    /// assert_eq!(loaded.results[2].status, PortStatus::Error("Connection reset by peer".into()));
+    /// assert_eq!(serde_json::to_value(&loaded)?, serde_json::to_value(&report)?);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn from_json_file(path: &Path) -> FormatterResult<Self> {
+        let data = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&data)?)
+    }
+
+    /// Load a CSV report written by `CsvFormatter`, with any column selection
+    pub fn from_csv_file(path: &Path) -> FormatterResult<Self> {
+        let data = std::fs::read_to_string(path)?;
+        Self::from_csv(&data)
+    }
+
+    /// Load a report by extension: `.csv` as CSV, anything else as JSON
+    pub fn from_file(path: &Path) -> FormatterResult<Self> {
+        let is_csv = path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("csv"));
+        if is_csv {
+            Self::from_csv_file(path)
+        } else {
+            Self::from_json_file(path)
+        }
+    }
+
+    /// Parse CSV report text.
+    ///
+    /// CSV keeps only per-port columns, so the target is `UNKNOWN_TARGET`,
+    /// scan settings are left at their defaults and statistics are recounted
+    /// from the rows. `Port` and `Status` columns are required.
+    pub fn from_csv(data: &str) -> FormatterResult<Self> {
+        let mut lines = data.lines()
+            .enumerate()
+            .map(|(i, line)| (i + 1, line))
+            .filter(|(_, line)| !line.trim().is_empty());
+
+        let (header_line, header) = lines.next().ok_or(FormatterError::InvalidReport {
+            line: 1,
+            reason: "empty file".to_string(),
+        })?;
+        let columns = split_row(header).iter()
+            .map(|name| ReportField::parse(name))
+            .collect::<FormatterResult<Vec<_>>>()?;
+        for required in [ReportField::Port, ReportField::Status] {
+            if !columns.contains(&required) {
+                return Err(invalid(header_line, format!("missing {} column", required.csv_header())));
+            }
+        }
+
+        let mut results = Vec::new();
+        for (line, row) in lines {
+            let cells = split_row(row);
+            if cells.len() != columns.len() {
+                return Err(invalid(line, format!("expected {} columns, found {}", columns.len(), cells.len())));
+            }
+            results.push(parse_result(&columns, &cells).map_err(|reason| invalid(line, reason))?);
+        }
+
+        let results = ScanResults::new(results);
+        Ok(Self {
+            schema_version: REPORT_SCHEMA_VERSION.to_string(),
+            tool_version: TOOL_VERSION.to_string(),
+            scan_info: ScanInfo {
+                target_ip: UNKNOWN_TARGET.to_string(),
+                hostname: None,
+                scan_mode: format!("Custom: {} ports", results.total_ports),
+                timeout_ms: 0,
+                parallel_enabled: false,
+                thread_count: None,
+                version_detection: false,
+                os_detection: false,
+                stealth_enabled: false,
+                scanner_version: String::new(),
+                command_line: Vec::new(),
+                started_at: None,
+                finished_at: None,
+            },
+            statistics: ScanStatistics::new(&results, 0.0),
+            os_guess: None,
+            open_port_estimate: None,
+            findings: FindingEngine::with_defaults().evaluate(&results),
+            results: results.results,
+        })
+    }
+}
+
+/// Results with counts recomputed, keeping the honeypot flag and OS guess
+impl From<ScanReport> for ScanResults {
+    fn from(report: ScanReport) -> Self {
+        let mut results = ScanResults::new(report.results);
+        results.honeypot_suspected = report.statistics.honeypot_suspected;
+        results.os_guess = report.os_guess;
+        results
+    }
+}
+
+fn invalid(line: usize, reason: String) -> FormatterError {
+    FormatterError::InvalidReport { line, reason }
+}
+
+/// Rebuild one result from its row; columns absent from the file stay unset
+fn parse_result(columns: &[ReportField], cells: &[String]) -> Result<PortScanResult, String> {
+    let cell = |field: ReportField| {
+        columns.iter()
+            .position(|c| *c == field)
+            .map(|i| cells[i].trim())
+            .filter(|value| !value.is_empty())
+    };
+
+    let port = cell(ReportField::Port).unwrap_or_default();
+    let port = port.parse().map_err(|_| format!("invalid port '{}'", port))?;
+    let status = cell(ReportField::Status).unwrap_or_default();
+    let status = PortStatus::from_label(status).ok_or_else(|| format!("invalid status '{}'", status))?;
+
+    let protocol = match cell(ReportField::Protocol) {
+        Some(label) => Protocol::from_label(label).ok_or_else(|| format!("invalid protocol '{}'", label))?,
+        None => Protocol::Tcp,
+    };
+
+    let mut result = PortScanResult::new(port, status).with_protocol(protocol);
+    result.scanned_at = match cell(ReportField::ScannedAt) {
+        Some(millis) => {
+            let millis = millis.parse().map_err(|_| format!("invalid timestamp '{}'", millis))?;
+            UNIX_EPOCH + Duration::from_millis(millis)
+        }
+        None => UNIX_EPOCH,
+    };
+    if let Some(code) = cell(ReportField::ErrorCode) {
+        result.error_code = Some(code.parse().map_err(|_| format!("invalid error code '{}'", code))?);
+    }
+    if let Some(millis) = cell(ReportField::ConnectLatency) {
+        let millis: f64 = millis.parse().ok()
+            .filter(|ms: &f64| ms.is_finite() && *ms >= 0.0)
+            .ok_or_else(|| format!("invalid latency '{}'", millis))?;
+        result.connect_latency = Some(Duration::from_secs_f64(millis / 1000.0));
+    }
+
+    if let Some(service) = cell(ReportField::Service) {
+        let mut version = ServiceVersion::new(service, protocol.to_string());
+        if let Some(v) = cell(ReportField::Version) {
+            version = version.with_version(v);
+        }
+        if let Some(confidence) = cell(ReportField::Confidence) {
+            let confidence = confidence.parse().map_err(|_| format!("invalid confidence '{}'", confidence))?;
+            version = version.with_confidence(confidence);
+        }
+        if let Some(banner) = cell(ReportField::Banner) {
+            version = version.with_banner(banner);
+        }
+        if let Some(info) = cell(ReportField::ExtraInfo) {
+            version = version.with_extra_info(info);
+        }
+        result = result.with_version(version);
+    }
+
+    let os_info = OSInfo {
+        os_name: cell(ReportField::OsName).map(str::to_string),
+        os_version: cell(ReportField::OsVersion).map(str::to_string),
+        os_build: cell(ReportField::OsBuild).map(str::to_string),
+        smb_version: cell(ReportField::SmbVersion).map(str::to_string),
+        computer_name: cell(ReportField::ComputerName).map(str::to_string),
+        domain: cell(ReportField::Domain).map(str::to_string),
+        ..OSInfo::new()
+    };
+    let os_fields = [&os_info.os_name, &os_info.os_version, &os_info.os_build,
+        &os_info.smb_version, &os_info.computer_name, &os_info.domain];
+    if os_fields.iter().any(|field| field.is_some()) {
+        result = result.with_os_info(os_info);
+    }
+    Ok(result)
+}
+
+/// Split a CSV line; commas inside double quotes are literal and `""` is a quote
+fn split_row(line: &str) -> Vec<String> {
+    let mut cells = Vec::new();
+    let mut cell = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                cell.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => cells.push(std::mem::take(&mut cell)),
+            _ => cell.push(c),
+        }
+    }
+    cells.push(cell);
+    cells
+}
@@ -1,10 +1,26 @@
-/// Presentation layer module exports
+//! Presentation layer module exports
 
 pub mod observer;
 pub mod formatter;
+pub mod diff;
+pub mod import;
+pub mod sink;
+pub mod fields;
+pub mod redact;
+pub mod capabilities;
+#[cfg(feature = "tui")]
+pub mod tui;
 
-pub use observer::{ScanObserver, ProgressObserver, MetricsCollector};
+pub use observer::{ScanObserver, ProgressObserver, MetricsCollector, WebhookObserver};
 pub use formatter::{
-    OutputFormat, OutputFormatter, OutputFormatterFactory,
-    ScanReport, ScanInfo, ScanStatistics, JsonFormatter, TextFormatter, CsvFormatter
+    OutputFormat, OutputFormatter, OutputFormatterFactory, FormatterConstructor,
+    ScanReport, ScanInfo, ScanStatistics, JsonFormatter, TextFormatter, CsvFormatter, NdjsonFormatter,
+    port_based_note, category_histogram, atomic_write, atomic_write_with,
 };
+pub use diff::{ScanDiff, HostDiff, PortChange, VersionChange};
+pub use sink::{ResultSink, FileSink, ConsoleSink, MultiFormatter, NullSink};
+pub use fields::ReportField;
+pub use redact::Redactor;
+pub use capabilities::Capabilities;
+#[cfg(feature = "tui")]
+pub use tui::{TuiState, OpenPortRow, Dashboard, run_dashboard};
@@ -1,6 +1,16 @@
-/// Observer pattern for scan events
+//! Observer pattern for scan events
 
+use std::io::{self, Write};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+use serde_json::{json, Value};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tracing::{debug, warn};
+
+use crate::constants::{DEFAULT_PROGRESS_LINE_LIMIT, PROGRESS_SUMMARY_INTERVAL, WEBHOOK_TIMEOUT_MS};
 use crate::domain::{PortScanResult, ScanResults};
+use crate::infrastructure::{Clock, SystemClock};
 
 /// Trait for scan observers
 pub trait ScanObserver: Send {
@@ -9,35 +19,148 @@ pub trait ScanObserver: Send {
     fn on_scan_completed(&mut self, results: &ScanResults);
 }
 
-/// Progress bar observer
+/// Shared observer, so the caller can still read it after handing a clone to a scan
+impl<O: ScanObserver> ScanObserver for Arc<Mutex<O>> {
+    fn on_port_scanned(&mut self, result: &PortScanResult) {
+        self.lock().unwrap_or_else(|e| e.into_inner()).on_port_scanned(result);
+    }
+
+    fn on_scan_started(&mut self, total_ports: usize) {
+        self.lock().unwrap_or_else(|e| e.into_inner()).on_scan_started(total_ports);
+    }
+
+    fn on_scan_completed(&mut self, results: &ScanResults) {
+        self.lock().unwrap_or_else(|e| e.into_inner()).on_scan_completed(results);
+    }
+}
+
+/// Console progress: one line per open port (every port when verbose).
+///
+/// After `line_limit` port lines it stops listing ports and prints a running
+/// count of open ports every `summary_interval` instead, so a scan of a host
+/// that answers on everything doesn't flood the terminal.
+///
+/// ```
+/// use std::io::{self, Write};
+/// use std::sync::{Arc, Mutex};
+/// use port_scanner::domain::{PortScanResult, PortStatus};
+/// use port_scanner::presentation::{ProgressObserver, ScanObserver};
+///
+/// #[derive(Clone, Default)]
+/// struct Captured(Arc<Mutex<Vec<u8>>>);
+///
+/// impl Write for Captured {
+///     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+///         self.0.lock().unwrap().write(buf)
+///     }
+///     fn flush(&mut self) -> io::Result<()> {
+///         Ok(())
+///     }
+/// }
+///
+/// let captured = Captured::default();
+/// let mut observer = ProgressObserver::new(false)
+///     .with_line_limit(Some(100))
+///     .with_summary_interval(500)
+///     .with_output(captured.clone());
+/// observer.on_scan_started(2000);
+/// for port in 1..=2000 {
+///     observer.on_port_scanned(&PortScanResult::new(port, PortStatus::Open));
+/// }
+///
+/// let output = String::from_utf8(captured.0.lock().unwrap().clone())?;
+/// let port_lines: Vec<_> = output.lines().filter(|line| line.starts_with("Port ")).collect();
+/// assert_eq!(port_lines.len(), 100);
+/// assert_eq!(port_lines.last(), Some(&"Port 100/tcp: OPEN"));
+/// let summaries: Vec<_> = output.lines().filter(|line| line.ends_with("open ports found so far")).collect();
+/// assert_eq!(summaries, ["...500 open ports found so far", "...1000 open ports found so far",
+///     "...1500 open ports found so far", "...2000 open ports found so far"]);
+/// # Ok::<(), std::string::FromUtf8Error>(())
+/// ```
 pub struct ProgressObserver {
     verbose: bool,
-    count: usize,
+    line_limit: Option<usize>,
+    summary_interval: usize,
+    lines: usize,
+    open: usize,
+    out: Box<dyn Write + Send>,
 }
 
 impl ProgressObserver {
     pub fn new(verbose: bool) -> Self {
-        Self { verbose, count: 0 }
+        Self {
+            verbose,
+            line_limit: Some(DEFAULT_PROGRESS_LINE_LIMIT),
+            summary_interval: PROGRESS_SUMMARY_INTERVAL,
+            lines: 0,
+            open: 0,
+            out: Box::new(io::stdout()),
+        }
+    }
+
+    /// Per-port lines to print before switching to counts; `None` prints every port
+    pub fn with_line_limit(mut self, limit: Option<usize>) -> Self {
+        self.line_limit = limit;
+        self
+    }
+
+    /// Open ports between two running counts once the line limit is reached
+    pub fn with_summary_interval(mut self, interval: usize) -> Self {
+        self.summary_interval = interval.max(1);
+        self
+    }
+
+    /// Write progress to `out` instead of stdout
+    pub fn with_output(mut self, out: impl Write + Send + 'static) -> Self {
+        self.out = Box::new(out);
+        self
+    }
+
+    /// Count the result; false once port lines are capped
+    fn take_line(&mut self, result: &PortScanResult) -> bool {
+        if result.is_open() {
+            self.open += 1;
+        }
+        if !result.is_open() && !self.verbose {
+            return false;
+        }
+        match self.line_limit {
+            Some(limit) if self.lines >= limit => {
+                if self.lines == limit {
+                    // Once, so the switch to counts is explained
+                    self.lines += 1;
+                    let _ = writeln!(self.out, "...more than {} ports to list; counting open ports from here", limit);
+                }
+                if result.is_open() && self.open.is_multiple_of(self.summary_interval) {
+                    let _ = writeln!(self.out, "...{} open ports found so far", self.open);
+                }
+                false
+            }
+            _ => {
+                self.lines += 1;
+                true
+            }
+        }
     }
 }
 
 impl ScanObserver for ProgressObserver {
     fn on_port_scanned(&mut self, result: &PortScanResult) {
-        self.count += 1;
-        
-        if result.is_open() || self.verbose {
+        if self.take_line(result) {
             let status_str = match &result.status {
                 crate::domain::PortStatus::Open => "OPEN",
                 crate::domain::PortStatus::Closed => "CLOSED",
                 crate::domain::PortStatus::Filtered => "FILTERED",
+                crate::domain::PortStatus::Reset => "RESET",
+                crate::domain::PortStatus::OpenFiltered => "OPEN|FILTERED",
                 crate::domain::PortStatus::Error(_) => "ERROR",
             };
             
-            println!("Port {}: {}", result.port, status_str);
+            let _ = writeln!(self.out, "Port {}: {}", result.label(), status_str);
             
             if let Some(version) = &result.service_version {
                 if version.banner.is_some() {
-                    println!("  └─ Service: {} {}", 
+                    let _ = writeln!(self.out, "  └─ Service: {} {}", 
                         version.service_name,
                         version.version.as_deref().unwrap_or(""));
                 }
@@ -46,16 +169,19 @@ impl ScanObserver for ProgressObserver {
     }
 
     fn on_scan_started(&mut self, total_ports: usize) {
-        println!("Starting scan of {} ports...", total_ports);
-        self.count = 0;
+        self.lines = 0;
+        self.open = 0;
+        let _ = writeln!(self.out, "Starting scan of {} ports...", total_ports);
     }
 
     fn on_scan_completed(&mut self, results: &ScanResults) {
-        println!("\nScan completed: {} ports scanned", self.count);
-        println!("Open: {}, Closed: {}, Filtered: {}", 
+        let _ = writeln!(self.out, "\nScan completed: {} ports scanned", results.total_ports);
+        let _ = writeln!(self.out, "Open: {}, Closed: {}, Filtered: {}, Reset: {}, Open|Filtered: {}", 
             results.open_ports, 
             results.closed_ports, 
-            results.filtered_ports);
+            results.filtered_ports,
+            results.reset_ports,
+            results.open_filtered_ports);
     }
 }
 
@@ -63,20 +189,60 @@ impl ScanObserver for ProgressObserver {
 pub struct MetricsCollector {
     pub start_time: std::time::Instant,
     pub ports_scanned: usize,
+    first_result: Option<SystemTime>,
+    last_result: Option<SystemTime>,
+    clock: Arc<dyn Clock>,
 }
 
 impl MetricsCollector {
     pub fn new() -> Self {
+        Self::with_clock(Arc::new(SystemClock))
+    }
+
+    /// Collector that reads elapsed time from `clock`
+    pub fn with_clock(clock: Arc<dyn Clock>) -> Self {
         Self {
-            start_time: std::time::Instant::now(),
+            start_time: clock.instant(),
             ports_scanned: 0,
+            first_result: None,
+            last_result: None,
+            clock,
+        }
+    }
+
+    /// Time between the earliest and latest recorded port result
+    pub fn scan_spread(&self) -> Duration {
+        match (self.first_result, self.last_result) {
+            (Some(first), Some(last)) => last.duration_since(first).unwrap_or_default(),
+            _ => Duration::ZERO,
         }
     }
 
     pub fn elapsed(&self) -> std::time::Duration {
-        self.start_time.elapsed()
+        self.clock.instant().saturating_duration_since(self.start_time)
     }
 
+    /// Ports recorded per second of clock time since the scan started
+    ///
+    /// ```
+    /// use port_scanner::infrastructure::MockClock;
+    /// use port_scanner::prelude::*;
+    /// use port_scanner::presentation::{MetricsCollector, ScanObserver};
+    /// use std::sync::Arc;
+    /// use std::time::{Duration, SystemTime};
+    ///
+    /// let clock = Arc::new(MockClock::new(SystemTime::now()));
+    /// let mut metrics = MetricsCollector::with_clock(clock.clone());
+    /// metrics.on_scan_started(100);
+    /// assert_eq!(metrics.ports_per_second(), 0.0);
+    ///
+    /// for port in 1..=50 {
+    ///     metrics.on_port_scanned(&PortScanResult::new(port, PortStatus::Closed));
+    /// }
+    /// clock.advance(Duration::from_secs(4));
+    /// assert_eq!(metrics.elapsed(), Duration::from_secs(4));
+    /// assert_eq!(metrics.ports_per_second(), 12.5);
+    /// ```
     pub fn ports_per_second(&self) -> f64 {
         let elapsed = self.elapsed().as_secs_f64();
         if elapsed > 0.0 {
@@ -94,16 +260,173 @@ impl Default for MetricsCollector {
 }
 
 impl ScanObserver for MetricsCollector {
-    fn on_port_scanned(&mut self, _result: &PortScanResult) {
+    fn on_port_scanned(&mut self, result: &PortScanResult) {
         self.ports_scanned += 1;
+        // Results arrive in completion order, which need not match scanned_at order
+        let at = result.scanned_at;
+        self.first_result = Some(self.first_result.map_or(at, |first| first.min(at)));
+        self.last_result = Some(self.last_result.map_or(at, |last| last.max(at)));
     }
 
     fn on_scan_started(&mut self, _total_ports: usize) {
-        self.start_time = std::time::Instant::now();
+        self.start_time = self.clock.instant();
         self.ports_scanned = 0;
+        self.first_result = None;
+        self.last_result = None;
     }
 
     fn on_scan_completed(&mut self, _results: &ScanResults) {
         // Metrics are collected, caller can query them
     }
 }
+
+/// Webhook observer that POSTs scan events as JSON.
+///
+/// Events are queued and delivered by a background task so scanning never
+/// waits on the HTTP endpoint. Delivery failures are logged and dropped.
+///
+/// ```
+/// use port_scanner::prelude::*;
+/// use port_scanner::presentation::{ScanObserver, WebhookObserver};
+/// use serde_json::Value;
+/// use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+/// use tokio::net::TcpListener;
+/// use tokio::sync::mpsc;
+///
+/// # #[tokio::main]
+/// # async fn main() -> anyhow::Result<()> {
+/// // Minimal HTTP server collecting each POST body
+/// let listener = TcpListener::bind("127.0.0.1:0").await?;
+/// let url = format!("http://{}/events", listener.local_addr()?);
+/// let (posts, mut received) = mpsc::unbounded_channel::<Value>();
+/// tokio::spawn(async move {
+///     while let Ok((stream, _)) = listener.accept().await {
+///         let mut reader = BufReader::new(stream);
+///         let (mut line, mut length) = (String::new(), 0);
+///         assert!(reader.read_line(&mut line).await.unwrap() > 0 && line.starts_with("POST /events "));
+///         loop {
+///             line.clear();
+///             reader.read_line(&mut line).await.unwrap();
+///             if let Some(value) = line.to_ascii_lowercase().strip_prefix("content-length:") {
+///                 length = value.trim().parse().unwrap();
+///             }
+///             if line == "\r\n" {
+///                 break;
+///             }
+///         }
+///         let mut body = vec![0; length];
+///         reader.read_exact(&mut body).await.unwrap();
+///         posts.send(serde_json::from_slice(&body).unwrap()).unwrap();
+///         reader.get_mut().write_all(b"HTTP/1.1 204 No Content\r\nConnection: close\r\n\r\n").await.unwrap();
+///     }
+/// });
+///
+/// let results = vec![
+///     PortScanResult::new(22, PortStatus::Open),
+///     PortScanResult::new(80, PortStatus::Closed),
+///     PortScanResult::new(443, PortStatus::Open),
+/// ];
+/// let mut observer = WebhookObserver::new(url);
+/// observer.on_scan_started(results.len());
+/// for result in &results {
+///     observer.on_port_scanned(result);
+/// }
+/// observer.on_scan_completed(&ScanResults::new(results));
+/// observer.close().expect("worker running").await?;
+///
+/// // One post per event, closed ports skipped
+/// let mut bodies = Vec::new();
+/// while let Ok(body) = received.try_recv() {
+///     bodies.push(body);
+/// }
+/// let events: Vec<_> = bodies.iter().map(|body| body["event"].as_str().unwrap()).collect();
+/// assert_eq!(events, ["scan_started", "port_open", "port_open", "scan_completed"]);
+/// assert_eq!(bodies[0]["total_ports"], 3);
+/// assert_eq!(bodies[1]["result"]["port"], 22);
+/// assert_eq!(bodies[2]["result"]["port"], 443);
+/// assert_eq!(bodies[3]["summary"]["open_ports"], 2);
+/// assert_eq!(bodies[3]["summary"]["closed_ports"], 1);
+/// # Ok(())
+/// # }
+/// ```
+pub struct WebhookObserver {
+    sender: Option<mpsc::UnboundedSender<Value>>,
+    worker: Option<JoinHandle<()>>,
+}
+
+impl WebhookObserver {
+    /// Create an observer posting to `url`; must be called within a tokio runtime
+    pub fn new(url: impl Into<String>) -> Self {
+        let url = url.into();
+        let (sender, mut receiver) = mpsc::unbounded_channel::<Value>();
+
+        let worker = tokio::spawn(async move {
+            let client = match reqwest::Client::builder()
+                .timeout(Duration::from_millis(WEBHOOK_TIMEOUT_MS))
+                .build()
+            {
+                Ok(client) => client,
+                Err(e) => {
+                    warn!("Webhook disabled, failed to create HTTP client: {}", e);
+                    return;
+                }
+            };
+
+            while let Some(payload) = receiver.recv().await {
+                match client.post(&url).json(&payload).send().await {
+                    Ok(response) if response.status().is_success() => {
+                        debug!("Webhook delivered {} to {}", payload["event"], url);
+                    }
+                    Ok(response) => warn!("Webhook {} returned {}", url, response.status()),
+                    Err(e) => warn!("Webhook delivery to {} failed: {}", url, e),
+                }
+            }
+        });
+
+        Self {
+            sender: Some(sender),
+            worker: Some(worker),
+        }
+    }
+
+    /// Stop accepting events; await the returned handle to let queued
+    /// deliveries finish
+    pub fn close(&mut self) -> Option<JoinHandle<()>> {
+        self.sender = None;
+        self.worker.take()
+    }
+
+    fn send(&self, payload: Value) {
+        if let Some(sender) = &self.sender {
+            // Only fails once the worker has exited, which it already logged
+            let _ = sender.send(payload);
+        }
+    }
+}
+
+impl ScanObserver for WebhookObserver {
+    fn on_port_scanned(&mut self, result: &PortScanResult) {
+        if result.is_open() {
+            self.send(json!({ "event": "port_open", "result": result }));
+        }
+    }
+
+    fn on_scan_started(&mut self, total_ports: usize) {
+        self.send(json!({ "event": "scan_started", "total_ports": total_ports }));
+    }
+
+    fn on_scan_completed(&mut self, results: &ScanResults) {
+        self.send(json!({
+            "event": "scan_completed",
+            "summary": {
+                "total_ports": results.total_ports,
+                "open_ports": results.open_ports,
+                "closed_ports": results.closed_ports,
+                "filtered_ports": results.filtered_ports,
+                "reset_ports": results.reset_ports,
+                "open_filtered_ports": results.open_filtered_ports,
+                "error_ports": results.error_ports,
+            },
+        }));
+    }
+}
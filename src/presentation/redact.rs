@@ -0,0 +1,156 @@
+//! Masking of sensitive banner content before results leave the scanner
+
+use regex::{Captures, Regex};
+
+use crate::constants::REDACTION_MASK;
+use crate::domain::{PortScanResult, ScanResults};
+use crate::errors::{ConfigError, ConfigResult};
+
+/// Patterns masked by `Redactor::builtin`. A `value` group masks only that
+/// part of the match, so the reader still sees what kind of secret it was.
+const BUILTIN_PATTERNS: &[&str] = &[
+    // Credentials as key=value or key: value
+    r#"(?i)\b(?:password|passwd|pwd|secret|token|api[_-]?key)\s*[=:]\s*(?P<value>[^\s;&,'"]+)"#,
+    // Private (RFC 1918) IPv4 addresses
+    r"\b(?:10(?:\.\d{1,3}){3}|172\.(?:1[6-9]|2\d|3[01])(?:\.\d{1,3}){2}|192\.168(?:\.\d{1,3}){2})\b",
+    // Email addresses
+    r"\b[\w.+-]+@[\w-]+(?:\.[\w-]+)+\b",
+];
+
+/// Masks pattern matches in banners and version strings, for reports that
+/// are shared beyond the person who ran the scan.
+///
+/// Results that had anything masked are marked `redacted`, so a report
+/// says that content is missing rather than silently dropping it.
+///
+/// ```
+/// use port_scanner::domain::PortStatus;
+/// use port_scanner::presentation::Redactor;
+/// use port_scanner::prelude::*;
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let config = ScanConfigBuilder::new()
+///     .target("127.0.0.1".parse()?)
+///     .custom_ports(vec![21])
+///     .build()?;
+/// let version = ServiceVersion::new("FTP", "tcp").with_banner("220 ready: password=secret for anonymous");
+/// let mut results = ScanResults::new(vec![
+///     PortScanResult::new(21, PortStatus::Open).with_version(version),
+/// ]);
+///
+/// assert_eq!(Redactor::builtin().redact(&mut results), 1);
+/// assert!(results.results[0].service_version.as_ref().unwrap().redacted);
+///
+/// let report = ScanReport::new(&config, results, 1.0);
+/// for format in [OutputFormat::Json, OutputFormat::Csv, OutputFormat::Text, OutputFormat::Ndjson] {
+///     let output = OutputFormatterFactory::create(format, false).format(&report)?;
+///     assert!(!output.contains("secret"), "{format:?} leaks the password");
+///     assert!(output.contains("220 ready: password=[REDACTED] for anonymous"), "{format:?}");
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Redactor {
+    patterns: Vec<Regex>,
+}
+
+impl Redactor {
+    /// Redactor without patterns
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Credentials, private IPv4 addresses and email addresses
+    pub fn builtin() -> Self {
+        Self {
+            patterns: BUILTIN_PATTERNS.iter()
+                .map(|pattern| Regex::new(pattern).expect("built-in redaction pattern compiles"))
+                .collect(),
+        }
+    }
+
+    /// Also mask matches of `pattern`, or only its `value` group if it has one
+    pub fn with_pattern(mut self, pattern: &str) -> ConfigResult<Self> {
+        let pattern = Regex::new(pattern)
+            .map_err(|e| ConfigError::InvalidPattern(e.to_string()))?;
+        self.patterns.push(pattern);
+        Ok(self)
+    }
+
+    pub fn patterns(&self) -> &[Regex] {
+        &self.patterns
+    }
+
+    /// `text` with every match masked, or `None` if nothing matched
+    pub fn redact_text(&self, text: &str) -> Option<String> {
+        let mut redacted = None;
+        for pattern in &self.patterns {
+            let current = redacted.as_deref().unwrap_or(text);
+            if pattern.is_match(current) {
+                redacted = Some(pattern.replace_all(current, mask).into_owned());
+            }
+        }
+        redacted
+    }
+
+    /// Mask the banner and version strings of one result; true if anything
+    /// was masked
+    pub fn redact_result(&self, result: &mut PortScanResult) -> bool {
+        let mut masked = false;
+        if let Some(version) = &mut result.service_version {
+            for field in [
+                &mut version.banner,
+                &mut version.raw_banner,
+                &mut version.product,
+                &mut version.version,
+                &mut version.os,
+                &mut version.extra_info,
+            ] {
+                masked |= self.redact_field(field);
+            }
+            if masked {
+                // The hex would give the masked text straight back
+                version.banner_hex = None;
+            }
+            version.redacted |= masked;
+        }
+        if let Some(info) = &mut result.service_info {
+            for field in [&mut info.product, &mut info.version, &mut info.extra_info] {
+                masked |= self.redact_field(field);
+            }
+        }
+        masked
+    }
+
+    /// Mask every result; returns how many results had something masked
+    pub fn redact(&self, results: &mut ScanResults) -> usize {
+        results.results.iter_mut()
+            .map(|result| self.redact_result(result))
+            .filter(|&masked| masked)
+            .count()
+    }
+
+    fn redact_field(&self, field: &mut Option<String>) -> bool {
+        match field.as_deref().and_then(|text| self.redact_text(text)) {
+            Some(redacted) => {
+                *field = Some(redacted);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// Replacement for one match: the `value` group if present, else all of it
+fn mask(captures: &Captures) -> String {
+    let whole = captures.get(0).expect("group 0 is the whole match");
+    match captures.name("value") {
+        Some(value) => {
+            let text = whole.as_str();
+            let (start, end) = (value.start() - whole.start(), value.end() - whole.start());
+            format!("{}{}{}", &text[..start], REDACTION_MASK, &text[end..])
+        }
+        None => REDACTION_MASK.to_string(),
+    }
+}
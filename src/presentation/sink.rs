@@ -0,0 +1,331 @@
+//! Result sinks: where scan results and the final report end up
+
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::time::Instant;
+use futures::StreamExt;
+use tracing::warn;
+
+use crate::application::PortScanner;
+use crate::domain::PortScanResult;
+use crate::errors::{FormatterError, FormatterResult};
+use crate::presentation::{OutputFormat, OutputFormatter, OutputFormatterFactory, ReportField, ScanReport};
+
+/// Receives results as they complete and the report once the scan ends
+///
+/// ```
+/// use port_scanner::errors::FormatterResult;
+/// use port_scanner::prelude::*;
+/// use port_scanner::presentation::ResultSink;
+/// use std::net::TcpListener;
+///
+/// /// Keeps everything in memory
+/// #[derive(Default)]
+/// struct Collect {
+///     results: Vec<Port>,
+///     report: Option<(usize, usize)>,
+/// }
+///
+/// impl ResultSink for Collect {
+///     fn on_result(&mut self, result: &PortScanResult) {
+///         self.results.push(result.port);
+///     }
+///
+///     fn on_complete(&mut self, report: &ScanReport) -> FormatterResult<()> {
+///         self.report = Some((report.results.len(), report.statistics.open_ports));
+///         Ok(())
+///     }
+/// }
+///
+/// # #[tokio::main]
+/// # async fn main() -> anyhow::Result<()> {
+/// let listener = TcpListener::bind("127.0.0.1:0")?;
+/// let open = listener.local_addr()?.port();
+/// let config = ScanConfigBuilder::new()
+///     .target("127.0.0.1".parse()?)
+///     .custom_ports(vec![1, 2, open])
+///     .build()?;
+///
+/// let mut sink = Collect::default();
+/// let report = PortScanner::new(config)?.scan_with_sink(&mut sink).await?;
+///
+/// sink.results.sort_unstable();
+/// let mut expected = vec![1, 2, open];
+/// expected.sort_unstable();
+/// assert_eq!(sink.results, expected);
+/// assert_eq!(sink.report, Some((3, 1)));
+/// assert_eq!(report.statistics.open_ports, 1);
+/// # Ok(())
+/// # }
+/// ```
+pub trait ResultSink: Send {
+    fn on_result(&mut self, _result: &PortScanResult) {}
+    fn on_complete(&mut self, report: &ScanReport) -> FormatterResult<()>;
+}
+
+/// Writes the report to a file
+pub struct FileSink {
+    format: OutputFormat,
+    path: PathBuf,
+    open_only: bool,
+    fields: Option<Vec<ReportField>>,
+    append: bool,
+}
+
+impl FileSink {
+    pub fn new(format: OutputFormat, path: impl Into<PathBuf>, open_only: bool) -> Self {
+        Self { format, path: path.into(), open_only, fields: None, append: false }
+    }
+
+    /// File named after the target and the current time, e.g. `scan_10_0_0_1_<ts>.json`
+    pub fn with_default_name(format: OutputFormat, target_ip: &str, open_only: bool) -> Self {
+        Self::new(format, ScanReport::default_filename(target_ip, format), open_only)
+    }
+
+    /// Restrict CSV/JSON results to these fields
+    pub fn with_fields(mut self, fields: Option<Vec<ReportField>>) -> Self {
+        self.fields = fields;
+        self
+    }
+
+    /// Add the report to an existing file instead of replacing it, see
+    /// `OutputFormatter::append_to_file`
+    pub fn append(mut self, append: bool) -> Self {
+        self.append = append;
+        self
+    }
+
+    pub fn path(&self) -> &PathBuf {
+        &self.path
+    }
+}
+
+impl ResultSink for FileSink {
+    fn on_complete(&mut self, report: &ScanReport) -> FormatterResult<()> {
+        let formatter = OutputFormatterFactory::create_with_fields(self.format, self.open_only, self.fields.as_deref());
+        if self.append {
+            formatter.append_to_file(report, &self.path)
+        } else {
+            formatter.write_to_file(report, &self.path)
+        }
+    }
+}
+
+/// Writes the report to stdout
+pub struct ConsoleSink {
+    format: OutputFormat,
+    open_only: bool,
+    fields: Option<Vec<ReportField>>,
+}
+
+impl ConsoleSink {
+    pub fn new(format: OutputFormat, open_only: bool) -> Self {
+        Self { format, open_only, fields: None }
+    }
+
+    /// Restrict CSV/JSON results to these fields
+    pub fn with_fields(mut self, fields: Option<Vec<ReportField>>) -> Self {
+        self.fields = fields;
+        self
+    }
+}
+
+impl ResultSink for ConsoleSink {
+    fn on_complete(&mut self, report: &ScanReport) -> FormatterResult<()> {
+        OutputFormatterFactory::create_with_fields(self.format, self.open_only, self.fields.as_deref())
+            .write_to(report, &mut io::stdout().lock())
+    }
+}
+
+/// Where one output of a `MultiFormatter` goes
+enum Destination {
+    /// Streamed to as results complete, or written once at the end
+    Writer(Box<dyn Write + Send>),
+    /// Written atomically once the report is complete
+    File(PathBuf),
+    /// Added to the file's existing content once the report is complete
+    AppendedFile(PathBuf),
+}
+
+/// Feeds one scan to several formatters at once, e.g. a JSON file, a CSV
+/// file and NDJSON on stdout.
+///
+/// Streaming formatters (`OutputFormatter::streams`) get each result as it
+/// completes; the others write the full report at the end. A failing
+/// output does not stop the rest; the first error is returned from
+/// `on_complete`.
+///
+/// ```
+/// use port_scanner::prelude::*;
+/// use port_scanner::presentation::{MultiFormatter, NdjsonFormatter, JsonFormatter, CsvFormatter};
+/// use std::io::{self, Write};
+/// use std::net::TcpListener;
+/// use std::sync::{Arc, Mutex};
+///
+/// #[derive(Clone, Default)]
+/// struct Shared(Arc<Mutex<Vec<u8>>>);
+///
+/// impl Write for Shared {
+///     fn write(&mut self, buf: &[u8]) -> io::Result<usize> { self.0.lock().unwrap().write(buf) }
+///     fn flush(&mut self) -> io::Result<()> { Ok(()) }
+/// }
+///
+/// # #[tokio::main]
+/// # async fn main() -> anyhow::Result<()> {
+/// let listener = TcpListener::bind("127.0.0.1:0")?;
+/// let open = listener.local_addr()?.port();
+/// let config = ScanConfigBuilder::new()
+///     .target("127.0.0.1".parse()?)
+///     .custom_ports(vec![open, 1, 2])
+///     .build()?;
+///
+/// let dir = std::env::temp_dir().join(format!("multi-formatter-{}", std::process::id()));
+/// std::fs::create_dir_all(&dir)?;
+/// let stdout = Shared::default();
+/// let mut outputs = MultiFormatter::new()
+///     .with_file(Box::new(JsonFormatter::new(false)), dir.join("scan.json"))?
+///     .with_file(Box::new(CsvFormatter::new(false)), dir.join("scan.csv"))?
+///     .with_writer(Box::new(NdjsonFormatter::new(false)), stdout.clone());
+/// PortScanner::new(config)?.scan_with_sink(&mut outputs).await?;
+///
+/// let json: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(dir.join("scan.json"))?)?;
+/// let csv = std::fs::read_to_string(dir.join("scan.csv"))?;
+/// let ndjson = String::from_utf8(stdout.0.lock().unwrap().clone())?;
+/// assert_eq!(json["results"].as_array().unwrap().len(), 3);
+/// assert_eq!(csv.lines().count(), 1 + 3);
+/// assert_eq!(ndjson.lines().count(), 3);
+/// let open_line = ndjson.lines()
+///     .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap())
+///     .find(|result| result["port"] == open)
+///     .unwrap();
+/// assert_eq!(open_line["status"], "Open");
+/// assert!(csv.lines().any(|row| row.starts_with(&format!("{},OPEN,", open))));
+/// # std::fs::remove_dir_all(&dir)?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct MultiFormatter {
+    outputs: Vec<(Box<dyn OutputFormatter>, Destination)>,
+    paths: Vec<PathBuf>,
+    error: Option<FormatterError>,
+}
+
+impl MultiFormatter {
+    pub fn new() -> Self {
+        Self { outputs: Vec::new(), paths: Vec::new(), error: None }
+    }
+
+    /// Add an output written to `writer`, e.g. stdout
+    pub fn with_writer(mut self, formatter: Box<dyn OutputFormatter>, writer: impl Write + Send + 'static) -> Self {
+        self.outputs.push((formatter, Destination::Writer(Box::new(writer))));
+        self
+    }
+
+    /// Add an output saved to `path`. Streaming formats create the file
+    /// right away; the others write it atomically at the end.
+    pub fn with_file(mut self, formatter: Box<dyn OutputFormatter>, path: impl Into<PathBuf>) -> FormatterResult<Self> {
+        let path = path.into();
+        let destination = if formatter.streams() {
+            Destination::Writer(Box::new(File::create(&path)?))
+        } else {
+            Destination::File(path.clone())
+        };
+        self.paths.push(path);
+        self.outputs.push((formatter, destination));
+        Ok(self)
+    }
+
+    /// Like `with_file`, but keeps what `path` already holds: streaming
+    /// formats append to it, the others extend it at the end through
+    /// `OutputFormatter::append_to_file`
+    pub fn with_appended_file(mut self, formatter: Box<dyn OutputFormatter>, path: impl Into<PathBuf>) -> FormatterResult<Self> {
+        let path = path.into();
+        let destination = if formatter.streams() {
+            Destination::Writer(Box::new(OpenOptions::new().create(true).append(true).open(&path)?))
+        } else {
+            Destination::AppendedFile(path.clone())
+        };
+        self.paths.push(path);
+        self.outputs.push((formatter, destination));
+        Ok(self)
+    }
+
+    /// Files this formatter writes, in the order they were added
+    pub fn paths(&self) -> &[PathBuf] {
+        &self.paths
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.outputs.is_empty()
+    }
+}
+
+impl Default for MultiFormatter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ResultSink for MultiFormatter {
+    fn on_result(&mut self, result: &PortScanResult) {
+        for (formatter, destination) in &mut self.outputs {
+            let Destination::Writer(writer) = destination else {
+                continue;
+            };
+            if !formatter.streams() {
+                continue;
+            }
+            if let Err(e) = formatter.write_result(result, writer.as_mut()) {
+                warn!("Failed to stream {} result: {}", formatter.extension(), e);
+                self.error.get_or_insert(e);
+            }
+        }
+    }
+
+    fn on_complete(&mut self, report: &ScanReport) -> FormatterResult<()> {
+        for (formatter, destination) in &mut self.outputs {
+            let written = match destination {
+                Destination::Writer(writer) if formatter.streams() => writer.flush().map_err(FormatterError::from),
+                Destination::Writer(writer) => formatter.write_to(report, writer.as_mut()),
+                Destination::File(path) => formatter.write_to_file(report, path),
+                Destination::AppendedFile(path) => formatter.append_to_file(report, path),
+            };
+            if let Err(e) = written {
+                warn!("Failed to write {} report: {}", formatter.extension(), e);
+                self.error.get_or_insert(e);
+            }
+        }
+        self.error.take().map_or(Ok(()), Err)
+    }
+}
+
+/// Discards everything
+pub struct NullSink;
+
+impl ResultSink for NullSink {
+    fn on_complete(&mut self, _report: &ScanReport) -> FormatterResult<()> {
+        Ok(())
+    }
+}
+
+impl PortScanner {
+    /// Scan all configured ports, feeding each result and the final report to `sink`.
+    ///
+    /// Results reach the sink in completion order; the report lists them by port.
+    pub async fn scan_with_sink(&self, sink: &mut dyn ResultSink) -> FormatterResult<ScanReport> {
+        let start = Instant::now();
+        let mut stream = Box::pin(self.scan_stream());
+        let mut results = Vec::new();
+
+        while let Some(result) = stream.next().await {
+            sink.on_result(&result);
+            results.push(result);
+        }
+
+        let results = self.complete(results).await;
+        let report = ScanReport::new(self.config(), results, start.elapsed().as_secs_f64());
+        sink.on_complete(&report)?;
+        Ok(report)
+    }
+}
@@ -0,0 +1,307 @@
+//! Live terminal dashboard (`--tui`)
+
+use std::future::Future;
+use std::io::{self, Stdout};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use ratatui::backend::CrosstermBackend;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::crossterm::execute;
+use ratatui::crossterm::terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::widgets::{Block, Gauge, Paragraph, Row, Table};
+use ratatui::{Frame, Terminal};
+
+use crate::constants::TUI_REFRESH_INTERVAL;
+use crate::domain::{Port, PortScanResult, PortStatus, ScanResults};
+use crate::presentation::observer::ScanObserver;
+
+/// An open port as listed in the dashboard table
+#[derive(Debug, Clone)]
+pub struct OpenPortRow {
+    pub port: Port,
+    pub service: String,
+    pub version: String,
+}
+
+/// Dashboard state, updated from observer callbacks and rendered on a timer
+#[derive(Debug)]
+pub struct TuiState {
+    pub total_ports: usize,
+    pub scanned: usize,
+    pub open: usize,
+    pub closed: usize,
+    pub filtered: usize,
+    pub reset: usize,
+    pub open_filtered: usize,
+    pub errors: usize,
+    /// Open ports in discovery order
+    pub open_ports: Vec<OpenPortRow>,
+    started: Instant,
+    finished: Option<Duration>,
+}
+
+impl TuiState {
+    pub fn new(total_ports: usize) -> Self {
+        Self {
+            total_ports,
+            scanned: 0,
+            open: 0,
+            closed: 0,
+            filtered: 0,
+            reset: 0,
+            open_filtered: 0,
+            errors: 0,
+            open_ports: Vec::new(),
+            started: Instant::now(),
+            finished: None,
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.finished.is_some()
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.finished.unwrap_or_else(|| self.started.elapsed())
+    }
+
+    /// Fraction of ports scanned, 0.0..=1.0
+    pub fn progress(&self) -> f64 {
+        if self.total_ports == 0 {
+            return 1.0;
+        }
+        (self.scanned as f64 / self.total_ports as f64).min(1.0)
+    }
+
+    pub fn ports_per_second(&self) -> f64 {
+        let elapsed = self.elapsed().as_secs_f64();
+        if elapsed > 0.0 {
+            self.scanned as f64 / elapsed
+        } else {
+            0.0
+        }
+    }
+
+    /// Remaining time at the current rate; `None` until a rate is known
+    pub fn eta(&self) -> Option<Duration> {
+        let rate = self.ports_per_second();
+        if self.is_finished() {
+            return Some(Duration::ZERO);
+        }
+        if rate <= 0.0 {
+            return None;
+        }
+        let remaining = self.total_ports.saturating_sub(self.scanned);
+        Some(Duration::from_secs_f64(remaining as f64 / rate))
+    }
+
+    /// Draw the dashboard into `frame`
+    ///
+    /// ```
+    /// use port_scanner::prelude::*;
+    /// use port_scanner::presentation::{ScanObserver, TuiState};
+    /// use ratatui::backend::TestBackend;
+    /// use ratatui::Terminal;
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// let mut state = TuiState::new(0);
+    /// state.on_scan_started(4);
+    /// let ssh = ServiceVersion::new("ssh", "tcp").with_version("9.6p1");
+    /// let results = vec![
+    ///     PortScanResult::new(22, PortStatus::Open).with_version(ssh),
+    ///     PortScanResult::new(80, PortStatus::Closed),
+    ///     PortScanResult::new(81, PortStatus::Filtered),
+    /// ];
+    /// for result in &results {
+    ///     state.on_port_scanned(result);
+    /// }
+    ///
+    /// let mut terminal = Terminal::new(TestBackend::new(160, 12))?;
+    /// let mut screen = |state: &TuiState| -> anyhow::Result<String> {
+    ///     terminal.draw(|frame| state.render(frame))?;
+    ///     Ok(terminal.backend().buffer().content.iter().map(|cell| cell.symbol()).collect())
+    /// };
+    ///
+    /// let text = screen(&state)?;
+    /// for expected in ["3/4 ports (75%)", "Open: 1", "Closed: 1", "Filtered: 1", "Open Ports (1)", "22", "ssh", "9.6p1", "q / Esc"] {
+    ///     assert!(text.contains(expected), "missing {expected:?}");
+    /// }
+    ///
+    /// state.on_scan_completed(&ScanResults::new(results));
+    /// assert!(screen(&state)?.contains("Scan complete - press q to exit"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn render(&self, frame: &mut Frame) {
+        let [gauge_area, stats_area, table_area, help_area] = Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Length(3),
+            Constraint::Min(3),
+            Constraint::Length(1),
+        ]).areas(frame.area());
+
+        let gauge = Gauge::default()
+            .block(Block::bordered().title(" Progress "))
+            .gauge_style(Style::new().fg(Color::Green))
+            .ratio(self.progress())
+            .label(format!("{}/{} ports ({:.0}%)", self.scanned, self.total_ports, self.progress() * 100.0));
+        frame.render_widget(gauge, gauge_area);
+
+        let eta = match self.eta() {
+            Some(eta) => format!("{}s", eta.as_secs()),
+            None => "--".to_string(),
+        };
+        let stats = Paragraph::new(format!(
+            "Rate: {:.0} ports/s   Elapsed: {}s   ETA: {}   Open: {}   Closed: {}   Filtered: {}   Reset: {}   Open|Filtered: {}   Errors: {}",
+            self.ports_per_second(), self.elapsed().as_secs(), eta,
+            self.open, self.closed, self.filtered, self.reset, self.open_filtered, self.errors,
+        )).block(Block::bordered().title(" Metrics "));
+        frame.render_widget(stats, stats_area);
+
+        // Keep the most recent discoveries in view (borders and header take 3 rows)
+        let visible = table_area.height.saturating_sub(3) as usize;
+        let rows = self.open_ports.iter()
+            .skip(self.open_ports.len().saturating_sub(visible))
+            .map(|row| Row::new(vec![row.port.to_string(), row.service.clone(), row.version.clone()]));
+        let table = Table::new(rows, [Constraint::Length(7), Constraint::Length(20), Constraint::Fill(1)])
+            .header(Row::new(vec!["Port", "Service", "Version"]).style(Style::new().add_modifier(Modifier::BOLD)))
+            .block(Block::bordered().title(format!(" Open Ports ({}) ", self.open)));
+        frame.render_widget(table, table_area);
+
+        let help = if self.is_finished() {
+            "Scan complete - press q to exit"
+        } else {
+            "q / Esc / Ctrl-C: cancel scan"
+        };
+        frame.render_widget(Paragraph::new(help), help_area);
+    }
+}
+
+impl ScanObserver for TuiState {
+    fn on_port_scanned(&mut self, result: &PortScanResult) {
+        self.scanned += 1;
+        match &result.status {
+            PortStatus::Open => {
+                self.open += 1;
+                let version = result.service_version.as_ref();
+                self.open_ports.push(OpenPortRow {
+                    port: result.port,
+                    service: version.map(|v| v.service_name.clone()).unwrap_or_default(),
+                    version: version.and_then(|v| v.version.clone()).unwrap_or_default(),
+                });
+            }
+            PortStatus::Closed => self.closed += 1,
+            PortStatus::Filtered => self.filtered += 1,
+            PortStatus::Reset => self.reset += 1,
+            PortStatus::OpenFiltered => self.open_filtered += 1,
+            PortStatus::Error(_) => self.errors += 1,
+        }
+    }
+
+    fn on_scan_started(&mut self, total_ports: usize) {
+        *self = Self::new(total_ports);
+    }
+
+    fn on_scan_completed(&mut self, _results: &ScanResults) {
+        self.finished = Some(self.started.elapsed());
+    }
+}
+
+/// Terminal in raw mode on the alternate screen; restored when dropped
+pub struct Dashboard {
+    terminal: Terminal<CrosstermBackend<Stdout>>,
+}
+
+impl Dashboard {
+    pub fn enter() -> io::Result<Self> {
+        enable_raw_mode()?;
+        let terminal = execute!(io::stdout(), EnterAlternateScreen)
+            .and_then(|_| Terminal::new(CrosstermBackend::new(io::stdout())));
+        match terminal {
+            Ok(mut terminal) => {
+                let _ = terminal.hide_cursor();
+                Ok(Self { terminal })
+            }
+            Err(e) => {
+                restore_terminal();
+                Err(e)
+            }
+        }
+    }
+
+    pub fn draw(&mut self, state: &TuiState) -> io::Result<()> {
+        self.terminal.draw(|frame| state.render(frame))?;
+        Ok(())
+    }
+
+    /// Drain pending input; true if the user pressed q, Esc or Ctrl-C
+    pub fn quit_requested(&self) -> io::Result<bool> {
+        while event::poll(Duration::ZERO)? {
+            if let Event::Key(key) = event::read()? {
+                if key.kind != KeyEventKind::Press {
+                    continue;
+                }
+                let ctrl_c = key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL);
+                if ctrl_c || matches!(key.code, KeyCode::Char('q') | KeyCode::Esc) {
+                    return Ok(true);
+                }
+            }
+        }
+        Ok(false)
+    }
+}
+
+impl Drop for Dashboard {
+    fn drop(&mut self) {
+        let _ = self.terminal.show_cursor();
+        restore_terminal();
+    }
+}
+
+fn restore_terminal() {
+    let _ = disable_raw_mode();
+    let _ = execute!(io::stdout(), LeaveAlternateScreen);
+}
+
+/// Run `scan` behind the dashboard, redrawing `state` every `TUI_REFRESH_INTERVAL`.
+///
+/// `state` must observe the scan, e.g. through `ScanBuilder::observer`.
+/// Returns `None` if the user cancelled; the scan future is dropped, which
+/// aborts outstanding probes. A failed scan leaves the dashboard at once;
+/// otherwise the final view stays up until the user presses q.
+pub async fn run_dashboard<F, T, E>(scan: F, state: Arc<Mutex<TuiState>>) -> io::Result<Option<Result<T, E>>>
+where
+    F: Future<Output = Result<T, E>>,
+{
+    let mut dashboard = Dashboard::enter()?;
+    let mut ticker = tokio::time::interval(TUI_REFRESH_INTERVAL);
+    tokio::pin!(scan);
+
+    let outcome = loop {
+        tokio::select! {
+            outcome = &mut scan => break outcome,
+            _ = ticker.tick() => {
+                if let Ok(state) = state.lock() {
+                    dashboard.draw(&state)?;
+                }
+                if dashboard.quit_requested()? {
+                    return Ok(None);
+                }
+            }
+        }
+    };
+
+    if outcome.is_err() {
+        return Ok(Some(outcome));
+    }
+    if let Ok(state) = state.lock() {
+        dashboard.draw(&state)?;
+    }
+    while !dashboard.quit_requested()? {
+        ticker.tick().await;
+    }
+    Ok(Some(outcome))
+}
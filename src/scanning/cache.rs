@@ -0,0 +1,58 @@
+//! Per-scan cache of detection results
+
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::Mutex;
+
+use crate::domain::{OSInfo, ServiceVersion};
+
+/// Detection results recorded for one host:port
+#[derive(Debug, Clone, Default)]
+pub struct CachedDetection {
+    pub service: Option<ServiceVersion>,
+    pub os_info: Option<OSInfo>,
+}
+
+/// Cache of detection results keyed by host:port.
+///
+/// Shared by the detector registry and detectors whose single exchange
+/// yields several results (e.g. SMB negotiation gives both the service
+/// and the OS), so an open port is not reconnected for each of them.
+#[derive(Debug, Default)]
+pub struct DetectionCache {
+    entries: Mutex<HashMap<SocketAddr, CachedDetection>>,
+}
+
+impl DetectionCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn service(&self, socket: &SocketAddr) -> Option<ServiceVersion> {
+        self.entries.lock().ok()?.get(socket)?.service.clone()
+    }
+
+    pub fn os_info(&self, socket: &SocketAddr) -> Option<OSInfo> {
+        self.entries.lock().ok()?.get(socket)?.os_info.clone()
+    }
+
+    pub fn store_service(&self, socket: SocketAddr, service: ServiceVersion) {
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.entry(socket).or_default().service = Some(service);
+        }
+    }
+
+    pub fn store_os_info(&self, socket: SocketAddr, os_info: OSInfo) {
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.entry(socket).or_default().os_info = Some(os_info);
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.lock().map(|e| e.len()).unwrap_or(0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
@@ -0,0 +1,131 @@
+//! Cancelling a running scan without losing detection on open ports
+//!
+//! Cancellation stops new probes and drops the ones still waiting on their
+//! port. Probes that already found their port open (see `mark_open`) get
+//! up to `drain_timeout` to finish detection, so partial results keep what
+//! was found on them.
+
+use std::future::Future;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tokio::sync::watch;
+use tracing::debug;
+
+tokio::task_local! {
+    static OPEN: Arc<AtomicBool>;
+}
+
+/// Cancellation of one scan, shared by clones of its config.
+///
+/// ```
+/// use port_scanner::prelude::*;
+/// use port_scanner::infrastructure::testing::{Reply, ScriptedConnector};
+/// use port_scanner::scanning::{Detector, DetectorRegistry, StandardScan};
+/// use std::net::SocketAddr;
+/// use std::sync::Arc;
+/// use std::time::{Duration, Instant};
+///
+/// /// Takes a while to identify anything
+/// struct Slow;
+///
+/// #[async_trait::async_trait]
+/// impl Detector for Slow {
+///     fn name(&self) -> &str { "slow" }
+///     fn can_detect(&self, _: Port) -> bool { true }
+///     fn detect_service(&self, _: &SocketAddr, _: Duration) -> Option<ServiceVersion> { None }
+///     async fn detect_service_async(&self, _: &SocketAddr, _: Duration) -> Option<ServiceVersion> {
+///         tokio::time::sleep(Duration::from_millis(300)).await;
+///         Some(ServiceVersion::new("slow-service", "tcp"))
+///     }
+/// }
+///
+/// # #[tokio::main]
+/// # async fn main() -> anyhow::Result<()> {
+/// let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+/// let open = listener.local_addr()?.port();
+/// let config = ScanConfigBuilder::new()
+///     .target("127.0.0.1".parse()?)
+///     .custom_ports(vec![open, 1])
+///     .timeout(Duration::from_secs(30))
+///     .detect_versions(true)
+///     // Cancelled while `open` is still being identified
+///     .max_scan_duration(Some(Duration::from_millis(100)))
+///     .drain_timeout(Duration::from_secs(2))
+///     .build()?;
+///
+/// let mut detectors = DetectorRegistry::new();
+/// detectors.register(Box::new(Slow));
+/// // Port 1 never answers
+/// let blackhole = ScriptedConnector::new(Reply::Accept).on_port(1, [Reply::Silent]);
+/// let strategy = StandardScan::with_detectors(Arc::new(detectors)).with_connector(Arc::new(blackhole));
+/// let started = Instant::now();
+/// let results = PortScanner::new(config)?.with_strategy(Arc::new(strategy)).scan_all(|_| {}).await;
+///
+/// // The open port kept its detection; the silent one was dropped
+/// assert_eq!(results.total_ports, 1);
+/// let version = results.results[0].service_version.as_ref().unwrap();
+/// assert_eq!(version.service_name, "slow-service");
+/// assert!(started.elapsed() < Duration::from_secs(2));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct ScanCancellation(Arc<watch::Sender<bool>>);
+
+impl ScanCancellation {
+    pub fn new() -> Self {
+        Self(Arc::new(watch::channel(false).0))
+    }
+
+    pub fn cancel(&self) {
+        self.0.send_replace(true);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        *self.0.borrow()
+    }
+
+    /// Clear a previous cancellation before the next scan starts
+    pub(crate) fn reset(&self) {
+        self.0.send_replace(false);
+    }
+
+    /// Wait until `cancel` is called
+    pub async fn cancelled(&self) {
+        let mut cancelled = self.0.subscribe();
+        // The sender lives in `self`, so this only returns once cancelled
+        let _ = cancelled.wait_for(|cancelled| *cancelled).await;
+    }
+
+    /// Run `probe` to completion unless the scan is cancelled first. A
+    /// cancelled probe that already marked its port open gets up to
+    /// `drain_timeout` more to finish; the others are dropped (`None`).
+    pub async fn drain<F: Future>(&self, drain_timeout: Duration, probe: F) -> Option<F::Output> {
+        let open = Arc::new(AtomicBool::new(false));
+        let probe = OPEN.scope(Arc::clone(&open), probe);
+        tokio::pin!(probe);
+        tokio::select! {
+            biased;
+            result = &mut probe => return Some(result),
+            _ = self.cancelled() => {}
+        }
+        if !open.load(Ordering::Relaxed) {
+            return None;
+        }
+        debug!("Scan cancelled; letting detection on an open port finish");
+        tokio::time::timeout(drain_timeout, probe).await.ok()
+    }
+}
+
+impl Default for ScanCancellation {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Mark the running probe as having found its port open, so cancelling the
+/// scan lets its detection finish; does nothing outside a scan
+pub fn mark_open() {
+    let _ = OPEN.try_with(|open| open.store(true, Ordering::Relaxed));
+}
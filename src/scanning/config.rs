@@ -1,12 +1,16 @@
-/// Scan configuration and modes
+//! Scan configuration and modes
 
+use std::future::Future;
 use std::net::IpAddr;
+use rand::seq::SliceRandom;
 use std::time::Duration;
 use serde::Serialize;
 
 use crate::constants::*;
 use crate::errors::{ConfigError, ConfigResult};
-use crate::domain::Port;
+use crate::domain::{CommonPortSet, Port, Protocol};
+use crate::infrastructure::{AttemptCounter, ReadLimits, ScanRng};
+use crate::scanning::ScanCancellation;
 
 /// Scan mode for port scanning
 #[derive(Debug, Clone, PartialEq, Serialize)]
@@ -15,8 +19,12 @@ pub enum ScanMode {
     Range { start: Port, end: Port },
     /// Scan only common ports
     CommonPorts,
+    /// Scan the common ports of one kind of service, e.g. databases
+    CommonSet(CommonPortSet),
     /// Scan a custom list of ports
     CustomList(Vec<Port>),
+    /// Scan `count` ports drawn at random from the inclusive `range`
+    Sample { range: (Port, Port), count: usize },
 }
 
 impl ScanMode {
@@ -26,54 +34,514 @@ impl ScanMode {
                 if start > end {
                     return Err(ConfigError::InvalidScanMode);
                 }
-                if *start < MIN_PORT || *end > MAX_PORT {
+                if !(MIN_PORT..=MAX_PORT).contains(start) || !(MIN_PORT..=MAX_PORT).contains(end) {
                     return Err(ConfigError::InvalidScanMode);
                 }
                 Ok(())
             }
-            ScanMode::CommonPorts => Ok(()),
+            ScanMode::CommonPorts | ScanMode::CommonSet(_) => Ok(()),
             ScanMode::CustomList(ports) => {
                 if ports.is_empty() {
                     return Err(ConfigError::InvalidScanMode);
                 }
                 for &port in ports {
-                    if port < MIN_PORT || port > MAX_PORT {
+                    if !(MIN_PORT..=MAX_PORT).contains(&port) {
                         return Err(ConfigError::InvalidScanMode);
                     }
                 }
                 Ok(())
             }
+            ScanMode::Sample { range: (start, end), count } => {
+                ScanMode::Range { start: *start, end: *end }.validate()?;
+                if *count == 0 || *count > (end - start) as usize + 1 {
+                    return Err(ConfigError::InvalidScanMode);
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Number of ports a `Sample` draws from; `None` for other modes
+    pub fn sample_population(&self) -> Option<usize> {
+        match self {
+            ScanMode::Sample { range: (start, end), .. } => Some((end - start) as usize + 1),
+            _ => None,
         }
     }
 
+    /// `CommonSet` named by `label`, e.g. `web`, `db` or `remote`.
+    ///
+    /// ```
+    /// use port_scanner::domain::CommonPortSet;
+    /// use port_scanner::scanning::{ScanConfigBuilder, ScanMode};
+    /// use port_scanner::ConfigError;
+    ///
+    /// let expected: [(&str, &[u16]); 3] = [
+    ///     ("web", &[80, 443, 3000, 5000, 8000, 8008, 8080, 8081, 8443, 8888, 9000, 9443]),
+    ///     ("db", &[1433, 1521, 3306, 5432, 5984, 6379, 9042, 9200, 11211, 27017]),
+    ///     ("remote", &[22, 23, 512, 513, 514, 3389, 5900, 5901, 5985, 5986]),
+    /// ];
+    /// for (label, ports) in expected {
+    ///     let mode = ScanMode::common_set(label).unwrap();
+    ///     assert_eq!(mode, ScanMode::CommonSet(CommonPortSet::from_label(label).unwrap()));
+    ///     let config = ScanConfigBuilder::new()
+    ///         .target("127.0.0.1".parse().unwrap())
+    ///         .scan_mode(mode)
+    ///         .build()
+    ///         .unwrap();
+    ///     assert_eq!(config.get_ports(), ports);
+    ///     assert_eq!(config.port_count(), ports.len());
+    /// }
+    /// assert_eq!(ScanMode::common_set("DB").unwrap(), ScanMode::CommonSet(CommonPortSet::Db));
+    /// assert!(matches!(ScanMode::common_set("games"), Err(ConfigError::UnknownPortSet(name)) if name == "games"));
+    /// ```
+    pub fn common_set(label: &str) -> ConfigResult<Self> {
+        CommonPortSet::from_label(label)
+            .map(ScanMode::CommonSet)
+            .ok_or_else(|| ConfigError::UnknownPortSet(label.to_string()))
+    }
+
+    /// The `n` most frequently open ports, most common first
+    pub fn top_ports(n: usize) -> Self {
+        ScanMode::CustomList(TOP_PORTS_BY_FREQUENCY.iter().copied().take(n).collect())
+    }
+
     pub fn port_count(&self) -> usize {
         match self {
             ScanMode::Range { start, end } => (end - start + 1) as usize,
             ScanMode::CommonPorts => 26, // Approximate
+            ScanMode::CommonSet(set) => set.ports().len(),
             ScanMode::CustomList(ports) => ports.len(),
+            ScanMode::Sample { count, .. } => *count,
+        }
+    }
+}
+
+/// Order in which ports are probed.
+///
+/// Results are sorted by port afterwards, whatever the probe order.
+///
+/// ```
+/// use port_scanner::prelude::*;
+///
+/// let ports = |order| -> anyhow::Result<Vec<Port>> {
+///     Ok(ScanConfigBuilder::new()
+///         .target("10.0.0.1".parse()?)
+///         .custom_ports(vec![49152, 8, 443, 1000, 80])
+///         .ordering(order)
+///         .build()?
+///         .get_ports())
+/// };
+///
+/// let frequent = ports(ScanOrder::Frequency)?;
+/// let rank = |port| frequent.iter().position(|&p| p == port).unwrap();
+/// assert!(rank(80) < rank(49152) && rank(443) < rank(49152));
+///
+/// let mut shuffled = ports(ScanOrder::Random)?;
+/// shuffled.sort_unstable();
+/// assert_eq!(shuffled, ports(ScanOrder::Numeric)?);
+/// assert_eq!(shuffled, [8, 80, 443, 1000, 49152]);
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+pub enum ScanOrder {
+    /// Ascending port number
+    #[default]
+    Numeric,
+    /// Most frequently open ports first, then the rest ascending
+    Frequency,
+    /// Shuffled, to avoid a sequential sweep pattern
+    Random,
+}
+
+impl ScanOrder {
+    /// Reorder `ports` in place, shuffling with `rng` for `Random`
+    pub fn apply(&self, ports: &mut [Port], rng: &ScanRng) {
+        match self {
+            ScanOrder::Numeric => ports.sort_unstable(),
+            ScanOrder::Frequency => ports.sort_by_key(|&port| {
+                let rank = TOP_PORTS_BY_FREQUENCY
+                    .iter()
+                    .position(|&p| p == port)
+                    .unwrap_or(usize::MAX);
+                (rank, port)
+            }),
+            ScanOrder::Random => rng.with(|rng| ports.shuffle(rng)),
+        }
+    }
+}
+
+/// Transports each port is probed over.
+///
+/// With `Both`, every port yields a TCP and a UDP result:
+///
+/// ```
+/// use port_scanner::prelude::*;
+/// use std::net::{IpAddr, TcpListener};
+///
+/// # #[tokio::main]
+/// # async fn main() -> anyhow::Result<()> {
+/// let listener = TcpListener::bind("127.0.0.1:0")?;
+/// let port = listener.local_addr()?.port();
+///
+/// let hosts = Scan::builder()
+///     .targets(["127.0.0.1".parse::<IpAddr>()?])
+///     .ports(vec![port])
+///     .configure(|config| config
+///         .protocol(ScanProtocol::Both)
+///         .timeout(std::time::Duration::from_millis(300)))
+///     .run()
+///     .await?;
+///
+/// let results = &hosts[0].results.results;
+/// assert_eq!(results.len(), 2);
+/// assert_eq!((results[0].protocol, &results[0].status), (Protocol::Tcp, &PortStatus::Open));
+/// assert_eq!(results[1].protocol, Protocol::Udp);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+pub enum ScanProtocol {
+    #[default]
+    Tcp,
+    Udp,
+    /// One TCP and one UDP probe per port
+    Both,
+}
+
+impl ScanProtocol {
+    /// Transports to probe, TCP first
+    pub fn protocols(&self) -> &'static [Protocol] {
+        match self {
+            ScanProtocol::Tcp => &[Protocol::Tcp],
+            ScanProtocol::Udp => &[Protocol::Udp],
+            ScanProtocol::Both => &[Protocol::Tcp, Protocol::Udp],
+        }
+    }
+
+    pub fn includes(&self, protocol: Protocol) -> bool {
+        self.protocols().contains(&protocol)
+    }
+}
+
+/// How each port is probed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ScanType {
+    /// Full TCP connect
+    #[default]
+    Connect,
+    /// Half-open SYN over raw sockets
+    Syn,
+    /// Bare FIN segment
+    Fin,
+    /// Segment with no flags set
+    Null,
+    /// FIN, PSH and URG set
+    Xmas,
+}
+
+impl ScanType {
+    pub const ALL: [ScanType; 5] = [ScanType::Connect, ScanType::Syn, ScanType::Fin, ScanType::Null, ScanType::Xmas];
+
+    /// Needs raw sockets (the `syn-scan` feature and root/CAP_NET_RAW)
+    pub fn is_raw(&self) -> bool {
+        !matches!(self, ScanType::Connect)
+    }
+}
+
+impl std::fmt::Display for ScanType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            ScanType::Connect => "TCP connect",
+            ScanType::Syn => "SYN",
+            ScanType::Fin => "FIN",
+            ScanType::Null => "NULL",
+            ScanType::Xmas => "Xmas",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Bundled settings for common scan profiles.
+///
+/// A preset only seeds a builder; settings applied afterwards override it.
+///
+/// ```
+/// use port_scanner::prelude::*;
+/// use std::time::Duration;
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let build = |preset: ScanPreset| preset.builder().target("127.0.0.1".parse().unwrap()).build();
+///
+/// let fast = build(ScanPreset::Fast)?;
+/// assert_eq!((fast.timeout, fast.get_ports().len()), (Duration::from_millis(200), 100));
+/// assert_eq!(fast.concurrency(), 1000);
+/// assert!(!fast.detect_versions && !fast.detect_os);
+///
+/// let normal = build(ScanPreset::Normal)?;
+/// assert_eq!((normal.timeout, normal.scan_mode.clone()), (Duration::from_millis(500), ScanMode::CommonPorts));
+/// assert!(!normal.detect_versions && !normal.detect_os);
+///
+/// let thorough = build(ScanPreset::Thorough)?;
+/// assert_eq!((thorough.timeout, thorough.get_ports().len()), (Duration::from_secs(2), 65535));
+/// assert!(thorough.detect_versions && thorough.detect_os && thorough.verify_filtered);
+///
+/// // Explicit settings win over the preset
+/// let custom = ScanPreset::Fast.builder()
+///     .target("127.0.0.1".parse()?)
+///     .timeout(Duration::from_secs(1))
+///     .range(1, 1000)
+///     .build()?;
+/// assert_eq!((custom.timeout, custom.get_ports().len()), (Duration::from_secs(1), 1000));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScanPreset {
+    /// Top 100 ports, short timeout, high concurrency, no detection
+    Fast,
+    /// Common ports with the default timeout and concurrency
+    Normal,
+    /// All ports, long timeout, service and OS detection
+    Thorough,
+}
+
+impl ScanPreset {
+    /// Builder seeded with this preset's settings
+    pub fn builder(self) -> ScanConfigBuilder {
+        let builder = ScanConfigBuilder::new();
+        match self {
+            ScanPreset::Fast => builder
+                .scan_mode(ScanMode::top_ports(FAST_PRESET_PORTS))
+                .timeout(Duration::from_millis(FAST_PRESET_TIMEOUT_MS))
+                .max_concurrent(Some(FAST_PRESET_CONCURRENCY))
+                .ordering(ScanOrder::Frequency)
+                .detect_versions(false)
+                .detect_os(false),
+            ScanPreset::Normal => builder
+                .common_ports()
+                .timeout(DEFAULT_TIMEOUT),
+            ScanPreset::Thorough => builder
+                .range(MIN_PORT, MAX_PORT)
+                .timeout(Duration::from_millis(THOROUGH_PRESET_TIMEOUT_MS))
+                .detect_versions(true)
+                .detect_os(true)
+                .verify_filtered(true),
+        }
+    }
+}
+
+/// Named port sets and detection settings for compliance scans.
+///
+/// Like a preset, a profile only seeds a builder (or is applied to one);
+/// settings applied afterwards override it.
+///
+/// ```
+/// use port_scanner::constants::{COMMON_VULNS_PROFILE_PORTS, PCI_DSS_PROFILE_PORTS};
+/// use port_scanner::prelude::*;
+/// use port_scanner::scanning::ScanProfile;
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let pci = ScanProfile::PciDss.builder().target("127.0.0.1".parse()?).build()?;
+/// assert_eq!(pci.scan_mode, ScanMode::CustomList(vec![
+///     21, 22, 23, 25, 53, 80, 110, 135, 139, 143, 443, 445,
+///     1433, 1521, 3306, 3389, 5432, 5900, 8080, 8443, 27017,
+/// ]));
+/// assert!(pci.detect_versions && !pci.detect_os);
+///
+/// let vulns = ScanProfile::CommonVulns.builder().target("127.0.0.1".parse()?).build()?;
+/// assert_eq!(vulns.scan_mode, ScanMode::CustomList(vec![
+///     21, 22, 23, 80, 111, 135, 139, 443, 445, 512, 513, 514, 1099,
+///     1433, 2049, 2375, 3306, 3389, 5432, 5900, 5985, 6379, 8080, 9200, 11211, 27017,
+/// ]));
+/// assert!(vulns.detect_versions && vulns.detect_os);
+///
+/// assert_eq!(ScanProfile::PciDss.ports(), PCI_DSS_PROFILE_PORTS);
+/// assert_eq!(ScanProfile::CommonVulns.ports(), COMMON_VULNS_PROFILE_PORTS);
+///
+/// // Composes with other builder settings, which win when applied later
+/// let config = ScanProfile::PciDss.apply(ScanConfigBuilder::new().timeout(std::time::Duration::from_secs(3)))
+///     .detect_versions(false)
+///     .target("127.0.0.1".parse()?)
+///     .build()?;
+/// assert_eq!(config.timeout, std::time::Duration::from_secs(3));
+/// assert!(!config.detect_versions);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScanProfile {
+    /// Ports a PCI DSS external scan looks at, with version detection
+    PciDss,
+    /// Services behind frequently exploited CVEs, with version and OS detection
+    CommonVulns,
+}
+
+impl ScanProfile {
+    pub fn ports(self) -> &'static [Port] {
+        match self {
+            ScanProfile::PciDss => PCI_DSS_PROFILE_PORTS,
+            ScanProfile::CommonVulns => COMMON_VULNS_PROFILE_PORTS,
+        }
+    }
+
+    pub fn scan_mode(self) -> ScanMode {
+        ScanMode::CustomList(self.ports().to_vec())
+    }
+
+    pub fn detect_versions(self) -> bool {
+        match self {
+            ScanProfile::PciDss | ScanProfile::CommonVulns => true,
         }
     }
+
+    pub fn detect_os(self) -> bool {
+        matches!(self, ScanProfile::CommonVulns)
+    }
+
+    /// `builder` with this profile's ports and detection settings
+    pub fn apply(self, builder: ScanConfigBuilder) -> ScanConfigBuilder {
+        builder
+            .scan_mode(self.scan_mode())
+            .detect_versions(self.detect_versions())
+            .detect_os(self.detect_os())
+    }
+
+    /// Builder seeded with this profile's settings
+    pub fn builder(self) -> ScanConfigBuilder {
+        self.apply(ScanConfigBuilder::new())
+    }
 }
 
 /// Configuration for port scanning
 #[derive(Debug, Clone)]
 pub struct ScanConfig {
     pub target_ip: IpAddr,
+    /// IPv6 zone index for link-local targets (0 when unscoped)
+    pub scope_id: u32,
     pub scan_mode: ScanMode,
+    /// Never probe ports below this, whatever the scan mode selects
+    pub min_port: Option<Port>,
+    /// Never probe ports above this, whatever the scan mode selects
+    pub max_port: Option<Port>,
     pub timeout: Duration,
     pub verbose: bool,
     pub detect_versions: bool,
     pub detect_os: bool,
+    /// Only run service and OS detection on these ports; `None` lets the
+    /// detectors pick from every open port
+    pub detect_ports: Option<Vec<Port>>,
     pub parallel: bool,
     pub thread_count: usize,
     pub randomize_source_port: bool,
     pub delay_between_probes: Option<Duration>,
+    /// Domain to attempt a zone transfer for when DNS is detected
+    pub dns_axfr_domain: Option<String>,
+    /// Grab banners over the scan connection instead of reconnecting
+    pub reuse_connection: bool,
+    /// Close probe connections with a FIN instead of letting a drop reset them
+    pub graceful_close: bool,
+    /// `SO_LINGER` on probe sockets; zero resets on close so no TIME_WAIT
+    /// entry ties up the local port. `None` keeps the OS default
+    pub linger: Option<Duration>,
+    /// Enable TCP keepalive on probe sockets, idle for this long before the first probe
+    pub keepalive: Option<Duration>,
+    /// Send connect and UDP probes through this network interface
+    /// (`SO_BINDTODEVICE`, Linux only)
+    pub bind_interface: Option<String>,
+    /// Order in which ports are probed
+    pub ordering: ScanOrder,
+    /// Shuffle the whole host×port work list, so probes jump between hosts
+    /// as well as ports; overrides `ordering`
+    pub randomize_scan_order: bool,
+    /// Transports each port is probed over
+    pub protocol: ScanProtocol,
+    /// Report UDP ports that answer with ICMP port-unreachable as closed.
+    /// Off, they stay open|filtered like silent ports, for platforms or
+    /// paths that drop or fake ICMP errors
+    pub udp_icmp_closed: bool,
+    /// Concurrent async probes; `None` derives it from `thread_count`
+    pub max_concurrent: Option<usize>,
+    /// SNMP community strings to try (empty uses the default)
+    pub snmp_communities: Vec<String>,
+    /// Stop running detectors on a port once one reports at least this
+    /// confidence; `None` runs them all and keeps the most confident
+    pub detection_early_exit: Option<f32>,
+    /// Hard ceiling on total scan time; partial results are returned
+    pub max_scan_duration: Option<Duration>,
+    /// How long probes that found their port open may keep detecting once
+    /// the scan is cancelled; the others stop at once
+    pub drain_timeout: Duration,
+    /// Stop scanning a host once more than this many ports are open
+    pub max_open_ports: Option<usize>,
+    /// Re-probe filtered ports with a longer timeout before reporting them
+    pub verify_filtered: bool,
+    /// Re-probe ports whose connect was reset, as stateful firewalls under
+    /// load sometimes reset open ports; refused connects stay closed
+    pub retry_on_reset: bool,
+    /// Re-probe ports that timed out or errored up to this many more times
+    pub probe_retries: usize,
+    /// Give up retrying a port once it has errored this many times, so
+    /// ports that always fail (e.g. through a flaky proxy) stop costing
+    /// probes; timeouts keep every retry. `None` never gives up early
+    pub max_port_errors: Option<usize>,
+    /// Random high ports probed to tell a firewalled host from a down one
+    /// when most ports are filtered; 0 disables the check
+    pub closed_port_samples: usize,
+    /// Probe type; raw-socket types fall back to a connect scan
+    pub scan_type: ScanType,
+    /// IP TTL of raw-socket probes; `None` uses 64
+    pub ttl: Option<u8>,
+    /// TCP window of raw-socket probes; `None` uses 1024
+    pub tcp_window: Option<u16>,
+    /// Fail instead of falling back when a feature lacks privileges
+    pub require_privileges: bool,
+    /// Start with few concurrent probes and double up to the limit
+    pub ramp_up: bool,
+    /// Seed for source ports, jitter and random ordering; `None` uses entropy
+    pub seed: Option<u64>,
+    /// Most bytes a detector reads from one connection
+    pub max_read_bytes: usize,
+    /// Longest a detector keeps reading from one connection
+    pub max_read_duration: Duration,
+    /// Longest a detector waits between chunks of a reply that has started
+    pub max_read_idle: Duration,
+    /// Pause between connecting and the first banner read, for services
+    /// that greet late or drop clients that speak first; `None` reads at once
+    pub banner_initial_delay: Option<Duration>,
+    /// How long to wait for a banner once connected; `None` uses
+    /// `BANNER_READ_TIMEOUT_MS`. `timeout` only bounds the connect
+    pub banner_timeout: Option<Duration>,
+    /// Keep the hex of the leading raw banner bytes (`banner_hex`) in results
+    pub include_raw_banner: bool,
+    /// TCP connects and UDP probes made so far, counted across retries and
+    /// detection; shared by clones and reset per scan by `PortScanner`
+    pub connection_attempts: AttemptCounter,
+    /// Cancels the running scan; shared by clones and reset per scan by
+    /// `PortScanner`
+    pub cancellation: ScanCancellation,
+    /// Random source for source ports, jitter, sampling and random order,
+    /// seeded from `seed`; shared by clones and reseeded per scan by
+    /// `PortScanner`
+    pub rng: ScanRng,
 }
 
+/// Ports of `ScanMode::CommonPorts`
+const COMMON_SCAN_PORTS: [Port; 26] = [
+    21, 22, 23, 25, 53, 80, 110, 111, 135, 139, 143, 443, 445, 993, 995,
+    1723, 3306, 3389, 5432, 5900, 6379, 8080, 8443, 8888, 9090, 27017,
+];
+
 impl ScanConfig {
     /// Validate the configuration
     pub fn validate(&self) -> ConfigResult<()> {
         self.scan_mode.validate()?;
+
+        let (min, max) = self.port_clamp();
+        if min < MIN_PORT || min > max {
+            return Err(ConfigError::InvalidPortClamp(format!("{}-{}", min, max)));
+        }
         
         if self.timeout.as_millis() == 0 {
             return Err(ConfigError::InvalidTimeout(self.timeout));
@@ -82,27 +550,310 @@ impl ScanConfig {
         if self.parallel && self.thread_count == 0 {
             return Err(ConfigError::InvalidThreadCount(self.thread_count));
         }
+
+        if self.max_concurrent == Some(0) {
+            return Err(ConfigError::InvalidConcurrency(0));
+        }
+
+        if let Some(limit) = self.max_scan_duration.filter(|limit| limit.is_zero()) {
+            return Err(ConfigError::InvalidTimeout(limit));
+        }
+
+        if let Some(timeout) = self.banner_timeout.filter(|timeout| timeout.is_zero()) {
+            return Err(ConfigError::InvalidTimeout(timeout));
+        }
+
+        if let Some(threshold) = self.detection_early_exit.filter(|t| !(0.0..=1.0).contains(t)) {
+            return Err(ConfigError::InvalidConfidence(threshold));
+        }
+
+        if self.max_read_bytes == 0 {
+            return Err(ConfigError::InvalidReadLimit(self.max_read_bytes));
+        }
+
+        if self.max_read_duration.is_zero() {
+            return Err(ConfigError::InvalidTimeout(self.max_read_duration));
+        }
+
+        if self.max_read_idle.is_zero() {
+            return Err(ConfigError::InvalidTimeout(self.max_read_idle));
+        }
+
+        if self.ttl == Some(0) {
+            return Err(ConfigError::InvalidPacketField("TTL must be 1-255".to_string()));
+        }
+
+        if self.tcp_window == Some(0) {
+            return Err(ConfigError::InvalidPacketField("TCP window must be 1-65535".to_string()));
+        }
+
+        if let Some(interface) = &self.bind_interface {
+            crate::infrastructure::network_utils::check_bind_interface(interface)?;
+        }
         
         Ok(())
     }
 
-    /// Get the list of ports to scan
+    /// Get the list of ports to scan, in probe order.
+    ///
+    /// A `Sample` draws distinct ports from the scan's (seedable) RNG:
+    ///
+    /// ```
+    /// use port_scanner::scanning::{ScanConfigBuilder, ScanMode};
+    ///
+    /// let config = ScanConfigBuilder::new()
+    ///     .target("127.0.0.1".parse().unwrap())
+    ///     .scan_mode(ScanMode::Sample { range: (1000, 1999), count: 50 })
+    ///     .build()
+    ///     .unwrap();
+    /// let mut ports = config.get_ports();
+    /// assert_eq!(ports.len(), 50);
+    /// assert!(ports.iter().all(|port| (1000..=1999).contains(port)));
+    /// ports.dedup();
+    /// assert_eq!(ports.len(), 50);
+    /// ```
     pub fn get_ports(&self) -> Vec<Port> {
-        match &self.scan_mode {
-            ScanMode::Range { start, end } => (*start..=*end).collect(),
-            ScanMode::CommonPorts => {
-                vec![
-                    21, 22, 23, 25, 53, 80, 110, 111, 135, 139, 143, 443, 445, 993, 995,
-                    1723, 3306, 3389, 5432, 5900, 6379, 8080, 8443, 8888, 9090, 27017
-                ]
-            }
-            ScanMode::CustomList(ports) => ports.clone(),
+        let mut ports = match &self.scan_mode {
+            ScanMode::Range { start, end } => match self.clamp_range(*start, *end) {
+                Some((start, end)) => (start..=end).collect(),
+                None => Vec::new(),
+            },
+            ScanMode::CommonPorts => COMMON_SCAN_PORTS.iter().copied().filter(|&port| self.in_port_clamp(port)).collect(),
+            ScanMode::CommonSet(set) => set.ports().iter().copied().filter(|&port| self.in_port_clamp(port)).collect(),
+            ScanMode::CustomList(ports) => ports.iter().copied().filter(|&port| self.in_port_clamp(port)).collect(),
+            ScanMode::Sample { range: (start, end), count } => match self.clamp_range(*start, *end) {
+                // Sample within the clamp, so only an overlap too small for `count` shrinks it
+                Some((start, end)) => {
+                    let population = (end - start) as usize + 1;
+                    self.rng.with(|rng| {
+                        rand::seq::index::sample(rng, population, (*count).min(population))
+                            .into_iter()
+                            .map(|offset| start + offset as Port)
+                            .collect()
+                    })
+                }
+                None => Vec::new(),
+            },
+        };
+        if self.randomize_scan_order {
+            ScanOrder::Random.apply(&mut ports, &self.rng);
+        } else {
+            self.ordering.apply(&mut ports, &self.rng);
+        }
+        ports
+    }
+
+    /// Number of concurrent probes for the parallel executor.
+    ///
+    /// Uses `max_concurrent` when set, otherwise `thread_count * TASKS_PER_THREAD`.
+    /// `ParallelExecutor::new` still clamps the result to
+    /// `MIN_CONCURRENCY..=MAX_CONCURRENCY`.
+    pub fn concurrency(&self) -> usize {
+        self.max_concurrent
+            .unwrap_or(self.thread_count * TASKS_PER_THREAD)
+    }
+
+    /// Byte and time ceilings for detector reads
+    pub fn read_limits(&self) -> ReadLimits {
+        ReadLimits {
+            max_bytes: self.max_read_bytes,
+            max_duration: self.max_read_duration,
+            max_idle: self.max_read_idle,
         }
     }
 
+    /// Run `future` as part of this scan: its connections count into
+    /// `connection_attempts`, `with_rng` draws from `rng` and detector reads
+    /// stop at `read_limits()`
+    pub(crate) async fn scope<F: Future>(&self, future: F) -> F::Output {
+        let future = self.connection_attempts.scope(future);
+        self.read_limits().scope(self.rng.scope(future)).await
+    }
+
+    /// Whether service and OS detection may probe `port`.
+    ///
+    /// With `detect_ports` set, detection runs on those ports only, however
+    /// many other ports are open; the detectors still choose which of them
+    /// they understand. Other open ports keep the port-number service guess.
+    ///
+    /// ```
+    /// use port_scanner::prelude::*;
+    /// use std::io::Write;
+    /// use std::net::TcpListener;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// // Three open ports, all greeting like an SSH server
+    /// let mut ports = Vec::new();
+    /// for _ in 0..3 {
+    ///     let listener = TcpListener::bind("127.0.0.1:0")?;
+    ///     ports.push(listener.local_addr()?.port());
+    ///     std::thread::spawn(move || {
+    ///         for mut stream in listener.incoming().flatten() {
+    ///             let _ = stream.write_all(b"SSH-2.0-OpenSSH_9.6\r\n");
+    ///         }
+    ///     });
+    /// }
+    ///
+    /// let config = ScanConfigBuilder::new()
+    ///     .target("127.0.0.1".parse()?)
+    ///     .custom_ports(ports.clone())
+    ///     .detect_versions(true)
+    ///     .detect_ports(Some(vec![ports[0]]))
+    ///     .build()?;
+    /// assert!(config.detects_on(ports[0]) && !config.detects_on(ports[1]));
+    ///
+    /// let results = PortScanner::new(config)?.scan_all(|_| {}).await;
+    /// assert_eq!(results.open_ports, 3);
+    /// for result in &results.results {
+    ///     let product = result.service_version.as_ref().and_then(|v| v.product.as_deref());
+    ///     if result.port == ports[0] {
+    ///         assert_eq!(product, Some("OpenSSH"));
+    ///     } else {
+    ///         assert_eq!(product, None);
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn detects_on(&self, port: Port) -> bool {
+        self.detect_ports.as_ref().is_none_or(|ports| ports.contains(&port))
+    }
+
     /// Get the number of ports to scan
     pub fn port_count(&self) -> usize {
-        self.scan_mode.port_count()
+        self.scan_mode.port_count() - self.clamped_port_count()
+    }
+
+    /// Lowest and highest port any scan mode may probe
+    pub fn port_clamp(&self) -> (Port, Port) {
+        (self.min_port.unwrap_or(MIN_PORT), self.max_port.unwrap_or(MAX_PORT))
+    }
+
+    fn in_port_clamp(&self, port: Port) -> bool {
+        let (min, max) = self.port_clamp();
+        (min..=max).contains(&port)
+    }
+
+    /// `start..=end` narrowed to the port clamp; `None` when nothing is left
+    fn clamp_range(&self, start: Port, end: Port) -> Option<(Port, Port)> {
+        let (min, max) = self.port_clamp();
+        let (start, end) = (start.max(min), end.min(max));
+        (start <= end).then_some((start, end))
+    }
+
+    /// Ports of the scan mode that `min_port`/`max_port` drop
+    fn clamped_port_count(&self) -> usize {
+        let clamped_len = |start, end| self.clamp_range(start, end).map_or(0, |(start, end)| (end - start) as usize + 1);
+        match &self.scan_mode {
+            ScanMode::Range { start, end } => self.scan_mode.port_count() - clamped_len(*start, *end),
+            ScanMode::CommonPorts => COMMON_SCAN_PORTS.iter().filter(|&&port| !self.in_port_clamp(port)).count(),
+            ScanMode::CommonSet(set) => set.ports().iter().filter(|&&port| !self.in_port_clamp(port)).count(),
+            ScanMode::CustomList(ports) => ports.iter().filter(|&&port| !self.in_port_clamp(port)).count(),
+            ScanMode::Sample { range: (start, end), count } => count - (*count).min(clamped_len(*start, *end)),
+        }
+    }
+
+    /// Warning to show when `min_port`/`max_port` drop ports the scan mode
+    /// selected; `PortScanner::new` logs it.
+    ///
+    /// ```
+    /// use port_scanner::scanning::ScanConfigBuilder;
+    ///
+    /// let config = ScanConfigBuilder::new()
+    ///     .target("127.0.0.1".parse().unwrap())
+    ///     .range(1, 100)
+    ///     .min_port(Some(10))
+    ///     .build()
+    ///     .unwrap();
+    /// assert_eq!(config.get_ports(), (10..=100).collect::<Vec<_>>());
+    /// assert_eq!(config.port_count(), 91);
+    /// assert_eq!(
+    ///     config.port_clamp_warning().as_deref(),
+    ///     Some("Dropping 9 ports outside the allowed range 10-65535"),
+    /// );
+    ///
+    /// let unclamped = ScanConfigBuilder::from(config).min_port(None).build().unwrap();
+    /// assert_eq!(unclamped.port_clamp_warning(), None);
+    /// ```
+    pub fn port_clamp_warning(&self) -> Option<String> {
+        let dropped = self.clamped_port_count();
+        let (min, max) = self.port_clamp();
+        (dropped > 0).then(|| format!("Dropping {} port{} outside the allowed range {}-{}", dropped, if dropped == 1 { "" } else { "s" }, min, max))
+    }
+
+    /// Number of probes (and results) a scan makes: one per port and transport
+    pub fn probe_count(&self) -> usize {
+        self.port_count() * self.protocol.protocols().len()
+    }
+
+    /// Rough scan time for planning, before any probe is sent.
+    ///
+    /// Probes run in waves of `concurrency()` (one at a time without
+    /// `parallel`), each taking the probe delay plus its own time. The worst
+    /// case has every probe time out (and re-probed by `verify_filtered`);
+    /// the expected case has `ESTIMATE_SILENT_SHARE` of them time out and the
+    /// rest answered within `ESTIMATE_ANSWER_TIME`. Service detection and
+    /// host checks are not included. Both are capped by `max_scan_duration`.
+    ///
+    /// ```
+    /// use port_scanner::prelude::*;
+    /// use std::time::Duration;
+    ///
+    /// let config = |ports: u16, concurrency: usize, timeout_ms: u64| {
+    ///     ScanConfigBuilder::new()
+    ///         .target("127.0.0.1".parse().unwrap())
+    ///         .range(1, ports)
+    ///         .parallel(true)
+    ///         .max_concurrent(Some(concurrency))
+    ///         .timeout(Duration::from_millis(timeout_ms))
+    ///         .build()
+    ///         .unwrap()
+    ///         .estimated_duration()
+    /// };
+    ///
+    /// let base = config(1000, 100, 1000);
+    /// assert_eq!(base.worst_case, Duration::from_secs(10));
+    /// assert!(base.expected < base.worst_case);
+    ///
+    /// // Twice the ports, twice the time; twice the concurrency, half
+    /// assert_eq!(config(2000, 100, 1000).worst_case, base.worst_case * 2);
+    /// assert_eq!(config(2000, 100, 1000).expected, base.expected * 2);
+    /// assert_eq!(config(1000, 200, 1000).worst_case, base.worst_case / 2);
+    /// assert_eq!(config(1000, 200, 1000).expected, base.expected / 2);
+    ///
+    /// // The worst case grows with the timeout, the expected case less so
+    /// let slow = config(1000, 100, 3000);
+    /// assert_eq!(slow.worst_case, base.worst_case * 3);
+    /// assert!(slow.expected > base.expected && slow.expected < base.expected * 3);
+    /// assert_eq!(base.to_string(), "~1.2s (worst case 10s)");
+    /// ```
+    pub fn estimated_duration(&self) -> DurationEstimate {
+        let slots = if self.parallel {
+            self.concurrency().clamp(MIN_CONCURRENCY, MAX_CONCURRENCY)
+        } else {
+            1
+        };
+        let waves = self.probe_count().div_ceil(slots) as u32;
+        let delay = self.delay_between_probes.unwrap_or_default();
+        let verify = if self.verify_filtered { self.timeout * FILTERED_VERIFY_TIMEOUT_FACTOR } else { Duration::ZERO };
+
+        let worst_case = (delay + self.timeout + verify) * waves;
+        let silent = (self.timeout + verify).mul_f64(ESTIMATE_SILENT_SHARE);
+        let answered = ESTIMATE_ANSWER_TIME.mul_f64(1.0 - ESTIMATE_SILENT_SHARE);
+        let expected = (delay + silent + answered) * waves;
+
+        let cap = |estimate: Duration| self.max_scan_duration.map_or(estimate, |limit| estimate.min(limit));
+        DurationEstimate { expected: cap(expected), worst_case: cap(worst_case) }
+    }
+
+    /// Target as displayed to users, including any IPv6 zone
+    pub fn target_display(&self) -> String {
+        if self.scope_id != 0 && self.target_ip.is_ipv6() {
+            format!("{}%{}", self.target_ip, self.scope_id)
+        } else {
+            self.target_ip.to_string()
+        }
     }
 
     /// Check if stealth mode is enabled
@@ -111,33 +862,138 @@ impl ScanConfig {
     }
 }
 
+/// Expected and worst-case scan time, see `ScanConfig::estimated_duration`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DurationEstimate {
+    pub expected: Duration,
+    pub worst_case: Duration,
+}
+
+impl std::fmt::Display for DurationEstimate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "~{} (worst case {})", rough_duration(self.expected), rough_duration(self.worst_case))
+    }
+}
+
+/// Duration to about two significant figures: `850ms`, `1.2s`, `45s`, `3m 20s`, `2h 5m`
+fn rough_duration(duration: Duration) -> String {
+    let secs = duration.as_secs_f64();
+    if secs < 1.0 {
+        format!("{}ms", duration.as_millis())
+    } else if secs < 10.0 {
+        format!("{:.1}s", secs)
+    } else if secs < 60.0 {
+        format!("{:.0}s", secs)
+    } else if secs < 3600.0 {
+        let secs = secs.round() as u64;
+        format!("{}m {}s", secs / 60, secs % 60)
+    } else {
+        let mins = (secs / 60.0).round() as u64;
+        format!("{}h {}m", mins / 60, mins % 60)
+    }
+}
+
 /// Builder for ScanConfig
 pub struct ScanConfigBuilder {
     target_ip: Option<IpAddr>,
+    scope_id: u32,
     scan_mode: Option<ScanMode>,
+    min_port: Option<Port>,
+    max_port: Option<Port>,
     timeout: Duration,
     verbose: bool,
     detect_versions: bool,
     detect_os: bool,
+    detect_ports: Option<Vec<Port>>,
     parallel: bool,
     thread_count: usize,
     randomize_source_port: bool,
     delay_between_probes: Option<Duration>,
+    dns_axfr_domain: Option<String>,
+    reuse_connection: bool,
+    graceful_close: bool,
+    linger: Option<Duration>,
+    keepalive: Option<Duration>,
+    bind_interface: Option<String>,
+    ordering: ScanOrder,
+    randomize_scan_order: bool,
+    protocol: ScanProtocol,
+    udp_icmp_closed: bool,
+    max_concurrent: Option<usize>,
+    snmp_communities: Vec<String>,
+    detection_early_exit: Option<f32>,
+    max_scan_duration: Option<Duration>,
+    drain_timeout: Duration,
+    max_open_ports: Option<usize>,
+    verify_filtered: bool,
+    retry_on_reset: bool,
+    probe_retries: usize,
+    max_port_errors: Option<usize>,
+    closed_port_samples: usize,
+    scan_type: ScanType,
+    ttl: Option<u8>,
+    tcp_window: Option<u16>,
+    require_privileges: bool,
+    ramp_up: bool,
+    seed: Option<u64>,
+    max_read_bytes: usize,
+    max_read_duration: Duration,
+    max_read_idle: Duration,
+    banner_initial_delay: Option<Duration>,
+    banner_timeout: Option<Duration>,
+    include_raw_banner: bool,
 }
 
 impl ScanConfigBuilder {
     pub fn new() -> Self {
         Self {
             target_ip: None,
+            scope_id: 0,
             scan_mode: None,
+            min_port: None,
+            max_port: None,
             timeout: DEFAULT_TIMEOUT,
             verbose: DEFAULT_VERBOSE,
             detect_versions: DEFAULT_DETECT_VERSIONS,
             detect_os: DEFAULT_DETECT_OS,
+            detect_ports: None,
             parallel: DEFAULT_PARALLEL,
             thread_count: crate::infrastructure::network_utils::num_cpus(),
             randomize_source_port: DEFAULT_RANDOMIZE_SOURCE,
             delay_between_probes: None,
+            dns_axfr_domain: None,
+            reuse_connection: DEFAULT_REUSE_CONNECTION,
+            graceful_close: DEFAULT_GRACEFUL_CLOSE,
+            linger: None,
+            keepalive: None,
+            bind_interface: None,
+            ordering: ScanOrder::default(),
+            randomize_scan_order: false,
+            protocol: ScanProtocol::default(),
+            udp_icmp_closed: DEFAULT_UDP_ICMP_CLOSED,
+            max_concurrent: None,
+            snmp_communities: Vec::new(),
+            detection_early_exit: None,
+            max_scan_duration: None,
+            drain_timeout: DEFAULT_DRAIN_TIMEOUT,
+            max_open_ports: None,
+            verify_filtered: DEFAULT_VERIFY_FILTERED,
+            retry_on_reset: DEFAULT_RETRY_ON_RESET,
+            probe_retries: DEFAULT_PROBE_RETRIES,
+            max_port_errors: None,
+            closed_port_samples: DEFAULT_CLOSED_PORT_SAMPLES,
+            scan_type: ScanType::default(),
+            ttl: None,
+            tcp_window: None,
+            require_privileges: DEFAULT_REQUIRE_PRIVILEGES,
+            ramp_up: DEFAULT_RAMP_UP,
+            seed: None,
+            max_read_bytes: DEFAULT_MAX_READ_BYTES,
+            max_read_duration: DEFAULT_MAX_READ_DURATION,
+            max_read_idle: DEFAULT_MAX_READ_IDLE,
+            banner_initial_delay: None,
+            banner_timeout: None,
+            include_raw_banner: false,
         }
     }
 
@@ -146,6 +1002,48 @@ impl ScanConfigBuilder {
         self
     }
 
+    /// Set the IPv6 zone index used for link-local targets
+    pub fn scope_id(mut self, scope_id: u32) -> Self {
+        self.scope_id = scope_id;
+        self
+    }
+
+    /// Set the target from a string, accepting an IPv6 zone (`fe80::1%eth0`)
+    ///
+    /// ```
+    /// use port_scanner::infrastructure::network_utils;
+    /// use port_scanner::infrastructure::testing::{Reply, ScriptedConnector};
+    /// use port_scanner::prelude::*;
+    /// use port_scanner::scanning::StandardScan;
+    /// use std::net::SocketAddr;
+    /// use std::sync::Arc;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// let loopback = network_utils::interface_index("lo").expect("loopback interface");
+    /// let config = ScanConfigBuilder::new().target_str("fe80::1%lo")?.custom_ports(vec![22]).build()?;
+    /// assert_eq!(config.scope_id, loopback);
+    ///
+    /// let connector = Arc::new(ScriptedConnector::new(Reply::Refuse));
+    /// let strategy = StandardScan::new().with_connector(connector.clone());
+    /// PortScanner::new(config)?.with_strategy(Arc::new(strategy)).scan_all(|_| {}).await;
+    /// let SocketAddr::V6(addr) = connector.connects()[0] else { panic!("not IPv6") };
+    /// assert_eq!((addr.ip().to_string(), addr.port(), addr.scope_id()), ("fe80::1".into(), 22, loopback));
+    ///
+    /// assert!(matches!(
+    ///     ScanConfigBuilder::new().target_str("fe80::1%no-such-if0"),
+    ///     Err(ConfigError::UnknownInterface(name)) if name == "no-such-if0"
+    /// ));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn target_str(mut self, target: &str) -> ConfigResult<Self> {
+        let (ip, scope_id) = crate::infrastructure::network_utils::parse_scoped_ip(target)?;
+        self.target_ip = Some(ip);
+        self.scope_id = scope_id;
+        Ok(self)
+    }
+
     pub fn scan_mode(mut self, mode: ScanMode) -> Self {
         self.scan_mode = Some(mode);
         self
@@ -166,6 +1064,18 @@ impl ScanConfigBuilder {
         self
     }
 
+    /// Drop ports below `port` from any scan mode, see `ScanConfig::port_clamp_warning`
+    pub fn min_port(mut self, port: Option<Port>) -> Self {
+        self.min_port = port;
+        self
+    }
+
+    /// Drop ports above `port` from any scan mode
+    pub fn max_port(mut self, port: Option<Port>) -> Self {
+        self.max_port = port;
+        self
+    }
+
     pub fn timeout(mut self, timeout: Duration) -> Self {
         self.timeout = timeout;
         self
@@ -186,6 +1096,11 @@ impl ScanConfigBuilder {
         self
     }
 
+    pub fn detect_ports(mut self, ports: Option<Vec<Port>>) -> Self {
+        self.detect_ports = ports;
+        self
+    }
+
     pub fn parallel(mut self, parallel: bool) -> Self {
         self.parallel = parallel;
         self
@@ -206,6 +1121,612 @@ impl ScanConfigBuilder {
         self
     }
 
+    pub fn dns_axfr_domain(mut self, domain: Option<String>) -> Self {
+        self.dns_axfr_domain = domain;
+        self
+    }
+
+    /// Read the banner over the scan connection instead of reconnecting
+    /// for version detection.
+    ///
+    /// ```
+    /// use port_scanner::prelude::*;
+    /// use std::sync::Arc;
+    /// use std::sync::atomic::{AtomicUsize, Ordering};
+    /// use tokio::io::AsyncWriteExt;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// // Three SSH-like services counting the connections they accept
+    /// let accepted = Arc::new(AtomicUsize::new(0));
+    /// let mut ports = Vec::new();
+    /// for _ in 0..3 {
+    ///     let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+    ///     ports.push(listener.local_addr()?.port());
+    ///     let accepted = Arc::clone(&accepted);
+    ///     tokio::spawn(async move {
+    ///         while let Ok((mut socket, _)) = listener.accept().await {
+    ///             accepted.fetch_add(1, Ordering::SeqCst);
+    ///             let _ = socket.write_all(b"SSH-2.0-OpenSSH_9.6\r\n").await;
+    ///         }
+    ///     });
+    /// }
+    ///
+    /// for (reuse, connections) in [(true, 3), (false, 6)] {
+    ///     accepted.store(0, Ordering::SeqCst);
+    ///     let config = ScanConfigBuilder::new()
+    ///         .target("127.0.0.1".parse()?)
+    ///         .custom_ports(ports.clone())
+    ///         .detect_versions(true)
+    ///         .reuse_connection(reuse)
+    ///         .build()?;
+    ///     let results = PortScanner::new(config)?.scan_all(|_| {}).await;
+    ///
+    ///     assert!(results.results.iter().all(|r| r.service_version.as_ref().is_some_and(|v| v.service_name == "SSH")));
+    ///     assert_eq!(results.connection_attempts, connections);
+    ///     assert_eq!(accepted.load(Ordering::SeqCst), connections as usize);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn reuse_connection(mut self, reuse: bool) -> Self {
+        self.reuse_connection = reuse;
+        self
+    }
+
+    /// Close open-port probes with a FIN (the default) instead of dropping them.
+    ///
+    /// A drop resets the connection when unread data is pending or, as here,
+    /// when `SO_LINGER` is zero:
+    ///
+    /// ```
+    /// use port_scanner::prelude::*;
+    /// use std::io::ErrorKind;
+    /// use std::time::Duration;
+    /// use tokio::io::AsyncReadExt;
+    /// use tokio::net::TcpListener;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// // Reports whether each client closed cleanly (FIN) or reset
+    /// let listener = TcpListener::bind("127.0.0.1:0").await?;
+    /// let port = listener.local_addr()?.port();
+    /// let (closes, mut closed) = tokio::sync::mpsc::unbounded_channel();
+    /// tokio::spawn(async move {
+    ///     while let Ok((mut peer, _)) = listener.accept().await {
+    ///         let outcome = match peer.read(&mut [0u8; 64]).await {
+    ///             Ok(0) => "clean",
+    ///             Err(e) if e.kind() == ErrorKind::ConnectionReset => "reset",
+    ///             _ => "other",
+    ///         };
+    ///         let _ = closes.send(outcome);
+    ///     }
+    /// });
+    ///
+    /// for (graceful, expected) in [(true, "clean"), (false, "reset")] {
+    ///     let config = ScanConfigBuilder::new()
+    ///         .target("127.0.0.1".parse()?)
+    ///         .custom_ports(vec![port])
+    ///         .linger(Some(Duration::ZERO))
+    ///         .graceful_close(graceful)
+    ///         .build()?;
+    ///     let results = PortScanner::new(config)?.scan_all(|_| {}).await;
+    ///     assert_eq!(results.open_ports, 1);
+    ///     assert_eq!(closed.recv().await, Some(expected));
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn graceful_close(mut self, graceful: bool) -> Self {
+        self.graceful_close = graceful;
+        self
+    }
+
+    /// `Some(Duration::ZERO)` resets probe connections on close instead of
+    /// leaving them in TIME_WAIT, which keeps large scans from running out
+    /// of local ports
+    pub fn linger(mut self, linger: Option<Duration>) -> Self {
+        self.linger = linger;
+        self
+    }
+
+    pub fn keepalive(mut self, idle: Option<Duration>) -> Self {
+        self.keepalive = idle;
+        self
+    }
+
+    /// Send probes through the named interface, e.g. a VPN tunnel on a
+    /// multi-homed host; `build()` fails if it does not exist
+    ///
+    /// ```
+    /// use port_scanner::errors::ConfigError;
+    /// use port_scanner::scanning::ScanConfigBuilder;
+    ///
+    /// let result = ScanConfigBuilder::new()
+    ///     .target("127.0.0.1".parse().unwrap())
+    ///     .common_ports()
+    ///     .bind_interface(Some("no-such-nic0".to_string()))
+    ///     .build();
+    /// assert!(matches!(
+    ///     result,
+    ///     Err(ConfigError::UnknownInterface(_) | ConfigError::InterfaceBindUnsupported(_))
+    /// ));
+    /// ```
+    pub fn bind_interface(mut self, interface: Option<String>) -> Self {
+        self.bind_interface = interface;
+        self
+    }
+
+    pub fn ordering(mut self, ordering: ScanOrder) -> Self {
+        self.ordering = ordering;
+        self
+    }
+
+    /// Shuffle the whole host×port work list of a multi-host `Scan`
+    ///
+    /// ```
+    /// use port_scanner::application::Scan;
+    /// use port_scanner::infrastructure::testing::{Reply, ScriptedConnector};
+    /// use port_scanner::scanning::StandardScan;
+    /// use std::net::{IpAddr, SocketAddr};
+    /// use std::sync::Arc;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// let hosts: Vec<IpAddr> = (1..=3).map(|i| IpAddr::from([127, 0, 0, i])).collect();
+    /// let connector = Arc::new(ScriptedConnector::new(Reply::Refuse));
+    /// let results = Scan::builder()
+    ///     .targets(hosts.clone())
+    ///     .ports((1..=20).collect())
+    ///     .configure(|b| b.randomize_scan_order(true).parallel(false))
+    ///     .strategy(Arc::new(StandardScan::new().with_connector(connector.clone())))
+    ///     .run()
+    ///     .await?;
+    ///
+    /// let work: Vec<SocketAddr> = hosts.iter()
+    ///     .flat_map(|&ip| (1..=20).map(move |port| SocketAddr::new(ip, port)))
+    ///     .collect();
+    /// let connects = connector.connects();
+    /// assert_ne!(connects, work);
+    /// assert!(connects[..8].iter().any(|addr| addr.ip() != connects[0].ip()));
+    ///
+    /// // A permutation of the work list: every host:port pair exactly once
+    /// let mut sorted = connects.clone();
+    /// sorted.sort_unstable();
+    /// assert_eq!(sorted, work);
+    ///
+    /// // Results still come back sorted by port
+    /// assert!(results.iter().all(|host| host.results.results.windows(2).all(|w| w[0].port < w[1].port)));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn randomize_scan_order(mut self, randomize: bool) -> Self {
+        self.randomize_scan_order = randomize;
+        self
+    }
+
+    pub fn protocol(mut self, protocol: ScanProtocol) -> Self {
+        self.protocol = protocol;
+        self
+    }
+
+    pub fn udp_icmp_closed(mut self, closed: bool) -> Self {
+        self.udp_icmp_closed = closed;
+        self
+    }
+
+    pub fn max_concurrent(mut self, max: Option<usize>) -> Self {
+        self.max_concurrent = max;
+        self
+    }
+
+    pub fn snmp_communities(mut self, communities: Vec<String>) -> Self {
+        self.snmp_communities = communities;
+        self
+    }
+
+    pub fn detection_early_exit(mut self, threshold: Option<f32>) -> Self {
+        self.detection_early_exit = threshold;
+        self
+    }
+
+    /// Stop the whole scan after `limit` and return what it has
+    ///
+    /// ```
+    /// use port_scanner::infrastructure::testing::{Reply, ScriptedConnector};
+    /// use port_scanner::prelude::*;
+    /// use port_scanner::scanning::StandardScan;
+    /// use std::sync::Arc;
+    /// use std::time::{Duration, Instant};
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// // 1000 ports, 10 at a time: about 10s without a limit
+    /// let config = ScanConfigBuilder::new()
+    ///     .target("127.0.0.1".parse()?)
+    ///     .range(1, 1000)
+    ///     .max_concurrent(Some(10))
+    ///     .max_scan_duration(Some(Duration::from_millis(500)))
+    ///     .drain_timeout(Duration::from_millis(100))
+    ///     .build()?;
+    /// let slow = ScriptedConnector::new(Reply::Refuse).delay(Duration::from_millis(100));
+    /// let scanner = PortScanner::new(config)?.with_strategy(Arc::new(StandardScan::new().with_connector(Arc::new(slow))));
+    ///
+    /// let started = Instant::now();
+    /// let results = scanner.scan_all(|_| {}).await;
+    /// assert!(started.elapsed() < Duration::from_millis(1500));
+    /// // Partial: what finished before the deadline, unscanned ports omitted
+    /// assert!(results.total_ports >= 20 && results.total_ports < 100);
+    /// assert_eq!(results.closed_ports, results.total_ports);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn max_scan_duration(mut self, limit: Option<Duration>) -> Self {
+        self.max_scan_duration = limit;
+        self
+    }
+
+    /// Time detection on already-open ports may take to finish after
+    /// cancellation, see `ScanCancellation`
+    pub fn drain_timeout(mut self, timeout: Duration) -> Self {
+        self.drain_timeout = timeout;
+        self
+    }
+
+    /// Stop probing a host once more than `limit` of its ports are open,
+    /// flagging it as a likely honeypot
+    ///
+    /// ```
+    /// use port_scanner::infrastructure::testing::{Reply, ScriptedConnector};
+    /// use port_scanner::prelude::*;
+    /// use port_scanner::scanning::StandardScan;
+    /// use std::sync::Arc;
+    /// use tokio::net::TcpListener;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// let listener = TcpListener::bind("127.0.0.1:0").await?;
+    /// let config = ScanConfigBuilder::new()
+    ///     .target("10.0.0.5".parse()?)
+    ///     .range(1, 1000)
+    ///     .max_concurrent(Some(10))
+    ///     .max_open_ports(Some(5))
+    ///     .build()?;
+    /// // Every port accepts: each connect lands on the one listener
+    /// let all_open = ScriptedConnector::new(Reply::AcceptAt(listener.local_addr()?));
+    /// let strategy = StandardScan::new().with_connector(Arc::new(all_open));
+    /// let scanner = PortScanner::new(config)?.with_strategy(Arc::new(strategy));
+    /// let results = scanner.scan_all(|_| {}).await;
+    ///
+    /// assert!(results.honeypot_suspected);
+    /// // Only the probes already in flight finish after the sixth open port
+    /// assert!(results.open_ports > 5 && results.open_ports <= 5 + 10);
+    /// assert!(scanner.config().connection_attempts.get() <= 5 + 10);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn max_open_ports(mut self, limit: Option<usize>) -> Self {
+        self.max_open_ports = limit;
+        self
+    }
+
+    /// Re-probe filtered ports with a longer timeout after the scan, so
+    /// slow hosts are not reported as firewalled
+    ///
+    /// ```
+    /// use port_scanner::infrastructure::testing::{Reply, ScriptedConnector};
+    /// use port_scanner::prelude::*;
+    /// use port_scanner::scanning::StandardScan;
+    /// use std::sync::Arc;
+    /// use std::time::Duration;
+    /// use tokio::net::TcpListener;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// let listener = TcpListener::bind("127.0.0.1:0").await?;
+    /// let scan = |verify| -> anyhow::Result<PortScanner> {
+    ///     let config = ScanConfigBuilder::new()
+    ///         .target("10.0.0.5".parse()?)
+    ///         .custom_ports(vec![80, 81])
+    ///         .timeout(Duration::from_millis(100))
+    ///         .verify_filtered(verify)
+    ///         .build()?;
+    ///     // Port 80 accepts after 300ms, port 81 never answers
+    ///     let slow = ScriptedConnector::new(Reply::Silent)
+    ///         .on_port(80, [Reply::AcceptAt(listener.local_addr()?)])
+    ///         .delay(Duration::from_millis(300));
+    ///     let strategy = StandardScan::new().with_connector(Arc::new(slow));
+    ///     Ok(PortScanner::new(config)?.with_strategy(Arc::new(strategy)))
+    /// };
+    ///
+    /// let status = |results: &ScanResults, port| results.results.iter().find(|r| r.port == port).unwrap().status.clone();
+    /// let quick = scan(false)?.scan_all(|_| {}).await;
+    /// assert_eq!((status(&quick, 80), status(&quick, 81)), (PortStatus::Filtered, PortStatus::Filtered));
+    ///
+    /// // The longer verification timeout catches the late answer
+    /// let verified = scan(true)?.scan_all(|_| {}).await;
+    /// assert_eq!((status(&verified, 80), status(&verified, 81)), (PortStatus::Open, PortStatus::Filtered));
+    /// assert_eq!((verified.open_ports, verified.filtered_ports), (1, 1));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn verify_filtered(mut self, verify: bool) -> Self {
+        self.verify_filtered = verify;
+        self
+    }
+
+    pub fn retry_on_reset(mut self, retry: bool) -> Self {
+        self.retry_on_reset = retry;
+        self
+    }
+
+    pub fn probe_retries(mut self, retries: usize) -> Self {
+        self.probe_retries = retries;
+        self
+    }
+
+    /// Stop retrying a port after `limit` errored probes, while timeouts
+    /// elsewhere still get every retry. A limit of 0 acts as 1.
+    ///
+    /// ```
+    /// use port_scanner::infrastructure::testing::{Reply, ScriptedConnector};
+    /// use port_scanner::prelude::*;
+    /// use port_scanner::scanning::StandardScan;
+    /// use std::sync::Arc;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// let open = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+    /// let flaky = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+    /// let (open, flaky) = (open.local_addr()?.port(), flaky.local_addr()?.port());
+    /// let config = ScanConfigBuilder::new()
+    ///     .target("127.0.0.1".parse()?)
+    ///     .custom_ports(vec![1, open, flaky])
+    ///     .probe_retries(5)
+    ///     .max_port_errors(Some(2))
+    ///     .build()?;
+    /// // A proxy that always fails port 1 and times out twice on `flaky`
+    /// let proxy = Arc::new(ScriptedConnector::new(Reply::Accept)
+    ///     .on_port(1, [Reply::Fail("proxy error")])
+    ///     .on_port(flaky, [Reply::Timeout, Reply::Timeout, Reply::Accept]));
+    /// let strategy = Arc::new(StandardScan::new().with_connector(proxy.clone()));
+    /// let results = PortScanner::new(config)?.with_strategy(strategy).scan_all(|_| {}).await;
+    ///
+    /// let status = |port| results.results.iter().find(|r| r.port == port).unwrap().status.clone();
+    /// assert!(status(1).is_error());
+    /// assert!(status(open).is_open());
+    /// assert!(status(flaky).is_open());
+    ///
+    /// assert_eq!((proxy.attempts(1), proxy.attempts(open), proxy.attempts(flaky)), (2, 1, 3));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn max_port_errors(mut self, limit: Option<usize>) -> Self {
+        self.max_port_errors = limit;
+        self
+    }
+
+    pub fn closed_port_samples(mut self, samples: usize) -> Self {
+        self.closed_port_samples = samples;
+        self
+    }
+
+    pub fn scan_type(mut self, scan_type: ScanType) -> Self {
+        self.scan_type = scan_type;
+        self
+    }
+
+    /// Shorthand for `scan_type(ScanType::Syn)`
+    pub fn syn_scan(mut self, syn: bool) -> Self {
+        if syn {
+            self.scan_type = ScanType::Syn;
+        }
+        self
+    }
+
+    /// IP TTL for raw-socket probes (1-255); ignored by connect scans
+    pub fn ttl(mut self, ttl: Option<u8>) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// TCP window for raw-socket probes (1-65535); ignored by connect scans
+    pub fn tcp_window(mut self, window: Option<u16>) -> Self {
+        self.tcp_window = window;
+        self
+    }
+
+    pub fn require_privileges(mut self, require: bool) -> Self {
+        self.require_privileges = require;
+        self
+    }
+
+    /// Start with `RAMP_UP_INITIAL_CONCURRENCY` probes in flight and double
+    /// every `RAMP_UP_INTERVAL` up to the concurrency limit, instead of
+    /// opening the whole limit at once
+    ///
+    /// ```
+    /// use port_scanner::infrastructure::testing::{Reply, ScriptedConnector};
+    /// use port_scanner::prelude::*;
+    /// use port_scanner::scanning::StandardScan;
+    /// use std::sync::Arc;
+    /// use std::time::Duration;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// // Probes started during the first second
+    /// let first_second = |ramp_up| async move {
+    ///     let config = ScanConfigBuilder::new()
+    ///         .target("10.0.0.5".parse()?)
+    ///         .range(1, 1000)
+    ///         .max_concurrent(Some(500))
+    ///         .timeout(Duration::from_secs(5))
+    ///         .ramp_up(ramp_up)
+    ///         .build()?;
+    ///     let connector = Arc::new(ScriptedConnector::new(Reply::Silent));
+    ///     let strategy = StandardScan::new().with_connector(connector.clone());
+    ///     let stream = PortScanner::new(config)?.with_strategy(Arc::new(strategy)).scan_stream();
+    ///     tokio::time::sleep(Duration::from_secs(1)).await;
+    ///     drop(stream);
+    ///     anyhow::Ok(connector.connects().len())
+    /// };
+    ///
+    /// assert_eq!(first_second(false).await?, 500);
+    /// // 10, then 20 after the first interval
+    /// let ramped = first_second(true).await?;
+    /// assert!((10..50).contains(&ramped), "{ramped}");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn ramp_up(mut self, ramp_up: bool) -> Self {
+        self.ramp_up = ramp_up;
+        self
+    }
+
+    /// Seed the scan's RNG so random port order, sampling, source ports and
+    /// jitter repeat from run to run
+    ///
+    /// ```
+    /// use port_scanner::prelude::*;
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// let probes = |seed| -> anyhow::Result<_> {
+    ///     let config = ScanConfigBuilder::new()
+    ///         .target("10.0.0.5".parse()?)
+    ///         .range(1, 1000)
+    ///         .randomize_scan_order(true)
+    ///         .seed(seed)
+    ///         .build()?;
+    ///     Ok(PortScanner::new(config)?.dry_run())
+    /// };
+    ///
+    /// assert_eq!(probes(Some(7))?, probes(Some(7))?);
+    /// assert_ne!(probes(Some(7))?, probes(Some(8))?);
+    /// assert_ne!(probes(None)?, probes(None)?);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn seed(mut self, seed: Option<u64>) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Most bytes a detector reads from one connection.
+    ///
+    /// The limit belongs to the scan, so scanners with different limits can
+    /// run side by side:
+    ///
+    /// ```
+    /// use port_scanner::prelude::*;
+    /// use tokio::io::AsyncWriteExt;
+    /// use tokio::net::TcpListener;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// // Greets every client with 2000 bytes and no line end
+    /// let listener = TcpListener::bind("127.0.0.1:0").await?;
+    /// let port = listener.local_addr()?.port();
+    /// tokio::spawn(async move {
+    ///     while let Ok((mut peer, _)) = listener.accept().await {
+    ///         tokio::spawn(async move {
+    ///             let _ = peer.write_all(&[b'x'; 2000]).await;
+    ///             tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+    ///         });
+    ///     }
+    /// });
+    ///
+    /// let scan = |max_read_bytes| async move {
+    ///     let config = ScanConfigBuilder::new()
+    ///         .target("127.0.0.1".parse()?)
+    ///         .custom_ports(vec![port])
+    ///         .detect_versions(true)
+    ///         .max_read_bytes(max_read_bytes)
+    ///         .build()?;
+    ///     let results = PortScanner::new(config)?.scan_all(|_| {}).await;
+    ///     anyhow::Ok(results.results[0].service_version.clone().expect("banner"))
+    /// };
+    /// let (small, large) = tokio::join!(scan(64), scan(4096));
+    /// let (small, large) = (small?, large?);
+    ///
+    /// assert!(small.banner_truncated);
+    /// assert_eq!(small.banner.as_deref().map(str::len), Some(64));
+    /// assert!(!large.banner_truncated);
+    /// assert_eq!(large.banner.as_deref().map(str::len), Some(2000));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn max_read_bytes(mut self, bytes: usize) -> Self {
+        self.max_read_bytes = bytes;
+        self
+    }
+
+    pub fn max_read_duration(mut self, duration: Duration) -> Self {
+        self.max_read_duration = duration;
+        self
+    }
+
+    /// Longest gap a detector waits between chunks of a started reply.
+    ///
+    /// Like the other read limits it belongs to the scan:
+    ///
+    /// ```
+    /// use port_scanner::prelude::*;
+    /// use std::time::Duration;
+    /// use tokio::io::AsyncWriteExt;
+    /// use tokio::net::TcpListener;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// // Greets with "slow", pauses 300ms, then finishes the line
+    /// let listener = TcpListener::bind("127.0.0.1:0").await?;
+    /// let port = listener.local_addr()?.port();
+    /// tokio::spawn(async move {
+    ///     while let Ok((mut peer, _)) = listener.accept().await {
+    ///         tokio::spawn(async move {
+    ///             let _ = peer.write_all(b"slow").await;
+    ///             tokio::time::sleep(Duration::from_millis(300)).await;
+    ///             let _ = peer.write_all(b" banner\r\n").await;
+    ///         });
+    ///     }
+    /// });
+    ///
+    /// let scan = |max_read_idle| async move {
+    ///     let config = ScanConfigBuilder::new()
+    ///         .target("127.0.0.1".parse()?)
+    ///         .custom_ports(vec![port])
+    ///         .detect_versions(true)
+    ///         .max_read_idle(max_read_idle)
+    ///         .build()?;
+    ///     let results = PortScanner::new(config)?.scan_all(|_| {}).await;
+    ///     anyhow::Ok(results.results[0].service_version.clone().and_then(|v| v.banner))
+    /// };
+    /// let (impatient, patient) = tokio::join!(scan(Duration::from_millis(50)), scan(Duration::from_secs(2)));
+    ///
+    /// assert_eq!(impatient?.as_deref(), Some("slow"));
+    /// assert_eq!(patient?.as_deref(), Some("slow banner"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn max_read_idle(mut self, idle: Duration) -> Self {
+        self.max_read_idle = idle;
+        self
+    }
+
+    pub fn banner_initial_delay(mut self, delay: Option<Duration>) -> Self {
+        self.banner_initial_delay = delay;
+        self
+    }
+
+    pub fn banner_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.banner_timeout = timeout;
+        self
+    }
+
+    pub fn include_raw_banner(mut self, include: bool) -> Self {
+        self.include_raw_banner = include;
+        self
+    }
+
     pub fn build(self) -> ConfigResult<ScanConfig> {
         let target_ip = self.target_ip
             .ok_or_else(|| ConfigError::MissingField("target_ip".to_string()))?;
@@ -215,15 +1736,55 @@ impl ScanConfigBuilder {
 
         let config = ScanConfig {
             target_ip,
+            scope_id: self.scope_id,
             scan_mode,
+            min_port: self.min_port,
+            max_port: self.max_port,
             timeout: self.timeout,
             verbose: self.verbose,
             detect_versions: self.detect_versions,
             detect_os: self.detect_os,
+            detect_ports: self.detect_ports,
             parallel: self.parallel,
             thread_count: self.thread_count,
             randomize_source_port: self.randomize_source_port,
             delay_between_probes: self.delay_between_probes,
+            dns_axfr_domain: self.dns_axfr_domain,
+            reuse_connection: self.reuse_connection,
+            graceful_close: self.graceful_close,
+            linger: self.linger,
+            keepalive: self.keepalive,
+            bind_interface: self.bind_interface,
+            ordering: self.ordering,
+            randomize_scan_order: self.randomize_scan_order,
+            protocol: self.protocol,
+            udp_icmp_closed: self.udp_icmp_closed,
+            max_concurrent: self.max_concurrent,
+            snmp_communities: self.snmp_communities,
+            detection_early_exit: self.detection_early_exit,
+            max_scan_duration: self.max_scan_duration,
+            drain_timeout: self.drain_timeout,
+            max_open_ports: self.max_open_ports,
+            verify_filtered: self.verify_filtered,
+            retry_on_reset: self.retry_on_reset,
+            probe_retries: self.probe_retries,
+            max_port_errors: self.max_port_errors,
+            closed_port_samples: self.closed_port_samples,
+            scan_type: self.scan_type,
+            ttl: self.ttl,
+            tcp_window: self.tcp_window,
+            require_privileges: self.require_privileges,
+            ramp_up: self.ramp_up,
+            seed: self.seed,
+            max_read_bytes: self.max_read_bytes,
+            max_read_duration: self.max_read_duration,
+            max_read_idle: self.max_read_idle,
+            banner_initial_delay: self.banner_initial_delay,
+            banner_timeout: self.banner_timeout,
+            include_raw_banner: self.include_raw_banner,
+            connection_attempts: AttemptCounter::new(),
+            cancellation: ScanCancellation::new(),
+            rng: ScanRng::new(self.seed),
         };
 
         config.validate()?;
@@ -236,3 +1797,58 @@ impl Default for ScanConfigBuilder {
         Self::new()
     }
 }
+
+/// Builder holding every setting of `config`, to derive variations of it
+impl From<ScanConfig> for ScanConfigBuilder {
+    fn from(config: ScanConfig) -> Self {
+        Self {
+            target_ip: Some(config.target_ip),
+            scope_id: config.scope_id,
+            scan_mode: Some(config.scan_mode),
+            min_port: config.min_port,
+            max_port: config.max_port,
+            timeout: config.timeout,
+            verbose: config.verbose,
+            detect_versions: config.detect_versions,
+            detect_os: config.detect_os,
+            detect_ports: config.detect_ports,
+            parallel: config.parallel,
+            thread_count: config.thread_count,
+            randomize_source_port: config.randomize_source_port,
+            delay_between_probes: config.delay_between_probes,
+            dns_axfr_domain: config.dns_axfr_domain,
+            reuse_connection: config.reuse_connection,
+            graceful_close: config.graceful_close,
+            linger: config.linger,
+            keepalive: config.keepalive,
+            bind_interface: config.bind_interface,
+            ordering: config.ordering,
+            randomize_scan_order: config.randomize_scan_order,
+            protocol: config.protocol,
+            udp_icmp_closed: config.udp_icmp_closed,
+            max_concurrent: config.max_concurrent,
+            snmp_communities: config.snmp_communities,
+            detection_early_exit: config.detection_early_exit,
+            max_scan_duration: config.max_scan_duration,
+            drain_timeout: config.drain_timeout,
+            max_open_ports: config.max_open_ports,
+            verify_filtered: config.verify_filtered,
+            retry_on_reset: config.retry_on_reset,
+            probe_retries: config.probe_retries,
+            max_port_errors: config.max_port_errors,
+            closed_port_samples: config.closed_port_samples,
+            scan_type: config.scan_type,
+            ttl: config.ttl,
+            tcp_window: config.tcp_window,
+            require_privileges: config.require_privileges,
+            ramp_up: config.ramp_up,
+            seed: config.seed,
+            max_read_bytes: config.max_read_bytes,
+            max_read_duration: config.max_read_duration,
+            max_read_idle: config.max_read_idle,
+            banner_initial_delay: config.banner_initial_delay,
+            banner_timeout: config.banner_timeout,
+            include_raw_banner: config.include_raw_banner,
+        }
+    }
+}
@@ -1,11 +1,17 @@
-/// Detector plugin architecture
+//! Detector plugin architecture
 
 use std::net::SocketAddr;
+use std::sync::Arc;
 use std::time::Duration;
 
 use crate::domain::{Port, ServiceVersion, OSInfo};
+use crate::application::{VersionDetector, SMBFingerprinter, DnsDetector, RdpDetector, SnmpDetector, VncDetector, TelnetDetector, SmtpDetector, ImapDetector, Pop3Detector, RedisDetector, MongoDbDetector, TlsDetector};
+use crate::scanning::config::ScanConfig;
+use crate::scanning::cache::DetectionCache;
+use tracing::trace;
 
 /// Trait for detection plugins
+#[async_trait::async_trait]
 pub trait Detector: Send + Sync {
     /// Name of the detector
     fn name(&self) -> &str;
@@ -13,40 +19,364 @@ pub trait Detector: Send + Sync {
     /// Check if this detector can run on the given port
     fn can_detect(&self, port: Port) -> bool;
     
+    /// Whether this detector probes over UDP.
+    ///
+    /// UDP detectors run in the UDP pass of a scan (`ScanProtocol::Udp` or
+    /// `Both`) on the ports detection covers; a reply marks the port open.
+    /// TCP results are never replaced by them:
+    ///
+    /// ```
+    /// use port_scanner::prelude::*;
+    /// use port_scanner::scanning::{Detector, DetectorRegistry};
+    /// use std::net::SocketAddr;
+    /// use std::sync::atomic::{AtomicUsize, Ordering};
+    /// use std::sync::Arc;
+    /// use std::time::Duration;
+    /// use tokio::net::UdpSocket;
+    ///
+    /// /// Greets over UDP and names whoever answers
+    /// struct Beacon(Arc<AtomicUsize>);
+    ///
+    /// #[async_trait::async_trait]
+    /// impl Detector for Beacon {
+    ///     fn name(&self) -> &str { "Beacon" }
+    ///     fn can_detect(&self, _: Port) -> bool { true }
+    ///     fn is_udp(&self) -> bool { true }
+    ///     fn detect_service(&self, _: &SocketAddr, _: Duration) -> Option<ServiceVersion> { None }
+    ///     async fn detect_service_async(&self, socket: &SocketAddr, timeout: Duration) -> Option<ServiceVersion> {
+    ///         self.0.fetch_add(1, Ordering::SeqCst);
+    ///         let udp = UdpSocket::bind("127.0.0.1:0").await.ok()?;
+    ///         udp.send_to(b"hello", socket).await.ok()?;
+    ///         let mut reply = [0; 64];
+    ///         let (len, _) = tokio::time::timeout(timeout, udp.recv_from(&mut reply)).await.ok()?.ok()?;
+    ///         Some(ServiceVersion::new("Beacon", "udp").with_product(String::from_utf8_lossy(&reply[..len])))
+    ///     }
+    /// }
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// // Two UDP services echoing their name; nothing listens on TCP
+    /// let mut ports = Vec::new();
+    /// for name in ["alpha", "beta"] {
+    ///     let udp = UdpSocket::bind("127.0.0.1:0").await?;
+    ///     ports.push(udp.local_addr()?.port());
+    ///     tokio::spawn(async move {
+    ///         let mut buf = [0; 64];
+    ///         while let Ok((_, from)) = udp.recv_from(&mut buf).await {
+    ///             let _ = udp.send_to(name.as_bytes(), from).await;
+    ///         }
+    ///     });
+    /// }
+    ///
+    /// let calls = Arc::new(AtomicUsize::new(0));
+    /// let mut detectors = DetectorRegistry::new();
+    /// detectors.register(Box::new(Beacon(Arc::clone(&calls))));
+    /// let config = ScanConfigBuilder::new()
+    ///     .target("127.0.0.1".parse()?)
+    ///     .custom_ports(ports.clone())
+    ///     .protocol(ScanProtocol::Both)
+    ///     .detect_versions(true)
+    ///     .detect_ports(Some(vec![ports[0]]))
+    ///     .build()?;
+    /// let results = PortScanner::new(config)?.with_detectors(Arc::new(detectors)).scan_all(|_| {}).await;
+    ///
+    /// let find = |port, protocol| results.results.iter().find(|r| r.port == port && r.protocol == protocol).unwrap();
+    /// for &port in &ports {
+    ///     assert_eq!(find(port, Protocol::Tcp).status, PortStatus::Closed);
+    ///     assert_eq!(find(port, Protocol::Udp).status, PortStatus::Open);
+    /// }
+    /// let alpha = find(ports[0], Protocol::Udp).service_version.as_ref().unwrap();
+    /// assert_eq!((alpha.service_name.as_str(), alpha.product.as_deref()), ("Beacon", Some("alpha")));
+    /// // Detection stays on the ports it was asked for
+    /// assert!(find(ports[1], Protocol::Udp).service_version.is_none());
+    /// assert_eq!(calls.load(Ordering::SeqCst), 1);
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn is_udp(&self) -> bool {
+        false
+    }
+    
+    /// Whether this detector only reads the service's banner.
+    ///
+    /// Banner grabbers are skipped on a port whose banner an earlier
+    /// detector already read, instead of reconnecting to read it again:
+    ///
+    /// ```
+    /// use port_scanner::prelude::*;
+    /// use port_scanner::scanning::DetectorRegistry;
+    /// use std::sync::atomic::{AtomicUsize, Ordering};
+    /// use std::sync::Arc;
+    /// use tokio::io::AsyncWriteExt;
+    /// use tokio::net::TcpListener;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// // Three SSH servers counting their connections
+    /// let accepts = Arc::new(AtomicUsize::new(0));
+    /// let mut ports = Vec::new();
+    /// for _ in 0..3 {
+    ///     let listener = TcpListener::bind("127.0.0.1:0").await?;
+    ///     ports.push(listener.local_addr()?.port());
+    ///     let accepts = Arc::clone(&accepts);
+    ///     tokio::spawn(async move {
+    ///         while let Ok((mut stream, _)) = listener.accept().await {
+    ///             accepts.fetch_add(1, Ordering::SeqCst);
+    ///             let _ = stream.write_all(b"SSH-2.0-OpenSSH_9.6\r\n").await;
+    ///         }
+    ///     });
+    /// }
+    ///
+    /// // Both banner grabbers match every port, and OS detection runs too
+    /// let mut detectors = DetectorRegistry::new();
+    /// detectors.register(Box::new(VersionDetector::new()));
+    /// detectors.register(Box::new(VersionDetector::new()));
+    /// let config = ScanConfigBuilder::new()
+    ///     .target("127.0.0.1".parse()?)
+    ///     .custom_ports(ports)
+    ///     .detect_versions(true)
+    ///     .detect_os(true)
+    ///     .build()?;
+    /// let results = PortScanner::new(config)?.with_detectors(Arc::new(detectors)).scan_all(|_| {}).await;
+    ///
+    /// assert_eq!(results.open_ports, 3);
+    /// // Per port: the scan connect plus a single banner read
+    /// assert_eq!(accepts.load(Ordering::SeqCst), 3 * 2);
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn grabs_banner(&self) -> bool {
+        false
+    }
+
     /// Perform detection on the given socket
     fn detect_service(&self, socket: &SocketAddr, timeout: Duration) -> Option<ServiceVersion>;
     
     /// Perform OS detection (if supported)
-    fn detect_os(&self, socket: &SocketAddr, timeout: Duration) -> Option<OSInfo> {
+    fn detect_os(&self, _socket: &SocketAddr, _timeout: Duration) -> Option<OSInfo> {
         None // Most detectors don't do OS detection
     }
+
+    /// Perform service detection without blocking the async runtime.
+    ///
+    /// Defaults to the blocking implementation; detectors that do network
+    /// I/O should override this. Strategies only call this one, so
+    /// detections on many open ports overlap even on a single worker thread:
+    ///
+    /// ```
+    /// use port_scanner::prelude::*;
+    /// use port_scanner::scanning::DetectorRegistry;
+    /// use std::sync::Arc;
+    /// use std::time::{Duration, Instant};
+    /// use tokio::io::AsyncWriteExt;
+    ///
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// // 20 services that each take 300ms to greet
+    /// let mut ports = Vec::new();
+    /// for _ in 0..20 {
+    ///     let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+    ///     ports.push(listener.local_addr()?.port());
+    ///     tokio::spawn(async move {
+    ///         while let Ok((mut socket, _)) = listener.accept().await {
+    ///             tokio::spawn(async move {
+    ///                 tokio::time::sleep(Duration::from_millis(300)).await;
+    ///                 let _ = socket.write_all(b"SSH-2.0-OpenSSH_9.6\r\n").await;
+    ///             });
+    ///         }
+    ///     });
+    /// }
+    /// let config = ScanConfigBuilder::new()
+    ///     .target("127.0.0.1".parse()?)
+    ///     .custom_ports(ports)
+    ///     .detect_versions(true)
+    ///     .build()?;
+    ///
+    /// let mut detectors = DetectorRegistry::new();
+    /// detectors.register(Box::new(VersionDetector::new()));
+    /// let started = Instant::now();
+    /// let results = PortScanner::new(config)?.with_detectors(Arc::new(detectors)).scan_all(|_| {}).await;
+    ///
+    /// assert!(results.results.iter().all(|r| r.service_version.as_ref().is_some_and(|v| v.service_name == "SSH")));
+    /// // Blocking detection would take 20 x 300ms here
+    /// assert!(started.elapsed() < Duration::from_secs(3));
+    /// # Ok(())
+    /// # }
+    /// ```
+    async fn detect_service_async(&self, socket: &SocketAddr, timeout: Duration) -> Option<ServiceVersion> {
+        self.detect_service(socket, timeout)
+    }
+
+    /// Perform OS detection without blocking the async runtime
+    async fn detect_os_async(&self, socket: &SocketAddr, timeout: Duration) -> Option<OSInfo> {
+        self.detect_os(socket, timeout)
+    }
 }
 
-/// Registry for managing detectors
+/// Registry for managing detectors.
+///
+/// Detectors run in registration order. Every applicable detector gets a
+/// turn and the most confident answer wins, the earlier detector on ties;
+/// `with_early_exit` stops at the first answer that is confident enough.
+///
+/// ```
+/// use std::net::SocketAddr;
+/// use std::time::Duration;
+/// use port_scanner::domain::{Port, ServiceVersion};
+/// use port_scanner::scanning::{Detector, DetectorRegistry};
+///
+/// struct Fixed(&'static str, f32);
+///
+/// impl Detector for Fixed {
+///     fn name(&self) -> &str { self.0 }
+///     fn can_detect(&self, port: Port) -> bool { port == 8080 }
+///     fn detect_service(&self, _: &SocketAddr, _: Duration) -> Option<ServiceVersion> {
+///         Some(ServiceVersion::new(self.0, "tcp").with_confidence(self.1))
+///     }
+/// }
+///
+/// let port: Port = 8080;
+/// let socket: SocketAddr = "127.0.0.1:8080".parse()?;
+/// let timeout = Duration::from_millis(100);
+///
+/// let mut registry = DetectorRegistry::new();
+/// registry.register(Box::new(Fixed("weak", 0.3)));
+/// registry.register(Box::new(Fixed("strong", 0.9)));
+/// assert_eq!(registry.detect_service(port, &socket, timeout).unwrap().service_name, "strong");
+///
+/// // Good enough is good enough
+/// let mut registry = DetectorRegistry::new().with_early_exit(0.3);
+/// registry.register(Box::new(Fixed("weak", 0.3)));
+/// registry.register(Box::new(Fixed("strong", 0.9)));
+/// assert_eq!(registry.detect_service(port, &socket, timeout).unwrap().service_name, "weak");
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
 pub struct DetectorRegistry {
     detectors: Vec<Box<dyn Detector>>,
+    cache: Arc<DetectionCache>,
+    early_exit: Option<f32>,
 }
 
 impl DetectorRegistry {
     pub fn new() -> Self {
         Self {
             detectors: Vec::new(),
+            cache: Arc::new(DetectionCache::new()),
+            early_exit: None,
+        }
+    }
+
+    /// Stop at the first service match with at least this confidence
+    pub fn with_early_exit(mut self, threshold: f32) -> Self {
+        self.early_exit = Some(threshold);
+        self
+    }
+
+    /// Detection results recorded by this registry (one per scan)
+    pub fn cache(&self) -> &Arc<DetectionCache> {
+        &self.cache
+    }
+
+    /// Registry with the built-in detectors.
+    ///
+    /// Protocol-specific detectors are registered ahead of the generic
+    /// banner grabber so they get the first chance at a port.
+    ///
+    /// ```
+    /// use port_scanner::prelude::*;
+    /// use port_scanner::scanning::DetectorRegistry;
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// let config = ScanConfigBuilder::new().target("127.0.0.1".parse()?).custom_ports(vec![143]).build()?;
+    /// let defaults = DetectorRegistry::with_defaults();
+    /// let names = defaults.detector_names();
+    ///
+    /// // Both constructors register the same detectors, each once
+    /// assert_eq!(names, DetectorRegistry::for_config(&config).detector_names());
+    /// for name in ["ImapDetector", "Pop3Detector"] {
+    ///     assert_eq!(names.iter().filter(|n| **n == name).count(), 1);
+    /// }
+    /// assert_eq!(names.last(), Some(&"VersionDetector"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_defaults() -> Self {
+        let mut registry = Self::new();
+        registry.register_builtin(DnsDetector::new(), SnmpDetector::new(), VersionDetector::new());
+        registry
+    }
+
+    /// Registry with the built-in detectors configured from scan settings
+    pub fn for_config(config: &ScanConfig) -> Self {
+        let mut dns = DnsDetector::new();
+        if let Some(domain) = &config.dns_axfr_domain {
+            dns = dns.with_axfr_domain(domain.clone());
         }
+
+        let snmp = SnmpDetector::new().with_communities(config.snmp_communities.clone());
+
+        let mut registry = Self::new();
+        registry.early_exit = config.detection_early_exit;
+        registry.register_builtin(dns, snmp, VersionDetector::for_config(config));
+        registry
+    }
+
+    /// Register the built-in detectors, given the ones that take settings.
+    ///
+    /// The one list of built-ins, in the order they get a port: protocol
+    /// detectors first, the generic banner grabber last.
+    fn register_builtin(&mut self, dns: DnsDetector, snmp: SnmpDetector, banner: VersionDetector) {
+        let smb = SMBFingerprinter::new().with_cache(Arc::clone(&self.cache));
+        self.register(Box::new(smb));
+        self.register(Box::new(dns));
+        self.register(Box::new(RdpDetector::new()));
+        self.register(Box::new(VncDetector::new()));
+        self.register(Box::new(TelnetDetector::new()));
+        self.register(Box::new(SmtpDetector::new()));
+        self.register(Box::new(ImapDetector::new()));
+        self.register(Box::new(Pop3Detector::new()));
+        self.register(Box::new(RedisDetector::new()));
+        self.register(Box::new(MongoDbDetector::new()));
+        self.register(Box::new(TlsDetector::new()));
+        self.register(Box::new(snmp));
+        self.register(Box::new(banner));
     }
 
     pub fn register(&mut self, detector: Box<dyn Detector>) {
         self.detectors.push(detector);
     }
 
+    /// Most confident service match among the applicable TCP detectors
     pub fn detect_service(&self, port: Port, socket: &SocketAddr, timeout: Duration) -> Option<ServiceVersion> {
+        let mut best = None;
         for detector in &self.detectors {
-            if detector.can_detect(port) {
+            if detector.can_detect(port) && !detector.is_udp() && !Self::banner_read(detector.as_ref(), &best) {
                 if let Some(version) = detector.detect_service(socket, timeout) {
-                    return Some(version);
+                    if self.keep(&mut best, detector.name(), version) {
+                        break;
+                    }
                 }
             }
         }
-        None
+        best
+    }
+
+    /// Whether `detector` would only reread the banner already in `best`
+    fn banner_read(detector: &dyn Detector, best: &Option<ServiceVersion>) -> bool {
+        let read = detector.grabs_banner() && best.as_ref().is_some_and(|best| best.banner.is_some());
+        if read {
+            trace!("Skipping {}, banner already read", detector.name());
+        }
+        read
+    }
+
+    /// Keep `found` if it beats `best`; true once `best` clears the early-exit threshold
+    fn keep(&self, best: &mut Option<ServiceVersion>, detector: &str, found: ServiceVersion) -> bool {
+        trace!("{} matched {} (confidence {:.2})", detector, found.service_name, found.confidence);
+        if best.as_ref().is_none_or(|best| found.confidence > best.confidence) {
+            *best = Some(found);
+        }
+        matches!((best.as_ref(), self.early_exit), (Some(best), Some(threshold)) if best.confidence >= threshold)
     }
 
     pub fn detect_os(&self, port: Port, socket: &SocketAddr, timeout: Duration) -> Option<OSInfo> {
@@ -60,9 +390,71 @@ impl DetectorRegistry {
         None
     }
 
+    /// Detect the service on an open port, reusing any result cached for this host:port.
+    /// Like `detect_service`, the most confident match wins.
+    pub async fn detect_service_async(&self, port: Port, socket: &SocketAddr, timeout: Duration) -> Option<ServiceVersion> {
+        if let Some(version) = self.cache.service(socket) {
+            trace!("Reusing cached service detection for {}", socket);
+            return Some(version);
+        }
+        let mut best = None;
+        for detector in &self.detectors {
+            if detector.can_detect(port) && !detector.is_udp() && !Self::banner_read(detector.as_ref(), &best) {
+                if let Some(version) = detector.detect_service_async(socket, timeout).await {
+                    if self.keep(&mut best, detector.name(), version) {
+                        break;
+                    }
+                }
+            }
+        }
+        if let Some(version) = &best {
+            self.cache.store_service(*socket, version.clone());
+        }
+        best
+    }
+
+    /// Whether any UDP detector applies to this port
+    pub fn has_udp_detector(&self, port: Port) -> bool {
+        self.detectors.iter().any(|d| d.is_udp() && d.can_detect(port))
+    }
+
+    /// Run the UDP detectors for this port, first match wins
+    pub async fn detect_udp_async(&self, port: Port, socket: &SocketAddr, timeout: Duration) -> Option<ServiceVersion> {
+        for detector in &self.detectors {
+            if detector.can_detect(port) && detector.is_udp() {
+                if let Some(version) = detector.detect_service_async(socket, timeout).await {
+                    return Some(version);
+                }
+            }
+        }
+        None
+    }
+
+    /// Detect the OS via an open port, reusing any result cached for this host:port
+    pub async fn detect_os_async(&self, port: Port, socket: &SocketAddr, timeout: Duration) -> Option<OSInfo> {
+        if let Some(os_info) = self.cache.os_info(socket) {
+            trace!("Reusing cached OS detection for {}", socket);
+            return Some(os_info);
+        }
+        for detector in &self.detectors {
+            if detector.can_detect(port) {
+                if let Some(os_info) = detector.detect_os_async(socket, timeout).await {
+                    self.cache.store_os_info(*socket, os_info.clone());
+                    return Some(os_info);
+                }
+            }
+        }
+        None
+    }
+
     pub fn detector_count(&self) -> usize {
         self.detectors.len()
     }
+
+    /// Names of the registered detectors, in the order they are tried
+    pub fn detector_names(&self) -> Vec<&str> {
+        self.detectors.iter().map(|detector| detector.name()).collect()
+    }
 }
 
 impl Default for DetectorRegistry {
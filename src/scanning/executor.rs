@@ -1,10 +1,13 @@
-/// Async parallel scanning implementation using tokio
+//! Async parallel scanning implementation using tokio
 
-use tokio::task::JoinSet;
-use tokio::sync::Semaphore;
-use std::sync::Arc;
-use tracing::{info, debug};
+use tokio::task::{JoinHandle, JoinSet};
+use tokio::sync::{mpsc, Semaphore};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tracing::{info, debug, warn};
 
+use crate::constants::{RESULT_CHANNEL_CAPACITY, MIN_CONCURRENCY, MAX_CONCURRENCY, RAMP_UP_INITIAL_CONCURRENCY, RAMP_UP_INTERVAL};
 use crate::domain::{Port, PortScanResult};
 use crate::scanning::config::ScanConfig;
 use crate::scanning::strategy::ScanStrategy;
@@ -15,12 +18,58 @@ pub struct ParallelExecutor {
 }
 
 impl ParallelExecutor {
+    /// Create an executor running at most `max_concurrent` probes at once,
+    /// clamped to `MIN_CONCURRENCY..=MAX_CONCURRENCY`
     pub fn new(max_concurrent: usize) -> Self {
         // Limit concurrency to reasonable bounds
-        let max_concurrent = max_concurrent.min(2000).max(10);
+        let max_concurrent = max_concurrent.clamp(MIN_CONCURRENCY, MAX_CONCURRENCY);
         Self { max_concurrent }
     }
 
+    /// Executor sized by `config.concurrency()`: `max_concurrent` when set,
+    /// otherwise the `thread_count` heuristic
+    ///
+    /// ```
+    /// use port_scanner::infrastructure::testing::{Reply, ScriptedConnector};
+    /// use port_scanner::prelude::*;
+    /// use port_scanner::scanning::{ParallelExecutor, StandardScan};
+    /// use std::sync::Arc;
+    /// use std::time::Duration;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// let config = |max_concurrent| {
+    ///     ScanConfigBuilder::new()
+    ///         .target("127.0.0.1".parse().unwrap())
+    ///         .range(1, 200)
+    ///         .thread_count(100)
+    ///         .max_concurrent(max_concurrent)
+    ///         .build()
+    /// };
+    ///
+    /// // thread_count alone: 100 * TASKS_PER_THREAD
+    /// assert_eq!(ParallelExecutor::for_config(&config(None)?).max_concurrent(), 400);
+    ///
+    /// // max_concurrent wins over the thread-count multiple
+    /// let config = config(Some(25))?;
+    /// assert_eq!(ParallelExecutor::for_config(&config).max_concurrent(), 25);
+    ///
+    /// let connector = Arc::new(ScriptedConnector::new(Reply::Refuse).delay(Duration::from_millis(20)));
+    /// let strategy = StandardScan::new().with_connector(connector.clone());
+    /// PortScanner::new(config)?.with_strategy(Arc::new(strategy)).scan_all(|_| {}).await;
+    /// assert_eq!(connector.peak_in_flight(), 25);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn for_config(config: &ScanConfig) -> Self {
+        Self::new(config.concurrency())
+    }
+
+    /// Effective concurrency limit after clamping
+    pub fn max_concurrent(&self) -> usize {
+        self.max_concurrent
+    }
+
     pub async fn scan_ports<F>(
         &self,
         ports: Vec<Port>,
@@ -31,39 +80,167 @@ impl ParallelExecutor {
     where
         F: Fn(&PortScanResult) + Send + Sync + 'static,
     {
+        let (tx, rx) = mpsc::channel(RESULT_CHANNEL_CAPACITY);
+        let (_, results) = tokio::join!(
+            self.stream_ports(ports, strategy, config, tx),
+            collect_results(rx, callback),
+        );
+        results
+    }
+
+    /// Scan ports concurrently, sending each result into `tx` as it completes
+    pub async fn stream_ports(
+        &self,
+        ports: Vec<Port>,
+        strategy: Arc<dyn ScanStrategy + Send + Sync>,
+        config: &ScanConfig,
+        tx: mpsc::Sender<PortScanResult>,
+    ) {
         info!("Starting async parallel scan with max {} concurrent tasks", self.max_concurrent);
         
-        let semaphore = Arc::new(Semaphore::new(self.max_concurrent));
+        let (semaphore, ramp) = self.semaphore(config);
         let mut set = JoinSet::new();
-        let callback = Arc::new(callback);
         let config = Arc::new(config.clone());
+        let guard = Arc::new(OpenPortGuard::new(config.max_open_ports));
+        let errors = Arc::new(PortErrors::new(config.max_port_errors));
 
         // Spawn async tasks for each port
         for port in ports {
-            let permit = semaphore.clone().acquire_owned().await.unwrap();
+            let permit = tokio::select! {
+                permit = semaphore.clone().acquire_owned() => permit.unwrap(),
+                _ = config.cancellation.cancelled() => break,
+                // Nobody is reading results any more
+                _ = tx.closed() => break,
+            };
+            if guard.tripped() {
+                break;
+            }
             let strategy = Arc::clone(&strategy);
             let config = Arc::clone(&config);
-            let callback = Arc::clone(&callback);
+            let guard = Arc::clone(&guard);
+            let errors = Arc::clone(&errors);
+            let tx = tx.clone();
 
             set.spawn(async move {
                 debug!("Scanning port {}", port);
-                let result = strategy.scan_async(port, config.target_ip, &config).await;
-                callback(&result);
+                if let Some(result) = probe(&*strategy, port, &config, &errors).await {
+                    guard.record(&result);
+                    // A closed receiver aborts the set below
+                    let _ = tx.send(result).await;
+                }
                 drop(permit); // Release semaphore
-                result
             });
         }
 
-        // Collect results
-        let mut results = Vec::new();
-        while let Some(res) = set.join_next().await {
-            if let Ok(result) = res {
-                results.push(result);
+        // Wait for all in-flight tasks
+        let mut completed = 0;
+        while set.join_next().await.is_some() {
+            completed += 1;
+            if guard.tripped() || tx.is_closed() {
+                // Stop probing a host that answers on everything, or for a
+                // consumer that is gone
+                set.abort_all();
             }
         }
+        if let Some(ramp) = ramp {
+            ramp.abort();
+        }
 
-        info!("Async parallel scan completed. Scanned {} ports", results.len());
-        results
+        info!("Async parallel scan completed. Scanned {} ports", completed);
+    }
+
+    /// Run an interleaved multi-host schedule (see `schedule::interleave`),
+    /// sending each result into `tx` tagged with its host index.
+    ///
+    /// `configs[i]` is the config of host `i`; the first one supplies the
+    /// ramp-up setting. A host that trips its open-port limit gets no further
+    /// probes while the others carry on.
+    pub async fn stream_hosts(
+        &self,
+        probes: Vec<(usize, Port)>,
+        strategy: Arc<dyn ScanStrategy + Send + Sync>,
+        configs: &[ScanConfig],
+        tx: mpsc::Sender<(usize, PortScanResult)>,
+    ) {
+        let Some(first) = configs.first() else {
+            return;
+        };
+        info!("Starting interleaved scan of {} hosts with max {} concurrent tasks", configs.len(), self.max_concurrent);
+
+        let (semaphore, ramp) = self.semaphore(first);
+        let mut set = JoinSet::new();
+        let configs: Vec<Arc<ScanConfig>> = configs.iter().cloned().map(Arc::new).collect();
+        let guards: Vec<Arc<OpenPortGuard>> = configs.iter()
+            .map(|config| Arc::new(OpenPortGuard::new(config.max_open_ports)))
+            .collect();
+        let errors: Vec<Arc<PortErrors>> = configs.iter()
+            .map(|config| Arc::new(PortErrors::new(config.max_port_errors)))
+            .collect();
+
+        for (host, port) in probes {
+            let permit = tokio::select! {
+                permit = semaphore.clone().acquire_owned() => permit.unwrap(),
+                _ = configs[host].cancellation.cancelled() => break,
+                _ = tx.closed() => break,
+            };
+            if guards[host].tripped() {
+                continue;
+            }
+            let strategy = Arc::clone(&strategy);
+            let config = Arc::clone(&configs[host]);
+            let guard = Arc::clone(&guards[host]);
+            let errors = Arc::clone(&errors[host]);
+            let tx = tx.clone();
+
+            set.spawn(async move {
+                debug!("Scanning port {} on {}", port, config.target_ip);
+                if let Some(result) = probe(&*strategy, port, &config, &errors).await {
+                    guard.record(&result);
+                    let _ = tx.send((host, result)).await;
+                }
+                drop(permit);
+            });
+        }
+
+        let mut completed = 0;
+        while set.join_next().await.is_some() {
+            completed += 1;
+            if tx.is_closed() {
+                set.abort_all();
+            }
+        }
+        if let Some(ramp) = ramp {
+            ramp.abort();
+        }
+
+        info!("Interleaved scan completed. Scanned {} ports", completed);
+    }
+
+    /// Probe permits for a scan; with ramp-up, start with a few permits and
+    /// return the background task that adds the rest
+    fn semaphore(&self, config: &ScanConfig) -> (Arc<Semaphore>, Option<JoinHandle<()>>) {
+        let initial = if config.ramp_up {
+            RAMP_UP_INITIAL_CONCURRENCY.min(self.max_concurrent)
+        } else {
+            self.max_concurrent
+        };
+        let semaphore = Arc::new(Semaphore::new(initial));
+        let ramp = (initial < self.max_concurrent)
+            .then(|| tokio::spawn(ramp_up(Arc::clone(&semaphore), initial, self.max_concurrent)));
+        (semaphore, ramp)
+    }
+}
+
+/// Double the semaphore's permits every `RAMP_UP_INTERVAL` until `max` are available
+async fn ramp_up(semaphore: Arc<Semaphore>, mut current: usize, max: usize) {
+    let mut interval = tokio::time::interval(RAMP_UP_INTERVAL);
+    interval.tick().await; // first tick completes immediately
+    while current < max {
+        interval.tick().await;
+        let next = (current * 2).min(max);
+        semaphore.add_permits(next - current);
+        debug!("Concurrency ramped up to {}", next);
+        current = next;
     }
 }
 
@@ -85,19 +262,90 @@ impl SequentialExecutor {
     where
         F: Fn(&PortScanResult),
     {
+        let (tx, rx) = mpsc::channel(RESULT_CHANNEL_CAPACITY);
+        let (_, results) = tokio::join!(
+            self.stream_ports(ports, strategy, config, tx),
+            collect_results(rx, callback),
+        );
+        results
+    }
+
+    /// Scan ports one at a time, sending each result into `tx`
+    pub async fn stream_ports(
+        &self,
+        ports: Vec<Port>,
+        strategy: Arc<dyn ScanStrategy + Send + Sync>,
+        config: &ScanConfig,
+        tx: mpsc::Sender<PortScanResult>,
+    ) {
         info!("Starting sequential scan");
         
-        let mut results = Vec::with_capacity(ports.len());
+        let mut scanned = 0;
+        let guard = OpenPortGuard::new(config.max_open_ports);
+        let errors = PortErrors::new(config.max_port_errors);
         
         for port in ports {
+            if config.cancellation.is_cancelled() {
+                break;
+            }
             debug!("Scanning port {}", port);
-            let result = strategy.scan_async(port, config.target_ip, config).await;
-            callback(&result);
-            results.push(result);
+            let Some(result) = probe(&*strategy, port, config, &errors).await else {
+                break;
+            };
+            guard.record(&result);
+            if tx.send(result).await.is_err() {
+                debug!("Result receiver dropped, stopping sequential scan");
+                break;
+            }
+            scanned += 1;
+            if guard.tripped() {
+                break;
+            }
         }
         
-        info!("Sequential scan completed. Scanned {} ports", results.len());
-        results
+        info!("Sequential scan completed. Scanned {} ports", scanned);
+    }
+
+    /// Run an interleaved multi-host schedule one probe at a time, sending
+    /// each result into `tx` tagged with its host index
+    pub async fn stream_hosts(
+        &self,
+        probes: Vec<(usize, Port)>,
+        strategy: Arc<dyn ScanStrategy + Send + Sync>,
+        configs: &[ScanConfig],
+        tx: mpsc::Sender<(usize, PortScanResult)>,
+    ) {
+        info!("Starting sequential interleaved scan of {} hosts", configs.len());
+
+        let mut scanned = 0;
+        let guards: Vec<OpenPortGuard> = configs.iter()
+            .map(|config| OpenPortGuard::new(config.max_open_ports))
+            .collect();
+        let errors: Vec<PortErrors> = configs.iter()
+            .map(|config| PortErrors::new(config.max_port_errors))
+            .collect();
+
+        for (host, port) in probes {
+            if guards[host].tripped() {
+                continue;
+            }
+            let config = &configs[host];
+            if config.cancellation.is_cancelled() {
+                break;
+            }
+            debug!("Scanning port {} on {}", port, config.target_ip);
+            let Some(result) = probe(&*strategy, port, config, &errors[host]).await else {
+                break;
+            };
+            guards[host].record(&result);
+            if tx.send((host, result)).await.is_err() {
+                debug!("Result receiver dropped, stopping sequential scan");
+                break;
+            }
+            scanned += 1;
+        }
+
+        info!("Sequential interleaved scan completed. Scanned {} ports", scanned);
     }
 }
 
@@ -105,4 +353,89 @@ impl Default for SequentialExecutor {
     fn default() -> Self {
         Self::new()
     }
-}
\ No newline at end of file
+}
+
+/// Counts open ports on the target and trips once `max_open_ports` is exceeded
+struct OpenPortGuard {
+    limit: Option<usize>,
+    open: AtomicUsize,
+}
+
+impl OpenPortGuard {
+    fn new(limit: Option<usize>) -> Self {
+        Self { limit, open: AtomicUsize::new(0) }
+    }
+
+    fn record(&self, result: &PortScanResult) {
+        if !result.is_open() {
+            return;
+        }
+        let open = self.open.fetch_add(1, Ordering::Relaxed) + 1;
+        if self.limit.is_some_and(|limit| open == limit + 1) {
+            warn!("More than {} open ports, host is likely a honeypot; stopping", open - 1);
+        }
+    }
+
+    fn tripped(&self) -> bool {
+        self.limit
+            .is_some_and(|limit| self.open.load(Ordering::Relaxed) > limit)
+    }
+}
+
+/// Counts errored probes per port, so a port that keeps erroring gets no
+/// more retries once it reaches `max_port_errors`
+struct PortErrors {
+    limit: Option<usize>,
+    counts: Mutex<HashMap<Port, usize>>,
+}
+
+impl PortErrors {
+    fn new(limit: Option<usize>) -> Self {
+        Self { limit, counts: Mutex::new(HashMap::new()) }
+    }
+
+    /// Count an errored probe of `port`; true once the port is out of retries
+    fn record(&self, port: Port) -> bool {
+        let mut counts = self.counts.lock().unwrap_or_else(|e| e.into_inner());
+        let count = counts.entry(port).or_default();
+        *count += 1;
+        self.limit.is_some_and(|limit| *count >= limit.max(1))
+    }
+}
+
+/// Probe `port`, re-probing timeouts and errors up to `probe_retries` times;
+/// a port that used up its errors is reported as it last failed. Connections
+/// count into the scan's `connection_attempts`. `None` when the scan was
+/// cancelled before the probe finished (see `ScanCancellation::drain`)
+async fn probe(strategy: &dyn ScanStrategy, port: Port, config: &ScanConfig, errors: &PortErrors) -> Option<PortScanResult> {
+    let probing = config.scope(async {
+        let mut result = strategy.scan_async(port, config.target_ip, config).await;
+        for attempt in 1..=config.probe_retries {
+            if result.status.is_error() {
+                if errors.record(port) {
+                    debug!("Port {} keeps erroring ({}); not retrying it", port, result.status);
+                    break;
+                }
+            } else if !result.status.is_filtered() {
+                break;
+            }
+            debug!("Retrying port {} after {} ({}/{})", port, result.status, attempt, config.probe_retries);
+            result = strategy.scan_async(port, config.target_ip, config).await;
+        }
+        result
+    });
+    config.cancellation.drain(config.drain_timeout, probing).await
+}
+
+/// Drain a result channel, invoking the callback for each result
+async fn collect_results<F>(mut rx: mpsc::Receiver<PortScanResult>, callback: F) -> Vec<PortScanResult>
+where
+    F: Fn(&PortScanResult),
+{
+    let mut results = Vec::new();
+    while let Some(result) = rx.recv().await {
+        callback(&result);
+        results.push(result);
+    }
+    results
+}
@@ -0,0 +1,164 @@
+//! Raw-socket FIN, NULL and Xmas scan strategies
+//!
+//! Per RFC 793 a closed port answers a segment without SYN, RST or ACK with
+//! RST, while an open port drops it. Stateless firewalls that only block SYN
+//! often let these probes through. Silence cannot tell an open port from a
+//! filtered one, so it is reported as `OpenFiltered`. Stacks that ignore the
+//! RFC (notably Windows) answer RST on every port.
+
+use std::io;
+use std::net::IpAddr;
+use std::sync::Arc;
+use tracing::{debug, trace};
+
+use crate::domain::{Port, PortStatus, PortScanResult};
+use crate::scanning::config::ScanConfig;
+use crate::scanning::detector::DetectorRegistry;
+use crate::scanning::strategy::{ScanStrategy, StandardScan};
+use crate::scanning::syn::{self, TCP_FLAG_FIN, TCP_FLAG_PSH, TCP_FLAG_RST, TCP_FLAG_URG};
+
+/// FIN scan: sends a bare FIN
+///
+/// Against localhost a closed port answers RST and an open one stays silent;
+/// the NULL and Xmas scans classify the same way.
+///
+/// ```
+/// use port_scanner::prelude::*;
+/// use port_scanner::scanning::{DetectorRegistry, FinScan, NullScan, ScanStrategy, XmasScan};
+/// use std::net::TcpListener;
+/// use std::sync::Arc;
+/// use std::time::Duration;
+///
+/// # #[tokio::main]
+/// # async fn main() -> anyhow::Result<()> {
+/// let detectors = Arc::new(DetectorRegistry::new());
+/// let Ok(fin) = FinScan::with_detectors(detectors.clone()) else {
+///     // Needs root or CAP_NET_RAW; nothing to check without them
+///     return Ok(());
+/// };
+/// let scans: Vec<Box<dyn ScanStrategy>> = vec![
+///     Box::new(fin),
+///     Box::new(NullScan::with_detectors(detectors.clone())?),
+///     Box::new(XmasScan::with_detectors(detectors)?),
+/// ];
+/// let listener = TcpListener::bind("127.0.0.1:0")?;
+/// let open = listener.local_addr()?.port();
+/// let closed = TcpListener::bind("127.0.0.1:0")?.local_addr()?.port();
+/// let config = ScanConfigBuilder::new()
+///     .target("127.0.0.1".parse()?)
+///     .custom_ports(vec![open, closed])
+///     .timeout(Duration::from_millis(300))
+///     .build()?;
+///
+/// for scan in &scans {
+///     let result = scan.scan_async(closed, config.target_ip, &config).await;
+///     assert_eq!(result.status, PortStatus::Closed, "{}", scan.name());
+///     assert!(result.ttl.is_some());
+///     let result = scan.scan_async(open, config.target_ip, &config).await;
+///     assert_eq!(result.status, PortStatus::OpenFiltered, "{}", scan.name());
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct FinScan {
+    inner: StandardScan,
+}
+
+impl FinScan {
+    /// Fails when raw sockets are not permitted for this process
+    pub fn with_detectors(detectors: Arc<DetectorRegistry>) -> io::Result<Self> {
+        syn::raw_socket()?;
+        Ok(Self { inner: StandardScan::with_detectors(detectors) })
+    }
+}
+
+#[async_trait::async_trait]
+impl ScanStrategy for FinScan {
+    async fn scan_async(&self, port: Port, target_ip: IpAddr, config: &ScanConfig) -> PortScanResult {
+        scan_with_flags(&self.inner, TCP_FLAG_FIN, port, target_ip, config).await
+    }
+
+    fn name(&self) -> &'static str {
+        "TCP FIN"
+    }
+}
+
+/// NULL scan: sends a segment with no flags set
+pub struct NullScan {
+    inner: StandardScan,
+}
+
+impl NullScan {
+    /// Fails when raw sockets are not permitted for this process
+    pub fn with_detectors(detectors: Arc<DetectorRegistry>) -> io::Result<Self> {
+        syn::raw_socket()?;
+        Ok(Self { inner: StandardScan::with_detectors(detectors) })
+    }
+}
+
+#[async_trait::async_trait]
+impl ScanStrategy for NullScan {
+    async fn scan_async(&self, port: Port, target_ip: IpAddr, config: &ScanConfig) -> PortScanResult {
+        scan_with_flags(&self.inner, 0, port, target_ip, config).await
+    }
+
+    fn name(&self) -> &'static str {
+        "TCP NULL"
+    }
+}
+
+/// Xmas scan: sends FIN, PSH and URG together
+pub struct XmasScan {
+    inner: StandardScan,
+}
+
+impl XmasScan {
+    /// Fails when raw sockets are not permitted for this process
+    pub fn with_detectors(detectors: Arc<DetectorRegistry>) -> io::Result<Self> {
+        syn::raw_socket()?;
+        Ok(Self { inner: StandardScan::with_detectors(detectors) })
+    }
+}
+
+#[async_trait::async_trait]
+impl ScanStrategy for XmasScan {
+    async fn scan_async(&self, port: Port, target_ip: IpAddr, config: &ScanConfig) -> PortScanResult {
+        scan_with_flags(&self.inner, TCP_FLAG_FIN | TCP_FLAG_PSH | TCP_FLAG_URG, port, target_ip, config).await
+    }
+
+    fn name(&self) -> &'static str {
+        "TCP Xmas"
+    }
+}
+
+/// Probe with `flags` and classify the reply: RST is closed, silence open|filtered.
+///
+/// IPv6 targets use `inner` (a regular connect scan). No service detection is
+/// attempted since an open port cannot be confirmed without connecting.
+async fn scan_with_flags(inner: &StandardScan, flags: u8, port: Port, target_ip: IpAddr, config: &ScanConfig) -> PortScanResult {
+    let IpAddr::V4(target) = target_ip else {
+        return inner.scan_async(port, target_ip, config).await;
+    };
+    if let Some(delay) = config.delay_between_probes {
+        tokio::time::sleep(delay).await;
+    }
+
+    match syn::send_probe(target, port, flags, config).await {
+        Ok(Some(reply)) if reply.flags & TCP_FLAG_RST != 0 => {
+            trace!("Port {} is CLOSED (RST)", port);
+            PortScanResult::new(port, PortStatus::Closed).with_ttl(reply.ttl)
+        }
+        Ok(Some(reply)) => {
+            debug!("Unexpected reply flags {:#04x} from port {}", reply.flags, port);
+            PortScanResult::new(port, PortStatus::OpenFiltered).with_ttl(reply.ttl)
+        }
+        Ok(None) => {
+            trace!("Port {} is OPEN|FILTERED (no reply)", port);
+            PortScanResult::new(port, PortStatus::OpenFiltered)
+        }
+        Err(e) => {
+            debug!("Probe to port {} failed: {}", port, e);
+            PortScanResult::from_error(port, &e)
+        }
+    }
+}
@@ -1,11 +1,28 @@
-/// Scanning module exports
+//! Scanning module exports
 
 pub mod config;
 pub mod strategy;
 pub mod detector;
 pub mod executor;
+pub mod cache;
+pub mod targets;
+pub mod schedule;
+pub mod privileges;
+pub mod cancel;
+#[cfg(all(feature = "syn-scan", unix))]
+pub mod syn;
+#[cfg(all(feature = "syn-scan", unix))]
+pub mod flag_scan;
 
-pub use config::{ScanConfig, ScanConfigBuilder, ScanMode};
-pub use strategy::{ScanStrategy, StandardScan, StealthScan, ScanStrategyFactory};
+pub use config::{DurationEstimate, ScanConfig, ScanConfigBuilder, ScanMode, ScanOrder, ScanPreset, ScanProfile, ScanProtocol, ScanType};
+pub use strategy::{ScanStrategy, StandardScan, StealthScan, UdpScan, ScanStrategyFactory};
 pub use detector::{Detector, DetectorRegistry};
 pub use executor::{ParallelExecutor, SequentialExecutor};
+pub use cache::{DetectionCache, CachedDetection};
+pub use targets::{Target, IpNetwork, TargetExclusions};
+pub use privileges::{PrivilegeWarning, check_privileges, check_privileges_with};
+pub use cancel::{ScanCancellation, mark_open};
+#[cfg(all(feature = "syn-scan", unix))]
+pub use syn::SynScan;
+#[cfg(all(feature = "syn-scan", unix))]
+pub use flag_scan::{FinScan, NullScan, XmasScan};
@@ -0,0 +1,116 @@
+//! Up-front check that requested features can run with this process's privileges
+
+use std::collections::HashSet;
+use std::fmt;
+use std::io;
+use std::sync::{Mutex, OnceLock};
+use tracing::warn;
+
+use crate::scanning::config::ScanConfig;
+
+/// A requested feature that will be replaced by a fallback
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PrivilegeWarning {
+    /// What was asked for, e.g. "SYN scan"
+    pub feature: String,
+    /// Why it cannot run
+    pub reason: String,
+    /// What the scan does instead
+    pub fallback: String,
+    /// What the user can do about it
+    pub hint: String,
+}
+
+impl fmt::Display for PrivilegeWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} unavailable ({}); {}", self.feature, self.reason, self.fallback)
+    }
+}
+
+/// Features in `config` this process cannot honor
+pub fn check_privileges(config: &ScanConfig) -> Vec<PrivilegeWarning> {
+    check_privileges_with(config, raw_socket_available)
+}
+
+/// `check_privileges` with `open_raw_socket` standing in for opening a raw
+/// TCP socket, so a refused socket can be simulated.
+///
+/// Checking every target of a scan still logs the warning once:
+///
+/// ```
+/// use port_scanner::prelude::*;
+/// use port_scanner::scanning::privileges::{check_privileges_with, warn_once};
+/// use std::io;
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let denied = || Err(io::Error::from(io::ErrorKind::PermissionDenied));
+/// let mut logged = 0;
+/// for target in ["10.0.0.1", "10.0.0.2", "10.0.0.3"] {
+///     let config = ScanConfigBuilder::new()
+///         .target(target.parse()?)
+///         .custom_ports(vec![22])
+///         .scan_type(ScanType::Syn)
+///         .build()?;
+///     let warnings = check_privileges_with(&config, denied);
+///     assert_eq!(warnings.len(), 1);
+///     assert_eq!(warnings[0].feature, "SYN scan");
+///     assert!(warnings[0].hint.contains("CAP_NET_RAW"));
+///     logged += warnings.iter().filter(|warning| warn_once(warning)).count();
+/// }
+/// assert_eq!(logged, 1);
+///
+/// // A connect scan needs no privileges
+/// let config = ScanConfigBuilder::new().target("10.0.0.1".parse()?).custom_ports(vec![22]).build()?;
+/// assert!(check_privileges_with(&config, denied).is_empty());
+/// # Ok(())
+/// # }
+/// ```
+pub fn check_privileges_with(config: &ScanConfig, open_raw_socket: impl FnOnce() -> io::Result<()>) -> Vec<PrivilegeWarning> {
+    let mut warnings = Vec::new();
+    if config.scan_type.is_raw() {
+        if let Err(e) = open_raw_socket() {
+            let hint = if e.kind() == io::ErrorKind::Unsupported {
+                "rebuild with --features syn-scan, or use a connect scan"
+            } else {
+                "run as root or with CAP_NET_RAW, or use a connect scan"
+            };
+            let mut fallback = "falling back to TCP connect scan".to_string();
+            if config.ttl.is_some() || config.tcp_window.is_some() {
+                fallback.push_str(" without the custom TTL/window");
+            }
+            warnings.push(PrivilegeWarning {
+                feature: format!("{} scan", config.scan_type),
+                reason: e.to_string(),
+                fallback,
+                hint: hint.to_string(),
+            });
+        }
+    }
+    warnings
+}
+
+/// Log `warning` unless it was already logged by this process.
+///
+/// Returns whether it was logged, so multi-target scans report it once.
+pub fn warn_once(warning: &PrivilegeWarning) -> bool {
+    static REPORTED: OnceLock<Mutex<HashSet<PrivilegeWarning>>> = OnceLock::new();
+    let first = REPORTED.get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(warning.clone());
+    if first {
+        warn!("{}; {}", warning, warning.hint);
+    }
+    first
+}
+
+/// Fails when raw TCP sockets cannot be opened
+#[cfg(all(feature = "syn-scan", unix))]
+fn raw_socket_available() -> io::Result<()> {
+    crate::scanning::syn::raw_socket().map(drop)
+}
+
+#[cfg(not(all(feature = "syn-scan", unix)))]
+fn raw_socket_available() -> io::Result<()> {
+    Err(io::Error::new(io::ErrorKind::Unsupported, "built without the syn-scan feature"))
+}
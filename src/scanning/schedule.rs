@@ -0,0 +1,93 @@
+//! Probe scheduling across several hosts
+
+use rand::seq::SliceRandom;
+
+use crate::domain::Port;
+use crate::infrastructure::ScanRng;
+
+/// Interleave per-host port lists so that hosts take turns.
+///
+/// Returns `(host index, port)` pairs. Each host's ports are shuffled and
+/// hosts are visited in a shuffled order, all drawn from `rng`; each round
+/// probes the next port of every host that still has ports left, so no
+/// host gets a back-to-back sweep.
+///
+/// ```
+/// use port_scanner::infrastructure::ScanRng;
+/// use port_scanner::scanning::schedule;
+///
+/// let lists = vec![(1..=100).collect::<Vec<u16>>(); 3];
+/// let probes = schedule::interleave(&lists, &ScanRng::new(None));
+///
+/// // Every host gets each of its ports exactly once, in shuffled order
+/// for host in 0..3 {
+///     let mut ports: Vec<u16> = probes.iter()
+///         .filter(|(h, _)| *h == host)
+///         .map(|&(_, port)| port)
+///         .collect();
+///     assert_ne!(ports, lists[host]);
+///     ports.sort_unstable();
+///     assert_eq!(ports, lists[host]);
+/// }
+/// // Probes to the same host are at least three slots apart
+/// assert!(probes.windows(3).all(|w| w[0].0 != w[1].0 && w[0].0 != w[2].0 && w[1].0 != w[2].0));
+/// ```
+pub fn interleave(port_lists: &[Vec<Port>], rng: &ScanRng) -> Vec<(usize, Port)> {
+    let mut port_lists = port_lists.to_vec();
+    let mut hosts: Vec<usize> = (0..port_lists.len()).collect();
+    rng.with(|rng| {
+        for ports in &mut port_lists {
+            ports.shuffle(rng);
+        }
+        hosts.shuffle(rng);
+    });
+
+    let rounds = port_lists.iter().map(Vec::len).max().unwrap_or(0);
+    let mut probes = Vec::with_capacity(port_lists.iter().map(Vec::len).sum());
+    for round in 0..rounds {
+        for &host in &hosts {
+            if let Some(&port) = port_lists[host].get(round) {
+                probes.push((host, port));
+            }
+        }
+    }
+    probes
+}
+
+/// Every `(host index, port)` pair of the port lists in one shuffled list.
+///
+/// Unlike `interleave`, hosts do not take turns: each probe goes to a
+/// random host and port, so neither a host nor the network sees a pattern.
+/// The shuffle draws from `rng`.
+///
+/// ```
+/// use port_scanner::infrastructure::ScanRng;
+/// use port_scanner::scanning::schedule;
+///
+/// let lists = vec![(1..=50).collect::<Vec<u16>>(); 4];
+/// let work: Vec<(usize, u16)> = (0..4)
+///     .flat_map(|host| (1..=50).map(move |port| (host, port)))
+///     .collect();
+///
+/// let probes = schedule::shuffle_all(&lists, &ScanRng::new(Some(7)));
+/// assert_ne!(probes, work);
+///
+/// // A permutation of the full work list: every pair exactly once
+/// let mut sorted = probes.clone();
+/// sorted.sort_unstable();
+/// assert_eq!(sorted, work);
+///
+/// // Hosts are mixed from the start, not swept one after another
+/// assert!(probes[..8].iter().any(|&(host, _)| host != probes[0].0));
+///
+/// // Same seed, same order
+/// assert_eq!(schedule::shuffle_all(&lists, &ScanRng::new(Some(7))), probes);
+/// ```
+pub fn shuffle_all(port_lists: &[Vec<Port>], rng: &ScanRng) -> Vec<(usize, Port)> {
+    let mut probes: Vec<(usize, Port)> = port_lists.iter()
+        .enumerate()
+        .flat_map(|(host, ports)| ports.iter().map(move |&port| (host, port)))
+        .collect();
+    rng.with(|rng| probes.shuffle(rng));
+    probes
+}
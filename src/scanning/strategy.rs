@@ -1,28 +1,146 @@
-/// Scan strategy pattern implementation with async support
+//! Scan strategy pattern implementation with async support
 
-use std::net::{SocketAddr, IpAddr};
-use tokio::net::TcpStream;
-use tokio::time::{timeout, Duration};
+use std::io;
+use std::net::{IpAddr, SocketAddr};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::net::TcpStream as AsyncTcpStream;
 use tracing::{debug, trace};
 
-use crate::domain::{Port, PortStatus, PortScanResult};
-use crate::scanning::config::ScanConfig;
-use crate::application::{VersionDetector, SMBFingerprinter};
+use crate::domain::{Port, PortStatus, PortScanResult, Protocol};
+use crate::scanning::config::{ScanConfig, ScanType};
+use crate::scanning::detector::DetectorRegistry;
+use crate::scanning::cancel::mark_open;
+use crate::application::VersionDetector;
+use crate::constants::RESET_RETRY_ATTEMPTS;
+use crate::infrastructure::{network_utils, AsyncDatagramProber, AsyncNetworkConnector, AsyncTcpConnector, AsyncUdpProber, record_attempt};
 
 /// Trait for different scanning strategies (now async)
 #[async_trait::async_trait]
 pub trait ScanStrategy: Send + Sync {
     async fn scan_async(&self, port: Port, target_ip: IpAddr, config: &ScanConfig) -> PortScanResult;
     fn name(&self) -> &'static str;
+
+    /// Fail if `addr` cannot be reached at all; checked once before a host is scanned
+    fn check_route(&self, addr: &SocketAddr) -> std::io::Result<()> {
+        network_utils::check_route(addr)
+    }
 }
 
 /// Standard TCP connect scan (async)
-pub struct StandardScan;
+///
+/// A refused connect is closed and a reset one reset, unless
+/// `retry_on_reset` re-probes it: firewalls under load sometimes reset
+/// connects to ports that are open.
+///
+/// ```
+/// use std::sync::Arc;
+/// use port_scanner::infrastructure::testing::{Reply, ScriptedConnector};
+/// use port_scanner::prelude::*;
+/// use port_scanner::scanning::{ScanStrategy, StandardScan};
+///
+/// # #[tokio::main]
+/// # async fn main() -> anyhow::Result<()> {
+/// let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+/// let port = listener.local_addr()?.port();
+///
+/// for (retry, expected) in [(false, PortStatus::Reset), (true, PortStatus::Open)] {
+///     let config = ScanConfigBuilder::new()
+///         .target("127.0.0.1".parse()?)
+///         .custom_ports(vec![port])
+///         .retry_on_reset(retry)
+///         .build()?;
+///     // Resets the first connect, then connects for real
+///     let flaky = ScriptedConnector::new(Reply::Accept).on_port(port, [Reply::Reset, Reply::Accept]);
+///     let scan = StandardScan::new().with_connector(Arc::new(flaky));
+///     assert_eq!(scan.scan_async(port, config.target_ip, &config).await.status, expected);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct StandardScan {
+    detectors: Arc<DetectorRegistry>,
+    connector: Arc<dyn AsyncNetworkConnector>,
+}
 
 impl StandardScan {
     pub fn new() -> Self {
-        Self
+        Self::with_detectors(Arc::new(DetectorRegistry::with_defaults()))
+    }
+
+    /// Create a scan that runs the given detectors on open ports.
+    ///
+    /// UDP detectors are left to the UDP pass of a scan, so a closed TCP
+    /// port stays closed even when the same UDP port answers:
+    ///
+    /// ```
+    /// use port_scanner::prelude::*;
+    /// use port_scanner::scanning::{Detector, DetectorRegistry, ScanStrategy, StandardScan};
+    /// use std::net::{SocketAddr, TcpListener};
+    /// use std::sync::atomic::{AtomicUsize, Ordering};
+    /// use std::sync::Arc;
+    /// use std::time::Duration;
+    ///
+    /// /// UDP detector that would claim any port
+    /// struct Claims(Arc<AtomicUsize>);
+    ///
+    /// #[async_trait::async_trait]
+    /// impl Detector for Claims {
+    ///     fn name(&self) -> &str { "Claims" }
+    ///     fn can_detect(&self, _: Port) -> bool { true }
+    ///     fn is_udp(&self) -> bool { true }
+    ///     fn detect_service(&self, _: &SocketAddr, _: Duration) -> Option<ServiceVersion> { None }
+    ///     async fn detect_service_async(&self, _: &SocketAddr, _: Duration) -> Option<ServiceVersion> {
+    ///         self.0.fetch_add(1, Ordering::SeqCst);
+    ///         Some(ServiceVersion::new("snmp", "udp"))
+    ///     }
+    /// }
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// let closed = TcpListener::bind("127.0.0.1:0")?.local_addr()?.port();
+    /// let calls = Arc::new(AtomicUsize::new(0));
+    /// let mut detectors = DetectorRegistry::new();
+    /// detectors.register(Box::new(Claims(Arc::clone(&calls))));
+    /// let config = ScanConfigBuilder::new()
+    ///     .target("127.0.0.1".parse()?)
+    ///     .custom_ports(vec![closed])
+    ///     .detect_versions(true)
+    ///     .build()?;
+    ///
+    /// let result = StandardScan::with_detectors(Arc::new(detectors)).scan_async(closed, config.target_ip, &config).await;
+    /// assert_eq!((result.status, result.protocol), (PortStatus::Closed, Protocol::Tcp));
+    /// assert!(result.service_version.is_none());
+    /// assert_eq!(calls.load(Ordering::SeqCst), 0);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_detectors(detectors: Arc<DetectorRegistry>) -> Self {
+        Self { detectors, connector: Arc::new(AsyncTcpConnector::new()) }
+    }
+
+    /// Open probe connections through `connector` instead of a plain connect
+    pub fn with_connector(mut self, connector: Arc<dyn AsyncNetworkConnector>) -> Self {
+        self.connector = connector;
+        self
+    }
+
+    /// Connect and time the handshake, re-probing up to
+    /// `RESET_RETRY_ATTEMPTS` times after a reset when `retry_on_reset` is set
+    async fn connect(&self, socket: &SocketAddr, config: &ScanConfig) -> io::Result<(AsyncTcpStream, Duration)> {
+        let mut retries = 0;
+        loop {
+            let started = Instant::now();
+            record_attempt();
+            match self.connector.connect(socket, config.timeout).await {
+                Ok(stream) => return Ok((stream, started.elapsed())),
+                Err(e) if config.retry_on_reset && retries < RESET_RETRY_ATTEMPTS && network_utils::is_connection_reset(&e) => {
+                    retries += 1;
+                    debug!("Port {} reset ({}); retrying ({}/{})", socket.port(), e, retries, RESET_RETRY_ATTEMPTS);
+                }
+                Err(e) => return Err(e),
+            }
+        }
     }
 }
 
@@ -35,52 +153,91 @@ impl Default for StandardScan {
 #[async_trait::async_trait]
 impl ScanStrategy for StandardScan {
     async fn scan_async(&self, port: Port, target_ip: IpAddr, config: &ScanConfig) -> PortScanResult {
-        let socket = SocketAddr::new(target_ip, port);
+        let socket = network_utils::socket_addr(target_ip, port, config.scope_id);
         
         trace!("Async scanning port {} on {}", port, target_ip);
 
         // Async TCP connection with timeout
-        match timeout(config.timeout, TcpStream::connect(&socket)).await {
-            Ok(Ok(stream)) => {
-                debug!("Port {} is OPEN", port);
-                let mut result = PortScanResult::new(port, PortStatus::Open);
+        let result = match self.connect(&socket, config).await {
+            Ok((mut stream, latency)) => {
+                debug!("Port {} is OPEN ({:?})", port, latency);
+                mark_open();
+                let mut result = PortScanResult::new(port, PortStatus::Open).with_connect_latency(latency);
+
+                // Banner-grab-only mode: read the banner over the scan connection
+                let detect_versions = config.detect_versions && config.detects_on(port);
+                let grabbed = if detect_versions && config.reuse_connection {
+                    debug!("Grabbing banner over scan connection on port {}", port);
+                    let version = VersionDetector::for_config(config).grab_on_stream(port, &mut stream).await;
+                    Some((version.service_name != "unknown" || version.banner.is_some()).then_some(version))
+                } else {
+                    None
+                };
+                if config.graceful_close {
+                    network_utils::close_gracefully(stream).await;
+                } else {
+                    drop(stream);
+                }
                 
                 // Perform service version detection if enabled
-                if config.detect_versions {
-                    debug!("Service detection enabled - attempting on port {}", port);
-                    let version = VersionDetector::detect_version_async(&socket, config.timeout).await;
-                    if version.service_name != "Unknown" {
-                        let version_str = version.version.as_deref().unwrap_or("unknown version");
-                        debug!("Detected service on port {}: {} {}", port, version.service_name, version_str);
-                        result = result.with_version(version);
-                    } else {
-                        trace!("No service detected on port {}", port);
+                if detect_versions {
+                    let detected = match grabbed {
+                        Some(detected) => detected,
+                        None => {
+                            debug!("Service detection enabled - attempting on port {}", port);
+                            self.detectors.detect_service_async(port, &socket, config.timeout).await
+                        }
+                    };
+                    match detected {
+                        Some(version) => {
+                            let version_str = version.version.as_deref().unwrap_or("unknown version");
+                            debug!("Detected service on port {}: {} {}", port, version.service_name, version_str);
+                            result = result.with_version(version);
+                        }
+                        None => trace!("No service detected on port {}", port),
                     }
                 }
                 
-                // Perform OS detection if enabled and port is 445 (SMB)
-                if config.detect_os && port == 445 {
-                    debug!("OS detection enabled - attempting SMB fingerprinting on port {}", port);
-                    let os_info = SMBFingerprinter::fingerprint_async(&socket, config.timeout).await;
-                    if os_info.os_name.as_ref().map_or(false, |n| n != "Unknown") {
-                        debug!("OS detected via SMB: {}", os_info.summary());
-                        result = result.with_os_info(os_info);
-                    } else {
-                        debug!("OS detection on port {} did not yield results", port);
+                // Fall back to the well-known service name when nothing was detected
+                if result.service_version.is_none() {
+                    if let Some(guess) = VersionDetector::guess_from_port(port) {
+                        trace!("Port {} guessed as {} from port number", port, guess.service_name);
+                        result = result.with_version(guess);
+                    }
+                }
+                
+                // Perform OS detection if enabled (detectors decide which ports apply)
+                if config.detect_os && config.detects_on(port) {
+                    match self.detectors.detect_os_async(port, &socket, config.timeout).await {
+                        Some(os_info) => {
+                            debug!("OS detected on port {}: {}", port, os_info.summary());
+                            result = result.with_os_info(os_info);
+                        }
+                        None => trace!("OS detection on port {} did not yield results", port),
                     }
                 }
                 
                 result
             }
-            Ok(Err(_)) => {
+            Err(e) if network_utils::is_connection_refused(&e) => {
                 trace!("Port {} is CLOSED", port);
                 PortScanResult::new(port, PortStatus::Closed)
             }
-            Err(_) => {
-                trace!("Port {} is FILTERED (timeout)", port);
+            Err(e) if network_utils::is_connection_reset(&e) => {
+                trace!("Port {} is RESET ({})", port, e);
+                PortScanResult::new(port, PortStatus::Reset)
+            }
+            Err(e) if network_utils::is_timeout(&e) => {
+                trace!("Port {} is FILTERED ({})", port, e);
                 PortScanResult::new(port, PortStatus::Filtered)
             }
-        }
+            Err(e) => {
+                debug!("Port {} connect error: {}", port, e);
+                PortScanResult::from_error(port, &e)
+            }
+        };
+
+        result
     }
 
     fn name(&self) -> &'static str {
@@ -88,12 +245,188 @@ impl ScanStrategy for StandardScan {
     }
 }
 
+/// UDP scan sending one datagram per port (async)
+///
+/// A reply means open, an ICMP port unreachable means closed, and silence is
+/// open|filtered: the service may have ignored the probe, or a filter dropped
+/// it. With service detection on, ports that have a UDP detector (e.g. SNMP)
+/// are probed through it first.
+///
+/// The unreachable is read from the connected socket's next receive, which
+/// fails with `ECONNREFUSED` on Linux and the BSDs and `WSAECONNRESET` on
+/// Windows. Where ICMP errors don't arrive or can't be trusted, turn
+/// `udp_icmp_closed` off and they are reported as open|filtered too.
+///
+/// ```
+/// use std::io;
+/// use std::net::SocketAddr;
+/// use std::sync::Arc;
+/// use std::time::Duration;
+/// use port_scanner::infrastructure::AsyncDatagramProber;
+/// use port_scanner::prelude::*;
+/// use port_scanner::scanning::{ScanStrategy, UdpScan};
+///
+/// /// Port 53 answers, port 54 is unreachable, everything else is silent
+/// struct MockProber;
+///
+/// #[async_trait::async_trait]
+/// impl AsyncDatagramProber for MockProber {
+///     async fn probe(&self, addr: &SocketAddr, _: &[u8], _: Duration) -> io::Result<Option<Vec<u8>>> {
+///         match addr.port() {
+///             53 => Ok(Some(b"reply".to_vec())),
+///             54 => Err(io::ErrorKind::ConnectionRefused.into()),
+///             _ => Ok(None),
+///         }
+///     }
+/// }
+///
+/// # #[tokio::main]
+/// # async fn main() -> anyhow::Result<()> {
+/// let config = ScanConfigBuilder::new().target("127.0.0.1".parse()?).custom_ports(vec![53, 54, 55]).build()?;
+/// let scan = UdpScan::new().with_prober(Arc::new(MockProber));
+///
+/// let statuses = [53, 54, 55].map(|port| scan.scan_async(port, config.target_ip, &config));
+/// let [open, closed, silent] = futures::future::join_all(statuses).await.try_into().unwrap();
+/// assert_eq!((open.protocol, open.status), (Protocol::Udp, PortStatus::Open));
+/// assert_eq!(closed.status, PortStatus::Closed);
+/// assert_eq!(silent.status, PortStatus::OpenFiltered);
+/// # Ok(())
+/// # }
+/// ```
+///
+/// Against a real closed port (Linux delivers the ICMP error to the socket):
+///
+/// ```
+/// use std::net::UdpSocket;
+/// use port_scanner::prelude::*;
+/// use port_scanner::scanning::{ScanStrategy, UdpScan};
+///
+/// # #[tokio::main]
+/// # async fn main() -> anyhow::Result<()> {
+/// # if cfg!(target_os = "linux") {
+/// // A port that was just free is almost certainly still closed
+/// let port = UdpSocket::bind("127.0.0.1:0")?.local_addr()?.port();
+/// let config = ScanConfigBuilder::new().target("127.0.0.1".parse()?).custom_ports(vec![port]).build()?;
+/// let scan = UdpScan::new();
+/// assert_eq!(scan.scan_async(port, config.target_ip, &config).await.status, PortStatus::Closed);
+///
+/// let untrusted = ScanConfigBuilder::from(config).udp_icmp_closed(false).build()?;
+/// assert_eq!(scan.scan_async(port, untrusted.target_ip, &untrusted).await.status, PortStatus::OpenFiltered);
+/// # }
+/// # Ok(())
+/// # }
+/// ```
+pub struct UdpScan {
+    detectors: Arc<DetectorRegistry>,
+    prober: Arc<dyn AsyncDatagramProber>,
+}
+
+impl UdpScan {
+    pub fn new() -> Self {
+        Self::with_detectors(Arc::new(DetectorRegistry::with_defaults()))
+    }
+
+    /// Create a scan that runs the given UDP detectors
+    pub fn with_detectors(detectors: Arc<DetectorRegistry>) -> Self {
+        Self { detectors, prober: Arc::new(AsyncUdpProber::new()) }
+    }
+
+    /// Send probes through `prober` instead of a real socket
+    pub fn with_prober(mut self, prober: Arc<dyn AsyncDatagramProber>) -> Self {
+        self.prober = prober;
+        self
+    }
+}
+
+impl Default for UdpScan {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Datagram most likely to get a reply from the usual service on `port`;
+/// empty when there is none
+fn udp_payload(port: Port) -> &'static [u8] {
+    // DNS: standard query for the root NS records
+    const DNS_QUERY: [u8; 17] = [0x50, 0x53, 0x01, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x00, 0x01];
+    // NTP: version 4 client request
+    const NTP_REQUEST: [u8; 48] = {
+        let mut request = [0u8; 48];
+        request[0] = 0x23;
+        request
+    };
+    match port {
+        53 => &DNS_QUERY,
+        123 => &NTP_REQUEST,
+        _ => &[],
+    }
+}
+
+#[async_trait::async_trait]
+impl ScanStrategy for UdpScan {
+    async fn scan_async(&self, port: Port, target_ip: IpAddr, config: &ScanConfig) -> PortScanResult {
+        let socket = network_utils::socket_addr(target_ip, port, config.scope_id);
+        trace!("Async UDP scanning port {} on {}", port, target_ip);
+
+        if config.detect_versions && config.detects_on(port) && self.detectors.has_udp_detector(port) {
+            if let Some(version) = self.detectors.detect_udp_async(port, &socket, config.timeout).await {
+                debug!("Port {}/udp is OPEN ({})", port, version.service_name);
+                return PortScanResult::new(port, PortStatus::Open)
+                    .with_protocol(Protocol::Udp)
+                    .with_version(version);
+            }
+        }
+
+        record_attempt();
+        let status = match self.prober.probe(&socket, udp_payload(port), config.timeout).await {
+            Ok(Some(reply)) => {
+                debug!("Port {}/udp is OPEN ({} byte reply)", port, reply.len());
+                PortStatus::Open
+            }
+            Ok(None) => {
+                trace!("Port {}/udp is OPEN|FILTERED", port);
+                PortStatus::OpenFiltered
+            }
+            Err(e) if network_utils::is_port_unreachable(&e) && config.udp_icmp_closed => {
+                trace!("Port {}/udp is CLOSED", port);
+                PortStatus::Closed
+            }
+            Err(e) if network_utils::is_port_unreachable(&e) => {
+                trace!("Port {}/udp is OPEN|FILTERED (ignoring ICMP unreachable)", port);
+                PortStatus::OpenFiltered
+            }
+            Err(e) => {
+                debug!("Port {}/udp probe error: {}", port, e);
+                return PortScanResult::from_error(port, &e).with_protocol(Protocol::Udp);
+            }
+        };
+        PortScanResult::new(port, status).with_protocol(Protocol::Udp)
+    }
+
+    fn name(&self) -> &'static str {
+        "UDP (Async)"
+    }
+}
+
 /// Stealth scan with source port randomization (async)
-pub struct StealthScan;
+pub struct StealthScan {
+    inner: StandardScan,
+}
 
 impl StealthScan {
     pub fn new() -> Self {
-        Self
+        Self { inner: StandardScan::new() }
+    }
+
+    /// Create a stealth scan that runs the given detectors on open ports
+    pub fn with_detectors(detectors: Arc<DetectorRegistry>) -> Self {
+        Self { inner: StandardScan::with_detectors(detectors) }
+    }
+
+    /// Open probe connections through `connector`
+    pub fn with_connector(mut self, connector: Arc<dyn AsyncNetworkConnector>) -> Self {
+        self.inner = self.inner.with_connector(connector);
+        self
     }
 }
 
@@ -112,8 +445,7 @@ impl ScanStrategy for StealthScan {
         }
 
         // Use standard scan logic (async version)
-        let standard = StandardScan::new();
-        standard.scan_async(port, target_ip, config).await
+        self.inner.scan_async(port, target_ip, config).await
     }
 
     fn name(&self) -> &'static str {
@@ -126,10 +458,66 @@ pub struct ScanStrategyFactory;
 
 impl ScanStrategyFactory {
     pub fn create(config: &ScanConfig) -> Arc<dyn ScanStrategy> {
+        Self::create_with_detectors(config, Arc::new(DetectorRegistry::for_config(config)))
+    }
+
+    /// Strategy probing over `protocol`; TCP gets the strategy
+    /// `create_with_detectors` picks for `config`
+    pub fn create_for_protocol(config: &ScanConfig, detectors: Arc<DetectorRegistry>, protocol: Protocol) -> Arc<dyn ScanStrategy> {
+        match protocol {
+            Protocol::Tcp => Self::create_with_detectors(config, detectors),
+            Protocol::Udp => Arc::new(UdpScan::with_detectors(detectors)
+                .with_prober(Arc::new(AsyncUdpProber::new().bind_device(config.bind_interface.clone())))),
+        }
+    }
+
+    /// Strategy for `config` that detects services with `detectors` instead
+    /// of the built-in registry
+    pub fn create_with_detectors(config: &ScanConfig, detectors: Arc<DetectorRegistry>) -> Arc<dyn ScanStrategy> {
+        if config.scan_type.is_raw() {
+            #[cfg(all(feature = "syn-scan", unix))]
+            match Self::create_raw(config.scan_type, Arc::clone(&detectors)) {
+                Ok(scan) => return scan,
+                // Reported once per process by `check_privileges` when the scanner is created
+                Err(e) => debug!("{} scan unavailable ({}); falling back to TCP connect scan", config.scan_type, e),
+            }
+            #[cfg(not(all(feature = "syn-scan", unix)))]
+            debug!("Built without the syn-scan feature; falling back to TCP connect scan");
+        }
+        let connector = AsyncTcpConnector::new()
+            .linger(config.linger)
+            .keepalive(config.keepalive)
+            .bind_device(config.bind_interface.clone());
         if config.randomize_source_port || config.delay_between_probes.is_some() {
-            Arc::new(StealthScan::new())
+            let connector = connector
+                .randomize_source_port(config.randomize_source_port)
+                .reuse_address(config.randomize_source_port);
+            Arc::new(StealthScan::with_detectors(detectors).with_connector(Arc::new(connector)))
         } else {
-            Arc::new(StandardScan::new())
+            Arc::new(StandardScan::with_detectors(detectors).with_connector(Arc::new(connector)))
         }
     }
+
+    /// Scan types this build can run; raw-socket types need the `syn-scan`
+    /// feature on Unix, and privileges at runtime
+    pub fn supported_scan_types() -> Vec<ScanType> {
+        ScanType::ALL.into_iter()
+            .filter(|scan_type| !scan_type.is_raw() || cfg!(all(feature = "syn-scan", unix)))
+            .collect()
+    }
+
+    /// Raw-socket strategy for `scan_type`; fails without raw-socket privileges
+    #[cfg(all(feature = "syn-scan", unix))]
+    fn create_raw(scan_type: ScanType, detectors: Arc<DetectorRegistry>) -> std::io::Result<Arc<dyn ScanStrategy>> {
+        use crate::scanning::flag_scan::{FinScan, NullScan, XmasScan};
+        use crate::scanning::syn::SynScan;
+
+        Ok(match scan_type {
+            ScanType::Fin => Arc::new(FinScan::with_detectors(detectors)?),
+            ScanType::Null => Arc::new(NullScan::with_detectors(detectors)?),
+            ScanType::Xmas => Arc::new(XmasScan::with_detectors(detectors)?),
+            ScanType::Syn => Arc::new(SynScan::with_detectors(detectors)?),
+            ScanType::Connect => Arc::new(StandardScan::with_detectors(detectors)),
+        })
+    }
 }
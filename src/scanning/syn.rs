@@ -0,0 +1,339 @@
+//! Raw-socket SYN (half-open) scan strategy
+
+use std::io::{self, Read};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr, UdpSocket};
+use std::sync::Arc;
+use rand::Rng;
+use socket2::{Domain, Protocol, Socket, Type};
+use tokio::io::unix::AsyncFd;
+use tokio::time::{timeout_at, Instant};
+use tracing::{debug, trace};
+
+use crate::application::VersionDetector;
+use crate::domain::{Port, PortStatus, PortScanResult};
+use crate::infrastructure::with_rng;
+use crate::scanning::config::ScanConfig;
+use crate::scanning::detector::DetectorRegistry;
+use crate::scanning::strategy::{ScanStrategy, StandardScan};
+
+const IPV4_HEADER_LEN: usize = 20;
+const TCP_HEADER_LEN: usize = 20;
+pub(crate) const TCP_FLAG_FIN: u8 = 0x01;
+pub(crate) const TCP_FLAG_SYN: u8 = 0x02;
+pub(crate) const TCP_FLAG_RST: u8 = 0x04;
+pub(crate) const TCP_FLAG_PSH: u8 = 0x08;
+pub(crate) const TCP_FLAG_ACK: u8 = 0x10;
+pub(crate) const TCP_FLAG_URG: u8 = 0x20;
+const PROBE_WINDOW: u16 = 1024;
+const PROBE_TTL: u8 = 64;
+const IPV4_DONT_FRAGMENT: u16 = 0x4000;
+const IPPROTO_TCP: u8 = 6;
+
+/// IP TTL and TCP window of crafted probes, set to mimic another OS's stack
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PacketShape {
+    pub ttl: u8,
+    pub window: u16,
+}
+
+impl PacketShape {
+    /// `config.ttl` and `config.tcp_window`, with defaults for unset fields
+    pub fn for_config(config: &ScanConfig) -> Self {
+        Self {
+            ttl: config.ttl.unwrap_or(PROBE_TTL),
+            window: config.tcp_window.unwrap_or(PROBE_WINDOW),
+        }
+    }
+}
+
+impl Default for PacketShape {
+    fn default() -> Self {
+        Self { ttl: PROBE_TTL, window: PROBE_WINDOW }
+    }
+}
+
+/// Answer to a SYN probe
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SynReply {
+    SynAck,
+    Rst,
+}
+
+/// TCP flags and IP TTL of a reply to one of our probes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct ProbeReply {
+    pub flags: u8,
+    pub ttl: u8,
+}
+
+/// SYN scan: sends a bare SYN and classifies the reply without completing
+/// the handshake (the kernel answers a SYN/ACK with RST).
+///
+/// Requires raw-socket privileges (root or `CAP_NET_RAW`) and IPv4; IPv6
+/// targets and service/OS detection use a regular connect.
+///
+/// ```
+/// use port_scanner::prelude::*;
+/// use port_scanner::scanning::{DetectorRegistry, ScanStrategy, SynScan};
+/// use std::net::TcpListener;
+/// use std::sync::Arc;
+/// use std::time::Duration;
+///
+/// # #[tokio::main]
+/// # async fn main() -> anyhow::Result<()> {
+/// let Ok(scan) = SynScan::with_detectors(Arc::new(DetectorRegistry::new())) else {
+///     // Needs root or CAP_NET_RAW; nothing to check without them
+///     return Ok(());
+/// };
+/// let listener = TcpListener::bind("127.0.0.1:0")?;
+/// let open = listener.local_addr()?.port();
+/// let closed = TcpListener::bind("127.0.0.1:0")?.local_addr()?.port();
+/// let config = ScanConfigBuilder::new()
+///     .target("127.0.0.1".parse()?)
+///     .custom_ports(vec![open, closed])
+///     .timeout(Duration::from_secs(1))
+///     .build()?;
+///
+/// let result = scan.scan_async(open, config.target_ip, &config).await;
+/// assert_eq!(result.status, PortStatus::Open);
+/// // Only a raw reply carries the TTL; a connect scan leaves it unset
+/// assert!(result.ttl.is_some());
+/// assert_eq!(scan.scan_async(closed, config.target_ip, &config).await.status, PortStatus::Closed);
+/// # Ok(())
+/// # }
+/// ```
+pub struct SynScan {
+    inner: StandardScan,
+}
+
+impl SynScan {
+    /// Fails when raw sockets are not permitted for this process
+    pub fn with_detectors(detectors: Arc<DetectorRegistry>) -> io::Result<Self> {
+        raw_socket()?;
+        Ok(Self { inner: StandardScan::with_detectors(detectors) })
+    }
+
+    async fn probe(target: Ipv4Addr, port: Port, config: &ScanConfig) -> io::Result<Option<(SynReply, u8)>> {
+        let reply = send_probe(target, port, TCP_FLAG_SYN, config).await?;
+        Ok(reply.and_then(|reply| Some((classify_syn_reply(reply.flags)?, reply.ttl))))
+    }
+}
+
+#[async_trait::async_trait]
+impl ScanStrategy for SynScan {
+    async fn scan_async(&self, port: Port, target_ip: IpAddr, config: &ScanConfig) -> PortScanResult {
+        let IpAddr::V4(target) = target_ip else {
+            return self.inner.scan_async(port, target_ip, config).await;
+        };
+        if let Some(delay) = config.delay_between_probes {
+            tokio::time::sleep(delay).await;
+        }
+
+        match Self::probe(target, port, config).await {
+            Ok(Some((SynReply::SynAck, ttl))) => {
+                debug!("Port {} is OPEN (SYN/ACK, TTL {})", port, ttl);
+                if (config.detect_versions || config.detect_os) && config.detects_on(port) {
+                    // Detection needs a real connection
+                    return self.inner.scan_async(port, target_ip, config).await.with_ttl(ttl);
+                }
+                let result = PortScanResult::new(port, PortStatus::Open).with_ttl(ttl);
+                match VersionDetector::guess_from_port(port) {
+                    Some(guess) => result.with_version(guess),
+                    None => result,
+                }
+            }
+            Ok(Some((SynReply::Rst, ttl))) => {
+                trace!("Port {} is CLOSED (RST)", port);
+                PortScanResult::new(port, PortStatus::Closed).with_ttl(ttl)
+            }
+            Ok(None) => {
+                trace!("Port {} is FILTERED (no reply)", port);
+                PortScanResult::new(port, PortStatus::Filtered)
+            }
+            Err(e) => {
+                debug!("SYN probe to port {} failed: {}", port, e);
+                PortScanResult::from_error(port, &e)
+            }
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        "TCP SYN (Half-Open)"
+    }
+}
+
+/// Send a TCP segment with `flags` to `target:port` and wait up to the
+/// configured timeout for the reply
+pub(crate) async fn send_probe(target: Ipv4Addr, port: Port, flags: u8, config: &ScanConfig) -> io::Result<Option<ProbeReply>> {
+    let source = source_address(target, port)?;
+    let (source_port, seq) = config.rng.with(|rng| (rng.gen::<u16>() | 0x8000, rng.gen::<u32>()));
+    let packet = build_packet(source, target, source_port, port, seq, flags, PacketShape::for_config(config));
+
+    let socket = raw_socket()?;
+    socket.set_nonblocking(true)?;
+    socket.set_header_included_v4(true)?;
+    socket.send_to(&packet, &SocketAddr::new(IpAddr::V4(target), 0).into())?;
+
+    let socket = AsyncFd::new(socket)?;
+    let deadline = Instant::now() + config.timeout;
+    let mut buffer = [0u8; 1500];
+    loop {
+        let mut guard = match timeout_at(deadline, socket.readable()).await {
+            Ok(guard) => guard?,
+            Err(_) => return Ok(None),
+        };
+        let n = match guard.try_io(|fd| fd.get_ref().read(&mut buffer)) {
+            Ok(result) => result?,
+            Err(_would_block) => continue,
+        };
+        // Replies to other probes are seen too; keep waiting for ours
+        if let Some(reply) = match_reply(&buffer[..n], target, port, source_port) {
+            return Ok(Some(reply));
+        }
+    }
+}
+
+pub(crate) fn raw_socket() -> io::Result<Socket> {
+    Socket::new(Domain::IPV4, Type::RAW, Some(Protocol::TCP))
+}
+
+/// Local address the kernel would use to reach `target`
+fn source_address(target: Ipv4Addr, port: Port) -> io::Result<Ipv4Addr> {
+    let udp = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0))?;
+    udp.connect((target, port))?;
+    match udp.local_addr()?.ip() {
+        IpAddr::V4(ip) => Ok(ip),
+        IpAddr::V6(_) => Err(io::Error::new(io::ErrorKind::Unsupported, "no IPv4 source address")),
+    }
+}
+
+/// Build a TCP SYN segment (no IP header; the kernel adds it)
+pub fn build_syn(source: Ipv4Addr, target: Ipv4Addr, source_port: u16, target_port: u16, seq: u32) -> [u8; TCP_HEADER_LEN] {
+    build_segment(source, target, source_port, target_port, seq, TCP_FLAG_SYN)
+}
+
+/// Build a bare TCP segment with the given flags (no IP header)
+pub fn build_segment(source: Ipv4Addr, target: Ipv4Addr, source_port: u16, target_port: u16, seq: u32, flags: u8) -> [u8; TCP_HEADER_LEN] {
+    segment_with_window(source, target, source_port, target_port, seq, flags, PROBE_WINDOW)
+}
+
+/// Build an IPv4 packet carrying a bare TCP segment with the given flags,
+/// with the TTL and window from `shape`
+///
+/// ```
+/// use port_scanner::scanning::syn::{build_packet, PacketShape};
+/// use std::net::Ipv4Addr;
+///
+/// let shape = PacketShape { ttl: 128, window: 8192 };
+/// let packet = build_packet(Ipv4Addr::new(10, 0, 0, 1), Ipv4Addr::new(10, 0, 0, 2), 40000, 80, 1, 0x02, shape);
+/// assert_eq!(packet[8], 128);
+/// assert_eq!(u16::from_be_bytes([packet[20 + 14], packet[20 + 15]]), 8192);
+/// assert_eq!(packet[20 + 13], 0x02);
+/// ```
+pub fn build_packet(
+    source: Ipv4Addr,
+    target: Ipv4Addr,
+    source_port: u16,
+    target_port: u16,
+    seq: u32,
+    flags: u8,
+    shape: PacketShape,
+) -> [u8; IPV4_HEADER_LEN + TCP_HEADER_LEN] {
+    let mut packet = [0u8; IPV4_HEADER_LEN + TCP_HEADER_LEN];
+    let id = with_rng(|rng| rng.gen::<u16>());
+    packet[0] = 0x40 | (IPV4_HEADER_LEN / 4) as u8;
+    packet[2..4].copy_from_slice(&((IPV4_HEADER_LEN + TCP_HEADER_LEN) as u16).to_be_bytes());
+    packet[4..6].copy_from_slice(&id.to_be_bytes());
+    packet[6..8].copy_from_slice(&IPV4_DONT_FRAGMENT.to_be_bytes());
+    packet[8] = shape.ttl;
+    packet[9] = IPPROTO_TCP;
+    packet[12..16].copy_from_slice(&source.octets());
+    packet[16..20].copy_from_slice(&target.octets());
+    let checksum = internet_checksum(&packet[..IPV4_HEADER_LEN]);
+    packet[10..12].copy_from_slice(&checksum.to_be_bytes());
+
+    let segment = segment_with_window(source, target, source_port, target_port, seq, flags, shape.window);
+    packet[IPV4_HEADER_LEN..].copy_from_slice(&segment);
+    packet
+}
+
+fn segment_with_window(source: Ipv4Addr, target: Ipv4Addr, source_port: u16, target_port: u16, seq: u32, flags: u8, window: u16) -> [u8; TCP_HEADER_LEN] {
+    let mut segment = [0u8; TCP_HEADER_LEN];
+    segment[0..2].copy_from_slice(&source_port.to_be_bytes());
+    segment[2..4].copy_from_slice(&target_port.to_be_bytes());
+    segment[4..8].copy_from_slice(&seq.to_be_bytes());
+    segment[12] = ((TCP_HEADER_LEN / 4) as u8) << 4;
+    segment[13] = flags;
+    segment[14..16].copy_from_slice(&window.to_be_bytes());
+
+    let checksum = tcp_checksum(source, target, &segment);
+    segment[16..18].copy_from_slice(&checksum.to_be_bytes());
+    segment
+}
+
+/// Internet checksum over the IPv4 pseudo-header and the segment
+fn tcp_checksum(source: Ipv4Addr, target: Ipv4Addr, segment: &[u8]) -> u16 {
+    let mut pseudo = Vec::with_capacity(12 + segment.len());
+    pseudo.extend_from_slice(&source.octets());
+    pseudo.extend_from_slice(&target.octets());
+    pseudo.extend_from_slice(&[0, IPPROTO_TCP]);
+    pseudo.extend_from_slice(&(segment.len() as u16).to_be_bytes());
+    pseudo.extend_from_slice(segment);
+    internet_checksum(&pseudo)
+}
+
+/// One's-complement sum of 16-bit words (RFC 1071)
+fn internet_checksum(data: &[u8]) -> u16 {
+    let mut sum: u32 = data.chunks(2)
+        .map(|c| u16::from_be_bytes([c[0], *c.get(1).unwrap_or(&0)]) as u32)
+        .sum();
+    while sum >> 16 != 0 {
+        sum = (sum & 0xffff) + (sum >> 16);
+    }
+    !(sum as u16)
+}
+
+/// Match an IPv4 packet read from the raw socket against our SYN probe
+///
+/// ```
+/// use port_scanner::scanning::syn::{build_packet, parse_reply, PacketShape, SynReply};
+/// use std::net::Ipv4Addr;
+///
+/// let (us, target) = (Ipv4Addr::new(10, 0, 0, 1), Ipv4Addr::new(10, 0, 0, 2));
+/// let reply = |flags, from_port| build_packet(target, us, from_port, 40000, 7, flags, PacketShape::default());
+///
+/// assert_eq!(parse_reply(&reply(0x12, 80), target, 80, 40000), Some(SynReply::SynAck));
+/// assert_eq!(parse_reply(&reply(0x14, 80), target, 80, 40000), Some(SynReply::Rst));
+/// // Replies to other probes are not ours
+/// assert_eq!(parse_reply(&reply(0x12, 443), target, 80, 40000), None);
+/// assert_eq!(parse_reply(&reply(0x12, 80), Ipv4Addr::new(10, 0, 0, 3), 80, 40000), None);
+/// ```
+pub fn parse_reply(packet: &[u8], target: Ipv4Addr, target_port: Port, source_port: u16) -> Option<SynReply> {
+    match_reply(packet, target, target_port, source_port).and_then(|reply| classify_syn_reply(reply.flags))
+}
+
+fn classify_syn_reply(flags: u8) -> Option<SynReply> {
+    if flags & TCP_FLAG_RST != 0 {
+        Some(SynReply::Rst)
+    } else if flags & (TCP_FLAG_SYN | TCP_FLAG_ACK) == TCP_FLAG_SYN | TCP_FLAG_ACK {
+        Some(SynReply::SynAck)
+    } else {
+        None
+    }
+}
+
+/// Flags and TTL of `packet` if it is a reply from `target:target_port` to `source_port`
+pub(crate) fn match_reply(packet: &[u8], target: Ipv4Addr, target_port: Port, source_port: u16) -> Option<ProbeReply> {
+    let header_len = (*packet.first()? & 0x0f) as usize * 4;
+    if packet.get(9) != Some(&IPPROTO_TCP) || packet.get(12..16)? != target.octets() {
+        return None;
+    }
+    let tcp = packet.get(header_len..header_len + TCP_HEADER_LEN)?;
+    let from_port = u16::from_be_bytes([tcp[0], tcp[1]]);
+    let to_port = u16::from_be_bytes([tcp[2], tcp[3]]);
+    if from_port != target_port || to_port != source_port {
+        return None;
+    }
+
+    Some(ProbeReply { flags: tcp[13], ttl: packet[8] })
+}
@@ -0,0 +1,370 @@
+//! Port and target lists supplied as text or files
+
+use std::collections::HashSet;
+use std::fmt;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::path::Path;
+
+use crate::constants::{MIN_PORT, MAX_PORT, MAX_EXPANDED_TARGETS};
+use crate::domain::Port;
+use crate::errors::{ConfigError, ConfigResult};
+use crate::infrastructure::network_utils;
+
+/// A host to scan, with the IPv6 zone index for link-local addresses
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Target {
+    pub ip: IpAddr,
+    pub scope_id: u32,
+}
+
+impl fmt::Display for Target {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.scope_id != 0 {
+            write!(f, "{}%{}", self.ip, self.scope_id)
+        } else {
+            write!(f, "{}", self.ip)
+        }
+    }
+}
+
+impl From<IpAddr> for Target {
+    fn from(ip: IpAddr) -> Self {
+        Self { ip, scope_id: 0 }
+    }
+}
+
+/// An address block in CIDR notation, e.g. `192.168.1.0/24`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IpNetwork {
+    /// Network address (host bits cleared)
+    network: IpAddr,
+    prefix: u8,
+}
+
+impl IpNetwork {
+    pub fn parse(s: &str) -> ConfigResult<Self> {
+        let invalid = || ConfigError::InvalidTarget(s.to_string());
+        let (addr, prefix) = s.trim().split_once('/').ok_or_else(invalid)?;
+        let addr: IpAddr = addr.parse().map_err(|_| invalid())?;
+        let prefix: u8 = prefix.parse().map_err(|_| invalid())?;
+        if prefix > Self::bits(addr) {
+            return Err(invalid());
+        }
+        let network = Self::from_bits(addr, Self::to_bits(addr) & Self::mask(addr, prefix));
+        Ok(Self { network, prefix })
+    }
+
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        ip.is_ipv4() == self.network.is_ipv4()
+            && Self::to_bits(ip) & Self::mask(ip, self.prefix) == Self::to_bits(self.network)
+    }
+
+    /// Number of addresses in the block, including network and broadcast
+    pub fn size(&self) -> u128 {
+        match Self::bits(self.network) - self.prefix {
+            128 => u128::MAX,
+            host_bits => 1u128 << host_bits,
+        }
+    }
+
+    /// Every address in the block, in ascending order
+    pub fn hosts(&self) -> impl Iterator<Item = IpAddr> {
+        let network = self.network;
+        let first = Self::to_bits(network);
+        (0..self.size()).map(move |offset| Self::from_bits(network, first + offset))
+    }
+
+    fn bits(ip: IpAddr) -> u8 {
+        if ip.is_ipv4() { 32 } else { 128 }
+    }
+
+    fn mask(ip: IpAddr, prefix: u8) -> u128 {
+        let host_bits = Self::bits(ip) - prefix;
+        let all = if ip.is_ipv4() { u32::MAX as u128 } else { u128::MAX };
+        all.checked_shl(host_bits as u32).unwrap_or(0) & all
+    }
+
+    fn to_bits(ip: IpAddr) -> u128 {
+        match ip {
+            IpAddr::V4(v4) => u32::from(v4) as u128,
+            IpAddr::V6(v6) => u128::from(v6),
+        }
+    }
+
+    fn from_bits(family: IpAddr, bits: u128) -> IpAddr {
+        match family {
+            IpAddr::V4(_) => IpAddr::V4(Ipv4Addr::from(bits as u32)),
+            IpAddr::V6(_) => IpAddr::V6(Ipv6Addr::from(bits)),
+        }
+    }
+}
+
+impl fmt::Display for IpNetwork {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.network, self.prefix)
+    }
+}
+
+/// Expand an nmap-style target spec into its addresses: a plain address, a
+/// CIDR block, or an IPv4 address whose octets may be ranges.
+///
+/// ```
+/// use port_scanner::scanning::targets::parse_target_spec;
+/// use std::net::IpAddr;
+///
+/// let ips = |spec| parse_target_spec(spec).unwrap();
+/// let ip = |s: &str| s.parse::<IpAddr>().unwrap();
+///
+/// assert_eq!(ips("192.168.1.1-3"), [ip("192.168.1.1"), ip("192.168.1.2"), ip("192.168.1.3")]);
+/// assert_eq!(ips("192.168.0-1.1"), [ip("192.168.0.1"), ip("192.168.1.1")]);
+/// assert_eq!(ips("10.0.0-2.1-5").len(), 15);
+/// assert_eq!(ips("10.0.0.0/30").len(), 4);
+/// assert_eq!(ips("::1"), [ip("::1")]);
+///
+/// // Octets must be 0-255 and ranges ascending
+/// assert!(parse_target_spec("192.168.1.256").is_err());
+/// assert!(parse_target_spec("192.168.1.1-300").is_err());
+/// assert!(parse_target_spec("192.168.1.5-2").is_err());
+/// assert!(parse_target_spec("192.168.1-.1").is_err());
+/// assert!(parse_target_spec("192.168.1.1-2-3").is_err());
+/// assert!(parse_target_spec("192.168.1-2").is_err());
+/// ```
+pub fn parse_target_spec(spec: &str) -> ConfigResult<Vec<IpAddr>> {
+    Ok(expand_target(spec)?.into_iter().map(|target| target.ip).collect())
+}
+
+/// Expand one target entry: a CIDR block, an IPv4 address with octet ranges
+/// (`192.168.1.1-20`), or a single (optionally scoped) address
+pub fn expand_target(entry: &str) -> ConfigResult<Vec<Target>> {
+    if entry.contains('-') && !entry.contains(['/', ':', '%']) {
+        let ips = expand_octet_ranges(entry)?;
+        return Ok(ips.into_iter().map(|ip| Target { ip, scope_id: 0 }).collect());
+    }
+    if !entry.contains('/') {
+        let (ip, scope_id) = network_utils::parse_scoped_ip(entry)?;
+        return Ok(vec![Target { ip, scope_id }]);
+    }
+    let network = IpNetwork::parse(entry)?;
+    if network.size() > MAX_EXPANDED_TARGETS as u128 {
+        return Err(ConfigError::InvalidTarget(format!(
+            "{} has more than {} addresses", entry, MAX_EXPANDED_TARGETS
+        )));
+    }
+    Ok(network.hosts().map(|ip| Target { ip, scope_id: 0 }).collect())
+}
+
+/// Every IPv4 address matching `spec`, where each octet is a number or an
+/// inclusive `low-high` range
+fn expand_octet_ranges(spec: &str) -> ConfigResult<Vec<IpAddr>> {
+    let invalid = |reason: String| ConfigError::InvalidTarget(format!("{}: {}", spec, reason));
+    let octet = |text: &str| {
+        text.parse::<u8>().ok()
+            .filter(|_| text.bytes().all(|b| b.is_ascii_digit()))
+            .ok_or_else(|| invalid(format!("octet '{}' is not a number from 0 to 255", text)))
+    };
+
+    let parts: Vec<&str> = spec.split('.').collect();
+    if parts.len() != 4 {
+        return Err(invalid("expected four dot-separated octets".to_string()));
+    }
+    let ranges = parts.iter()
+        .map(|part| {
+            let (low, high) = part.split_once('-').unwrap_or((part, part));
+            let (low, high) = (octet(low)?, octet(high)?);
+            if low > high {
+                return Err(invalid(format!("range {}-{} runs backwards", low, high)));
+            }
+            Ok(low..=high)
+        })
+        .collect::<ConfigResult<Vec<_>>>()?;
+
+    let count: usize = ranges.iter().map(|range| range.clone().count()).product();
+    if count > MAX_EXPANDED_TARGETS {
+        return Err(invalid(format!("more than {} addresses", MAX_EXPANDED_TARGETS)));
+    }
+    let mut ips = Vec::with_capacity(count);
+    for a in ranges[0].clone() {
+        for b in ranges[1].clone() {
+            for c in ranges[2].clone() {
+                for d in ranges[3].clone() {
+                    ips.push(IpAddr::V4(Ipv4Addr::new(a, b, c, d)));
+                }
+            }
+        }
+    }
+    Ok(ips)
+}
+
+/// Hosts and address blocks to leave out of a scan
+#[derive(Debug, Clone, Default)]
+pub struct TargetExclusions {
+    hosts: Vec<IpAddr>,
+    networks: Vec<IpNetwork>,
+}
+
+impl TargetExclusions {
+    pub fn parse<S: AsRef<str>>(hosts: &[S], networks: &[S]) -> ConfigResult<Self> {
+        let hosts = hosts.iter()
+            .map(|h| h.as_ref().trim().parse().map_err(|_| ConfigError::InvalidTarget(h.as_ref().to_string())))
+            .collect::<ConfigResult<_>>()?;
+        let networks = networks.iter()
+            .map(|n| IpNetwork::parse(n.as_ref()))
+            .collect::<ConfigResult<_>>()?;
+        Ok(Self { hosts, networks })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.hosts.is_empty() && self.networks.is_empty()
+    }
+
+    pub fn excludes(&self, ip: IpAddr) -> bool {
+        self.hosts.contains(&ip) || self.networks.iter().any(|n| n.contains(ip))
+    }
+
+    /// Drop excluded targets; fails if none are left
+    ///
+    /// ```
+    /// use port_scanner::errors::ConfigError;
+    /// use port_scanner::scanning::{targets, TargetExclusions};
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// let block = targets::expand_target("10.0.0.0/28")?;
+    /// assert_eq!(block.len(), 16);
+    ///
+    /// let exclusions = TargetExclusions::parse::<&str>(&[], &["10.0.0.0/29"])?;
+    /// let remaining = exclusions.apply(block.clone())?;
+    /// let ips: Vec<String> = remaining.iter().map(|t| t.ip.to_string()).collect();
+    /// assert_eq!(ips.len(), 8);
+    /// assert_eq!((ips[0].as_str(), ips[7].as_str()), ("10.0.0.8", "10.0.0.15"));
+    ///
+    /// // Hosts and blocks combine
+    /// let exclusions = TargetExclusions::parse(&["10.0.0.8", "10.0.0.15"], &["10.0.0.0/29"])?;
+    /// assert_eq!(exclusions.apply(block.clone())?.len(), 6);
+    ///
+    /// let everything = TargetExclusions::parse::<&str>(&[], &["10.0.0.0/24"])?;
+    /// assert!(matches!(everything.apply(block), Err(ConfigError::AllTargetsExcluded)));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn apply(&self, targets: Vec<Target>) -> ConfigResult<Vec<Target>> {
+        let remaining: Vec<Target> = targets.into_iter()
+            .filter(|t| !self.excludes(t.ip))
+            .collect();
+        if remaining.is_empty() {
+            return Err(ConfigError::AllTargetsExcluded);
+        }
+        Ok(remaining)
+    }
+}
+
+/// Parse a comma-separated port spec such as `22,80,8000-8100`
+pub fn parse_port_spec(spec: &str) -> Result<Vec<Port>, String> {
+    let mut ports = Vec::new();
+    for entry in spec.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+        match entry.split_once('-') {
+            Some((start, end)) => {
+                let start = parse_port(start)?;
+                let end = parse_port(end)?;
+                if start > end {
+                    return Err(format!("invalid range '{}': start is greater than end", entry));
+                }
+                ports.extend(start..=end);
+            }
+            None => ports.push(parse_port(entry)?),
+        }
+    }
+    Ok(ports)
+}
+
+fn parse_port(s: &str) -> Result<Port, String> {
+    let s = s.trim();
+    match s.parse::<Port>() {
+        Ok(port) if (MIN_PORT..=MAX_PORT).contains(&port) => Ok(port),
+        _ => Err(format!("invalid port '{}'", s)),
+    }
+}
+
+/// Parse a port list: entries one per line or comma-separated, `#` starts a comment.
+///
+/// Duplicates are dropped, keeping the first occurrence's position.
+pub fn parse_port_list(contents: &str) -> ConfigResult<Vec<Port>> {
+    let mut ports = Vec::new();
+    for (line, text) in entries(contents) {
+        for port in parse_port_spec(text).map_err(|reason| ConfigError::InvalidInputLine { line, reason })? {
+            if !ports.contains(&port) {
+                ports.push(port);
+            }
+        }
+    }
+    if ports.is_empty() {
+        return Err(ConfigError::InvalidScanMode);
+    }
+    Ok(ports)
+}
+
+/// Parse a host list: addresses, CIDR blocks or octet ranges one per line or comma-separated,
+/// `#` starts a comment
+pub fn parse_target_list(contents: &str) -> ConfigResult<Vec<Target>> {
+    let mut targets = Vec::new();
+    let mut seen = HashSet::new();
+    for (line, text) in entries(contents) {
+        for entry in text.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            let expanded = expand_target(entry)
+                .map_err(|e| ConfigError::InvalidInputLine { line, reason: e.to_string() })?;
+            targets.extend(expanded.into_iter().filter(|target| seen.insert(*target)));
+        }
+    }
+    if targets.is_empty() {
+        return Err(ConfigError::MissingField("target_ip".to_string()));
+    }
+    Ok(targets)
+}
+
+/// Read a port list file, see `parse_port_list`; errors name the line
+///
+/// ```
+/// use port_scanner::scanning::targets::{read_port_list, read_target_list};
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let dir = std::env::temp_dir().join(format!("port-list-doc-{}", std::process::id()));
+/// std::fs::create_dir_all(&dir)?;
+///
+/// let ports = dir.join("ports.txt");
+/// std::fs::write(&ports, "# web\n80, 443\n\n8000-8003  # dev servers\n22\n443\n")?;
+/// assert_eq!(read_port_list(&ports)?, [80, 443, 8000, 8001, 8002, 8003, 22]);
+///
+/// std::fs::write(&ports, "# web\n80\n\n8100-8000\n")?;
+/// assert_eq!(
+///     read_port_list(&ports).unwrap_err().to_string(),
+///     "Line 4: invalid range '8100-8000': start is greater than end",
+/// );
+/// std::fs::write(&ports, "22\nssh\n")?;
+/// assert_eq!(read_port_list(&ports).unwrap_err().to_string(), "Line 2: invalid port 'ssh'");
+///
+/// let hosts = dir.join("hosts.txt");
+/// std::fs::write(&hosts, "10.0.0.1\n# lab\n10.0.0.300\n")?;
+/// assert!(read_target_list(&hosts).unwrap_err().to_string().starts_with("Line 3: "));
+/// # std::fs::remove_dir_all(&dir)?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn read_port_list(path: &Path) -> ConfigResult<Vec<Port>> {
+    parse_port_list(&read_input_file(path)?)
+}
+
+pub fn read_target_list(path: &Path) -> ConfigResult<Vec<Target>> {
+    parse_target_list(&read_input_file(path)?)
+}
+
+fn read_input_file(path: &Path) -> ConfigResult<String> {
+    std::fs::read_to_string(path)
+        .map_err(|e| ConfigError::InputFile(format!("{}: {}", path.display(), e)))
+}
+
+/// Non-empty lines with comments stripped, numbered from 1
+fn entries(contents: &str) -> impl Iterator<Item = (usize, &str)> {
+    contents.lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.split('#').next().unwrap_or("").trim()))
+        .filter(|(_, line)| !line.is_empty())
+}